@@ -0,0 +1,138 @@
+use graph::{hash_map, Edge, Graph, HashMap, Vertex, Weight};
+
+// arbitrary data attached to the edges of a graph, e.g. capacities, labels or timestamps;
+// for undirected graphs the edges v - w and w - v share the same value
+pub struct EdgeMap<E> {
+    directed: bool,
+    values: HashMap<Edge, E>
+}
+
+impl<E> EdgeMap<E> {
+
+    // the key the value of the edge is stored under
+    fn key(&self, v: Vertex, w: Vertex) -> Edge {
+        if self.directed || v <= w { (v, w) } else { (w, v) }
+    }
+
+    // sets the value of the edge from v to w, returning the previous one
+    pub fn insert(&mut self, v: Vertex, w: Vertex, value: E) -> Option<E> {
+        let key = self.key(v, w);
+        self.values.insert(key, value)
+    }
+
+    pub fn get(&self, v: Vertex, w: Vertex) -> Option<&E> {
+        self.values.get(&self.key(v, w))
+    }
+
+    pub fn get_mut(&mut self, v: Vertex, w: Vertex) -> Option<&mut E> {
+        let key = self.key(v, w);
+        self.values.get_mut(&key)
+    }
+
+    pub fn remove(&mut self, v: Vertex, w: Vertex) -> Option<E> {
+        let key = self.key(v, w);
+        self.values.remove(&key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    // the edges with a value in arbitrary order; undirected edges are listed with the
+    // smaller vertex first
+    pub fn iter(&self) -> hash_map::Iter<'_, Edge, E> {
+        self.values.iter()
+    }
+}
+
+// data attached to the vertices of a graph, stored densely in order of the vertices and
+// used by algorithms to return a value for every vertex, such as a component or distance
+pub struct VertexMap<T> {
+    // the vertices of the graph in ascending order
+    vertices: Vec<Vertex>,
+    values: Vec<Option<T>>
+}
+
+impl<T> VertexMap<T> {
+
+    // an empty vertex map for the given vertices, which must be in ascending order
+    pub fn new(vertices: Vec<Vertex>) -> VertexMap<T> {
+        let values = vertices.iter().map(|_| None).collect();
+        VertexMap { vertices, values }
+    }
+
+    // sets the value of v, returning the previous one; v must be a vertex of the graph
+    pub fn set(&mut self, v: Vertex, value: T) -> Option<T> {
+        let i = self.vertices.binary_search(&v).expect("Unknown vertex!");
+        self.values[i].replace(value)
+    }
+
+    pub fn get(&self, v: Vertex) -> Option<&T> {
+        let i = self.vertices.binary_search(&v).ok()?;
+        self.values[i].as_ref()
+    }
+
+    // the number of vertices with a value
+    pub fn len(&self) -> usize {
+        self.values.iter().filter(|value| value.is_some()).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.iter().all(|value| value.is_none())
+    }
+
+    // the vertices with a value in ascending order
+    pub fn iter(&self) -> impl Iterator<Item = (Vertex, &T)> + '_ {
+        self.vertices.iter().zip(&self.values).filter_map(|(v, value)| value.as_ref().map(|value| (*v, value)))
+    }
+}
+
+impl Graph {
+
+    // an empty vertex map for the vertices of this graph
+    pub fn vertex_map<T>(&self) -> VertexMap<T> {
+        VertexMap::new(self.sorted_vertices())
+    }
+
+    // the connected component (strongly connected for directed graphs) of every vertex,
+    // numbered from 0 in order of the smallest vertex of each component
+    pub fn component_map(&self) -> VertexMap<usize> {
+        let components = if self.is_directed() { self.strongly_connected_components() } else { self.connected_components() };
+        let mut components: Vec<Vec<Vertex>> = components.into_iter().map(|c| c.into_iter().collect()).collect();
+        components.sort_by_key(|c| c.iter().min().cloned());
+        let mut map = self.vertex_map();
+        for (i, component) in components.iter().enumerate() {
+            for v in component {
+                map.set(*v, i);
+            }
+        }
+        map
+    }
+
+    // the number of edges on a shortest path from source to every vertex reachable from it
+    pub fn hop_distance_map(&self, source: Vertex) -> VertexMap<u32> {
+        let mut map = self.vertex_map();
+        for (v, distance) in self.distances_from(source) {
+            map.set(v, distance);
+        }
+        map
+    }
+
+    // an empty edge map for the edges of this graph
+    pub fn edge_map<E>(&self) -> EdgeMap<E> {
+        EdgeMap { directed: self.is_directed(), values: HashMap::new() }
+    }
+
+    // an edge map with the value computed from each edge and its weight for every edge
+    pub fn edge_map_from<E, F: Fn(Edge, Weight) -> E>(&self, value: F) -> EdgeMap<E> {
+        let mut map = self.edge_map();
+        for edge in self.edges() {
+            map.insert(edge.0, edge.1, value(*edge, self.weight(edge)));
+        }
+        map
+    }
+}
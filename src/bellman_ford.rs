@@ -0,0 +1,143 @@
+use std::collections::VecDeque;
+
+use graph::{Graph, HashMap, Vertex, Weight};
+use shortest_paths::Path;
+
+const NONE: usize = usize::MAX;
+
+// the arcs of the graph between the indices of their ends, both ways for undirected edges
+// and the smallest weight for parallel ones, sorted so that the relaxations and with them
+// the cycles found do not depend on hashing
+fn indexed_arcs(graph: &Graph, index: &HashMap<Vertex, usize>) -> Vec<(usize, usize, Weight)> {
+    let mut arcs: Vec<(usize, usize, Weight)> = graph.weighted_adjacencies().into_iter()
+        .flat_map(|(v, adjacent)| adjacent.into_iter().map(move |(w, weight)| (v, w, weight)))
+        .map(|(v, w, weight)| (index[&v], index[&w], weight))
+        .collect();
+    arcs.sort_by_key(|a| (a.0, a.1));
+    arcs
+}
+
+// relaxes all arcs the given number of rounds, stopping early once a round changes nothing;
+// returns the vertices the last round brought closer, which are none unless every round
+// did, as there is a negative cycle when the rounds are as many as the vertices
+fn relax_rounds(arcs: &[(usize, usize, Weight)], distance: &mut [Weight], parent: &mut [usize], rounds: usize) -> Vec<usize> {
+    let mut relaxed = vec![];
+    for _ in 0..rounds {
+        relaxed.clear();
+        for &(v, w, weight) in arcs {
+            if distance[v] + weight < distance[w] {
+                distance[w] = distance[v] + weight;
+                parent[w] = v;
+                relaxed.push(w);
+            }
+        }
+        if relaxed.is_empty() {
+            break;
+        }
+    }
+    relaxed
+}
+
+impl Graph {
+
+    // finds a cycle of negative total weight with the Bellman-Ford algorithm, starting from
+    // all vertices at once so that it does not matter which vertices reach the cycle; an
+    // edge of negative weight of an undirected graph is such a cycle there and back. Returns
+    // the cycle with its first vertex repeated at the end, or None if there is none
+    pub fn find_negative_cycle(&self) -> Option<Path> {
+        let (vertices, index) = self.indexed_vertices();
+        let arcs = indexed_arcs(self, &index);
+        let n = vertices.len();
+        let mut distance = vec![0.0; n];
+        let mut parent = vec![NONE; n];
+        let mut relaxed = relax_rounds(&arcs, &mut distance, &mut parent, n);
+        // a cycle of parents is always negative, but the parents may only close one after
+        // some more rounds
+        while !relaxed.is_empty() {
+            for &start in &relaxed {
+                let mut seen = vec![false; n];
+                let mut v = start;
+                while v != NONE && !seen[v] {
+                    seen[v] = true;
+                    v = parent[v];
+                }
+                if v != NONE {
+                    let mut cycle = vec![vertices[v]];
+                    let mut w = parent[v];
+                    while w != v {
+                        cycle.push(vertices[w]);
+                        w = parent[w];
+                    }
+                    cycle.push(vertices[v]);
+                    cycle.reverse();
+                    return Some(cycle);
+                }
+            }
+            relaxed = relax_rounds(&arcs, &mut distance, &mut parent, 1);
+        }
+        None
+    }
+
+    // the distances from the source with the Bellman-Ford algorithm, which allows negative
+    // weights: vertices behind a negative cycle that the source reaches have no shortest
+    // distance, as going around the cycle once more always gets them closer, and are given
+    // as minus infinity; vertices the source cannot reach are left out
+    pub fn bellman_ford(&self, source: Vertex) -> HashMap<Vertex, Weight> {
+        let (mut vertices, mut index) = self.indexed_vertices();
+        index.entry(source).or_insert_with(|| {
+            vertices.push(source);
+            vertices.len() - 1
+        });
+        let arcs = indexed_arcs(self, &index);
+        let n = vertices.len();
+        let mut distance = vec![Weight::INFINITY; n];
+        let mut parent = vec![NONE; n];
+        distance[index[&source]] = 0.0;
+        let relaxed = relax_rounds(&arcs, &mut distance, &mut parent, n);
+        // everything reachable from a vertex the last round still brought closer is behind
+        // a negative cycle
+        let mut successors = vec![vec![]; n];
+        for &(v, w, _) in &arcs {
+            successors[v].push(w);
+        }
+        let mut queue: VecDeque<usize> = relaxed.into_iter().collect();
+        while let Some(v) = queue.pop_front() {
+            if distance[v] == Weight::NEG_INFINITY {
+                continue;
+            }
+            distance[v] = Weight::NEG_INFINITY;
+            queue.extend(successors[v].iter().cloned());
+        }
+        vertices.into_iter().zip(distance).filter(|(_, d)| *d < Weight::INFINITY).collect()
+    }
+
+    // the vertices whose distance from the source is minus infinity, see bellman_ford, in
+    // ascending order
+    pub fn infinite_distance_vertices(&self, source: Vertex) -> Vec<Vertex> {
+        let mut vertices: Vec<Vertex> = self.bellman_ford(source).into_iter()
+            .filter(|(_, d)| *d == Weight::NEG_INFINITY)
+            .map(|(v, _)| v)
+            .collect();
+        vertices.sort();
+        vertices
+    }
+
+    // finds an arbitrage opportunity when the weights are exchange rates, an edge from v to
+    // w giving how much of currency w one unit of currency v buys: a cycle of trades whose
+    // rates multiply to more than 1 is a negative cycle of the costs -ln(rate). Rates that
+    // are not positive are ignored, as no trade can use them; returns the factor a trade
+    // around the cycle multiplies the money by with the cycle, or None if there is none
+    pub fn find_arbitrage(&self) -> Option<(Weight, Path)> {
+        let mut costs = self.map_weights(|_, rate| -rate.ln());
+        for (v, w) in self.edges() {
+            let rate = self.weight(&(*v, *w));
+            if rate <= 0.0 || rate.is_nan() {
+                costs.remove_edge(*v, *w);
+            }
+        }
+        let cycle = costs.find_negative_cycle()?;
+        let adjacent = costs.weighted_adjacencies();
+        let cost: Weight = cycle.windows(2).map(|pair| adjacent[&pair[0]][&pair[1]]).sum();
+        Some(((-cost).exp(), cycle))
+    }
+}
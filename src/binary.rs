@@ -0,0 +1,95 @@
+use std::convert::TryFrom;
+use std::io;
+use std::io::{Read, Write};
+use std::mem::size_of;
+
+use graph::{Edge, Graph, HashMap, HashSet, Vertex};
+
+// the binary format starts with a magic number and a version, followed by a byte of flags,
+// the number of vertices and the vertices, then the number of edges and the edges with
+// their weights if the graph is weighted; all numbers are little endian, vertices 32 bit
+// (64 bit if the wide flag is set, since version 2) and counts 64 bit unsigned integers,
+// weights 64 bit floats
+const MAGIC: &[u8; 4] = b"GRPH";
+const VERSION: u8 = 2;
+const DIRECTED: u8 = 1;
+const WEIGHTED: u8 = 2;
+const WIDE: u8 = 4;
+
+impl Graph {
+
+    // writes the graph in the binary format, listing vertices and edges in sorted order
+    pub fn write_binary<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let flags = if self.is_directed() { DIRECTED } else { 0 } | if self.is_weighted() { WEIGHTED } else { 0 }
+            | if size_of::<Vertex>() == 8 { WIDE } else { 0 };
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION, flags])?;
+        let vertices = self.sorted_vertices();
+        writer.write_all(&(vertices.len() as u64).to_le_bytes())?;
+        for v in vertices {
+            writer.write_all(&v.to_le_bytes())?;
+        }
+        let mut edges: Vec<&Edge> = self.edges().iter().collect();
+        edges.sort();
+        writer.write_all(&(edges.len() as u64).to_le_bytes())?;
+        for edge in edges {
+            writer.write_all(&edge.0.to_le_bytes())?;
+            writer.write_all(&edge.1.to_le_bytes())?;
+            if self.is_weighted() {
+                writer.write_all(&self.weight(edge).to_le_bytes())?;
+            }
+        }
+        Ok(())
+    }
+
+    // reads a graph written by write_binary; fails with InvalidData if the input is not in
+    // the binary format, was written by a later version or has vertices too wide for Vertex
+    pub fn read_binary<R: Read>(reader: &mut R) -> io::Result<Graph> {
+        let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+        let mut header = [0; 6];
+        reader.read_exact(&mut header)?;
+        if &header[..4] != MAGIC {
+            return Err(invalid("not a binary graph"));
+        }
+        if header[4] > VERSION {
+            return Err(invalid("unsupported binary graph version"));
+        }
+        let flags = header[5];
+        let vertex = |reader: &mut R| -> io::Result<Vertex> {
+            let v = if flags & WIDE != 0 { read_u64(reader)? } else { read_u32(reader)? as u64 };
+            Vertex::try_from(v).map_err(|_| invalid("vertex too large"))
+        };
+
+        let mut vertices = HashSet::new();
+        for _ in 0..read_u64(reader)? {
+            vertices.insert(vertex(reader)?);
+        }
+        let mut edges = HashSet::new();
+        let mut weights = HashMap::new();
+        for _ in 0..read_u64(reader)? {
+            let edge: Edge = (vertex(reader)?, vertex(reader)?);
+            if !vertices.contains(&edge.0) || !vertices.contains(&edge.1) {
+                return Err(invalid("edge between unknown vertices"));
+            }
+            if flags & WEIGHTED != 0 {
+                let mut bytes = [0; 8];
+                reader.read_exact(&mut bytes)?;
+                weights.insert(edge, f64::from_le_bytes(bytes));
+            }
+            edges.insert(edge);
+        }
+        Ok(Graph::with_weights(vertices, edges, weights, flags & DIRECTED != 0))
+    }
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> io::Result<u64> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
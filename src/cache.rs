@@ -0,0 +1,130 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use graph::{Adjacencies, Component, Graph, HashMap, Vertex, Weight, WeightedAdjacencies};
+use shortest_paths::{distances, k_nearest};
+
+// a graph that remembers the results of its expensive queries, so that asking again costs
+// nothing until the graph is changed: every query is computed on first use and kept until
+// a mutation through the cached graph or an explicit call of invalidate drops all results
+pub struct CachedGraph {
+    graph: Graph,
+    cache: RefCell<QueryCache>
+}
+
+#[derive(Default)]
+struct QueryCache {
+    adjacencies: Option<Rc<Adjacencies>>,
+    weighted_adjacencies: Option<Rc<WeightedAdjacencies>>,
+    components: Option<Rc<Vec<Component>>>,
+    strong_components: Option<Rc<Vec<Component>>>,
+    // the distances from each source asked for so far
+    distances: HashMap<Vertex, Rc<HashMap<Vertex, Weight>>>,
+    computations: usize
+}
+
+impl CachedGraph {
+
+    pub fn new(graph: Graph) -> CachedGraph {
+        CachedGraph { graph, cache: RefCell::new(QueryCache::default()) }
+    }
+
+    pub fn graph(&self) -> &Graph {
+        &self.graph
+    }
+
+    // the number of queries actually computed so far, as opposed to answered from the cache
+    pub fn computations(&self) -> usize {
+        self.cache.borrow().computations
+    }
+
+    // drops all remembered results, for instance to free their memory
+    pub fn invalidate(&mut self) {
+        let computations = self.cache.borrow().computations;
+        self.cache = RefCell::new(QueryCache { computations, ..QueryCache::default() });
+    }
+
+    // drops all remembered results and computes the adjacencies and components right away,
+    // so that later queries do not pay for them; distance maps are computed on first use
+    pub fn recompute(&mut self) {
+        self.invalidate();
+        self.adjacencies();
+        self.weighted_adjacencies();
+        self.connected_components();
+        self.strongly_connected_components();
+    }
+
+    pub fn adjacencies(&self) -> Rc<Adjacencies> {
+        self.cached(|cache| &mut cache.adjacencies, || self.graph.adjacencies())
+    }
+
+    pub fn weighted_adjacencies(&self) -> Rc<WeightedAdjacencies> {
+        self.cached(|cache| &mut cache.weighted_adjacencies, || self.graph.weighted_adjacencies())
+    }
+
+    pub fn connected_components(&self) -> Rc<Vec<Component>> {
+        self.cached(|cache| &mut cache.components, || self.graph.connected_components())
+    }
+
+    pub fn strongly_connected_components(&self) -> Rc<Vec<Component>> {
+        self.cached(|cache| &mut cache.strong_components, || self.graph.strongly_connected_components())
+    }
+
+    // the distances from source to all reachable vertices, see shortest_paths::distances
+    pub fn distances(&self, source: Vertex) -> Rc<HashMap<Vertex, Weight>> {
+        if let Some(distances) = self.cache.borrow().distances.get(&source) {
+            return distances.clone();
+        }
+        let computed = Rc::new(distances(&self.weighted_adjacencies(), source));
+        let mut cache = self.cache.borrow_mut();
+        cache.computations += 1;
+        cache.distances.insert(source, computed.clone());
+        computed
+    }
+
+    // the k vertices other than source nearest to it, see shortest_paths::k_nearest; only
+    // the adjacencies are cached, as every query explores just the vicinity of its source
+    pub fn k_nearest(&self, source: Vertex, k: usize) -> Vec<(Vertex, Weight)> {
+        k_nearest(&self.weighted_adjacencies(), source, k)
+    }
+
+    // adds the edge from v to w like Graph::add_edge, dropping all results if it is new
+    pub fn add_edge(&mut self, v: Vertex, w: Vertex) -> bool {
+        let added = self.graph.add_edge(v, w);
+        if added {
+            self.invalidate();
+        }
+        added
+    }
+
+    // adds the edge from v to w or changes its weight like Graph::add_weighted_edge,
+    // dropping all results
+    pub fn add_weighted_edge(&mut self, v: Vertex, w: Vertex, weight: Weight) {
+        self.graph.add_weighted_edge(v, w, weight);
+        self.invalidate();
+    }
+
+    // removes the edge from v to w like Graph::remove_edge, dropping all results if there
+    // was such an edge
+    pub fn remove_edge(&mut self, v: Vertex, w: Vertex) -> bool {
+        let removed = self.graph.remove_edge(v, w);
+        if removed {
+            self.invalidate();
+        }
+        removed
+    }
+
+    // returns the result kept in the given slot of the cache, computing it first if needed;
+    // the cache is not borrowed during the computation, which may use other cached results
+    fn cached<T, S, C>(&self, slot: S, compute: C) -> Rc<T>
+        where S: Fn(&mut QueryCache) -> &mut Option<Rc<T>>, C: FnOnce() -> T {
+        if let Some(result) = slot(&mut self.cache.borrow_mut()) {
+            return result.clone();
+        }
+        let computed = Rc::new(compute());
+        let mut cache = self.cache.borrow_mut();
+        cache.computations += 1;
+        *slot(&mut cache) = Some(computed.clone());
+        computed
+    }
+}
@@ -0,0 +1,207 @@
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use graph::{Edge, Graph};
+use signature::{combine, mix};
+
+// a checkpoint file starts with a magic number, a version and the stage, followed by the key
+// of the graph the stage runs on, the number of words of the state and their checksum, and
+// then the words of the state; all numbers are little endian 64 bit unsigned integers
+const MAGIC: &[u8; 4] = b"CKPT";
+const VERSION: u8 = 1;
+
+// the preprocessing stages that can save their state to a checkpoint and resume from it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stage {
+    ContractionHierarchy,
+    TransitiveClosure,
+    DistanceOracle
+}
+
+// where and how often a long preprocessing stage saves its state, so that a run that
+// crashed or was killed can resume from the last save instead of starting over: the state is
+// saved after every interval units of work, contracted vertices or processed sources, and
+// the checkpoint file is removed once the stage is done
+pub struct Checkpoint {
+    path: PathBuf,
+    interval: usize
+}
+
+// the checkpointing of one run of a stage on a graph, as started by Checkpoint::start
+pub struct CheckpointRun<'a> {
+    checkpoint: &'a Checkpoint,
+    stage: Stage,
+    key: u64
+}
+
+// the state of a stage being saved, as a sequence of words
+#[derive(Default)]
+pub struct State {
+    words: Vec<u64>
+}
+
+// the state of a stage read back from a checkpoint, taken word by word in the order it was
+// saved in; taking more words than were saved fails with InvalidData
+pub struct SavedState {
+    words: Vec<u64>,
+    position: usize
+}
+
+impl Checkpoint {
+
+    // a checkpoint saving to the file at the path after every interval units of work, or
+    // never if interval is 0, which still resumes from a file saved before
+    pub fn new(path: &Path, interval: usize) -> Checkpoint {
+        Checkpoint { path: path.to_path_buf(), interval }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    // starts checkpointing a run of the stage on the graph, returning the state saved by an
+    // earlier run of it on the same graph, or None if there is no checkpoint file; fails with
+    // InvalidData if the file is damaged or was saved by another stage or for another graph
+    pub fn start(&self, stage: Stage, graph: &Graph) -> io::Result<(CheckpointRun<'_>, Option<SavedState>)> {
+        let run = CheckpointRun { checkpoint: self, stage, key: graph_key(graph) };
+        let file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(ref error) if error.kind() == io::ErrorKind::NotFound => return Ok((run, None)),
+            Err(error) => return Err(error)
+        };
+        let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+        let mut reader = BufReader::new(file);
+        let mut header = [0; 6];
+        reader.read_exact(&mut header)?;
+        if &header[..4] != MAGIC {
+            return Err(invalid("not a checkpoint"));
+        }
+        if header[4] > VERSION {
+            return Err(invalid("unsupported checkpoint version"));
+        }
+        if header[5] != stage as u8 {
+            return Err(invalid("checkpoint of another stage"));
+        }
+        if read_word(&mut reader)? != run.key {
+            return Err(invalid("checkpoint of another graph"));
+        }
+        let length = read_word(&mut reader)?;
+        let checksum = read_word(&mut reader)?;
+        let mut words = vec![];
+        for _ in 0..length {
+            words.push(read_word(&mut reader)?);
+        }
+        if words.iter().cloned().fold(mix(length), combine) != checksum {
+            return Err(invalid("damaged checkpoint"));
+        }
+        Ok((run, Some(SavedState { words, position: 0 })))
+    }
+}
+
+impl<'a> CheckpointRun<'a> {
+
+    // returns true if the state should be saved after the given number of units of work
+    pub fn is_due(&self, done: usize) -> bool {
+        self.checkpoint.interval > 0 && done > 0 && done.is_multiple_of(self.checkpoint.interval)
+    }
+
+    // saves the state, replacing the file only once the new one is complete, so that a crash
+    // while saving leaves the previous state intact
+    pub fn save(&self, state: &State) -> io::Result<()> {
+        let mut temporary = self.checkpoint.path.clone().into_os_string();
+        temporary.push(".tmp");
+        {
+            let mut writer = BufWriter::new(File::create(&temporary)?);
+            writer.write_all(MAGIC)?;
+            writer.write_all(&[VERSION, self.stage as u8])?;
+            let length = state.words.len() as u64;
+            for word in [self.key, length, state.words.iter().cloned().fold(mix(length), combine)] {
+                writer.write_all(&word.to_le_bytes())?;
+            }
+            for word in &state.words {
+                writer.write_all(&word.to_le_bytes())?;
+            }
+            writer.into_inner().map_err(|error| error.into_error())?.sync_all()?;
+        }
+        fs::rename(&temporary, &self.checkpoint.path)
+    }
+
+    // removes the checkpoint file once the stage is done, as there is nothing left to resume
+    pub fn finish(self) -> io::Result<()> {
+        match fs::remove_file(&self.checkpoint.path) {
+            Err(ref error) if error.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result
+        }
+    }
+}
+
+impl State {
+
+    pub fn new() -> State {
+        State::default()
+    }
+
+    pub fn put(&mut self, word: u64) {
+        self.words.push(word);
+    }
+
+    pub fn put_index(&mut self, index: usize) {
+        self.words.push(index as u64);
+    }
+
+    pub fn put_weight(&mut self, weight: f64) {
+        self.words.push(weight.to_bits());
+    }
+}
+
+impl SavedState {
+
+    pub fn take(&mut self) -> io::Result<u64> {
+        let word = self.words.get(self.position).cloned()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "truncated checkpoint"))?;
+        self.position += 1;
+        Ok(word)
+    }
+
+    // takes an index or a count, which must be below the limit, like the number of vertices
+    pub fn take_index(&mut self, limit: usize) -> io::Result<usize> {
+        let word = self.take()?;
+        if word < limit as u64 {
+            Ok(word as usize)
+        } else {
+            Err(io::Error::new(io::ErrorKind::InvalidData, "index out of range in checkpoint"))
+        }
+    }
+
+    pub fn take_weight(&mut self) -> io::Result<f64> {
+        self.take().map(f64::from_bits)
+    }
+}
+
+// a hash of the vertices, edges and weights of the graph, for telling whether a checkpoint was
+// saved for it; unlike the fingerprint of the graph it depends on the vertex numbers
+fn graph_key(graph: &Graph) -> u64 {
+    let (vertices, index) = graph.indexed_vertices();
+    let mut key = combine(mix(graph.is_directed() as u64), graph.is_weighted() as u64);
+    for v in &vertices {
+        for byte in v.to_le_bytes() {
+            key = combine(key, byte as u64);
+        }
+    }
+    let mut edges: Vec<&Edge> = graph.edges().iter().collect();
+    edges.sort();
+    key = combine(key, edges.len() as u64);
+    for edge in edges {
+        key = combine(combine(combine(key, index[&edge.0] as u64), index[&edge.1] as u64), graph.weight(edge).to_bits());
+    }
+    key
+}
+
+fn read_word<R: Read>(reader: &mut R) -> io::Result<u64> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
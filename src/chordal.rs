@@ -0,0 +1,289 @@
+use std::collections::VecDeque;
+
+use graph::{Graph, HashMap, Vertex};
+use shortest_paths::Path;
+
+// the ordered partition of the unvisited vertices kept by a lexicographic breadth first
+// search: a doubly linked list of classes, with the class of every vertex and its position
+// in it, so that moving a vertex to a new class takes constant time
+struct Partition {
+    members: Vec<Vec<usize>>,
+    previous: Vec<usize>,
+    next: Vec<usize>,
+    head: usize,
+    class: Vec<usize>,
+    position: Vec<usize>
+}
+
+const NONE: usize = usize::MAX;
+
+impl Partition {
+
+    // a single class of all vertices, listed so that the smallest comes out first
+    fn new(n: usize) -> Partition {
+        let position = (0..n).map(|v| n - 1 - v).collect();
+        Partition {
+            members: vec![(0..n).rev().collect()], previous: vec![NONE], next: vec![NONE],
+            head: if n == 0 { NONE } else { 0 }, class: vec![0; n], position
+        }
+    }
+
+    // inserts a new empty class right before class c and returns it
+    fn insert_before(&mut self, c: usize) -> usize {
+        let new = self.members.len();
+        self.members.push(vec![]);
+        self.previous.push(self.previous[c]);
+        self.next.push(c);
+        match self.previous[c] {
+            NONE => self.head = new,
+            p => self.next[p] = new
+        }
+        self.previous[c] = new;
+        new
+    }
+
+    fn unlink(&mut self, c: usize) {
+        let (p, n) = (self.previous[c], self.next[c]);
+        match p {
+            NONE => self.head = n,
+            p => self.next[p] = n
+        }
+        if n != NONE {
+            self.previous[n] = p;
+        }
+    }
+
+    // moves vertex v from its class to class c
+    fn move_to(&mut self, v: usize, c: usize) {
+        let old = self.class[v];
+        let last = self.members[old].pop().unwrap();
+        if last != v {
+            self.members[old][self.position[v]] = last;
+            self.position[last] = self.position[v];
+        }
+        if self.members[old].is_empty() {
+            self.unlink(old);
+        }
+        self.class[v] = c;
+        self.position[v] = self.members[c].len();
+        self.members[c].push(v);
+    }
+}
+
+impl Graph {
+
+    // a lexicographic breadth first search order of the vertices: the next vertex is always
+    // one whose already visited neighbours, read as the positions at which they were visited,
+    // form the lexicographically smallest sequence, found by refining an ordered partition of
+    // the unvisited vertices by the neighbours of every visited one in O(n + m) time; starts
+    // at the smallest vertex, moves on to the next component once one is exhausted, and
+    // ignores edge directions and self-loops. Its reverse is a perfect elimination ordering
+    // exactly if the graph is chordal
+    pub fn lex_bfs(&self) -> Vec<Vertex> {
+        let (vertices, adj) = self.simple_undirected();
+        let n = vertices.len();
+        let mut partition = Partition::new(n);
+        let mut visited = vec![false; n];
+        // the class the neighbours of the current vertex move to from every class
+        let mut split = vec![NONE];
+        let mut order = Vec::with_capacity(n);
+        while partition.head != NONE {
+            let c = partition.head;
+            let v = partition.members[c].pop().unwrap();
+            if partition.members[c].is_empty() {
+                partition.unlink(c);
+            }
+            visited[v] = true;
+            order.push(vertices[v]);
+            let mut touched = vec![];
+            for w in adj[v].iter().cloned().filter(|w| !visited[*w]) {
+                let old = partition.class[w];
+                if split[old] == NONE {
+                    split[old] = partition.insert_before(old);
+                    split.push(NONE);
+                    touched.push(old);
+                }
+                partition.move_to(w, split[old]);
+            }
+            for old in touched {
+                split[old] = NONE;
+            }
+        }
+        order
+    }
+
+    // a perfect elimination ordering of the graph, in which the neighbours of every vertex
+    // that come after it form a clique, as a witness that the graph is chordal, meaning that
+    // every cycle of more than three vertices has a chord; it is the reverse of the order of
+    // lex_bfs if there is one at all, which is checked in O(n + m) time. Otherwise returns a
+    // chordless cycle of more than three vertices as the witness, with the first vertex
+    // repeated at the end, closed through a vertex whose later neighbours u and w in the
+    // reversed order are not adjacent by a shortest path from u to w avoiding its other
+    // neighbours; edge directions and self-loops are ignored
+    pub fn perfect_elimination_ordering(&self) -> Result<Vec<Vertex>, Path> {
+        let mut order = self.lex_bfs();
+        order.reverse();
+        let (vertices, adj) = self.simple_undirected();
+        let position = self.positions(&order).unwrap();
+        let (later, first) = later_neighbours(&adj, &position);
+        let (v, u, w) = match violation(&adj, &later, &first) {
+            Some(violation) => violation,
+            None => return Ok(order)
+        };
+        // a breadth first search from u to w outside the other neighbours of v
+        let n = vertices.len();
+        let mut parent = vec![NONE; n];
+        parent[v] = v;
+        for x in &adj[v] {
+            parent[*x] = v;
+        }
+        parent[u] = u;
+        parent[w] = NONE;
+        let mut queue = VecDeque::from(vec![u]);
+        while let Some(x) = queue.pop_front() {
+            for y in &adj[x] {
+                if parent[*y] == NONE {
+                    parent[*y] = x;
+                    queue.push_back(*y);
+                }
+            }
+            if parent[w] != NONE {
+                break;
+            }
+        }
+        let mut cycle = vec![vertices[v], vertices[w]];
+        let mut x = w;
+        while x != u {
+            x = parent[x];
+            cycle.push(vertices[x]);
+        }
+        cycle.push(vertices[v]);
+        Err(cycle)
+    }
+
+    // returns true if the graph is chordal, see perfect_elimination_ordering
+    pub fn is_chordal(&self) -> bool {
+        self.perfect_elimination_ordering().is_ok()
+    }
+
+    // the maximal cliques of a chordal graph, given a perfect elimination ordering, each in
+    // ascending order: the vertices with their later neighbours, leaving out those contained
+    // in the clique of a vertex whose first later neighbour they are, in O(n + m) time; edge
+    // directions and self-loops are ignored
+    // returns None if the order is not one of all vertices or not a perfect elimination one
+    pub fn maximal_cliques_along(&self, order: &[Vertex]) -> Option<Vec<Vec<Vertex>>> {
+        let (vertices, adj) = self.simple_undirected();
+        let position = self.positions(order)?;
+        let (later, first) = later_neighbours(&adj, &position);
+        if violation(&adj, &later, &first).is_some() {
+            return None;
+        }
+        let mut maximal = vec![true; vertices.len()];
+        for (v, u) in first.iter().enumerate() {
+            if *u != NONE && later[v].len() == later[*u].len() + 1 {
+                maximal[*u] = false;
+            }
+        }
+        let index = self.indexed_vertices().1;
+        let mut cliques = vec![];
+        for v in order.iter().map(|v| index[v]) {
+            if maximal[v] {
+                let mut clique: Vec<Vertex> = later[v].iter().map(|w| vertices[*w]).collect();
+                clique.push(vertices[v]);
+                clique.sort();
+                cliques.push(clique);
+            }
+        }
+        Some(cliques)
+    }
+
+    // a largest clique of a chordal graph given a perfect elimination ordering, the largest
+    // of its maximal cliques, or None if the order is none, see maximal_cliques_along
+    pub fn chordal_maximum_clique(&self, order: &[Vertex]) -> Option<Vec<Vertex>> {
+        let cliques = self.maximal_cliques_along(order)?;
+        Some(cliques.into_iter().rev().max_by_key(|clique| clique.len()).unwrap_or_default())
+    }
+
+    // colors a chordal graph with as few colors as possible given a perfect elimination
+    // ordering, going through it backwards and giving every vertex the smallest color not
+    // used by a neighbour, in O(n + m) time: the neighbours colored before it come after it
+    // in the order and form a clique with it, so no more colors are needed than the size of a
+    // largest clique; returns None if the order is not a perfect elimination one
+    pub fn chordal_coloring(&self, order: &[Vertex]) -> Option<HashMap<Vertex, usize>> {
+        let (vertices, adj) = self.simple_undirected();
+        let position = self.positions(order)?;
+        let (later, first) = later_neighbours(&adj, &position);
+        if violation(&adj, &later, &first).is_some() {
+            return None;
+        }
+        let index = self.indexed_vertices().1;
+        let mut color = vec![NONE; vertices.len()];
+        // the vertex whose neighbours last used every color
+        let mut used = vec![NONE; vertices.len() + 1];
+        for v in order.iter().rev().map(|v| index[v]) {
+            for w in &later[v] {
+                used[color[*w]] = v;
+            }
+            color[v] = (0..).find(|c| used[*c] != v).unwrap();
+        }
+        Some(vertices.into_iter().zip(color).collect())
+    }
+
+    // the position of every vertex index in the order, or None if it is not one of all vertices
+    fn positions(&self, order: &[Vertex]) -> Option<Vec<usize>> {
+        let index = self.indexed_vertices().1;
+        let mut position = vec![NONE; index.len()];
+        for (i, v) in order.iter().enumerate() {
+            let v = *index.get(v)?;
+            if position[v] != NONE {
+                return None;
+            }
+            position[v] = i;
+        }
+        if order.len() == index.len() { Some(position) } else { None }
+    }
+
+    // the vertices in ascending order with the sorted indices of the neighbours of each,
+    // ignoring edge directions, self-loops and edges listed both ways
+    pub fn simple_undirected(&self) -> (Vec<Vertex>, Vec<Vec<usize>>) {
+        let (vertices, mut adj) = self.dense_undirected();
+        for adjacent in &mut adj {
+            adjacent.sort();
+            adjacent.dedup();
+        }
+        (vertices, adj)
+    }
+}
+
+// the neighbours of every vertex that come after it in an order, given by the position of
+// every vertex, and the first of them, or NONE if there are none
+fn later_neighbours(adj: &[Vec<usize>], position: &[usize]) -> (Vec<Vec<usize>>, Vec<usize>) {
+    let later: Vec<Vec<usize>> = (0..adj.len()).map(|v| adj[v].iter().cloned().filter(|w| position[*w] > position[v]).collect()).collect();
+    let first = later.iter().map(|after| after.iter().cloned().min_by_key(|w| position[*w]).unwrap_or(NONE)).collect();
+    (later, first)
+}
+
+// finds a vertex v whose later neighbours do not form a clique, with its first later
+// neighbour u and another one w not adjacent to u, or None if the order is a perfect
+// elimination one: it is enough to check that the other later neighbours of every vertex are
+// adjacent to the first, as they are all later neighbours of that one in turn
+fn violation(adj: &[Vec<usize>], later: &[Vec<usize>], first: &[usize]) -> Option<(usize, usize, usize)> {
+    let n = adj.len();
+    // the later neighbours every vertex must be adjacent to, with the vertex requiring it
+    let mut required = vec![vec![]; n];
+    for v in 0..n {
+        if first[v] != NONE {
+            required[first[v]].extend(later[v].iter().filter(|w| **w != first[v]).map(|w| (*w, v)));
+        }
+    }
+    let mut mark = vec![NONE; n];
+    for u in 0..n {
+        for w in &adj[u] {
+            mark[*w] = u;
+        }
+        if let Some(&(w, v)) = required[u].iter().find(|(w, _)| mark[*w] != u) {
+            return Some((v, u, w));
+        }
+    }
+    None
+}
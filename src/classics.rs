@@ -0,0 +1,94 @@
+use graph::{Edge, Graph, HashSet, Vertex};
+
+// the edges of Zachary's karate club, between its 34 members
+const KARATE_CLUB: [Edge; 78] = [
+    (1, 2), (1, 3), (1, 4), (1, 5), (1, 6), (1, 7), (1, 8), (1, 9), (1, 11), (1, 12), (1, 13),
+    (1, 14), (1, 18), (1, 20), (1, 22), (1, 32), (2, 3), (2, 4), (2, 8), (2, 14), (2, 18),
+    (2, 20), (2, 22), (2, 31), (3, 4), (3, 8), (3, 9), (3, 10), (3, 14), (3, 28), (3, 29),
+    (3, 33), (4, 8), (4, 13), (4, 14), (5, 7), (5, 11), (6, 7), (6, 11), (6, 17), (7, 17),
+    (9, 31), (9, 33), (9, 34), (10, 34), (14, 34), (15, 33), (15, 34), (16, 33), (16, 34),
+    (19, 33), (19, 34), (20, 34), (21, 33), (21, 34), (23, 33), (23, 34), (24, 26), (24, 28),
+    (24, 30), (24, 33), (24, 34), (25, 26), (25, 28), (25, 32), (26, 32), (27, 30), (27, 34),
+    (28, 34), (29, 32), (29, 34), (30, 33), (30, 34), (31, 33), (31, 34), (32, 33), (32, 34),
+    (33, 34)
+];
+
+// the members who joined the instructor, member 1, when the club split up
+const INSTRUCTOR_FACTION: [Vertex; 17] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 11, 12, 13, 14, 17, 18, 20, 22];
+
+// the edges of the dodecahedron between its 20 corners
+const DODECAHEDRON: [Edge; 30] = [
+    (1, 2), (1, 11), (1, 20), (2, 3), (2, 9), (3, 4), (3, 7), (4, 5), (4, 20), (5, 6),
+    (5, 18), (6, 7), (6, 16), (7, 8), (8, 9), (8, 15), (9, 10), (10, 11), (10, 14), (11, 12),
+    (12, 13), (12, 19), (13, 14), (13, 17), (14, 15), (15, 16), (16, 17), (17, 18), (18, 19),
+    (19, 20)
+];
+
+// an undirected graph over the vertices 1..n with the given edges
+fn from_edges<I: IntoIterator<Item = Edge>>(n: Vertex, edges: I) -> Graph {
+    Graph::new((1..n + 1).collect(), edges.into_iter().collect(), false)
+}
+
+// the complete graph K5: 5 vertices, 10 edges, 4-regular with 10 triangles, chromatic
+// number 5, and the smallest complete graph that is not planar
+pub fn k5() -> Graph {
+    from_edges(5, (1..6).flat_map(|u| (u + 1..6).map(move |v| (u, v))))
+}
+
+// the complete bipartite graph K3,3 between the vertices 1..3 and 4..6: 6 vertices, 9
+// edges, 3-regular, bipartite and thus without triangles, girth 4, and not planar
+pub fn k33() -> Graph {
+    from_edges(6, (1..4).flat_map(|u| (4..7).map(move |v| (u, v))))
+}
+
+// the Petersen graph with the outer cycle 1..5, the inner pentagram 6..10 and the spokes
+// from i to i + 5: 10 vertices, 15 edges, 3-regular, girth 5, diameter 2, chromatic number
+// 3 and chromatic index 4, not planar and without a Hamiltonian cycle
+pub fn petersen() -> Graph {
+    let outer = (1..6).map(|i| (i, i % 5 + 1));
+    let inner = (1..6).map(|i| (i + 5, (i + 1) % 5 + 6));
+    let spokes = (1..6).map(|i| (i, i + 5));
+    from_edges(10, outer.chain(inner).chain(spokes))
+}
+
+// the cube graph Q3, in which vertices are adjacent if v - 1 and w - 1 differ in exactly
+// one bit: 8 vertices, 12 edges, 3-regular, bipartite, planar and Hamiltonian, girth 4 and
+// diameter 3
+pub fn cube() -> Graph {
+    from_edges(8, (0..8).flat_map(|u| (0..3).map(move |bit| (u, u ^ (1 << bit))))
+        .filter(|&(u, v)| u < v)
+        .map(|(u, v)| (u + 1, v + 1)))
+}
+
+// the graph of the corners and edges of the dodecahedron: 20 vertices, 30 edges, 3-regular,
+// planar and Hamiltonian, girth 5 and diameter 5
+pub fn dodecahedron() -> Graph {
+    from_edges(20, DODECAHEDRON.iter().cloned())
+}
+
+// the friendships between the 34 members of a karate club observed by Zachary before the
+// club split into two: 78 edges and 45 triangles; the two factions, see karate_club_split,
+// formed around the instructor, member 1, and the administrator, member 34
+pub fn karate_club() -> Graph {
+    from_edges(34, KARATE_CLUB.iter().cloned())
+}
+
+// the members of the karate club who sided with the instructor after the split; the other
+// members sided with the administrator
+pub fn karate_club_split() -> HashSet<Vertex> {
+    INSTRUCTOR_FACTION.iter().cloned().collect()
+}
+
+// the classic graph with the given name, any of k5, k33, petersen, cube, dodecahedron and
+// karate
+pub fn by_name(name: &str) -> Option<Graph> {
+    match name {
+        "k5" => Some(k5()),
+        "k33" => Some(k33()),
+        "petersen" => Some(petersen()),
+        "cube" => Some(cube()),
+        "dodecahedron" => Some(dodecahedron()),
+        "karate" => Some(karate_club()),
+        _ => None
+    }
+}
@@ -0,0 +1,404 @@
+use std::convert::Infallible;
+use std::io;
+
+use checkpoint::{Checkpoint, Stage, State};
+use graph::{Graph, HashMap, HashSet, Vertex};
+use random::Random;
+
+// number of randomized interval labelings kept by the reachability index
+const LABELINGS: usize = 3;
+
+// an interval of traversal numbers
+type Interval = (usize, usize);
+
+// the transitive closure of a graph, i.e. which vertices can be reached from which;
+// stores one bitset over all vertices per strongly connected component
+pub struct TransitiveClosure {
+    vertices: Vec<Vertex>,
+    index: HashMap<Vertex, usize>,
+    component: Vec<usize>,
+    reachable: Vec<Vec<u64>>
+}
+
+impl TransitiveClosure {
+
+    // returns true if vertex v can be reached from vertex u; every vertex reaches itself
+    pub fn reaches(&self, u: Vertex, v: Vertex) -> bool {
+        match (self.index.get(&u), self.index.get(&v)) {
+            (Some(i), Some(j)) => self.reachable[self.component[*i]][j / 64] & (1 << (j % 64)) != 0,
+            _ => false
+        }
+    }
+
+    // builds the reachability graph, containing an edge from u to v for every pair of
+    // distinct vertices such that v can be reached from u
+    pub fn to_graph(&self) -> Graph {
+        let mut edges = HashSet::new();
+        for (i, u) in self.vertices.iter().enumerate() {
+            for (j, v) in self.vertices.iter().enumerate() {
+                if i != j && self.reachable[self.component[i]][j / 64] & (1 << (j % 64)) != 0 {
+                    edges.insert((*u, *v));
+                }
+            }
+        }
+        Graph::new(self.vertices.iter().cloned().collect(), edges, true)
+    }
+}
+
+// an index answering reachability queries on a static graph without materializing the
+// transitive closure: the graph is condensed into the DAG of its strongly connected
+// components, whose nodes carry interval labels from several randomized depth first
+// traversals (GRAIL): if v is reachable from u, each of v's intervals is contained in u's
+// interval, so most negative queries are answered by the labels alone, and positive queries
+// within a traversal subtree are answered by the first traversal's tree intervals; all other
+// queries fall back to a depth first search pruned by the labels
+pub struct ReachabilityIndex {
+    component: HashMap<Vertex, usize>,
+    successors: Vec<Vec<usize>>,
+    labels: Vec<Vec<Interval>>,
+    tree: Vec<Interval>
+}
+
+impl ReachabilityIndex {
+
+    fn new(graph: &Graph) -> ReachabilityIndex {
+        let (component, successors) = condensation(graph);
+        let mut random = Random::new(0);
+        let mut labels = vec![];
+        let mut tree = vec![];
+        for labeling in 0..LABELINGS {
+            let (intervals, subtrees) = label(&successors, if labeling == 0 { None } else { Some(&mut random) });
+            labels.push(intervals);
+            if labeling == 0 {
+                tree = subtrees;
+            }
+        }
+        ReachabilityIndex { component, successors, labels, tree }
+    }
+
+    // returns true if vertex v can be reached from vertex u; every vertex reaches itself
+    pub fn reachable(&self, u: Vertex, v: Vertex) -> bool {
+        let (cu, cv) = match (self.component.get(&u), self.component.get(&v)) {
+            (Some(cu), Some(cv)) => (*cu, *cv),
+            _ => return false
+        };
+        if cu == cv || contains(self.tree[cu], self.tree[cv]) {
+            return true;
+        }
+        if !self.may_reach(cu, cv) {
+            return false;
+        }
+
+        let mut visited = HashSet::new();
+        let mut stack = vec![cu];
+        visited.insert(cu);
+        while let Some(c) = stack.pop() {
+            for d in &self.successors[c] {
+                if *d == cv {
+                    return true;
+                }
+                if !visited.contains(d) && self.may_reach(*d, cv) {
+                    visited.insert(*d);
+                    stack.push(*d);
+                }
+            }
+        }
+        false
+    }
+
+    // false if the labels prove that component d is not reachable from component c
+    fn may_reach(&self, c: usize, d: usize) -> bool {
+        self.labels.iter().all(|intervals| contains(intervals[c], intervals[d]))
+    }
+}
+
+// computes the interval labels of a depth first traversal of the condensation, visiting
+// roots and successors in random order, or in ascending order if no generator is given:
+// each node is labeled with its postorder rank and the smallest rank of anything it reaches,
+// and with the preorder and postorder numbers delimiting its subtree in the traversal forest
+fn label(successors: &[Vec<usize>], mut random: Option<&mut Random>) -> (Vec<Interval>, Vec<Interval>) {
+    struct State {
+        order: Vec<Vec<usize>>,
+        intervals: Vec<Option<Interval>>,
+        subtrees: Vec<Interval>,
+        clock: usize,
+        rank: usize
+    }
+
+    fn open(c: usize, state: &mut State) {
+        state.subtrees[c].0 = state.clock;
+        state.clock += 1;
+        state.intervals[c] = Some((usize::MAX, 0));
+    }
+
+    // the traversal keeps its own stack of the nodes being visited with the position of the
+    // next successor to look at and the smallest rank reached so far, so that long paths
+    // cannot overflow the call stack
+    fn visit(root: usize, state: &mut State) {
+        open(root, state);
+        let mut calls = vec![(root, 0, usize::MAX)];
+        while let Some(&mut (c, ref mut next, ref mut low)) = calls.last_mut() {
+            if let Some(&d) = state.order[c].get(*next) {
+                *next += 1;
+                match state.intervals[d] {
+                    None => {
+                        open(d, state);
+                        calls.push((d, 0, usize::MAX));
+                    },
+                    Some(interval) => *low = (*low).min(interval.0)
+                }
+                continue;
+            }
+            let low = (*low).min(state.rank);
+            calls.pop();
+            state.intervals[c] = Some((low, state.rank));
+            state.rank += 1;
+            state.subtrees[c].1 = state.clock;
+            state.clock += 1;
+            if let Some(parent) = calls.last_mut() {
+                parent.2 = parent.2.min(low);
+            }
+        }
+    }
+
+    let n = successors.len();
+    let mut order = successors.to_vec();
+    let mut roots: Vec<usize> = (0..n).collect();
+    if let Some(ref mut random) = random {
+        for next in order.iter_mut() {
+            random.shuffle(next);
+        }
+        random.shuffle(&mut roots);
+    }
+    let mut state = State { order, intervals: vec![None; n], subtrees: vec![(0, 0); n], clock: 0, rank: 0 };
+    for c in roots {
+        if state.intervals[c].is_none() {
+            visit(c, &mut state);
+        }
+    }
+    (state.intervals.into_iter().map(|interval| interval.unwrap()).collect(), state.subtrees)
+}
+
+fn contains(outer: Interval, inner: Interval) -> bool {
+    outer.0 <= inner.0 && inner.1 <= outer.1
+}
+
+// an index answering reachability queries with a chain decomposition (Jagadish): the DAG
+// of the strongly connected components is covered by disjoint chains, on each of which
+// every node reaches the next, and every node stores for every chain it reaches the
+// closest position on it that it reaches, as it then reaches the rest of that chain too;
+// v can be reached from u exactly if u reaches the chain of v at v or before. Unlike
+// ReachabilityIndex queries take no search, and unlike the transitive closure the index
+// takes space proportional to the number of vertices times the number of chains each
+// reaches, which stays small for DAGs without many mutually unreachable vertices; the
+// chains are found greedily, so there may be more than the minimum, the width of the DAG
+pub struct ChainIndex {
+    component: HashMap<Vertex, usize>,
+    // the chain of every component and its rank on it, counting from the end of the chain
+    chain: Vec<(u32, u32)>,
+    // the chains reached by every component with the highest rank reached, by chain
+    reach: Vec<Vec<(u32, u32)>>,
+    chains: usize
+}
+
+impl ChainIndex {
+
+    fn new(graph: &Graph) -> ChainIndex {
+        let (component, successors) = condensation(graph);
+        let n = successors.len();
+        let mut chain = vec![(0, 0); n];
+        let mut reach: Vec<Vec<(u32, u32)>> = Vec::with_capacity(n);
+        // the rank of the first node of every chain
+        let mut heads: Vec<u32> = vec![];
+        // the components come in reverse topological order, so everything a component
+        // reaches has been indexed before it
+        for c in 0..n {
+            let mut reached: Vec<(u32, u32)> = successors[c].iter().flat_map(|d| reach[*d].iter().cloned()).collect();
+            reached.sort_unstable_by_key(|&(chain, rank)| (chain, std::cmp::Reverse(rank)));
+            reached.dedup_by_key(|entry| entry.0);
+            // the component goes in front of a chain whose first node it reaches, or starts
+            // a new chain
+            match reached.iter_mut().find(|entry| heads[entry.0 as usize] == entry.1) {
+                Some(entry) => {
+                    entry.1 += 1;
+                    heads[entry.0 as usize] += 1;
+                    chain[c] = *entry;
+                },
+                None => {
+                    chain[c] = (heads.len() as u32, 0);
+                    reached.push(chain[c]);
+                    heads.push(0);
+                }
+            }
+            reached.shrink_to_fit();
+            reach.push(reached);
+        }
+        ChainIndex { component, chain, reach, chains: heads.len() }
+    }
+
+    // returns true if vertex v can be reached from vertex u; every vertex reaches itself
+    pub fn reachable(&self, u: Vertex, v: Vertex) -> bool {
+        let (cu, cv) = match (self.component.get(&u), self.component.get(&v)) {
+            (Some(cu), Some(cv)) => (*cu, *cv),
+            _ => return false
+        };
+        let (chain, rank) = self.chain[cv];
+        match self.reach[cu].binary_search_by_key(&chain, |entry| entry.0) {
+            Ok(i) => self.reach[cu][i].1 >= rank,
+            Err(_) => false
+        }
+    }
+
+    // the number of chains the components were covered with
+    pub fn chains(&self) -> usize {
+        self.chains
+    }
+
+    // the number of (chain, rank) pairs stored, which dominates the size of the index
+    pub fn entries(&self) -> usize {
+        self.reach.iter().map(|reached| reached.len()).sum()
+    }
+}
+
+// the component of every vertex and the successors of every strongly connected component,
+// which come in reverse topological order: successors have smaller numbers
+fn condensation(graph: &Graph) -> (HashMap<Vertex, usize>, Vec<Vec<usize>>) {
+    let components = graph.strongly_connected_components();
+    let mut component = HashMap::new();
+    for (c, members) in components.iter().enumerate() {
+        for v in members {
+            component.insert(*v, c);
+        }
+    }
+    let adj = graph.adjacencies();
+    let successors: Vec<Vec<usize>> = components.iter().enumerate()
+        .map(|(c, members)| {
+            let mut next: Vec<usize> = members.iter()
+                .flat_map(|v| adj[v].iter().map(|w| component[w]))
+                .filter(|d| *d != c)
+                .collect();
+            next.sort();
+            next.dedup();
+            next
+        })
+        .collect();
+    (component, successors)
+}
+
+impl Graph {
+
+    // builds an index for answering many reachability queries on this graph quickly,
+    // using far less memory than the full transitive closure
+    pub fn reachability_index(&self) -> ReachabilityIndex {
+        ReachabilityIndex::new(self)
+    }
+
+    // builds a chain decomposition index for answering reachability queries without any
+    // search, which suits large DAGs, see ChainIndex
+    pub fn chain_index(&self) -> ChainIndex {
+        ChainIndex::new(self)
+    }
+
+    // computes the transitive closure of the graph: all vertices of a strongly connected
+    // component reach the same vertices, namely the component itself plus everything
+    // reached by its successors, so the components are processed in reverse topological order
+    pub fn transitive_closure(&self) -> TransitiveClosure {
+        match self.transitive_closure_resumable(None, |_, _| Ok::<(), Infallible>(())) {
+            Ok(closure) => closure,
+            Err(never) => match never {}
+        }
+    }
+
+    // computes the transitive closure like transitive_closure, saving the reachable vertices
+    // of the components processed so far to the checkpoint, and resuming from the components
+    // saved by an earlier run on the same graph that did not get to finish
+    pub fn transitive_closure_checkpointed(&self, checkpoint: &Checkpoint) -> io::Result<TransitiveClosure> {
+        let (run, saved) = checkpoint.start(Stage::TransitiveClosure, self)?;
+        let resumed = match saved {
+            Some(mut state) => {
+                let n = self.vertices().len();
+                let component = (0..n).map(|_| state.take_index(n)).collect::<io::Result<Vec<usize>>>()?;
+                let mut reachable = vec![];
+                for _ in 0..state.take_index(n + 1)? {
+                    reachable.push((0..n.div_ceil(64)).map(|_| state.take()).collect::<io::Result<Vec<u64>>>()?);
+                }
+                Some((component, reachable))
+            },
+            None => None
+        };
+        let closure = self.transitive_closure_resumable(resumed, |component, reachable| {
+            if !run.is_due(reachable.len()) {
+                return Ok(());
+            }
+            let mut state = State::new();
+            for c in component {
+                state.put_index(*c);
+            }
+            state.put_index(reachable.len());
+            for word in reachable.iter().flatten() {
+                state.put(*word);
+            }
+            run.save(&state)
+        })?;
+        run.finish().map(|_| closure)
+    }
+
+    // computes the transitive closure, resuming from the component of every vertex and the
+    // reachable vertices of the first components if given, and passing them to save after
+    // every component, which stops the computation by failing
+    fn transitive_closure_resumable<E, F>(&self, resumed: Option<(Vec<usize>, Vec<Vec<u64>>)>, mut save: F) -> Result<TransitiveClosure, E>
+        where F: FnMut(&[usize], &[Vec<u64>]) -> Result<(), E> {
+        let (vertices, index) = self.indexed_vertices();
+        let (component, mut reachable) = resumed.unwrap_or_else(|| {
+            let mut component = vec![0; vertices.len()];
+            for (c, members) in self.strongly_connected_components().iter().enumerate() {
+                for v in members {
+                    component[index[v]] = c;
+                }
+            }
+            (component, vec![])
+        });
+        let mut components = vec![vec![]; component.iter().map(|c| c + 1).max().unwrap_or(0)];
+        for (v, c) in vertices.iter().zip(&component) {
+            components[*c].push(*v);
+        }
+
+        let adj = self.adjacencies();
+        let words = vertices.len().div_ceil(64);
+        for (c, members) in components.iter().enumerate().skip(reachable.len()) {
+            let mut bits = vec![0u64; words];
+            for v in members {
+                let i = index[v];
+                bits[i / 64] |= 1 << (i % 64);
+                for w in &adj[v] {
+                    let d = component[index[w]];
+                    if d != c {
+                        for (word, other) in bits.iter_mut().zip(&reachable[d]) {
+                            *word |= other;
+                        }
+                    }
+                }
+            }
+            reachable.push(bits);
+            if reachable.len() < components.len() {
+                save(&component, &reachable)?;
+            }
+        }
+        Ok(TransitiveClosure { vertices, index, component, reachable })
+    }
+
+    // computes the transitive reduction of a directed acyclic graph, i.e. the graph with the
+    // fewest edges that has the same reachability; an edge u -> v is redundant if v can also
+    // be reached through another successor of u
+    // returns None if the graph contains a cycle, as its reduction would not be unique
+    pub fn transitive_reduction(&self) -> Option<Graph> {
+        self.topological_order().ok()?;
+        let closure = self.transitive_closure();
+        let adj = self.adjacencies();
+        let edges = self.edges().iter()
+            .filter(|&&(u, v)| u != v && !adj[&u].iter().any(|w| *w != v && closure.reaches(*w, v)))
+            .cloned()
+            .collect();
+        Some(Graph::new(self.vertices().clone(), edges, true))
+    }
+}
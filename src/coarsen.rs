@@ -0,0 +1,129 @@
+use graph::{Edge, Graph, HashMap, HashSet, Vertex, Weight};
+use random::Random;
+
+// coarsening stops once a level removes less than this fraction of the vertices, as on
+// graphs like stars most vertices find no partner left to be matched with
+const MIN_REDUCTION: f64 = 0.1;
+
+// one level of a coarsening hierarchy: the coarser graph, in which every vertex stands for
+// one or two vertices of the finer graph and keeps the smaller of their numbers, and for
+// every vertex of the finer graph the vertex of the coarser one it was merged into
+pub struct CoarseLevel {
+    pub graph: Graph,
+    pub projection: HashMap<Vertex, Vertex>,
+    // the number of original vertices every coarse vertex stands for, which partitioning
+    // needs to balance the parts
+    pub vertex_weights: HashMap<Vertex, Weight>
+}
+
+// the levels of a multilevel coarsening, from the one coarsening the original graph to the
+// coarsest one
+pub struct Hierarchy {
+    pub levels: Vec<CoarseLevel>
+}
+
+impl Hierarchy {
+
+    // the coarsest graph, or None if the original graph was small enough already
+    pub fn coarsest(&self) -> Option<&Graph> {
+        self.levels.last().map(|level| &level.graph)
+    }
+
+    // the vertex of the coarsest graph that an original vertex ended up in
+    pub fn project(&self, v: Vertex) -> Vertex {
+        self.levels.iter().fold(v, |v, level| level.projection[&v])
+    }
+
+    // carries values computed for the vertices of the coarsest graph, such as the parts of
+    // a partition, the communities or the positions of a layout, back to the original
+    // vertices, each of which gets the value of the coarse vertex it ended up in; refining
+    // the result on the finer levels is left to the caller
+    pub fn prolong<T: Clone>(&self, coarse: &HashMap<Vertex, T>) -> HashMap<Vertex, T> {
+        let mut values = coarse.clone();
+        for level in self.levels.iter().rev() {
+            values = level.projection.iter().map(|(v, c)| (*v, values[c].clone())).collect();
+        }
+        values
+    }
+}
+
+impl Graph {
+
+    // coarsens the graph by heavy edge matching until it has at most min_vertices vertices
+    // or a level hardly shrinks it any more: every level visits the vertices in a random
+    // order derived from the seed and matches each one not matched yet with the neighbour
+    // not matched yet that it shares the heaviest edge with, then contracts the matched
+    // pairs; edges between merged vertices add up their weights, so the heavy edges that
+    // are best kept inside parts or communities disappear first. Edge directions are
+    // ignored and the coarse graphs are undirected
+    pub fn coarsen(&self, min_vertices: usize, seed: u64) -> Hierarchy {
+        let mut random = Random::new(seed);
+        let mut levels: Vec<CoarseLevel> = vec![];
+        loop {
+            let (graph, weights) = match levels.last() {
+                Some(level) => (&level.graph, level.vertex_weights.clone()),
+                None => (self, self.vertices().iter().map(|v| (*v, 1.0)).collect())
+            };
+            let n = graph.vertices().len();
+            if n <= min_vertices {
+                break;
+            }
+            let level = graph.heavy_edge_contraction(&weights, &mut random);
+            if (n - level.graph.vertices().len()) as f64 <= MIN_REDUCTION * n as f64 {
+                break;
+            }
+            levels.push(level);
+        }
+        Hierarchy { levels }
+    }
+
+    // contracts a heavy edge matching, see coarsen
+    fn heavy_edge_contraction(&self, vertex_weights: &HashMap<Vertex, Weight>, random: &mut Random) -> CoarseLevel {
+        let mut adj: HashMap<Vertex, HashMap<Vertex, Weight>> = self.vertices().iter().map(|v| (*v, HashMap::new())).collect();
+        for edge in self.edges().iter().filter(|&&(v, w)| v != w) {
+            let weight = self.weight(edge);
+            *adj.get_mut(&edge.0).unwrap().entry(edge.1).or_insert(0.0) += weight;
+            *adj.get_mut(&edge.1).unwrap().entry(edge.0).or_insert(0.0) += weight;
+        }
+
+        let mut order = self.sorted_vertices();
+        random.shuffle(&mut order);
+        let mut projection = HashMap::new();
+        for v in order {
+            if projection.contains_key(&v) {
+                continue;
+            }
+            // the heaviest edge, preferring lighter partners and then smaller ones so that
+            // merged vertices stay balanced and the choice is deterministic
+            let partner = adj[&v].iter()
+                .filter(|(w, _)| !projection.contains_key(*w))
+                .max_by(|a, b| a.1.partial_cmp(b.1).unwrap()
+                    .then(vertex_weights[b.0].partial_cmp(&vertex_weights[a.0]).unwrap())
+                    .then(b.0.cmp(a.0)))
+                .map(|(w, _)| *w);
+            let coarse = partner.map_or(v, |w| v.min(w));
+            projection.insert(v, coarse);
+            if let Some(w) = partner {
+                projection.insert(w, coarse);
+            }
+        }
+
+        let vertices: HashSet<Vertex> = projection.values().cloned().collect();
+        let mut weights: HashMap<Edge, Weight> = HashMap::new();
+        for (v, adjacent) in &adj {
+            for (w, weight) in adjacent {
+                let (a, b) = (projection[v], projection[w]);
+                // every edge is seen from both ends
+                if a < b {
+                    *weights.entry((a, b)).or_insert(0.0) += weight;
+                }
+            }
+        }
+        let mut coarse_weights: HashMap<Vertex, Weight> = HashMap::new();
+        for (v, c) in &projection {
+            *coarse_weights.entry(*c).or_insert(0.0) += vertex_weights[v];
+        }
+        let edges = weights.keys().cloned().collect();
+        CoarseLevel { graph: Graph::with_weights(vertices, edges, weights, false), projection, vertex_weights: coarse_weights }
+    }
+}
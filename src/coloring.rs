@@ -0,0 +1,332 @@
+use std::cmp::Reverse;
+use std::collections::BTreeSet;
+
+use graph::{Graph, HashMap, HashSet, Vertex};
+
+const NONE: usize = usize::MAX;
+
+// a coloring found by a heuristic together with lower bounds on the chromatic number, so
+// that the gap between its colors and the best bound tells how far from optimal it can be
+pub struct ColoringBounds {
+    pub coloring: HashMap<Vertex, usize>,
+    pub colors: usize,
+    // a clique found greedily, which needs as many colors, in ascending order
+    pub clique: Vec<Vertex>,
+    // whether the ends of some edge got the same color in a breadth first 2-coloring, which
+    // means there is an odd cycle, needing three colors
+    pub odd_cycle: bool,
+    // the colors needed for a component of n vertices covered by k cliques: an independent
+    // set meets every clique at most once, so no color can be used more than k times and
+    // at least n / k colors are needed, a bound that is good for dense graphs where cliques
+    // are small
+    pub cover_bound: usize,
+    // the best of the bounds above
+    pub lower_bound: usize,
+    // the degeneracy, below whose value plus one a smallest-last coloring always stays
+    pub degeneracy: usize
+}
+
+impl ColoringBounds {
+
+    // by how many colors the coloring may exceed the fewest possible at most
+    pub fn gap(&self) -> usize {
+        self.colors - self.lower_bound
+    }
+}
+
+impl Graph {
+
+    // the bipartite double cover of the graph, its tensor product with a single edge: every
+    // vertex v becomes the two vertices v and v + n, n the largest vertex number, and every
+    // edge v - w the two edges v - w + n and v + n - w, keeping its weight and direction.
+    // The cover is bipartite, and a connected graph has a connected cover exactly if it has
+    // an odd cycle, which then lifts to a cycle of twice its length
+    pub fn bipartite_double_cover(&self) -> Graph {
+        let n = self.vertices().iter().cloned().max().unwrap_or(0);
+        let vertices = self.vertices().iter().flat_map(|v| [*v, v.checked_add(n).expect("No vertex numbers left!")]).collect();
+        let mut cover = Graph::new(vertices, Default::default(), self.is_directed());
+        let mut edges: Vec<_> = self.edges().iter().cloned().collect();
+        edges.sort();
+        for (v, w) in edges {
+            let weight = self.weight(&(v, w));
+            for (a, b) in [(v, w + n), (v + n, w)] {
+                if self.is_weighted() {
+                    cover.add_weighted_edge(a, b, weight);
+                } else {
+                    cover.add_edge(a, b);
+                }
+            }
+        }
+        cover
+    }
+
+    // returns true if the vertices can be split into k parts without an edge inside a part,
+    // see k_coloring; edge directions are ignored
+    pub fn is_k_partite(&self, k: usize) -> bool {
+        self.k_coloring(k).is_some()
+    }
+
+    // colors the vertices greedily in the given order, which must list every vertex, giving
+    // each the smallest color none of its neighbours before it has, ignoring edge directions
+    // and self-loops
+    pub fn greedy_coloring(&self, order: &[Vertex]) -> HashMap<Vertex, usize> {
+        let adj = self.undirected_adjacencies();
+        let mut coloring: HashMap<Vertex, usize> = HashMap::new();
+        for v in order {
+            let used: HashSet<usize> = adj[v].iter().filter_map(|w| coloring.get(w).cloned()).collect();
+            coloring.insert(*v, (0..).find(|c| !used.contains(c)).unwrap());
+        }
+        coloring
+    }
+
+    // colors the graph greedily along smallest_last_order, with at most degeneracy + 1
+    // colors, which is optimal for forests, planar graphs often come out with few, and
+    // reports the lower bounds of color_bounds
+    pub fn smallest_last_coloring(&self) -> ColoringBounds {
+        self.color_bounds(self.greedy_coloring(&self.smallest_last_order()))
+    }
+
+    // colors the graph with the DSATUR heuristic of Brelaz, without backtracking: the vertex
+    // with the most distinct colors among its neighbours, then with the highest degree and
+    // then the smallest one, gets the smallest color none of them has, which colors
+    // bipartite graphs with two colors; edge directions and self-loops are ignored.
+    // Reports the lower bounds of color_bounds
+    pub fn dsatur_coloring(&self) -> ColoringBounds {
+        let (vertices, adj) = self.simple_undirected();
+        let n = vertices.len();
+        let mut color = vec![NONE; n];
+        let mut neighbour_colors: Vec<HashSet<usize>> = vec![HashSet::new(); n];
+        let key = |v: usize, neighbour_colors: &[HashSet<usize>]| (neighbour_colors[v].len(), adj[v].len(), Reverse(v));
+        let mut queue: BTreeSet<(usize, usize, Reverse<usize>)> = (0..n).map(|v| key(v, &neighbour_colors)).collect();
+        while let Some((_, _, Reverse(v))) = queue.pop_last() {
+            color[v] = (0..).find(|c| !neighbour_colors[v].contains(c)).unwrap();
+            for &w in &adj[v] {
+                if color[w] == NONE && !neighbour_colors[w].contains(&color[v]) {
+                    queue.remove(&key(w, &neighbour_colors));
+                    neighbour_colors[w].insert(color[v]);
+                    queue.insert(key(w, &neighbour_colors));
+                }
+            }
+        }
+        self.color_bounds(vertices.into_iter().zip(color).collect())
+    }
+
+    // lower bounds on the number of colors any coloring of the graph needs, reported with
+    // the given coloring, ignoring edge directions and self-loops: the size of a clique grown
+    // from every vertex greedily among its neighbours after it in smallest_last_order, three
+    // if there is an odd cycle, and the bound of a cover of every component by cliques,
+    // each vertex joining the first clique of a neighbour it is adjacent to all of, or a
+    // new one. These are cheap, not tight; the Lovasz number of the complement, which also
+    // lies between the clique number and the chromatic number, would take semidefinite
+    // programming
+    pub fn color_bounds(&self, coloring: HashMap<Vertex, usize>) -> ColoringBounds {
+        let (vertices, adj) = self.simple_undirected();
+        let n = vertices.len();
+        let index = self.indexed_vertices().1;
+        let order: Vec<usize> = self.smallest_last_order().iter().map(|v| index[v]).collect();
+        let mut position = vec![0; n];
+        for (i, v) in order.iter().enumerate() {
+            position[*v] = i;
+        }
+        let adjacent = |v: usize, w: usize| adj[v].binary_search(&w).is_ok();
+
+        let mut best: Vec<usize> = vec![];
+        for &v in &order {
+            let mut clique = vec![v];
+            for &w in adj[v].iter().filter(|w| position[**w] > position[v]) {
+                if clique.iter().all(|u| adjacent(*u, w)) {
+                    clique.push(w);
+                }
+            }
+            if clique.len() > best.len() {
+                best = clique;
+            }
+        }
+        let mut clique: Vec<Vertex> = best.into_iter().map(|v| vertices[v]).collect();
+        clique.sort();
+
+        // a breadth first 2-coloring of every component, along with the cliques covering it
+        let mut side = vec![NONE; n];
+        let mut odd_cycle = false;
+        let mut cover_bound = 0;
+        let mut member = vec![NONE; n];
+        let mut cliques: Vec<Vec<usize>> = vec![];
+        for root in 0..n {
+            if side[root] != NONE {
+                continue;
+            }
+            side[root] = 0;
+            let mut component = vec![root];
+            let mut next = 0;
+            while next < component.len() {
+                let v = component[next];
+                for &w in &adj[v] {
+                    if side[w] == NONE {
+                        side[w] = 1 - side[v];
+                        component.push(w);
+                    } else if side[w] == side[v] {
+                        odd_cycle = true;
+                    }
+                }
+                next += 1;
+            }
+            let first = cliques.len();
+            component.sort_by_key(|v| (Reverse(adj[*v].len()), *v));
+            for &v in &component {
+                let joined = adj[v].iter()
+                    .map(|w| member[*w])
+                    .filter(|c| *c != NONE)
+                    .find(|c| cliques[*c].iter().all(|u| adjacent(*u, v)));
+                let c = joined.unwrap_or_else(|| {
+                    cliques.push(vec![]);
+                    cliques.len() - 1
+                });
+                cliques[c].push(v);
+                member[v] = c;
+            }
+            cover_bound = cover_bound.max(component.len().div_ceil(cliques.len() - first));
+        }
+
+        let colors = coloring.values().map(|c| c + 1).max().unwrap_or(0);
+        let lower_bound = clique.len().max(if odd_cycle { 3 } else { 0 }).max(cover_bound);
+        ColoringBounds { coloring, colors, clique, odd_cycle, cover_bound, lower_bound, degeneracy: self.degeneracy() }
+    }
+
+    // colors the vertices with the colors 0..k so that the ends of every edge differ,
+    // ignoring edge directions, or returns None if that is impossible, e.g. because of a
+    // self-loop. Every connected component is colored by backtracking, always coloring the
+    // vertex with the most distinct colors among its neighbours next, as in DSATUR, and
+    // never trying a color before the smaller ones that are still unused; this settles two
+    // colors in linear time, but more colors may take exponential time
+    pub fn k_coloring(&self, k: usize) -> Option<HashMap<Vertex, usize>> {
+        if self.edges().iter().any(|edge| edge.0 == edge.1) {
+            return None;
+        }
+        let (vertices, adj) = self.dense_undirected();
+        // more colors than vertices are never used
+        let k = k.min(vertices.len());
+        let mut search = Coloring {
+            adj: &adj,
+            k,
+            color: vec![NONE; vertices.len()],
+            counts: vec![vec![0; k]; vertices.len()],
+            saturation: vec![0; vertices.len()],
+            queue: BTreeSet::new()
+        };
+        for root in 0..vertices.len() {
+            if search.color[root] == NONE && !search.color_component(root) {
+                return None;
+            }
+        }
+        Some(vertices.into_iter().zip(search.color).collect())
+    }
+}
+
+// the state of the backtracking search of k_coloring
+struct Coloring<'a> {
+    adj: &'a [Vec<usize>],
+    k: usize,
+    color: Vec<usize>,
+    // the number of neighbours of every vertex with every color, and how many of these
+    // numbers are not zero
+    counts: Vec<Vec<u32>>,
+    saturation: Vec<usize>,
+    // the vertices of the component waiting for a color, the most saturated one last
+    queue: BTreeSet<(usize, usize, Reverse<usize>)>
+}
+
+impl<'a> Coloring<'a> {
+
+    fn key(&self, v: usize) -> (usize, usize, Reverse<usize>) {
+        (self.saturation[v], self.adj[v].len(), Reverse(v))
+    }
+
+    // colors the component of the root, returning false if it is not k-colorable; the
+    // search is iterative, keeping every choice made with the number of colors used before
+    // it on a stack
+    fn color_component(&mut self, root: usize) -> bool {
+        let mut component = vec![root];
+        self.color[root] = 0;
+        let mut next = 0;
+        while next < component.len() {
+            for &w in &self.adj[component[next]] {
+                if self.color[w] == NONE {
+                    self.color[w] = 0;
+                    component.push(w);
+                }
+            }
+            next += 1;
+        }
+        for &v in &component {
+            self.color[v] = NONE;
+            self.queue.insert(self.key(v));
+        }
+
+        let mut stack: Vec<(usize, usize, usize)> = vec![];
+        let mut used = 0;
+        // the vertex being colored and the smallest color left to try for it
+        let mut current: Option<(usize, usize)> = None;
+        loop {
+            let (v, start) = match current {
+                Some(choice) => choice,
+                None => match self.queue.pop_last() {
+                    Some((_, _, Reverse(v))) => (v, 0),
+                    None => return true
+                }
+            };
+            match (start..self.k.min(used + 1)).find(|c| self.counts[v][*c] == 0) {
+                Some(c) => {
+                    self.assign(v, c);
+                    stack.push((v, c, used));
+                    used = used.max(c + 1);
+                    current = None;
+                },
+                None => {
+                    self.queue.insert(self.key(v));
+                    match stack.pop() {
+                        Some((u, c, previous)) => {
+                            self.unassign(u, c);
+                            used = previous;
+                            current = Some((u, c + 1));
+                        },
+                        None => return false
+                    }
+                }
+            }
+        }
+    }
+
+    fn assign(&mut self, v: usize, c: usize) {
+        self.color[v] = c;
+        for &w in self.adj[v].iter() {
+            self.count(w, c, true);
+        }
+    }
+
+    fn unassign(&mut self, v: usize, c: usize) {
+        self.color[v] = NONE;
+        for &w in self.adj[v].iter() {
+            self.count(w, c, false);
+        }
+    }
+
+    // counts a neighbour of w getting or losing the color c, moving w in the queue if it is
+    // waiting for a color and its saturation changes
+    fn count(&mut self, w: usize, c: usize, add: bool) {
+        let before = self.key(w);
+        if add {
+            self.counts[w][c] += 1;
+            if self.counts[w][c] == 1 {
+                self.saturation[w] += 1;
+            }
+        } else {
+            self.counts[w][c] -= 1;
+            if self.counts[w][c] == 0 {
+                self.saturation[w] -= 1;
+            }
+        }
+        if before != self.key(w) && self.queue.remove(&before) {
+            self.queue.insert(self.key(w));
+        }
+    }
+}
@@ -0,0 +1,463 @@
+use std::collections::BTreeMap;
+
+use graph::hash_map::Entry;
+use graph::{Edge, Graph, HashMap, HashSet, Vertex};
+use progress::{completed, Cancellation, Interrupted, Progress};
+use random::Random;
+
+// a community is a set of densely connected vertices
+pub type Community = HashSet<Vertex>;
+
+impl Graph {
+
+    // detects communities using asynchronous label propagation: every vertex starts
+    // out with its own label and then repeatedly adopts the label carried by most of
+    // its neighbours, until every vertex carries one of its neighbourhood's most frequent
+    // labels or max_iterations passes over the vertices are done, as labels may keep
+    // oscillating; vertices are visited in random order and ties are broken randomly
+    pub fn communities_label_propagation(&self, seed: u64, max_iterations: usize) -> Vec<Community> {
+        self.communities_label_propagation_with_progress(seed, max_iterations, |_| ())
+    }
+
+    // detects communities like communities_label_propagation, reporting the progress after
+    // every pass over the vertices with the number of vertices that changed their label in
+    // it as the active ones
+    pub fn communities_label_propagation_with_progress<F: FnMut(&Progress)>(&self, seed: u64, max_iterations: usize, mut on_progress: F) -> Vec<Community> {
+        completed(self.label_propagation_controlled(seed, max_iterations, |progress| { on_progress(progress); true }))
+    }
+
+    // detects communities like communities_label_propagation unless cancelled, which stops
+    // it after the current pass with the communities of the labels so far as the partial
+    // result
+    pub fn communities_label_propagation_cancellable(&self, seed: u64, max_iterations: usize, cancellation: &Cancellation) -> Result<Vec<Community>, Interrupted<Vec<Community>>> {
+        self.label_propagation_controlled(seed, max_iterations, |_| !cancellation.is_cancelled())
+    }
+
+    // detects communities by label propagation, passing the progress after every pass to
+    // go_on, which tells whether to carry on
+    fn label_propagation_controlled<F: FnMut(&Progress) -> bool>(&self, seed: u64, max_iterations: usize, mut go_on: F) -> Result<Vec<Community>, Interrupted<Vec<Community>>> {
+        let adj = self.adjacencies();
+        let mut random = Random::new(seed);
+        let mut order: Vec<Vertex> = self.vertices().iter().cloned().collect();
+        order.sort();
+        let mut labels: HashMap<Vertex, Vertex> = order.iter().map(|v| (*v, *v)).collect();
+
+        let mut done = false;
+        let mut pass = 0;
+        while !done && pass < max_iterations {
+            done = true;
+            pass += 1;
+            let mut changed = 0;
+            random.shuffle(&mut order);
+            for v in &order {
+                let mut counts: HashMap<Vertex, usize> = HashMap::new();
+                for w in adj[v].iter().filter(|w| *w != v) {
+                    *counts.entry(labels[w]).or_insert(0) += 1;
+                }
+                let max = match counts.values().max() {
+                    Some(max) => *max,
+                    None => continue
+                };
+                let mut best: Vec<Vertex> = counts.iter()
+                    .filter(|&(_, count)| *count == max)
+                    .map(|(label, _)| *label)
+                    .collect();
+                if best.contains(&labels[v]) {
+                    continue;
+                }
+                best.sort();
+                labels.insert(*v, best[random.below(best.len())]);
+                done = false;
+                changed += 1;
+            }
+            let progress = Progress { round: pass, rounds: None, processed: order.len(), total: order.len(), active: changed };
+            if !go_on(&progress) && !done && pass < max_iterations {
+                return Err(Interrupted { progress, partial: Some(label_communities(labels)) });
+            }
+        }
+        Ok(label_communities(labels))
+    }
+
+    // detects communities using the Louvain method: vertices are greedily moved between
+    // communities as long as that increases the modularity of the partition, then each
+    // community is aggregated into a single vertex and the process repeats on the
+    // aggregated graph until no further improvement is possible;
+    // edge directions are ignored and larger resolutions favour smaller communities
+    pub fn communities_louvain(&self, resolution: f64) -> Vec<Community> {
+        self.communities_louvain_with_progress(resolution, |_| ())
+    }
+
+    // detects communities like communities_louvain, reporting the progress after the moves
+    // on every level with the number of communities the level was reduced to as the active
+    // nodes, or none once no move improved the modularity
+    pub fn communities_louvain_with_progress<F: FnMut(&Progress)>(&self, resolution: f64, mut on_progress: F) -> Vec<Community> {
+        completed(self.louvain_controlled(resolution, |progress| { on_progress(progress); true }))
+    }
+
+    // detects communities like communities_louvain unless cancelled, which stops it after
+    // the moves on the current level with the communities found so far as the partial result
+    pub fn communities_louvain_cancellable(&self, resolution: f64, cancellation: &Cancellation) -> Result<Vec<Community>, Interrupted<Vec<Community>>> {
+        self.louvain_controlled(resolution, |_| !cancellation.is_cancelled())
+    }
+
+    // detects communities by the Louvain method, passing the progress after every level to
+    // go_on, which tells whether to carry on
+    fn louvain_controlled<F: FnMut(&Progress) -> bool>(&self, resolution: f64, mut go_on: F) -> Result<Vec<Community>, Interrupted<Vec<Community>>> {
+        let mut vertices: Vec<Vertex> = self.vertices().iter().cloned().collect();
+        vertices.sort();
+        let index: HashMap<Vertex, usize> = vertices.iter().enumerate().map(|(i, v)| (*v, i)).collect();
+        let mut level = Level::new(vertices.len());
+        for edge in self.edges() {
+            level.add(index[&edge.0], index[&edge.1], self.weight(edge));
+        }
+
+        // membership maps each vertex onto the node of the current level containing it
+        let mut membership: Vec<usize> = (0..vertices.len()).collect();
+        let mut round = 0;
+        loop {
+            let (community, count) = level.move_nodes(resolution);
+            let nodes = level.adj.len();
+            round += 1;
+            let progress = Progress { round, rounds: None, processed: nodes, total: nodes, active: if count == nodes { 0 } else { count } };
+            if count == nodes {
+                go_on(&progress);
+                break;
+            }
+            for node in membership.iter_mut() {
+                *node = community[*node];
+            }
+            if !go_on(&progress) {
+                return Err(Interrupted { progress, partial: Some(level_communities(&vertices, &membership, count)) });
+            }
+            level = level.aggregate(&community, count);
+        }
+        Ok(level_communities(&vertices, &membership, level.adj.len()))
+    }
+
+    // computes the modularity of a partition of the vertices into communities, i.e. the
+    // fraction of edge weight within communities minus the fraction expected if edges
+    // were placed at random; edge directions are ignored and vertices not in any of the
+    // communities count as communities of their own
+    pub fn modularity(&self, communities: &[Community], resolution: f64) -> f64 {
+        let mut community = HashMap::new();
+        for (c, members) in communities.iter().enumerate() {
+            for v in members {
+                community.insert(*v, c);
+            }
+        }
+        let mut internal = vec![0.0; communities.len()];
+        let mut total = vec![0.0; communities.len()];
+        let mut sum = 0.0;
+        for edge in self.edges() {
+            let weight = self.weight(edge);
+            for v in [edge.0, edge.1] {
+                if let Entry::Vacant(entry) = community.entry(v) {
+                    entry.insert(internal.len());
+                    internal.push(0.0);
+                    total.push(0.0);
+                }
+            }
+            let (c, d) = (community[&edge.0], community[&edge.1]);
+            total[c] += weight;
+            total[d] += weight;
+            sum += 2.0 * weight;
+            if c == d {
+                internal[c] += 2.0 * weight;
+            }
+        }
+        if sum == 0.0 {
+            return 0.0;
+        }
+        internal.iter().zip(total)
+            .map(|(internal, total)| internal / sum - resolution * (total / sum) * (total / sum))
+            .sum()
+    }
+}
+
+// groups the vertices by their labels
+fn label_communities(labels: HashMap<Vertex, Vertex>) -> Vec<Community> {
+    let mut communities: HashMap<Vertex, Community> = HashMap::new();
+    for (v, label) in labels {
+        communities.entry(label).or_default().insert(v);
+    }
+    communities.into_values().collect()
+}
+
+// groups the vertices by the nodes of a Louvain level containing them
+fn level_communities(vertices: &[Vertex], membership: &[usize], nodes: usize) -> Vec<Community> {
+    let mut communities = vec![HashSet::new(); nodes];
+    for (v, node) in vertices.iter().zip(membership) {
+        communities[*node].insert(*v);
+    }
+    communities
+}
+
+// one level of the Louvain hierarchy: a symmetric weighted adjacency matrix over the nodes
+// of the level, where self-loops count twice so that the degree of a node is its row sum
+struct Level {
+    adj: Vec<HashMap<usize, f64>>,
+    degree: Vec<f64>,
+    total: f64
+}
+
+impl Level {
+
+    fn new(n: usize) -> Level {
+        Level { adj: vec![HashMap::new(); n], degree: vec![0.0; n], total: 0.0 }
+    }
+
+    fn add(&mut self, i: usize, j: usize, weight: f64) {
+        *self.adj[i].entry(j).or_insert(0.0) += weight;
+        *self.adj[j].entry(i).or_insert(0.0) += weight;
+        self.degree[i] += weight;
+        self.degree[j] += weight;
+        self.total += 2.0 * weight;
+    }
+
+    // moves nodes into the neighbouring community with the largest modularity gain until
+    // no move improves the modularity any further; returns the densely numbered community
+    // of every node together with the number of communities
+    fn move_nodes(&self, resolution: f64) -> (Vec<usize>, usize) {
+        let n = self.adj.len();
+        let mut community: Vec<usize> = (0..n).collect();
+        let mut total = self.degree.clone();
+        let mut improved = self.total > 0.0;
+        while improved {
+            improved = false;
+            for i in 0..n {
+                let mut links = BTreeMap::new();
+                for (j, weight) in self.adj[i].iter().filter(|&(j, _)| *j != i) {
+                    *links.entry(community[*j]).or_insert(0.0) += *weight;
+                }
+                let current = community[i];
+                total[current] -= self.degree[i];
+                let gain = |c: usize, link: f64| link - resolution * total[c] * self.degree[i] / self.total;
+                let mut best = (current, gain(current, *links.get(&current).unwrap_or(&0.0)));
+                for (c, link) in links {
+                    let gain = gain(c, link);
+                    if gain > best.1 + 1e-12 {
+                        best = (c, gain);
+                    }
+                }
+                total[best.0] += self.degree[i];
+                if best.0 != current {
+                    community[i] = best.0;
+                    improved = true;
+                }
+            }
+        }
+
+        let mut numbering = HashMap::new();
+        for c in community.iter_mut() {
+            let next = numbering.len();
+            *c = *numbering.entry(*c).or_insert(next);
+        }
+        (community, numbering.len())
+    }
+
+    // builds the next level by collapsing each community into a single node
+    fn aggregate(&self, community: &[usize], count: usize) -> Level {
+        let mut level = Level::new(count);
+        for (i, adjacent) in self.adj.iter().enumerate() {
+            for (j, weight) in adjacent {
+                *level.adj[community[i]].entry(community[*j]).or_insert(0.0) += *weight;
+                level.degree[community[i]] += *weight;
+            }
+        }
+        level.total = self.total;
+        level
+    }
+}
+
+// the dendrogram of a divisive community detection: the clusters are numbered in the order
+// they appear, starting with the connected components, and every split divides a cluster
+// into two new ones
+pub struct Dendrogram {
+    pub clusters: Vec<Community>,
+    pub components: usize,
+    pub splits: Vec<Split>
+}
+
+// the division of the parent cluster into the left and right ones, after removing so many
+// edges from the graph in total
+pub struct Split {
+    pub parent: usize,
+    pub left: usize,
+    pub right: usize,
+    pub edges_removed: usize
+}
+
+impl Dendrogram {
+
+    // the communities after the first k splits
+    pub fn level(&self, k: usize) -> Vec<Community> {
+        let mut live: Vec<bool> = (0..self.clusters.len()).map(|c| c < self.components).collect();
+        for split in &self.splits[..k.min(self.splits.len())] {
+            live[split.parent] = false;
+            live[split.left] = true;
+            live[split.right] = true;
+        }
+        (0..self.clusters.len()).filter(|c| live[*c]).map(|c| self.clusters[c].clone()).collect()
+    }
+}
+
+impl Graph {
+
+    // the edge betweenness of every edge, the number of shortest paths between pairs of
+    // vertices that run through it, with paths shared by several shortest ones counted by
+    // their fraction, computed by the algorithm of Brandes from a breadth first search from
+    // every vertex; edge directions, weights and self-loops are ignored, and every pair of
+    // vertices is only counted once
+    pub fn edge_betweenness(&self) -> HashMap<Edge, f64> {
+        let (vertices, index) = self.indexed_vertices();
+        let adj = simple_adjacencies(self, &index);
+        let betweenness = brandes(&adj, 0..vertices.len());
+        self.edges().iter()
+            .map(|edge| {
+                let (u, v) = (index[&edge.0], index[&edge.1]);
+                (*edge, betweenness.get(&(u.min(v), u.max(v))).cloned().unwrap_or(0.0))
+            })
+            .collect()
+    }
+
+    // detects communities with the algorithm of Girvan and Newman: it keeps removing the
+    // edge of highest betweenness, the smallest one among equal ones, and recomputes the
+    // betweenness of the component the edge was in, until max_splits components have
+    // split in two or no edges are left; it takes O(m^2 n) time in the worst case
+    pub fn girvan_newman(&self, max_splits: usize) -> Dendrogram {
+        let (vertices, index) = self.indexed_vertices();
+        let mut adj = simple_adjacencies(self, &index);
+        let mut betweenness = brandes(&adj, 0..vertices.len());
+        let mut cluster = vec![usize::MAX; vertices.len()];
+        let mut members: Vec<Vec<usize>> = vec![];
+        for root in 0..vertices.len() {
+            if cluster[root] == usize::MAX {
+                let component = reached(&adj, root);
+                for &v in &component {
+                    cluster[v] = members.len();
+                }
+                members.push(component);
+            }
+        }
+        let components = members.len();
+
+        let mut splits = vec![];
+        let mut edges_removed = 0;
+        while splits.len() < max_splits {
+            let highest = betweenness.iter()
+                .max_by(|a, b| a.1.partial_cmp(b.1).unwrap().then(b.0.cmp(a.0)))
+                .map(|(edge, _)| *edge);
+            let (u, v) = match highest {
+                Some(edge) => edge,
+                None => break
+            };
+            adj[u].retain(|w| *w != v);
+            adj[v].retain(|w| *w != u);
+            betweenness.remove(&(u, v));
+            edges_removed += 1;
+
+            let parent = cluster[u];
+            let left = reached(&adj, u);
+            if left.len() < members[parent].len() {
+                let right: Vec<usize> = members[parent].iter().cloned().filter(|w| left.binary_search(w).is_err()).collect();
+                for part in [left, right] {
+                    for w in &part {
+                        cluster[*w] = members.len();
+                    }
+                    members.push(part);
+                }
+                splits.push(Split { parent, left: members.len() - 2, right: members.len() - 1, edges_removed });
+            }
+            // only the paths within the component of the removed edge change
+            let affected = members[parent].clone();
+            for &w in &affected {
+                for &x in &adj[w] {
+                    betweenness.remove(&(w.min(x), w.max(x)));
+                }
+            }
+            betweenness.extend(brandes(&adj, affected.into_iter()));
+        }
+
+        let clusters = members.into_iter()
+            .map(|part| part.into_iter().map(|v| vertices[v]).collect())
+            .collect();
+        Dendrogram { clusters, components, splits }
+    }
+}
+
+// the distinct neighbours of every vertex index other than itself
+fn simple_adjacencies(graph: &Graph, index: &HashMap<Vertex, usize>) -> Vec<Vec<usize>> {
+    let mut adj: Vec<Vec<usize>> = vec![vec![]; index.len()];
+    for edge in graph.edges().iter().filter(|edge| edge.0 != edge.1) {
+        let (u, v) = (index[&edge.0], index[&edge.1]);
+        adj[u].push(v);
+        adj[v].push(u);
+    }
+    for adjacent in &mut adj {
+        adjacent.sort();
+        adjacent.dedup();
+    }
+    adj
+}
+
+// the vertex indices reachable from the root in ascending order
+fn reached(adj: &[Vec<usize>], root: usize) -> Vec<usize> {
+    let mut found = HashSet::new();
+    found.insert(root);
+    let mut stack = vec![root];
+    while let Some(v) = stack.pop() {
+        for &w in &adj[v] {
+            if found.insert(w) {
+                stack.push(w);
+            }
+        }
+    }
+    let mut found: Vec<usize> = found.into_iter().collect();
+    found.sort();
+    found
+}
+
+// the edge betweenness of the edges, keyed by their smaller end first, accumulated over the
+// breadth first searches from the sources; every pair is found from both of its ends, so
+// the sums are halved
+fn brandes<I: Iterator<Item = usize>>(adj: &[Vec<usize>], sources: I) -> HashMap<(usize, usize), f64> {
+    let n = adj.len();
+    let mut betweenness = HashMap::new();
+    let mut distance = vec![usize::MAX; n];
+    let mut paths = vec![0.0; n];
+    let mut dependency = vec![0.0; n];
+    for source in sources {
+        let mut order = vec![source];
+        distance[source] = 0;
+        paths[source] = 1.0;
+        let mut next = 0;
+        while next < order.len() {
+            let v = order[next];
+            for &w in &adj[v] {
+                if distance[w] == usize::MAX {
+                    distance[w] = distance[v] + 1;
+                    order.push(w);
+                }
+                if distance[w] == distance[v] + 1 {
+                    paths[w] += paths[v];
+                }
+            }
+            next += 1;
+        }
+        for &w in order.iter().rev() {
+            for &v in &adj[w] {
+                if distance[v] != usize::MAX && distance[v] + 1 == distance[w] {
+                    let share = paths[v] / paths[w] * (1.0 + dependency[w]);
+                    *betweenness.entry((v.min(w), v.max(w))).or_insert(0.0) += share / 2.0;
+                    dependency[v] += share;
+                }
+            }
+        }
+        for v in order {
+            distance[v] = usize::MAX;
+            paths[v] = 0.0;
+            dependency[v] = 0.0;
+        }
+    }
+    betweenness
+}
@@ -0,0 +1,230 @@
+use std::convert::TryFrom;
+use std::io;
+use std::io::{Read, Write};
+
+use graph::{Graph, HashSet, Vertex};
+
+// the compressed file format starts with a magic number, a version and a byte of flags,
+// followed by the number of vertices, the vertices in ascending order as differences to
+// their predecessors, the number of bytes of adjacency lists and the adjacency lists as
+// encoded in memory; all numbers are varints
+const MAGIC: &[u8; 4] = b"GVAR";
+const VERSION: u8 = 1;
+const DIRECTED: u8 = 1;
+
+// an unweighted graph whose adjacency lists are stored in one byte array in the style of
+// WebGraph: the sorted neighbours of each vertex are encoded as the differences between
+// consecutive neighbours, which are small when neighbours have close numbers, and every
+// number as a varint of 7 bits per byte; the list of a vertex starts with its degree and
+// its first neighbour as the zigzag encoded difference to the vertex itself, followed by
+// the gaps between the neighbours less one; undirected edges are stored in both directions
+pub struct CompressedGraph {
+    directed: bool,
+    vertices: Vec<Vertex>,
+    // the position of the adjacency list of vertices[i] in data
+    offsets: Vec<usize>,
+    data: Vec<u8>,
+    arcs: u64
+}
+
+// iterates over the neighbours of a vertex, decoding them one at a time
+pub struct Neighbours<'a> {
+    data: &'a [u8],
+    position: usize,
+    remaining: u64,
+    previous: Option<u64>,
+    vertex: u64
+}
+
+impl<'a> Iterator for Neighbours<'a> {
+    type Item = Vertex;
+
+    fn next(&mut self) -> Option<Vertex> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let code = read_varint(self.data, &mut self.position).expect("Corrupt adjacency list!");
+        let w = match self.previous {
+            None => self.vertex.wrapping_add(unzigzag(code) as u64),
+            Some(previous) => previous + code + 1
+        };
+        self.previous = Some(w);
+        Some(Vertex::try_from(w).expect("Corrupt adjacency list!"))
+    }
+}
+
+impl Graph {
+
+    // builds the compressed form of the graph, dropping the edge weights
+    pub fn compress(&self) -> CompressedGraph {
+        let vertices = self.sorted_vertices();
+        let adj = self.adjacencies();
+        let mut offsets = Vec::with_capacity(vertices.len());
+        let mut data = vec![];
+        let mut arcs = 0;
+        for v in &vertices {
+            offsets.push(data.len());
+            let mut neighbours: Vec<Vertex> = adj[v].iter().cloned().collect();
+            neighbours.sort();
+            write_varint(&mut data, neighbours.len() as u64);
+            for (i, w) in neighbours.iter().enumerate() {
+                // the difference to the vertex is taken modulo 2^64, which covers all pairs of vertices
+                let code = if i == 0 {
+                    zigzag(wide(*w).wrapping_sub(wide(*v)) as i64)
+                } else {
+                    wide(*w) - wide(neighbours[i - 1]) - 1
+                };
+                write_varint(&mut data, code);
+            }
+            arcs += neighbours.len() as u64;
+        }
+        CompressedGraph { directed: self.is_directed(), vertices, offsets, data, arcs }
+    }
+}
+
+impl CompressedGraph {
+
+    pub fn order(&self) -> usize {
+        self.vertices.len()
+    }
+
+    // the number of adjacency list entries, i.e. undirected edges count twice
+    pub fn arc_count(&self) -> u64 {
+        self.arcs
+    }
+
+    // the number of bytes taken by the adjacency lists
+    pub fn encoded_size(&self) -> usize {
+        self.data.len()
+    }
+
+    // the neighbours of v in ascending order, decoded while iterating
+    pub fn neighbours(&self, v: Vertex) -> Neighbours<'_> {
+        let i = self.vertices.binary_search(&v).expect("Unknown vertex!");
+        let mut position = self.offsets[i];
+        let remaining = read_varint(&self.data, &mut position).expect("Corrupt adjacency list!");
+        Neighbours { data: &self.data, position, remaining, previous: None, vertex: wide(v) }
+    }
+
+    // decodes all adjacency lists back into a graph
+    pub fn decompress(&self) -> Graph {
+        let mut edges = HashSet::new();
+        for v in &self.vertices {
+            for w in self.neighbours(*v) {
+                if self.directed || *v <= w {
+                    edges.insert((*v, w));
+                }
+            }
+        }
+        Graph::new(self.vertices.iter().cloned().collect(), edges, self.directed)
+    }
+
+    // writes the graph in the compressed file format
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let mut header = vec![];
+        header.extend_from_slice(MAGIC);
+        header.extend_from_slice(&[VERSION, if self.directed { DIRECTED } else { 0 }]);
+        write_varint(&mut header, self.vertices.len() as u64);
+        for (i, v) in self.vertices.iter().enumerate() {
+            write_varint(&mut header, wide(*v) - if i == 0 { 0 } else { wide(self.vertices[i - 1]) });
+        }
+        write_varint(&mut header, self.data.len() as u64);
+        writer.write_all(&header)?;
+        writer.write_all(&self.data)
+    }
+
+    // reads a graph written by write, checking that the adjacency lists decode to known
+    // vertices; fails with InvalidData otherwise
+    pub fn read<R: Read>(reader: &mut R) -> io::Result<CompressedGraph> {
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "not a valid compressed graph");
+        let mut bytes = vec![];
+        reader.read_to_end(&mut bytes)?;
+        if bytes.len() < 6 || &bytes[..4] != MAGIC || bytes[4] > VERSION {
+            return Err(invalid());
+        }
+        let directed = bytes[5] & DIRECTED != 0;
+        let mut position = 6;
+        let n = read_varint(&bytes, &mut position).ok_or_else(invalid)?;
+        let mut vertices: Vec<Vertex> = vec![];
+        let mut previous = 0u64;
+        for i in 0..n {
+            let gap = read_varint(&bytes, &mut position).ok_or_else(invalid)?;
+            if i > 0 && gap == 0 {
+                return Err(invalid());
+            }
+            previous = previous.checked_add(gap).ok_or_else(invalid)?;
+            vertices.push(Vertex::try_from(previous).map_err(|_| invalid())?);
+        }
+        let length = read_varint(&bytes, &mut position).ok_or_else(invalid)? as usize;
+        if bytes.len() - position != length {
+            return Err(invalid());
+        }
+        let data = bytes.split_off(position);
+
+        let mut offsets = Vec::with_capacity(vertices.len());
+        let mut position = 0;
+        let mut arcs = 0;
+        for v in &vertices {
+            offsets.push(position);
+            let degree = read_varint(&data, &mut position).ok_or_else(invalid)?;
+            let mut previous: Option<u64> = None;
+            for _ in 0..degree {
+                let code = read_varint(&data, &mut position).ok_or_else(invalid)?;
+                let w = match previous {
+                    None => Some(wide(*v).wrapping_add(unzigzag(code) as u64)),
+                    Some(previous) => previous.checked_add(code).and_then(|w| w.checked_add(1))
+                }.ok_or_else(invalid)?;
+                if Vertex::try_from(w).map_or(true, |w| vertices.binary_search(&w).is_err()) {
+                    return Err(invalid());
+                }
+                previous = Some(w);
+            }
+            arcs += degree;
+        }
+        if position != data.len() {
+            return Err(invalid());
+        }
+        Ok(CompressedGraph { directed, vertices, offsets, data, arcs })
+    }
+}
+
+// appends the number 7 bits per byte, low bits first, with the high bit set on all bytes
+// but the last
+fn write_varint(data: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        data.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    data.push(value as u8);
+}
+
+// decodes the varint at the position and moves the position past it, or returns None if
+// the data ends first or the number does not fit into 64 bits
+fn read_varint(data: &[u8], position: &mut usize) -> Option<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *data.get(*position)?;
+        *position += 1;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+// the vertex as a 64 bit number whatever the width of vertices
+#[cfg_attr(feature = "wide_vertices", allow(clippy::useless_conversion))]
+fn wide(v: Vertex) -> u64 {
+    u64::from(v)
+}
+
+// maps signed to unsigned numbers so that small magnitudes get small codes
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn unzigzag(code: u64) -> i64 {
+    (code >> 1) as i64 ^ -((code & 1) as i64)
+}
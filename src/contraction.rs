@@ -0,0 +1,331 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::convert::Infallible;
+use std::io;
+
+use checkpoint::{Checkpoint, SavedState, Stage, State};
+use graph::{Graph, HashMap, Vertex, Weight};
+use shortest_paths::{Path, PathResult, QueueEntry};
+
+// maximum number of vertices settled by a witness search before giving up; giving up early
+// only adds superfluous shortcuts, it never makes queries incorrect
+const WITNESS_SETTLE_LIMIT: usize = 500;
+
+// edges of the remaining graph during contraction: target (or source) index mapped onto
+// the weight and, for shortcuts, the contracted vertex the shortcut bypasses
+type Edges = Vec<HashMap<usize, (Weight, Option<usize>)>>;
+
+// a contraction hierarchy for fast point-to-point shortest path queries: vertices are
+// contracted one by one in order of importance, adding shortcut edges between their
+// neighbours wherever the contracted vertex lay on the only shortest path between them;
+// a query then only has to search upwards in the hierarchy from both endpoints
+pub struct ContractionHierarchy {
+    vertices: Vec<Vertex>,
+    index: HashMap<Vertex, usize>,
+    // edges leading to higher ranked vertices, from the source and into the target side
+    up: Vec<Vec<(usize, Weight)>>,
+    down: Vec<Vec<(usize, Weight)>>,
+    // the bypassed vertex of every shortcut edge
+    middle: HashMap<(usize, usize), usize>
+}
+
+// the state of a contraction in progress, the remaining graph and the hierarchy built so
+// far, which is all a checkpoint needs to resume it
+struct Contraction {
+    out: Edges,
+    inn: Edges,
+    contracted: Vec<bool>,
+    contracted_neighbours: Vec<i64>,
+    hierarchy: ContractionHierarchy
+}
+
+impl Contraction {
+
+    fn new(graph: &Graph) -> Contraction {
+        let (vertices, index) = graph.indexed_vertices();
+        let n = vertices.len();
+        let mut out: Edges = vec![HashMap::new(); n];
+        let mut inn: Edges = vec![HashMap::new(); n];
+        for (v, adjacent) in graph.weighted_adjacencies() {
+            for (w, weight) in adjacent {
+                if v != w {
+                    out[index[&v]].insert(index[&w], (weight, None));
+                    inn[index[&w]].insert(index[&v], (weight, None));
+                }
+            }
+        }
+        let hierarchy = ContractionHierarchy {
+            vertices, index, up: vec![vec![]; n], down: vec![vec![]; n], middle: HashMap::new()
+        };
+        Contraction { out, inn, contracted: vec![false; n], contracted_neighbours: vec![0; n], hierarchy }
+    }
+
+    // contracts the remaining vertices, passing the state to save after every contraction
+    // along with the number of vertices contracted so far, which stops the contraction by
+    // failing
+    fn run<E, F: FnMut(&Contraction, usize) -> Result<(), E>>(mut self, mut save: F) -> Result<ContractionHierarchy, E> {
+        let n = self.contracted.len();
+        let priority = |v: usize, out: &Edges, inn: &Edges, contracted: &[i64]| {
+            let edges = (out[v].len() + inn[v].len()) as i64;
+            shortcuts(v, out, inn).len() as i64 - edges + 2 * contracted[v]
+        };
+        let mut queue: BinaryHeap<Reverse<(i64, usize)>> = (0..n)
+            .filter(|v| !self.contracted[*v])
+            .map(|v| Reverse((priority(v, &self.out, &self.inn, &self.contracted_neighbours), v)))
+            .collect();
+        let mut done = n - queue.len();
+
+        // the priority of a vertex changes as its neighbours get contracted, so it is
+        // updated lazily whenever the vertex comes up for contraction
+        while let Some(Reverse((_, v))) = queue.pop() {
+            let current = priority(v, &self.out, &self.inn, &self.contracted_neighbours);
+            if queue.peek().is_some_and(|next| current > (next.0).0) {
+                queue.push(Reverse((current, v)));
+                continue;
+            }
+            let (out, inn) = (&mut self.out, &mut self.inn);
+            for (u, w, weight) in shortcuts(v, out, inn) {
+                let better = out[u].get(&w).is_none_or(|edge| weight < edge.0);
+                if better {
+                    out[u].insert(w, (weight, Some(v)));
+                    inn[w].insert(u, (weight, Some(v)));
+                }
+            }
+            let successors = std::mem::take(&mut out[v]);
+            let predecessors = std::mem::take(&mut inn[v]);
+            let hierarchy = &mut self.hierarchy;
+            for (w, (weight, middle)) in successors {
+                inn[w].remove(&v);
+                self.contracted_neighbours[w] += 1;
+                hierarchy.up[v].push((w, weight));
+                if let Some(m) = middle {
+                    hierarchy.middle.insert((v, w), m);
+                }
+            }
+            for (u, (weight, middle)) in predecessors {
+                out[u].remove(&v);
+                self.contracted_neighbours[u] += 1;
+                hierarchy.down[v].push((u, weight));
+                if let Some(m) = middle {
+                    hierarchy.middle.insert((u, v), m);
+                }
+            }
+            self.contracted[v] = true;
+            done += 1;
+            if done < n {
+                save(&self, done)?;
+            }
+        }
+        Ok(self.hierarchy)
+    }
+
+    // the state as saved to a checkpoint: the contracted vertices, their contracted
+    // neighbours, the remaining edges with the bypassed vertex plus one of shortcuts, and
+    // the edges of the hierarchy; the edges into every vertex follow from the edges out of
+    // the others
+    fn save(&self) -> State {
+        let mut state = State::new();
+        for (contracted, neighbours) in self.contracted.iter().zip(&self.contracted_neighbours) {
+            state.put(*contracted as u64);
+            state.put(*neighbours as u64);
+        }
+        for edges in &self.out {
+            state.put_index(edges.len());
+            for (w, (weight, middle)) in edges {
+                state.put_index(*w);
+                state.put_weight(*weight);
+                state.put_index(middle.map_or(0, |m| m + 1));
+            }
+        }
+        for edges in self.hierarchy.up.iter().chain(&self.hierarchy.down) {
+            state.put_index(edges.len());
+            for (w, weight) in edges {
+                state.put_index(*w);
+                state.put_weight(*weight);
+            }
+        }
+        state.put_index(self.hierarchy.middle.len());
+        for ((u, v), m) in &self.hierarchy.middle {
+            state.put_index(*u);
+            state.put_index(*v);
+            state.put_index(*m);
+        }
+        state
+    }
+
+    // restores the state saved for the graph
+    fn restore(graph: &Graph, mut state: SavedState) -> io::Result<Contraction> {
+        let mut contraction = Contraction::new(graph);
+        let n = contraction.contracted.len();
+        for v in 0..n {
+            contraction.contracted[v] = state.take_index(2)? == 1;
+            contraction.contracted_neighbours[v] = state.take()? as i64;
+        }
+        let (mut out, mut inn): (Edges, Edges) = (vec![HashMap::new(); n], vec![HashMap::new(); n]);
+        for (v, edges) in out.iter_mut().enumerate() {
+            for _ in 0..state.take()? {
+                let w = state.take_index(n)?;
+                let weight = state.take_weight()?;
+                let middle = state.take_index(n + 1)?.checked_sub(1);
+                edges.insert(w, (weight, middle));
+                inn[w].insert(v, (weight, middle));
+            }
+        }
+        contraction.out = out;
+        contraction.inn = inn;
+        let hierarchy = &mut contraction.hierarchy;
+        for v in 0..2 * n {
+            let mut edges = vec![];
+            for _ in 0..state.take()? {
+                edges.push((state.take_index(n)?, state.take_weight()?));
+            }
+            if v < n { hierarchy.up[v] = edges } else { hierarchy.down[v - n] = edges }
+        }
+        for _ in 0..state.take()? {
+            hierarchy.middle.insert((state.take_index(n)?, state.take_index(n)?), state.take_index(n)?);
+        }
+        Ok(contraction)
+    }
+}
+
+impl ContractionHierarchy {
+
+    // the number of shortcut edges added during preprocessing
+    pub fn shortcut_count(&self) -> usize {
+        self.middle.len()
+    }
+
+    // finds a shortest path from source to target by searching upwards from the source and
+    // (backwards) from the target; the path is the best one through a vertex found by both
+    pub fn query(&self, source: Vertex, target: Vertex) -> Option<PathResult> {
+        let (s, t) = (*self.index.get(&source)?, *self.index.get(&target)?);
+        let (forward, forward_parent, forward_relaxations) = upward_search(&self.up, s);
+        let (backward, backward_parent, backward_relaxations) = upward_search(&self.down, t);
+        let (distance, meeting) = forward.iter()
+            .filter_map(|(v, d)| backward.get(v).map(|b| (d + b, *v)))
+            .min_by(|a, b| a.0.total_cmp(&b.0).then_with(|| a.1.cmp(&b.1)))?;
+
+        let mut hops = vec![meeting];
+        while let Some(p) = forward_parent.get(&hops[hops.len() - 1]) {
+            hops.push(*p);
+        }
+        hops.reverse();
+        while let Some(p) = backward_parent.get(&hops[hops.len() - 1]) {
+            hops.push(*p);
+        }
+        let mut path = vec![self.vertices[s]];
+        for hop in hops.windows(2) {
+            self.unpack(hop[0], hop[1], &mut path);
+        }
+        // the searches label vertices by shortcuts, so only those on the path are kept
+        Some(PathResult::new(distance, path, forward_relaxations + backward_relaxations, HashMap::new()))
+    }
+
+    // appends the vertices of the edge from u to v, replacing shortcuts by the edges they bypass
+    fn unpack(&self, u: usize, v: usize, path: &mut Path) {
+        match self.middle.get(&(u, v)) {
+            Some(m) => {
+                self.unpack(u, *m, path);
+                self.unpack(*m, v, path);
+            },
+            None => path.push(self.vertices[v])
+        }
+    }
+}
+
+// lists the shortcuts needed when contracting v: for every pair of neighbours u -> v -> w
+// for which there is no witness path from u to w avoiding v that is at most as short
+fn shortcuts(v: usize, out: &Edges, inn: &Edges) -> Vec<(usize, usize, Weight)> {
+    let mut needed = vec![];
+    for (u, (to_v, _)) in &inn[v] {
+        let limit = out[v].values().map(|edge| to_v + edge.0).fold(0.0, Weight::max);
+        let witness = witness_search(*u, v, limit, out);
+        for (w, (from_v, _)) in &out[v] {
+            let via = to_v + from_v;
+            if w != u && witness.get(w).is_none_or(|d| *d > via) {
+                needed.push((*u, *w, via));
+            }
+        }
+    }
+    needed.sort_by_key(|shortcut| (shortcut.0, shortcut.1));
+    needed
+}
+
+// Dijkstra's algorithm from u in the remaining graph without the vertex to be contracted,
+// stopping at distance limit or after settling a bounded number of vertices
+fn witness_search(u: usize, excluded: usize, limit: Weight, out: &Edges) -> HashMap<usize, Weight> {
+    let mut distance = HashMap::new();
+    let mut queue = BinaryHeap::new();
+    let mut settled = 0;
+    distance.insert(u, 0.0);
+    queue.push(QueueEntry { distance: 0.0, vertex: u });
+    while let Some(QueueEntry { distance: d, vertex: x }) = queue.pop() {
+        if d > limit || settled == WITNESS_SETTLE_LIMIT {
+            break;
+        }
+        if d > distance[&x] {
+            continue;
+        }
+        settled += 1;
+        for (y, (weight, _)) in &out[x] {
+            let alt = d + weight;
+            if *y != excluded && distance.get(y).is_none_or(|current| alt < *current) {
+                distance.insert(*y, alt);
+                queue.push(QueueEntry { distance: alt, vertex: *y });
+            }
+        }
+    }
+    distance
+}
+
+// Dijkstra's algorithm from source over the edges leading upwards in the hierarchy, also
+// counting the edges it relaxes
+fn upward_search(edges: &[Vec<(usize, Weight)>], source: usize) -> (HashMap<usize, Weight>, HashMap<usize, usize>, usize) {
+    let mut distance = HashMap::new();
+    let mut parent = HashMap::new();
+    let mut queue = BinaryHeap::new();
+    let mut relaxations = 0;
+    distance.insert(source, 0.0);
+    queue.push(QueueEntry { distance: 0.0, vertex: source });
+    while let Some(QueueEntry { distance: d, vertex: v }) = queue.pop() {
+        if d > distance[&v] {
+            continue;
+        }
+        relaxations += edges[v].len();
+        for (w, weight) in &edges[v] {
+            let alt = d + weight;
+            if distance.get(w).is_none_or(|current| alt < *current) {
+                distance.insert(*w, alt);
+                parent.insert(*w, v);
+                queue.push(QueueEntry { distance: alt, vertex: *w });
+            }
+        }
+    }
+    (distance, parent, relaxations)
+}
+
+impl Graph {
+
+    // preprocesses the graph into a contraction hierarchy; edge weights must not be negative
+    pub fn contraction_hierarchy(&self) -> ContractionHierarchy {
+        match Contraction::new(self).run(|_, _| Ok::<(), Infallible>(())) {
+            Ok(hierarchy) => hierarchy,
+            Err(never) => match never {}
+        }
+    }
+
+    // preprocesses the graph into a contraction hierarchy like contraction_hierarchy, saving
+    // the contraction so far to the checkpoint, and resuming from the contraction saved by an
+    // earlier run on the same graph that did not get to finish
+    pub fn contraction_hierarchy_checkpointed(&self, checkpoint: &Checkpoint) -> io::Result<ContractionHierarchy> {
+        let (run, saved) = checkpoint.start(Stage::ContractionHierarchy, self)?;
+        let contraction = match saved {
+            Some(state) => Contraction::restore(self, state)?,
+            None => Contraction::new(self)
+        };
+        let hierarchy = contraction.run(|contraction, done| {
+            if run.is_due(done) { run.save(&contraction.save()) } else { Ok(()) }
+        })?;
+        run.finish().map(|_| hierarchy)
+    }
+}
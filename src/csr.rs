@@ -0,0 +1,356 @@
+use std::convert::{TryFrom, TryInto};
+use std::fs::File;
+use std::io;
+use std::io::{BufRead, Seek, SeekFrom, Write};
+use std::mem::size_of;
+use std::path::Path;
+
+use graph::{Vertex, Weight};
+
+// the CSR file format starts with a magic number, a version and a byte of flags padded to
+// 8 bytes, followed by the number of vertices n and of adjacency list entries m, the n + 1
+// offsets, the m targets padded to a multiple of 8 bytes and, for weighted graphs, the m
+// weights; all numbers are little endian, counts and offsets 64 bit unsigned integers,
+// vertices 32 bit (64 bit if the wide flag is set) and weights 64 bit floats
+const MAGIC: &[u8; 4] = b"GCSR";
+const VERSION: u8 = 1;
+const DIRECTED: u8 = 1;
+const WEIGHTED: u8 = 2;
+const WIDE: u8 = 4;
+const HEADER_SIZE: usize = 24;
+
+// a graph over the vertices 1..n in compressed sparse row form: the neighbours of vertex v
+// are targets[offsets[v - 1]..offsets[v]], with the weights of these edges at the same
+// positions in weights if the graph is weighted; undirected edges are stored in both
+// directions, and edges contained more than once in the input are kept as parallel edges
+pub struct CsrGraph {
+    offsets: Vec<usize>,
+    targets: Vec<Vertex>,
+    weights: Vec<Weight>,
+    directed: bool
+}
+
+impl CsrGraph {
+
+    // loads a graph in the format of Graph::load or Graph::load_weighted, reading the edges
+    // twice: once to count the degrees and once to fill in the neighbours, so that nothing
+    // but the final arrays is ever held in memory; the reader is left after the last edge
+    pub fn load<R: BufRead + Seek>(reader: &mut R, directed: bool, weighted: bool) -> io::Result<CsrGraph> {
+        let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+        let mut buffer = String::new();
+        let (n, e) = match next_numbers(reader, &mut buffer)? {
+            Some((n, e, _)) => (n as usize, e),
+            None => return Err(invalid("missing number of vertices and edges"))
+        };
+        let mut edge = |reader: &mut R| -> io::Result<(usize, usize, Weight)> {
+            match next_numbers(reader, &mut buffer)? {
+                Some((u, v, weight)) if u >= 1 && v >= 1 && u as usize <= n && v as usize <= n => {
+                    if weighted && weight.is_none() {
+                        return Err(invalid("missing edge weight"));
+                    }
+                    Ok((u as usize - 1, v as usize - 1, weight.unwrap_or(1.0)))
+                },
+                Some(_) => Err(invalid("edge between unknown vertices")),
+                None => Err(invalid("missing edge"))
+            }
+        };
+
+        let start = reader.stream_position()?;
+        let mut offsets = vec![0; n + 1];
+        for _ in 0..e {
+            let (u, v, _) = edge(reader)?;
+            offsets[u + 1] += 1;
+            if !directed && u != v {
+                offsets[v + 1] += 1;
+            }
+        }
+        for i in 0..n {
+            offsets[i + 1] += offsets[i];
+        }
+
+        reader.seek(SeekFrom::Start(start))?;
+        let mut next = offsets.clone();
+        let mut targets = vec![0; offsets[n]];
+        let mut weights = if weighted { vec![0.0; offsets[n]] } else { vec![] };
+        for _ in 0..e {
+            let (u, v, weight) = edge(reader)?;
+            let arcs = if !directed && u != v { vec![(u, v), (v, u)] } else { vec![(u, v)] };
+            for (from, to) in arcs {
+                targets[next[from]] = to as Vertex + 1;
+                if weighted {
+                    weights[next[from]] = weight;
+                }
+                next[from] += 1;
+            }
+        }
+
+        // sort the neighbours of every vertex, carrying the weights along
+        let mut graph = CsrGraph { offsets, targets, weights, directed };
+        for v in 0..n {
+            let (first, last) = (graph.offsets[v], graph.offsets[v + 1]);
+            let mut neighbours: Vec<(Vertex, Weight)> = graph.neighbours(v as Vertex + 1).collect();
+            neighbours.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.total_cmp(&b.1)));
+            for (i, (w, weight)) in (first..last).zip(neighbours) {
+                graph.targets[i] = w;
+                if weighted {
+                    graph.weights[i] = weight;
+                }
+            }
+        }
+        Ok(graph)
+    }
+
+    pub fn order(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    // the number of edges, counting undirected edges once
+    pub fn size(&self) -> usize {
+        if self.directed {
+            return self.targets.len();
+        }
+        let self_loops = (1..self.order() as Vertex + 1)
+            .map(|v| self.neighbours(v).filter(|(w, _)| *w == v).count())
+            .sum::<usize>();
+        (self.targets.len() + self_loops) / 2
+    }
+
+    // the neighbours of v in ascending order together with the weights of the edges to
+    // them (1 if the graph is not weighted)
+    pub fn neighbours(&self, v: Vertex) -> impl Iterator<Item = (Vertex, Weight)> + '_ {
+        let range = self.offsets[v as usize - 1]..self.offsets[v as usize];
+        range.map(move |i| (self.targets[i], self.weights.get(i).cloned().unwrap_or(1.0)))
+    }
+
+    // writes the graph in the CSR file format, which MappedCsr reads without parsing
+    pub fn write_file<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let flags = if self.directed { DIRECTED } else { 0 } | if self.weights.is_empty() { 0 } else { WEIGHTED }
+            | if size_of::<Vertex>() == 8 { WIDE } else { 0 };
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION, flags, 0, 0])?;
+        writer.write_all(&(self.order() as u64).to_le_bytes())?;
+        writer.write_all(&(self.targets.len() as u64).to_le_bytes())?;
+        for offset in &self.offsets {
+            writer.write_all(&(*offset as u64).to_le_bytes())?;
+        }
+        for target in &self.targets {
+            writer.write_all(&target.to_le_bytes())?;
+        }
+        writer.write_all(&vec![0; padding(self.targets.len() * size_of::<Vertex>())])?;
+        for weight in &self.weights {
+            writer.write_all(&weight.to_le_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+// the number of bytes needed to pad the given length to a multiple of 8
+fn padding(length: usize) -> usize {
+    length.next_multiple_of(8) - length
+}
+
+// a graph in the CSR file format mapped into memory read-only, so that opening it takes no
+// parsing and processes working with the same file share its pages; the numbers are
+// decoded whenever they are read, which bypasses any alignment and endianness issues
+pub struct MappedCsr {
+    bytes: Mapping,
+    order: usize,
+    arcs: usize,
+    flags: u8
+}
+
+impl MappedCsr {
+
+    // maps the CSR file at the given path; fails with InvalidData if it is not in the CSR
+    // file format, was written by a later version or with vertices of another width. The
+    // mapping is private but not a copy: the file must not be truncated or rewritten, by
+    // this or another process, while the MappedCsr lives, as reading it could then crash
+    // with a bus error or see the numbers change after they were checked
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<MappedCsr> {
+        let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+        let bytes = Mapping::new(&File::open(path)?)?;
+        if bytes.len() < HEADER_SIZE || &bytes[..4] != MAGIC {
+            return Err(invalid("not a CSR file"));
+        }
+        if bytes[4] > VERSION {
+            return Err(invalid("unsupported CSR file version"));
+        }
+        let flags = bytes[5];
+        if (flags & WIDE != 0) != (size_of::<Vertex>() == 8) {
+            return Err(invalid("CSR file with vertices of another width"));
+        }
+        let number = |i: usize| usize::try_from(u64::from_le_bytes(bytes[i..i + 8].try_into().unwrap())).ok();
+        let (order, arcs) = match (number(8), number(16)) {
+            (Some(order), Some(arcs)) => (order, arcs),
+            _ => return Err(invalid("truncated CSR file"))
+        };
+        // the sizes of the sections, None if they do not even fit into memory
+        let targets = arcs.checked_mul(size_of::<Vertex>());
+        let weights = if flags & WEIGHTED != 0 { arcs.checked_mul(8) } else { Some(0) };
+        let length = order.checked_add(1).and_then(|offsets| offsets.checked_mul(8))
+            .zip(targets).zip(weights)
+            .and_then(|((offsets, targets), weights)| {
+                HEADER_SIZE.checked_add(offsets)?.checked_add(targets)?.checked_add(padding(targets))?.checked_add(weights)
+            });
+        if length != Some(bytes.len()) {
+            return Err(invalid("truncated CSR file"));
+        }
+        let graph = MappedCsr { bytes, order, arcs, flags };
+        if graph.offset(0) != 0 || graph.offset(order) != arcs || (1..order + 1).any(|v| graph.offset(v - 1) > graph.offset(v)) {
+            return Err(invalid("inconsistent CSR offsets"));
+        }
+        if (0..arcs).any(|i| usize::try_from(graph.target(i)).map_or(true, |target| target == 0 || target > order)) {
+            return Err(invalid("CSR file with edges to vertices it does not have"));
+        }
+        Ok(graph)
+    }
+
+    pub fn order(&self) -> usize {
+        self.order
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.flags & DIRECTED != 0
+    }
+
+    // the number of adjacency list entries, i.e. undirected edges count twice
+    pub fn arc_count(&self) -> usize {
+        self.arcs
+    }
+
+    // the neighbours of v with the weights of the edges to them, like CsrGraph::neighbours,
+    // and none for vertices the file does not have
+    pub fn neighbours(&self, v: Vertex) -> impl Iterator<Item = (Vertex, Weight)> + '_ {
+        let weights = self.targets() + self.arcs * size_of::<Vertex>() + padding(self.arcs * size_of::<Vertex>());
+        let range = match usize::try_from(v) {
+            Ok(v) if v >= 1 && v <= self.order => self.offset(v - 1)..self.offset(v),
+            _ => 0..0
+        };
+        range.map(move |i| {
+            let weight = if self.flags & WEIGHTED != 0 {
+                f64::from_le_bytes(self.bytes[weights + i * 8..weights + i * 8 + 8].try_into().unwrap())
+            } else {
+                1.0
+            };
+            (self.target(i), weight)
+        })
+    }
+
+    // where the targets start, after the offsets
+    fn targets(&self) -> usize {
+        HEADER_SIZE + (self.order + 1) * 8
+    }
+
+    fn target(&self, i: usize) -> Vertex {
+        let at = self.targets() + i * size_of::<Vertex>();
+        Vertex::from_le_bytes(self.bytes[at..at + size_of::<Vertex>()].try_into().unwrap())
+    }
+
+    fn offset(&self, i: usize) -> usize {
+        let at = HEADER_SIZE + i * 8;
+        u64::from_le_bytes(self.bytes[at..at + 8].try_into().unwrap()) as usize
+    }
+}
+
+// the contents of a file, mapped into memory on Unix and read into memory elsewhere; the
+// file must not be truncated while it is mapped, as reading the pages beyond its new end
+// raises SIGBUS
+#[cfg(unix)]
+pub struct Mapping {
+    address: *mut u8,
+    length: usize
+}
+
+#[cfg(unix)]
+mod unix {
+    use std::os::raw::{c_int, c_void};
+
+    pub const PROT_READ: c_int = 1;
+    pub const MAP_PRIVATE: c_int = 2;
+
+    extern "C" {
+        pub fn mmap(address: *mut c_void, length: usize, protection: c_int, flags: c_int, fd: c_int, offset: i64) -> *mut c_void;
+        pub fn munmap(address: *mut c_void, length: usize) -> c_int;
+    }
+}
+
+#[cfg(unix)]
+impl Mapping {
+
+    pub fn new(file: &File) -> io::Result<Mapping> {
+        use std::os::unix::io::AsRawFd;
+        let length = file.metadata()?.len() as usize;
+        if length == 0 {
+            return Ok(Mapping { address: ::std::ptr::null_mut(), length });
+        }
+        // the mapping is private and read-only, and stays valid after the file is closed
+        let address = unsafe {
+            unix::mmap(::std::ptr::null_mut(), length, unix::PROT_READ, unix::MAP_PRIVATE, file.as_raw_fd(), 0)
+        };
+        if address as isize == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Mapping { address: address as *mut u8, length })
+    }
+}
+
+#[cfg(unix)]
+impl ::std::ops::Deref for Mapping {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        if self.length == 0 {
+            return &[];
+        }
+        unsafe { ::std::slice::from_raw_parts(self.address, self.length) }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        if self.length > 0 {
+            unsafe { unix::munmap(self.address as *mut _, self.length); }
+        }
+    }
+}
+
+#[cfg(not(unix))]
+pub struct Mapping(Vec<u8>);
+
+#[cfg(not(unix))]
+impl Mapping {
+
+    pub fn new(mut file: &File) -> io::Result<Mapping> {
+        use std::io::Read;
+        let mut bytes = vec![];
+        file.read_to_end(&mut bytes)?;
+        Ok(Mapping(bytes))
+    }
+}
+
+#[cfg(not(unix))]
+impl ::std::ops::Deref for Mapping {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+// reads the next line and parses it as two vertices, optionally followed by a weight
+pub fn next_numbers<R: BufRead>(reader: &mut R, buffer: &mut String) -> io::Result<Option<(Vertex, Vertex, Option<Weight>)>> {
+    buffer.clear();
+    if reader.read_line(buffer)? == 0 {
+        return Ok(None);
+    }
+    let invalid = || io::Error::new(io::ErrorKind::InvalidData, "invalid number".to_string());
+    let mut fields = buffer.split_whitespace();
+    let mut vertex = || fields.next().and_then(|s| s.parse().ok()).ok_or_else(invalid);
+    let (u, v) = (vertex()?, vertex()?);
+    let weight = match fields.next() {
+        Some(s) => Some(s.parse().map_err(|_| invalid())?),
+        None => None
+    };
+    Ok(Some((u, v, weight)))
+}
@@ -0,0 +1,306 @@
+use disjoint_sets::DisjointSets;
+use graph::{Graph, HashMap, HashSet, Vertex, Weight};
+use random::Random;
+
+// the contraction algorithm used by randomized_min_cut
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum KargerVariant {
+    // every trial contracts the graph down to two nodes, finding a minimum cut with
+    // probability at least 2 / (n (n - 1))
+    Plain,
+    // every trial contracts the graph to about n / sqrt(2) nodes twice independently and
+    // recurses on both, finding a minimum cut with probability in Omega(1 / log n) at the
+    // cost of O(n^2 log n) contractions
+    Stein
+}
+
+// the lightest cut found by randomized_min_cut, with the vertices on one of its sides and a
+// lower bound on the probability that it is a minimum cut
+pub struct RandomizedCut {
+    pub weight: Weight,
+    pub side: HashSet<Vertex>,
+    pub success_probability: f64
+}
+
+// nodes standing for groups of original vertices, by their index, after contracting edges,
+// with the total weight of the edges between each pair of nodes
+struct Contracted {
+    groups: Vec<Vec<usize>>,
+    edges: Vec<(usize, usize, Weight)>
+}
+
+impl Graph {
+
+    // computes a minimum cut of the whole graph using the Stoer-Wagner algorithm; edge
+    // directions are ignored and edge weights must not be negative
+    // returns the weight of the cut and the vertices on one of its sides
+    pub fn global_min_cut(&self) -> (Weight, HashSet<Vertex>) {
+        let (vertices, mut w) = self.weight_matrix();
+        let n = vertices.len();
+        if n < 2 {
+            return (0.0, HashSet::new());
+        }
+
+        // every node of the contracted graph stands for a group of original vertices
+        let mut groups: Vec<Vec<Vertex>> = vertices.iter().map(|v| vec![*v]).collect();
+        let mut active: Vec<usize> = (0..n).collect();
+        let mut best = (Weight::INFINITY, vec![]);
+        while active.len() > 1 {
+            // minimum cut phase: grow a set by adding the most tightly connected node, the
+            // cut between the last node added and all the others is a minimum s-t cut
+            let mut key = vec![0.0; n];
+            let mut added = vec![false; n];
+            let mut previous = active[0];
+            for k in 0..active.len() {
+                let next = *active.iter()
+                    .filter(|i| !added[**i])
+                    .max_by(|a, b| key[**a].partial_cmp(&key[**b]).unwrap())
+                    .unwrap();
+                if k == active.len() - 1 {
+                    if key[next] < best.0 {
+                        best = (key[next], groups[next].clone());
+                    }
+                    // merge the last two nodes added
+                    let merged: Vec<Weight> = w[previous].iter().zip(&w[next]).map(|(a, b)| a + b).collect();
+                    for (i, weight) in merged.iter().enumerate() {
+                        w[i][previous] = *weight;
+                    }
+                    w[previous] = merged;
+                    let group = groups[next].split_off(0);
+                    groups[previous].extend(group);
+                    active.retain(|i| *i != next);
+                } else {
+                    added[next] = true;
+                    for i in &active {
+                        key[*i] += w[next][*i];
+                    }
+                    previous = next;
+                }
+            }
+        }
+        (best.0, best.1.into_iter().collect())
+    }
+
+    // computes a small cut of the whole graph using Karger's randomized contraction: each
+    // trial contracts randomly chosen edges, with probability proportional to their weight,
+    // until only two nodes remain; returns the lightest cut found over all trials
+    pub fn global_min_cut_karger(&self, trials: usize, seed: u64) -> (Weight, HashSet<Vertex>) {
+        let mut vertices: Vec<Vertex> = self.vertices().iter().cloned().collect();
+        vertices.sort();
+        let index: HashMap<Vertex, usize> = vertices.iter().enumerate().map(|(i, v)| (*v, i)).collect();
+        let mut edges: Vec<(usize, usize, Weight)> = self.edges().iter()
+            .filter(|&&(v, w)| v != w)
+            .map(|edge| (index[&edge.0], index[&edge.1], self.weight(edge)))
+            .collect();
+        edges.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        if vertices.len() < 2 {
+            return (0.0, HashSet::new());
+        }
+
+        let mut random = Random::new(seed);
+        let mut best = (Weight::INFINITY, HashSet::new());
+        for _ in 0..trials {
+            // contracting edges in the order of exponentially distributed keys with rate
+            // equal to their weight picks each next edge proportionally to its weight
+            let mut order: Vec<(f64, usize)> = edges.iter().enumerate()
+                .map(|(i, edge)| (-(1.0 - random.next_f64()).ln() / edge.2, i))
+                .collect();
+            order.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+            let mut sets = DisjointSets::new(vertices.len());
+            let mut nodes = vertices.len();
+            for (_, i) in order {
+                if nodes == 2 {
+                    break;
+                }
+                if sets.union(edges[i].0, edges[i].1) {
+                    nodes -= 1;
+                }
+            }
+            // a disconnected graph may leave more than two nodes, any of them is a cut; the sum
+            // starts from 0 rather than -0 so that an empty cut prints as 0
+            let side = sets.find(0);
+            let cut = edges.iter()
+                .filter(|&&(v, w, _)| (sets.find(v) == side) != (sets.find(w) == side))
+                .map(|edge| edge.2)
+                .fold(0.0, |cut, weight| cut + weight);
+            if cut < best.0 {
+                let members = (0..vertices.len()).filter(|i| sets.find(*i) == side).map(|i| vertices[i]).collect();
+                best = (cut, members);
+            }
+        }
+        best
+    }
+
+    // computes a small cut of the whole graph with the given number of independent trials
+    // of the variant of Karger's randomized contraction; edge directions are ignored and
+    // edge weights must not be negative. The success probability bounds the chance that
+    // some trial found a minimum cut, so more trials make a wrong answer unlikely: e.g.
+    // n (n - 1) / 2 * ln(1 / delta) plain trials fail with probability at most delta; at
+    // least one trial is run even if none are asked for
+    pub fn randomized_min_cut(&self, variant: KargerVariant, trials: usize, seed: u64) -> RandomizedCut {
+        let n = self.vertices().len();
+        let trials = trials.max(1);
+        let (weight, side) = match variant {
+            KargerVariant::Plain => self.global_min_cut_karger(trials, seed),
+            KargerVariant::Stein => self.global_min_cut_karger_stein(trials, seed)
+        };
+        // a cut of weight 0 cannot be beaten
+        let success = if n < 2 || weight == 0.0 {
+            1.0
+        } else {
+            let trial = match variant {
+                KargerVariant::Plain => 2.0 / (n as f64 * (n as f64 - 1.0)),
+                KargerVariant::Stein => karger_stein_success(n)
+            };
+            1.0 - (1.0 - trial).powi(trials.min(i32::MAX as usize) as i32)
+        };
+        RandomizedCut { weight, side, success_probability: success }
+    }
+
+    // computes a small cut of the whole graph using the recursive contraction of Karger and
+    // Stein; returns the lightest cut found over all trials
+    pub fn global_min_cut_karger_stein(&self, trials: usize, seed: u64) -> (Weight, HashSet<Vertex>) {
+        let (vertices, index) = self.indexed_vertices();
+        if vertices.len() < 2 {
+            return (0.0, HashSet::new());
+        }
+        let mut edges: Vec<(usize, usize, Weight)> = self.edges().iter()
+            .filter(|&&(v, w)| v != w)
+            .map(|edge| (index[&edge.0], index[&edge.1], self.weight(edge)))
+            .collect();
+        edges.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let graph = Contracted { groups: (0..vertices.len()).map(|i| vec![i]).collect(), edges };
+
+        let mut random = Random::new(seed);
+        let mut best = (Weight::INFINITY, vec![]);
+        for _ in 0..trials {
+            let cut = recursive_contraction(&graph, &mut random);
+            if cut.0 < best.0 {
+                best = cut;
+            }
+        }
+        (best.0, best.1.into_iter().map(|i| vertices[i]).collect())
+    }
+
+    // the total weight of the edges between the given vertices and all others, ignoring
+    // edge directions, e.g. to compare the same cut in a graph and in a sparsifier of it
+    pub fn cut_weight(&self, side: &HashSet<Vertex>) -> Weight {
+        self.edges().iter()
+            .filter(|&&(v, w)| side.contains(&v) != side.contains(&w))
+            .map(|edge| self.weight(edge))
+            .fold(0.0, |cut, weight| cut + weight)
+    }
+
+    // builds a dense symmetric matrix of the edge weights between all pairs of vertices,
+    // indexed by the position of the vertices in the returned list
+    fn weight_matrix(&self) -> (Vec<Vertex>, Vec<Vec<Weight>>) {
+        let mut vertices: Vec<Vertex> = self.vertices().iter().cloned().collect();
+        vertices.sort();
+        let index: HashMap<Vertex, usize> = vertices.iter().enumerate().map(|(i, v)| (*v, i)).collect();
+        let mut w = vec![vec![0.0; vertices.len()]; vertices.len()];
+        for edge in self.edges().iter().filter(|&&(v, w)| v != w) {
+            let (i, j) = (index[&edge.0], index[&edge.1]);
+            w[i][j] += self.weight(edge);
+            w[j][i] += self.weight(edge);
+        }
+        (vertices, w)
+    }
+}
+
+// the probability that a trial of the recursive contraction on n nodes finds a given
+// minimum cut is at least p(n) = 1 - (1 - q p(t))^2, where q bounds the probability that
+// contracting to t nodes keeps the cut, and 1 for the graphs small enough to be solved
+// exactly
+fn karger_stein_success(n: usize) -> f64 {
+    if n <= EXACT_CUT_NODES {
+        return 1.0;
+    }
+    let t = contraction_target(n);
+    let kept = (t * (t - 1)) as f64 / (n * (n - 1)) as f64;
+    1.0 - (1.0 - kept * karger_stein_success(t)).powi(2)
+}
+
+// graphs with at most this many nodes get their minimum cut by trying all partitions
+const EXACT_CUT_NODES: usize = 6;
+
+fn contraction_target(n: usize) -> usize {
+    (1.0 + n as f64 / 2.0_f64.sqrt()).ceil() as usize
+}
+
+// one trial of the recursive contraction, returning the weight of the cut found and the
+// original vertices on one side
+fn recursive_contraction(graph: &Contracted, random: &mut Random) -> (Weight, Vec<usize>) {
+    let n = graph.groups.len();
+    if graph.edges.is_empty() {
+        // the nodes are not connected, so any of them is a cut
+        return (0.0, graph.groups[0].clone());
+    }
+    if n <= EXACT_CUT_NODES {
+        return exact_cut(graph);
+    }
+    let t = contraction_target(n);
+    let first = recursive_contraction(&contract(graph, t, random), random);
+    let second = recursive_contraction(&contract(graph, t, random), random);
+    if second.0 < first.0 { second } else { first }
+}
+
+// contracts randomly chosen edges, with probability proportional to their weight, until
+// only the given number of nodes remain or no edges are left
+fn contract(graph: &Contracted, nodes: usize, random: &mut Random) -> Contracted {
+    // as in global_min_cut_karger, exponentially distributed keys pick each next edge
+    // proportionally to its weight
+    let mut order: Vec<(f64, usize)> = graph.edges.iter().enumerate()
+        .map(|(i, edge)| (-(1.0 - random.next_f64()).ln() / edge.2, i))
+        .collect();
+    order.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let mut sets = DisjointSets::new(graph.groups.len());
+    let mut remaining = graph.groups.len();
+    for (_, i) in order {
+        if remaining == nodes {
+            break;
+        }
+        if sets.union(graph.edges[i].0, graph.edges[i].1) {
+            remaining -= 1;
+        }
+    }
+
+    let mut label = HashMap::new();
+    let mut groups: Vec<Vec<usize>> = vec![];
+    for (i, group) in graph.groups.iter().enumerate() {
+        let root = sets.find(i);
+        let next = label.len();
+        let node = *label.entry(root).or_insert(next);
+        if node == groups.len() {
+            groups.push(vec![]);
+        }
+        groups[node].extend(group);
+    }
+    let mut weights: HashMap<(usize, usize), Weight> = HashMap::new();
+    for &(v, w, weight) in &graph.edges {
+        let (a, b) = (label[&sets.find(v)], label[&sets.find(w)]);
+        if a != b {
+            *weights.entry((a.min(b), a.max(b))).or_insert(0.0) += weight;
+        }
+    }
+    let mut edges: Vec<(usize, usize, Weight)> = weights.into_iter().map(|((a, b), weight)| (a, b, weight)).collect();
+    edges.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    Contracted { groups, edges }
+}
+
+// the minimum cut of a small contracted graph, trying every partition of its nodes
+fn exact_cut(graph: &Contracted) -> (Weight, Vec<usize>) {
+    let n = graph.groups.len();
+    let mut best = (Weight::INFINITY, 0);
+    // the last node always stays on the other side, so every partition is tried once
+    for side in 1..1usize << (n - 1) {
+        let cut = graph.edges.iter()
+            .filter(|&&(v, w, _)| (side >> v & 1) != (side >> w & 1))
+            .map(|edge| edge.2)
+            .fold(0.0, |cut, weight| cut + weight);
+        if cut < best.0 {
+            best = (cut, side);
+        }
+    }
+    (best.0, (0..n).filter(|i| best.1 >> i & 1 == 1).flat_map(|i| graph.groups[i].clone()).collect())
+}
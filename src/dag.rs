@@ -0,0 +1,405 @@
+use std::cmp::Reverse;
+use std::collections::BTreeSet;
+use std::collections::BinaryHeap;
+
+use graph::{Adjacencies, Edge, Graph, HashMap, Vertex, Weight};
+use preconditions::PreconditionError;
+use shortest_paths::{trace_path, Path};
+
+// the schedule of the tasks of a project computed by critical_path, with the times at which
+// each task can start at the earliest and at the latest without delaying the project
+pub struct Schedule {
+    pub earliest: HashMap<Vertex, Weight>,
+    pub latest: HashMap<Vertex, Weight>,
+    // latest less earliest start, i.e. by how much a task can be delayed
+    pub slack: HashMap<Vertex, Weight>,
+    // the time at which the last task starts
+    pub length: Weight,
+    // a longest chain of tasks, none of which can be delayed
+    pub critical_path: Path
+}
+
+impl Graph {
+
+    // computes a topological order of a directed graph using Kahn's algorithm, i.e. by
+    // repeatedly removing a vertex without incoming edges; fails with one of the cycles if
+    // there are any
+    pub fn topological_order(&self) -> Result<Vec<Vertex>, PreconditionError> {
+        let adj = self.adjacencies();
+        let mut in_degree: HashMap<Vertex, usize> = self.vertices().iter().map(|v| (*v, 0)).collect();
+        for adjacent in adj.values() {
+            for w in adjacent {
+                *in_degree.get_mut(w).unwrap() += 1;
+            }
+        }
+        let mut sources: Vec<Vertex> = in_degree.iter().filter(|&(_, d)| *d == 0).map(|(v, _)| *v).collect();
+        let mut order = vec![];
+        while let Some(v) = sources.pop() {
+            order.push(v);
+            for w in &adj[&v] {
+                let d = in_degree.get_mut(w).unwrap();
+                *d -= 1;
+                if *d == 0 {
+                    sources.push(*w);
+                }
+            }
+        }
+        if order.len() == self.vertices().len() { Ok(order) } else { Err(PreconditionError::Cycle(self.cycle_among(&in_degree))) }
+    }
+
+    // computes the lexicographically smallest topological order of a directed graph, using
+    // Kahn's algorithm with a min-heap so that the smallest available vertex always comes
+    // next; fails with one of the cycles if there are any
+    pub fn topological_order_lex_smallest(&self) -> Result<Vec<Vertex>, PreconditionError> {
+        let adj = self.adjacencies();
+        let mut in_degree: HashMap<Vertex, usize> = self.vertices().iter().map(|v| (*v, 0)).collect();
+        for w in adj.values().flatten() {
+            *in_degree.get_mut(w).unwrap() += 1;
+        }
+        let mut sources: BinaryHeap<Reverse<Vertex>> = in_degree.iter()
+            .filter(|&(_, d)| *d == 0)
+            .map(|(v, _)| Reverse(*v))
+            .collect();
+        let mut order = vec![];
+        while let Some(Reverse(v)) = sources.pop() {
+            order.push(v);
+            for w in &adj[&v] {
+                let d = in_degree.get_mut(w).unwrap();
+                *d -= 1;
+                if *d == 0 {
+                    sources.push(Reverse(*w));
+                }
+            }
+        }
+        if order.len() == self.vertices().len() { Ok(order) } else { Err(PreconditionError::Cycle(self.cycle_among(&in_degree))) }
+    }
+
+    // computes the shortest paths from source to all reachable vertices of a directed acyclic
+    // graph in linear time by relaxing the edges in topological order; negative weights are fine
+    // returns the distances and the predecessor of each vertex on its path, or None if the
+    // graph contains a cycle
+    pub fn dag_shortest_paths(&self, source: Vertex) -> Option<(HashMap<Vertex, Weight>, HashMap<Vertex, Vertex>)> {
+        self.dag_paths(&[source], false)
+    }
+
+    // computes the longest paths from source to all reachable vertices of a directed acyclic
+    // graph, see dag_shortest_paths
+    pub fn dag_longest_paths(&self, source: Vertex) -> Option<(HashMap<Vertex, Weight>, HashMap<Vertex, Vertex>)> {
+        self.dag_paths(&[source], true)
+    }
+
+    // finds the critical path of a directed acyclic graph, i.e. the longest path between any
+    // two vertices; returns its length and the path itself, or None if the graph contains a cycle
+    pub fn longest_path(&self) -> Option<(Weight, Path)> {
+        let sources: Vec<Vertex> = self.vertices().iter().cloned().collect();
+        let (distance, previous) = self.dag_paths(&sources, true)?;
+        distance.iter()
+            .max_by(|a, b| a.1.total_cmp(b.1).then_with(|| b.0.cmp(a.0)))
+            .map(|(v, d)| (*d, trace_path(&previous, *v)))
+    }
+
+    // schedules the tasks of a project with the critical path method, where the vertices of
+    // a directed acyclic graph are the tasks and an edge from u to v with weight w means
+    // that v can start no earlier than w after the start of u, e.g. when w is the duration
+    // of u: a forward pass in topological order finds the earliest start of every task,
+    // starting from 0, and a backward pass the latest start that still lets every later
+    // task start in time, with no task starting later than the last start of the project;
+    // tasks without slack are critical; fails with one of the cycles if there are any
+    pub fn critical_path(&self) -> Result<Schedule, PreconditionError> {
+        let order = self.topological_order()?;
+        let sources: Vec<Vertex> = self.vertices().iter().cloned().collect();
+        let (earliest, previous) = self.dag_paths(&sources, true).unwrap();
+        let end = earliest.iter()
+            .max_by(|a, b| a.1.total_cmp(b.1).then_with(|| b.0.cmp(a.0)))
+            .map(|(v, _)| *v);
+        let length = end.map_or(0.0, |v| earliest[&v]);
+
+        let adj = self.weighted_adjacencies();
+        let mut latest: HashMap<Vertex, Weight> = HashMap::new();
+        for v in order.into_iter().rev() {
+            let start = adj[&v].iter().map(|(w, weight)| latest[w] - weight).fold(length, Weight::min);
+            latest.insert(v, start);
+        }
+        let slack = earliest.iter().map(|(v, start)| (*v, latest[v] - start)).collect();
+        let critical_path = end.map_or(vec![], |v| trace_path(&previous, v));
+        Ok(Schedule { earliest, latest, slack, length, critical_path })
+    }
+
+    // returns an iterator over all topological orders of a directed acyclic graph, in
+    // lexicographic order of the vertices; yields nothing if the graph contains a cycle
+    pub fn topological_orders(&self) -> TopologicalOrders {
+        let (vertices, adj) = self.indexed_adjacencies();
+        let mut in_degree = vec![0; vertices.len()];
+        for w in adj.iter().flatten() {
+            in_degree[*w] += 1;
+        }
+        TopologicalOrders {
+            used: vec![false; vertices.len()], order: vec![], choices: vec![], started: false,
+            vertices, adj, in_degree
+        }
+    }
+
+    // counts the topological orders of a directed acyclic graph without enumerating them,
+    // using dynamic programming over the subsets of vertices that can form a prefix of an
+    // order; returns None for graphs with more than 20 vertices, where the table of 2^n
+    // counts would take more than 16 MB
+    pub fn count_topological_orders(&self) -> Option<u128> {
+        let (vertices, adj) = self.indexed_adjacencies();
+        let n = vertices.len();
+        if n > 20 {
+            return None;
+        }
+        let mut predecessors = vec![0usize; n];
+        for (v, adjacent) in adj.iter().enumerate() {
+            for w in adjacent {
+                predecessors[*w] |= 1 << v;
+            }
+        }
+        let mut count = vec![0u128; 1 << n];
+        count[0] = 1;
+        for prefix in 0..(1usize << n) {
+            if count[prefix] == 0 {
+                continue;
+            }
+            for (v, p) in predecessors.iter().enumerate() {
+                if prefix & (1 << v) == 0 && p & prefix == *p {
+                    count[prefix | (1 << v)] += count[prefix];
+                }
+            }
+        }
+        Some(count[(1 << n) - 1])
+    }
+
+    // assigns every vertex of a directed acyclic graph a layer such that every edge leads to
+    // a strictly higher one, as the first stage of a layered drawing or for the stages of a
+    // pipeline: the longest-path layering puts the sources at layer 0 and every other vertex
+    // right above its highest predecessor, its layer being the number of edges of a longest
+    // path to it, which uses as few layers as possible; fails with one of the cycles if
+    // there are any
+    pub fn layering(&self) -> Result<HashMap<Vertex, usize>, PreconditionError> {
+        let order = self.topological_order()?;
+        let adj = self.adjacencies();
+        let mut layer: HashMap<Vertex, usize> = self.vertices().iter().map(|v| (*v, 0)).collect();
+        for v in order {
+            let above = layer[&v] + 1;
+            for w in &adj[&v] {
+                let current = layer.get_mut(w).unwrap();
+                *current = (*current).max(above);
+            }
+        }
+        Ok(layer)
+    }
+
+    // checks a layering such as one from layering, returning the smallest edge that does not
+    // lead to a strictly higher layer, or whose ends lack a layer, or None if there is none
+    pub fn layering_violation(&self, layer: &HashMap<Vertex, usize>) -> Option<Edge> {
+        let mut edges: Vec<&Edge> = self.edges().iter().collect();
+        edges.sort();
+        edges.into_iter()
+            .find(|(v, w)| match (layer.get(v), layer.get(w)) {
+                (Some(from), Some(to)) => from >= to,
+                _ => true
+            })
+            .cloned()
+    }
+
+    // lists the vertices in sorted order together with the adjacency lists over their
+    // positions in that list, which is more convenient for dense bookkeeping
+    fn indexed_adjacencies(&self) -> (Vec<Vertex>, Vec<Vec<usize>>) {
+        let mut vertices: Vec<Vertex> = self.vertices().iter().cloned().collect();
+        vertices.sort();
+        let index: HashMap<Vertex, usize> = vertices.iter().enumerate().map(|(i, v)| (*v, i)).collect();
+        let adj = self.adjacencies();
+        let lists = vertices.iter()
+            .map(|v| {
+                let mut adjacent: Vec<usize> = adj[v].iter().map(|w| index[w]).collect();
+                adjacent.sort();
+                adjacent
+            })
+            .collect();
+        (vertices, lists)
+    }
+
+    // relaxes the edges of a directed acyclic graph in topological order, starting with
+    // distance 0 at each of the given sources
+    fn dag_paths(&self, sources: &[Vertex], longest: bool) -> Option<(HashMap<Vertex, Weight>, HashMap<Vertex, Vertex>)> {
+        let order = self.topological_order().ok()?;
+        let adj = self.weighted_adjacencies();
+        let mut distance: HashMap<Vertex, Weight> = sources.iter().map(|v| (*v, 0.0)).collect();
+        let mut previous = HashMap::new();
+        for v in order {
+            let d = match distance.get(&v) {
+                Some(d) => *d,
+                None => continue
+            };
+            for (w, weight) in &adj[&v] {
+                let alt = d + weight;
+                let better = distance.get(w).is_none_or(|current| if longest { alt > *current } else { alt < *current });
+                if better {
+                    distance.insert(*w, alt);
+                    previous.insert(*w, v);
+                }
+            }
+        }
+        Some((distance, previous))
+    }
+
+    // computes a small feedback arc set of a directed graph using the Eades-Lin-Smyth heuristic
+    // returns the feedback arcs together with a topological order of the acyclic graph that
+    // remains once they are removed; self-loops are always part of the feedback arc set
+    pub fn feedback_arc_set(&self) -> (Vec<Edge>, Vec<Vertex>) {
+        let mut succ = self.adjacencies();
+        let mut pred = self.reverse_adjacencies();
+        for (v, adjacent) in succ.iter_mut().chain(pred.iter_mut()) {
+            adjacent.remove(v);
+        }
+
+        let mut remaining: BTreeSet<Vertex> = self.vertices().iter().cloned().collect();
+        let mut sinks: Vec<Vertex> = remaining.iter().filter(|v| succ[v].is_empty()).cloned().collect();
+        let mut sources: Vec<Vertex> = remaining.iter().filter(|v| pred[v].is_empty()).cloned().collect();
+        let mut head = vec![];
+        let mut tail = vec![];
+
+        while !remaining.is_empty() {
+            // sinks go to the end of the ordering, sources to the front; if there are
+            // neither pick the vertex with the largest difference of out- and in-degree
+            let v = if let Some(v) = sinks.pop() {
+                if !remaining.contains(&v) { continue; }
+                tail.push(v);
+                v
+            } else if let Some(v) = sources.pop() {
+                if !remaining.contains(&v) { continue; }
+                head.push(v);
+                v
+            } else {
+                let delta = |v: &Vertex| succ[v].len() as i64 - pred[v].len() as i64;
+                let v = *remaining.iter().rev().max_by_key(|v| delta(v)).unwrap();
+                head.push(v);
+                v
+            };
+            remaining.remove(&v);
+            remove_vertex(v, &mut succ, &mut pred, &mut sources);
+            remove_vertex(v, &mut pred, &mut succ, &mut sinks);
+        }
+
+        tail.reverse();
+        head.append(&mut tail);
+        let position: HashMap<Vertex, usize> = head.iter().enumerate().map(|(i, v)| (*v, i)).collect();
+        let mut arcs: Vec<Edge> = self.edges().iter()
+            .filter(|&&(v, w)| position[&v] >= position[&w])
+            .cloned()
+            .collect();
+        arcs.sort();
+        (arcs, head)
+    }
+}
+
+// iterator over all topological orders of a directed acyclic graph, produced by
+// backtracking over the choice of source vertex at every position of the order
+pub struct TopologicalOrders {
+    vertices: Vec<Vertex>,
+    adj: Vec<Vec<usize>>,
+    in_degree: Vec<usize>,
+    used: Vec<bool>,
+    order: Vec<usize>,
+    // for every position of the order the candidate vertices and which one is used
+    choices: Vec<(Vec<usize>, usize)>,
+    started: bool
+}
+
+impl TopologicalOrders {
+
+    fn place(&mut self, v: usize) {
+        self.used[v] = true;
+        self.order.push(v);
+        for w in &self.adj[v] {
+            self.in_degree[*w] -= 1;
+        }
+    }
+
+    fn unplace(&mut self) {
+        let v = self.order.pop().unwrap();
+        self.used[v] = false;
+        for w in &self.adj[v] {
+            self.in_degree[*w] += 1;
+        }
+    }
+
+    // completes the current partial order by always picking the first candidate;
+    // returns false if there is no candidate left, which only happens for cyclic graphs
+    fn descend(&mut self) -> bool {
+        while self.order.len() < self.vertices.len() {
+            let candidates: Vec<usize> = (0..self.vertices.len())
+                .filter(|v| !self.used[*v] && self.in_degree[*v] == 0)
+                .collect();
+            if candidates.is_empty() {
+                return false;
+            }
+            self.place(candidates[0]);
+            self.choices.push((candidates, 0));
+        }
+        true
+    }
+
+    fn current(&self) -> Vec<Vertex> {
+        self.order.iter().map(|v| self.vertices[*v]).collect()
+    }
+}
+
+impl Iterator for TopologicalOrders {
+    type Item = Vec<Vertex>;
+
+    fn next(&mut self) -> Option<Vec<Vertex>> {
+        if !self.started {
+            self.started = true;
+            if !self.descend() {
+                self.choices.clear();
+                return None;
+            }
+            return Some(self.current());
+        }
+        // backtrack to the last position that has another candidate left
+        while !self.choices.is_empty() {
+            self.unplace();
+            let next = {
+                let (candidates, i) = self.choices.last_mut().unwrap();
+                *i += 1;
+                candidates.get(*i).cloned()
+            };
+            match next {
+                Some(v) => {
+                    self.place(v);
+                    if self.descend() {
+                        return Some(self.current());
+                    }
+                },
+                None => { self.choices.pop(); }
+            }
+        }
+        None
+    }
+}
+
+// removes vertex v from the `from` adjacency map and from the neighbour lists of the `to`
+// adjacency map, collecting all neighbours that are left without any adjacent vertices
+fn remove_vertex(v: Vertex, from: &mut Adjacencies, to: &mut Adjacencies, emptied: &mut Vec<Vertex>) {
+    for w in from.remove(&v).unwrap() {
+        let adjacent = to.get_mut(&w).unwrap();
+        adjacent.remove(&v);
+        if adjacent.is_empty() {
+            emptied.push(w);
+        }
+    }
+}
+
+// the vertices of every layer of a layering, from layer 0 up, each in ascending order
+pub fn layers(layer: &HashMap<Vertex, usize>) -> Vec<Vec<Vertex>> {
+    let mut layers = vec![vec![]; layer.values().max().map_or(0, |top| top + 1)];
+    for (v, l) in layer {
+        layers[*l].push(*v);
+    }
+    for members in &mut layers {
+        members.sort();
+    }
+    layers
+}
@@ -0,0 +1,164 @@
+use std::collections::BTreeSet;
+
+use graph::{Edge, Graph, HashSet, Vertex};
+use random::Random;
+
+impl Graph {
+
+    // the degrees of the vertices in ascending order of the vertices, counting edges in
+    // both directions for directed graphs and self-loops twice, as in stats
+    pub fn degree_sequence(&self) -> Vec<usize> {
+        let (vertices, index) = self.indexed_vertices();
+        let mut degree = vec![0; vertices.len()];
+        for edge in self.edges() {
+            degree[index[&edge.0]] += 1;
+            degree[index[&edge.1]] += 1;
+        }
+        degree
+    }
+
+    // randomizes the graph by double edge swaps, keeping the degree of every vertex, or its
+    // in and out degrees if directed: each of the iterations picks two edges a - b and c - d
+    // at random using the seed and replaces them by a - d and c - b, or for undirected graphs
+    // equally likely by a - c and b - d, unless that would create a self-loop or an edge the
+    // graph already has; every edge keeps its weight for the end it keeps. About ten swaps
+    // per edge give a null model with the same degrees for comparing e.g. clustering with
+    pub fn rewire(&self, iterations: usize, seed: u64) -> Graph {
+        let mut random = Random::new(seed);
+        let mut graph = self.clone();
+        let mut edges: Vec<Edge> = self.edges().iter().cloned().collect();
+        edges.sort();
+        if edges.len() < 2 {
+            return graph;
+        }
+        for _ in 0..iterations {
+            let (i, j) = (random.below(edges.len()), random.below(edges.len()));
+            let ((a, b), (mut c, mut d)) = (edges[i], edges[j]);
+            if !self.is_directed() && random.below(2) == 1 {
+                (c, d) = (d, c);
+            }
+            if i == j || a == d || c == b || graph.has_edge(a, d) || graph.has_edge(c, b) {
+                continue;
+            }
+            let (first, second) = (graph.weight(&edges[i]), graph.weight(&edges[j]));
+            graph.remove_edge(a, b);
+            graph.remove_edge(c, d);
+            if self.is_weighted() {
+                graph.add_weighted_edge(a, d, first);
+                graph.add_weighted_edge(c, b, second);
+            } else {
+                graph.add_edge(a, d);
+                graph.add_edge(c, b);
+            }
+            edges[i] = (a, d);
+            edges[j] = (c, b);
+        }
+        graph
+    }
+}
+
+// returns true if the sequence is the degree sequence of a simple undirected graph, by the
+// theorem of Erdős and Gallai: the sum must be even, and the k largest degrees must not
+// add up to more than the k (k - 1) ends of the edges among them plus, for every other
+// vertex, the edges it can have to them, the smaller of its degree and k
+pub fn is_graphical(sequence: &[usize]) -> bool {
+    let mut degrees = sequence.to_vec();
+    degrees.sort_by(|a, b| b.cmp(a));
+    let n = degrees.len();
+    let mut prefix = vec![0; n + 1];
+    for i in 0..n {
+        prefix[i + 1] = prefix[i] + degrees[i];
+    }
+    if prefix[n] % 2 == 1 {
+        return false;
+    }
+    // the number of degrees of at least k, which only shrinks as k grows
+    let mut large = n;
+    for k in 1..n + 1 {
+        while large > 0 && degrees[large - 1] < k {
+            large -= 1;
+        }
+        let capped = if large > k { k * (large - k) + prefix[n] - prefix[large] } else { prefix[n] - prefix[k] };
+        if prefix[k] > k * (k - 1) + capped {
+            return false;
+        }
+    }
+    true
+}
+
+// a simple undirected graph on the vertices 1..n in which vertex i has the i-th degree of
+// the sequence, built by the algorithm of Havel and Hakimi: the vertex of largest remaining
+// degree is joined to the vertices of the next largest ones, which always succeeds for a
+// graphical sequence; returns None if the sequence is not graphical. The graph built is
+// deterministic and far from random, see configuration_model for that
+pub fn realize_degree_sequence(sequence: &[usize]) -> Option<Graph> {
+    let mut graph = Graph::new((1..sequence.len() as Vertex + 1).collect(), HashSet::new(), false);
+    // the vertices with degree left, by their remaining degree and then their number
+    let mut remaining: BTreeSet<(usize, Vertex)> = sequence.iter().enumerate()
+        .filter(|(_, d)| **d > 0)
+        .map(|(i, d)| (*d, (i + 1) as Vertex))
+        .collect();
+    while let Some((d, v)) = remaining.pop_last() {
+        if remaining.len() < d {
+            return None;
+        }
+        let partners: Vec<(usize, Vertex)> = (0..d).map(|_| remaining.pop_last().unwrap()).collect();
+        for (e, w) in partners {
+            graph.add_edge(v, w);
+            if e > 1 {
+                remaining.insert((e - 1, w));
+            }
+        }
+    }
+    Some(graph)
+}
+
+// a random undirected graph on the vertices 1..n with about the given degrees, from the
+// erased configuration model: every vertex gets as many stubs as its degree, the stubs are
+// paired up uniformly at random using the seed, and the self-loops and repeated edges this
+// creates are dropped, which lowers the degrees of high degree vertices a little. Returns
+// None if the degrees add up to an odd number
+pub fn configuration_model(sequence: &[usize], seed: u64) -> Option<Graph> {
+    let mut stubs: Vec<Vertex> = sequence.iter().enumerate()
+        .flat_map(|(i, d)| std::iter::repeat_n((i + 1) as Vertex, *d))
+        .collect();
+    if stubs.len() % 2 == 1 {
+        return None;
+    }
+    Random::new(seed).shuffle(&mut stubs);
+    let mut graph = Graph::new((1..sequence.len() as Vertex + 1).collect(), HashSet::new(), false);
+    for pair in stubs.chunks(2).filter(|pair| pair[0] != pair[1]) {
+        graph.add_edge(pair[0], pair[1]);
+    }
+    Some(graph)
+}
+
+// a random graph on the vertices 1..n with m edges chosen uniformly at random among all
+// possible ones using the seed, without self-loops, or with all of them if m is more, as in
+// the G(n, m) model of Erdős and Rényi; see Graph::random_weights for weighting them
+pub fn random_graph(n: usize, m: usize, directed: bool, seed: u64) -> Graph {
+    let mut random = Random::new(seed);
+    let mut graph = Graph::new((1..n as Vertex + 1).collect(), HashSet::new(), directed);
+    let possible = if directed { n * n.saturating_sub(1) } else { n * n.saturating_sub(1) / 2 };
+    if 2 * m >= possible {
+        // dense: shuffle all pairs and take the first m
+        let mut pairs: Vec<Edge> = (1..n as Vertex + 1)
+            .flat_map(|v| (1..n as Vertex + 1).map(move |w| (v, w)))
+            .filter(|(v, w)| if directed { v != w } else { v < w })
+            .collect();
+        random.shuffle(&mut pairs);
+        for (v, w) in pairs.into_iter().take(m) {
+            graph.add_edge(v, w);
+        }
+        return graph;
+    }
+    // sparse: draw pairs until enough distinct ones are found, which takes at most twice
+    // as many draws on average
+    while graph.edges().len() < m {
+        let (v, w) = ((random.below(n) + 1) as Vertex, (random.below(n) + 1) as Vertex);
+        if v != w {
+            graph.add_edge(v, w);
+        }
+    }
+    graph
+}
@@ -0,0 +1,109 @@
+use std::collections::BTreeMap;
+use std::thread;
+
+use graph::{Graph, HashMap, Vertex, Weight};
+
+// a relaxation request: reach the vertex with the index in the given distance from the
+// vertex with the other index
+type Request = (usize, Weight, usize);
+
+impl Graph {
+
+    // computes the distances from source to all reachable vertices together with the
+    // predecessor of every vertex on a shortest path to it, like shortest_path_tree, using
+    // the delta-stepping algorithm: vertices are kept in buckets of distances delta wide,
+    // and all vertices of the first non-empty bucket are settled at once, with the edges
+    // leaving them relaxed in parallel by the given number of threads; edges lighter than
+    // delta may put vertices back into the current bucket, heavier edges are relaxed once
+    // the bucket is done; only the non-empty buckets are kept, so that heavy edges or a small
+    // delta cost no memory for the buckets skipped. Edge weights must not be negative and
+    // delta must be positive; both maps are empty if source is not in the graph
+    pub fn delta_stepping(&self, source: Vertex, delta: Weight, threads: usize) -> (HashMap<Vertex, Weight>, HashMap<Vertex, Vertex>) {
+        let (vertices, index) = self.indexed_vertices();
+        let mut adj: Vec<Vec<(usize, Weight)>> = vec![vec![]; vertices.len()];
+        for (v, adjacent) in self.weighted_adjacencies() {
+            adj[index[&v]] = adjacent.iter().map(|(w, weight)| (index[w], *weight)).collect();
+            adj[index[&v]].sort_by_key(|edge| edge.0);
+        }
+
+        let mut distance = vec![Weight::INFINITY; vertices.len()];
+        let mut previous: Vec<Option<usize>> = vec![None; vertices.len()];
+        let mut buckets: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        let mut in_phase = vec![false; vertices.len()];
+        let relax = |requests: Vec<Request>, distance: &mut Vec<Weight>, previous: &mut Vec<Option<usize>>, buckets: &mut BTreeMap<usize, Vec<usize>>| {
+            for (w, d, from) in requests {
+                if d < distance[w] {
+                    buckets.entry((d / delta) as usize).or_default().push(w);
+                    distance[w] = d;
+                    previous[w] = Some(from);
+                }
+            }
+        };
+        let s = match index.get(&source) {
+            Some(s) => *s,
+            None => return (HashMap::new(), HashMap::new())
+        };
+        distance[s] = 0.0;
+        buckets.insert(0, vec![s]);
+
+        while let Some(i) = buckets.keys().next().cloned() {
+            let mut settled = vec![];
+            while let Some(entries) = buckets.remove(&i) {
+                // entries of vertices whose distance has since dropped into an earlier bucket are stale
+                let mut current: Vec<usize> = entries.into_iter()
+                    .filter(|v| (distance[*v] / delta) as usize == i)
+                    .collect();
+                current.sort();
+                current.dedup();
+                for v in &current {
+                    if !in_phase[*v] {
+                        in_phase[*v] = true;
+                        settled.push(*v);
+                    }
+                }
+                let requests = requests(&current, &adj, &distance, threads, |weight| weight <= delta);
+                relax(requests, &mut distance, &mut previous, &mut buckets);
+            }
+            let requests = requests(&settled, &adj, &distance, threads, |weight| weight > delta);
+            relax(requests, &mut distance, &mut previous, &mut buckets);
+            for v in settled {
+                in_phase[v] = false;
+            }
+        }
+
+        let distances = (0..vertices.len())
+            .filter(|v| distance[*v] < Weight::INFINITY)
+            .map(|v| (vertices[v], distance[v]))
+            .collect();
+        let predecessors = (0..vertices.len())
+            .filter_map(|v| previous[v].map(|p| (vertices[v], vertices[p])))
+            .collect();
+        (distances, predecessors)
+    }
+}
+
+// collects the relaxation requests for the edges accepted by the filter leaving the given
+// vertices, splitting the vertices between the threads and listing the requests in order
+fn requests<F>(vertices: &[usize], adj: &[Vec<(usize, Weight)>], distance: &[Weight], threads: usize, filter: F) -> Vec<Request>
+    where F: Fn(Weight) -> bool + Sync {
+    let chunk = vertices.len().div_ceil(threads.max(1)).max(1);
+    thread::scope(|scope| {
+        let workers: Vec<_> = vertices.chunks(chunk)
+            .map(|vertices| {
+                let filter = &filter;
+                scope.spawn(move || {
+                    let mut requests = vec![];
+                    for v in vertices {
+                        for (w, weight) in &adj[*v] {
+                            if filter(*weight) {
+                                requests.push((*w, distance[*v] + weight, *v));
+                            }
+                        }
+                    }
+                    requests
+                })
+            })
+            .collect();
+        workers.into_iter().flat_map(|worker| worker.join().unwrap()).collect()
+    })
+}
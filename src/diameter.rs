@@ -0,0 +1,218 @@
+use std::collections::VecDeque;
+
+use graph::{Graph, HashMap, Vertex};
+
+// graphs with more vertices than this get their diameter from iFUB instead of from the
+// eccentricities of all vertices, which take a breadth first search from every vertex
+pub const EXACT_DIAMETER_LIMIT: usize = 1024;
+
+// bounds on the diameter of a graph, i.e. the largest number of edges on a shortest path
+// between two vertices connected to each other, with the number of breadth first searches
+// it took to find them
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diameter {
+    pub lower: u32,
+    pub upper: u32,
+    pub searches: usize
+}
+
+impl Diameter {
+
+    pub fn is_exact(&self) -> bool {
+        self.lower == self.upper
+    }
+}
+
+impl Graph {
+
+    // the eccentricity of every vertex, i.e. the number of edges on a shortest path to the
+    // farthest vertex connected to it, found by a breadth first search from every vertex;
+    // edge directions are ignored
+    pub fn eccentricities(&self) -> HashMap<Vertex, u32> {
+        let (vertices, adj) = self.dense_undirected();
+        let mut search = Search::new(vertices.len());
+        (0..vertices.len()).map(|v| (vertices[v], search.run(&adj, v))).collect()
+    }
+
+    // the diameter of the graph, ignoring edge directions: the largest eccentricity for
+    // graphs of up to EXACT_DIAMETER_LIMIT vertices, and from diameter_ifub otherwise
+    pub fn diameter(&self) -> u32 {
+        if self.vertices().len() <= EXACT_DIAMETER_LIMIT {
+            self.eccentricities().values().cloned().max().unwrap_or(0)
+        } else {
+            self.diameter_ifub(usize::MAX).upper
+        }
+    }
+
+    // bounds the diameter by a double sweep in every connected component large enough to
+    // matter: the eccentricity of the vertex farthest from a vertex of highest degree is a
+    // lower bound, which is often exact, and twice the eccentricity of that vertex of
+    // highest degree is an upper bound; takes two breadth first searches per component
+    pub fn diameter_double_sweep(&self) -> Diameter {
+        let (vertices, adj) = self.dense_undirected();
+        let mut search = Search::new(vertices.len());
+        let mut diameter = Diameter { lower: 0, upper: 0, searches: 0 };
+        for component in components(&adj, &mut search) {
+            if component.len() as u32 - 1 <= diameter.lower {
+                break;
+            }
+            let sweep = double_sweep(&adj, &component, &mut search);
+            diameter.lower = diameter.lower.max(sweep.lower);
+            diameter.upper = diameter.upper.max(sweep.upper);
+            diameter.searches += 2;
+        }
+        diameter.upper = diameter.upper.max(diameter.lower);
+        diameter
+    }
+
+    // computes the diameter with iFUB (Crescenzi et al.), ignoring edge directions: starting
+    // from a double sweep, a breadth first search from a vertex u in the middle of the
+    // longest path found spreads the vertices into levels by their distance to u; as two
+    // vertices at most i - 1 edges away from u are at most 2(i - 1) edges apart, the
+    // eccentricities of the vertices of the levels from the farthest one inwards settle the
+    // diameter once the largest of them exceeds that bound. This usually takes few searches
+    // on large sparse graphs, though there is no guarantee; after at most max_searches
+    // searches the bounds reached so far are returned
+    pub fn diameter_ifub(&self, max_searches: usize) -> Diameter {
+        let (vertices, adj) = self.dense_undirected();
+        let mut search = Search::new(vertices.len());
+        let mut diameter = Diameter { lower: 0, upper: 0, searches: 0 };
+        let components = components(&adj, &mut search);
+        for (i, component) in components.iter().enumerate() {
+            // the components come by decreasing size, and any remaining one has a diameter
+            // below its number of vertices
+            if component.len() as u32 - 1 <= diameter.lower {
+                break;
+            }
+            if diameter.searches + 3 > max_searches {
+                diameter.upper = diameter.upper.max(components[i..].iter().map(|c| c.len() as u32 - 1).max().unwrap());
+                break;
+            }
+            let bounds = ifub(&adj, component, &mut search, max_searches - diameter.searches);
+            diameter.lower = diameter.lower.max(bounds.lower);
+            diameter.upper = diameter.upper.max(bounds.upper);
+            diameter.searches += bounds.searches;
+        }
+        diameter.upper = diameter.upper.max(diameter.lower);
+        diameter
+    }
+
+    // the vertices in ascending order with the indices of the neighbours of each, ignoring
+    // edge directions and self-loops
+    pub fn dense_undirected(&self) -> (Vec<Vertex>, Vec<Vec<usize>>) {
+        let (vertices, index) = self.indexed_vertices();
+        let mut adj = vec![vec![]; vertices.len()];
+        for edge in self.edges().iter().filter(|edge| edge.0 != edge.1) {
+            let (u, v) = (index[&edge.0], index[&edge.1]);
+            adj[u].push(v);
+            adj[v].push(u);
+        }
+        (vertices, adj)
+    }
+}
+
+// breadth first search over dense vertex indices, reusing its arrays between searches so
+// that each search only pays for the component it explores
+struct Search {
+    distance: Vec<u32>,
+    parent: Vec<usize>,
+    // the vertices reached by the last search in the order they were reached
+    order: Vec<usize>
+}
+
+impl Search {
+
+    fn new(n: usize) -> Search {
+        Search { distance: vec![u32::MAX; n], parent: vec![0; n], order: vec![] }
+    }
+
+    // searches from the source, returning its eccentricity; the last vertex of order is
+    // one of the farthest
+    fn run(&mut self, adj: &[Vec<usize>], source: usize) -> u32 {
+        for v in self.order.drain(..) {
+            self.distance[v] = u32::MAX;
+        }
+        self.distance[source] = 0;
+        self.parent[source] = source;
+        self.order.push(source);
+        let mut queue = VecDeque::new();
+        queue.push_back(source);
+        while let Some(v) = queue.pop_front() {
+            for w in &adj[v] {
+                if self.distance[*w] == u32::MAX {
+                    self.distance[*w] = self.distance[v] + 1;
+                    self.parent[*w] = v;
+                    self.order.push(*w);
+                    queue.push_back(*w);
+                }
+            }
+        }
+        self.distance[*self.order.last().unwrap()]
+    }
+
+    fn farthest(&self) -> usize {
+        *self.order.last().unwrap()
+    }
+}
+
+// the connected components as lists of vertex indices, largest first
+fn components(adj: &[Vec<usize>], search: &mut Search) -> Vec<Vec<usize>> {
+    let mut seen = vec![false; adj.len()];
+    let mut components = vec![];
+    for v in 0..adj.len() {
+        if !seen[v] {
+            search.run(adj, v);
+            for w in &search.order {
+                seen[*w] = true;
+            }
+            components.push(search.order.clone());
+        }
+    }
+    components.sort_by_key(|component| std::cmp::Reverse(component.len()));
+    components
+}
+
+// sweeps the component twice from a vertex of highest degree, leaving the search from the
+// far end of the longest path found in search
+fn double_sweep(adj: &[Vec<usize>], component: &[usize], search: &mut Search) -> Diameter {
+    let start = *component.iter().max_by_key(|v| (adj[**v].len(), std::cmp::Reverse(**v))).unwrap();
+    let eccentricity = search.run(adj, start);
+    let lower = search.run(adj, search.farthest());
+    let upper = (2 * eccentricity).min(component.len() as u32 - 1);
+    Diameter { lower, upper, searches: 2 }
+}
+
+// runs iFUB on the component with at most the given number of searches, which must be at
+// least three
+fn ifub(adj: &[Vec<usize>], component: &[usize], search: &mut Search, max_searches: usize) -> Diameter {
+    let mut diameter = double_sweep(adj, component, search);
+    // the middle of the path from the far end found by the second sweep back to its start
+    let mut middle = search.farthest();
+    for _ in 0..diameter.lower / 2 {
+        middle = search.parent[middle];
+    }
+    let eccentricity = search.run(adj, middle);
+    diameter.searches += 1;
+    diameter.lower = diameter.lower.max(eccentricity);
+    diameter.upper = diameter.upper.min(2 * eccentricity);
+    let mut levels = vec![vec![]; eccentricity as usize + 1];
+    for v in &search.order {
+        levels[search.distance[*v] as usize].push(*v);
+    }
+
+    let mut i = eccentricity as usize;
+    while diameter.lower < diameter.upper && i > 0 {
+        for v in &levels[i] {
+            if diameter.searches == max_searches {
+                return diameter;
+            }
+            diameter.lower = diameter.lower.max(search.run(adj, *v));
+            diameter.searches += 1;
+        }
+        // any two vertices closer to the middle than level i are at most 2(i - 1) apart
+        diameter.upper = diameter.upper.min(diameter.lower.max(2 * (i as u32 - 1)));
+        i -= 1;
+    }
+    diameter.upper = diameter.upper.max(diameter.lower);
+    diameter
+}
@@ -0,0 +1,93 @@
+use std::fmt;
+
+use graph::{sorted_members, Edge, Graph, HashMap, Vertex, Weight};
+
+// the differences between two graphs found by Graph::diff, with all lists sorted; edges of
+// undirected graphs point from the smaller to the larger vertex
+pub struct GraphDiff {
+    pub added_vertices: Vec<Vertex>,
+    pub removed_vertices: Vec<Vertex>,
+    pub added_edges: Vec<Edge>,
+    pub removed_edges: Vec<Edge>,
+    // edges in both graphs with different weights, with the old and the new weight
+    pub reweighted_edges: Vec<(Edge, Weight, Weight)>,
+    // whether the vertices are split differently into (strongly) connected components
+    pub components_changed: bool
+}
+
+impl GraphDiff {
+
+    // true if the graphs have the same vertices and edges of the same weights
+    pub fn is_empty(&self) -> bool {
+        self.added_vertices.is_empty() && self.removed_vertices.is_empty() && self.added_edges.is_empty()
+            && self.removed_edges.is_empty() && self.reweighted_edges.is_empty()
+    }
+}
+
+// lists the changes one per line, prefixed with + for additions, - for removals and ~ for
+// changed weights
+impl fmt::Display for GraphDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for v in &self.added_vertices {
+            writeln!(f, "+ vertex {}", v)?;
+        }
+        for v in &self.removed_vertices {
+            writeln!(f, "- vertex {}", v)?;
+        }
+        for (u, v) in &self.added_edges {
+            writeln!(f, "+ edge {} {}", u, v)?;
+        }
+        for (u, v) in &self.removed_edges {
+            writeln!(f, "- edge {} {}", u, v)?;
+        }
+        for ((u, v), old, new) in &self.reweighted_edges {
+            writeln!(f, "~ edge {} {}: {} -> {}", u, v, old, new)?;
+        }
+        if self.components_changed {
+            writeln!(f, "~ components")?;
+        }
+        Ok(())
+    }
+}
+
+impl Graph {
+
+    // the edges with their weights, with the edges of undirected graphs pointing from the
+    // smaller to the larger vertex
+    fn keyed_weights(&self) -> HashMap<Edge, Weight> {
+        self.edges().iter()
+            .map(|edge| {
+                let key = if self.is_directed() { *edge } else { (edge.0.min(edge.1), edge.0.max(edge.1)) };
+                (key, self.weight(edge))
+            })
+            .collect()
+    }
+
+    // the (strongly) connected components as sorted lists, in order of their smallest vertex
+    fn sorted_components(&self) -> Vec<Vec<Vertex>> {
+        let components = if self.is_directed() { self.strongly_connected_components() } else { self.connected_components() };
+        let mut components = sorted_members(components);
+        components.sort();
+        components
+    }
+
+    // reports what changed from this graph to the other one, which should be directed if
+    // and only if this one is: the vertices and edges added and removed, the edges whose
+    // weight changed and whether the split into components changed
+    pub fn diff(&self, other: &Graph) -> GraphDiff {
+        let sorted = |mut items: Vec<Vertex>| { items.sort(); items };
+        let added_vertices = sorted(other.vertices().difference(self.vertices()).cloned().collect());
+        let removed_vertices = sorted(self.vertices().difference(other.vertices()).cloned().collect());
+        let (old, new) = (self.keyed_weights(), other.keyed_weights());
+        let mut added_edges: Vec<Edge> = new.keys().filter(|edge| !old.contains_key(edge)).cloned().collect();
+        let mut removed_edges: Vec<Edge> = old.keys().filter(|edge| !new.contains_key(edge)).cloned().collect();
+        let mut reweighted_edges: Vec<(Edge, Weight, Weight)> = old.iter()
+            .filter_map(|(edge, weight)| new.get(edge).filter(|w| *w != weight).map(|w| (*edge, *weight, *w)))
+            .collect();
+        added_edges.sort();
+        removed_edges.sort();
+        reweighted_edges.sort_by_key(|change| change.0);
+        let components_changed = self.sorted_components() != other.sorted_components();
+        GraphDiff { added_vertices, removed_vertices, added_edges, removed_edges, reweighted_edges, components_changed }
+    }
+}
@@ -0,0 +1,77 @@
+use std::io;
+use std::io::BufRead;
+
+use attributes::VertexMap;
+use graph::{Edge, Graph, HashMap, HashSet, Vertex, Weight};
+
+// the position of a vertex, e.g. longitude and latitude in millionths of a degree
+pub type Coordinates = (f64, f64);
+
+// reads the lines of a file in one of the DIMACS shortest path challenge formats, which
+// start with a letter telling the kind of line; comment lines ("c ...") are skipped, the
+// problem line ("p ...") and the lines of the given kind are passed on as their fields
+fn for_each_line<R, F>(reader: R, kind: &str, mut line: F) -> io::Result<Vec<String>>
+    where R: BufRead, F: FnMut(&[&str]) -> io::Result<()> {
+    let mut problem = None;
+    for text in reader.lines() {
+        let text = text?;
+        let fields: Vec<&str> = text.split_whitespace().collect();
+        match fields.first() {
+            Some(&"p") => problem = Some(fields.iter().map(|s| s.to_string()).collect()),
+            Some(first) if *first == kind => line(&fields)?,
+            _ => ()
+        }
+    }
+    problem.ok_or_else(|| invalid("missing problem line"))
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+fn field<T: ::std::str::FromStr>(fields: &[&str], i: usize) -> io::Result<T> {
+    fields.get(i).and_then(|s| s.parse().ok()).ok_or_else(|| invalid("invalid line"))
+}
+
+impl Graph {
+
+    // loads a directed weighted graph from a DIMACS shortest path file (.gr) with the
+    // problem line "p sp n m" followed by m arcs "a u v w" between the vertices 1..n; of
+    // several arcs between the same vertices, the lightest one is kept
+    pub fn load_dimacs<R: BufRead>(reader: R) -> io::Result<Graph> {
+        let mut edges = HashSet::new();
+        let mut weights: HashMap<Edge, Weight> = HashMap::new();
+        let problem = for_each_line(reader, "a", |fields| {
+            let edge = (field(fields, 1)?, field(fields, 2)?);
+            let weight: Weight = field(fields, 3)?;
+            edges.insert(edge);
+            let lightest = weights.entry(edge).or_insert(weight);
+            *lightest = lightest.min(weight);
+            Ok(())
+        })?;
+        let problem: Vec<&str> = problem.iter().map(|s| s.as_str()).collect();
+        if problem.get(1) != Some(&"sp") {
+            return Err(invalid("not a shortest path problem"));
+        }
+        let n: Vertex = field(&problem, 2)?;
+        if edges.iter().any(|&(u, v)| u < 1 || u > n || v < 1 || v > n) {
+            return Err(invalid("arc between unknown vertices"));
+        }
+        Ok(Graph::with_weights((1..n+1).collect(), edges, weights, true))
+    }
+
+    // loads the coordinates of the vertices from a DIMACS coordinate file (.co) with the
+    // problem line "p aux sp co n" followed by lines "v id x y"
+    pub fn load_dimacs_coordinates<R: BufRead>(&self, reader: R) -> io::Result<VertexMap<Coordinates>> {
+        let mut coordinates = self.vertex_map();
+        for_each_line(reader, "v", |fields| {
+            let v: Vertex = field(fields, 1)?;
+            if !self.vertices().contains(&v) {
+                return Err(invalid("coordinates of unknown vertex"));
+            }
+            coordinates.set(v, (field(fields, 2)?, field(fields, 3)?));
+            Ok(())
+        })?;
+        Ok(coordinates)
+    }
+}
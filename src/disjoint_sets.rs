@@ -0,0 +1,51 @@
+// disjoint sets (union-find) over the elements 0..n, using union by rank and path compression
+pub struct DisjointSets {
+    parent: Vec<usize>,
+    rank: Vec<u8>
+}
+
+impl DisjointSets {
+
+    pub fn new(n: usize) -> DisjointSets {
+        DisjointSets { parent: (0..n).collect(), rank: vec![0; n] }
+    }
+
+    // adds a new singleton set and returns its element
+    pub fn push(&mut self) -> usize {
+        self.parent.push(self.parent.len());
+        self.rank.push(0);
+        self.parent.len() - 1
+    }
+
+    // returns the representative of the set containing x
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut x = x;
+        while self.parent[x] != root {
+            let next = self.parent[x];
+            self.parent[x] = root;
+            x = next;
+        }
+        root
+    }
+
+    // merges the sets containing x and y; returns false if they already were the same set
+    pub fn union(&mut self, x: usize, y: usize) -> bool {
+        let (x, y) = (self.find(x), self.find(y));
+        if x == y {
+            return false;
+        }
+        if self.rank[x] < self.rank[y] {
+            self.parent[x] = y;
+        } else {
+            self.parent[y] = x;
+            if self.rank[x] == self.rank[y] {
+                self.rank[x] += 1;
+            }
+        }
+        true
+    }
+}
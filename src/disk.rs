@@ -0,0 +1,188 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::convert::TryInto;
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::mem::size_of;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use attributes::VertexMap;
+use csr::next_numbers;
+use disjoint_sets::DisjointSets;
+use graph::{HashMap, Vertex};
+
+// a graph over the vertices 1..n whose adjacency lists are kept on disk, for graphs with
+// more edges than fit into memory: the vertices are split into chunks of consecutive
+// vertices, and the neighbours of all vertices of a chunk are stored together in one file,
+// of which only a limited number of the most recently used are held in memory; only the
+// offsets of the adjacency lists, one number per vertex, are always in memory
+pub struct DiskGraph {
+    directory: PathBuf,
+    // the position of the neighbours of vertex v among all neighbours is offsets[v - 1]
+    offsets: Vec<u64>,
+    chunk_size: usize,
+    cache: RefCell<ChunkCache>
+}
+
+// the chunks loaded from disk, evicting the least recently used one when full
+struct ChunkCache {
+    capacity: usize,
+    chunks: HashMap<usize, (Rc<Vec<Vertex>>, u64)>,
+    clock: u64,
+    loads: usize
+}
+
+impl DiskGraph {
+
+    // builds the graph from an input stream in the format of Graph::load in a single pass:
+    // the edges are first distributed to one file per chunk, then each chunk is sorted in
+    // memory and written out as its adjacency lists, so at most one chunk of edges is in
+    // memory at a time; undirected edges are stored in both directions, and the chunk files
+    // are created in the given directory, which must exist
+    pub fn build<R: BufRead>(reader: &mut R, directory: &Path, directed: bool, chunk_size: usize, cache_chunks: usize) -> io::Result<DiskGraph> {
+        let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+        let mut buffer = String::new();
+        let (n, e) = match next_numbers(reader, &mut buffer)? {
+            Some((n, e, _)) => (n as usize, e),
+            None => return Err(invalid("missing number of vertices and edges"))
+        };
+        let chunk_size = chunk_size.max(1);
+        let chunks = n.div_ceil(chunk_size);
+        let unsorted = |i: usize| directory.join(format!("unsorted-{}", i));
+
+        let mut writers = (0..chunks)
+            .map(|i| File::create(unsorted(i)).map(BufWriter::new))
+            .collect::<io::Result<Vec<_>>>()?;
+        for _ in 0..e {
+            let (u, v) = match next_numbers(reader, &mut buffer)? {
+                Some((u, v, _)) if u >= 1 && v >= 1 && u as usize <= n && v as usize <= n => (u, v),
+                Some(_) => return Err(invalid("edge between unknown vertices")),
+                None => return Err(invalid("missing edge"))
+            };
+            let arcs = if !directed && u != v { vec![(u, v), (v, u)] } else { vec![(u, v)] };
+            for (from, to) in arcs {
+                let writer = &mut writers[(from as usize - 1) / chunk_size];
+                writer.write_all(&from.to_le_bytes())?;
+                writer.write_all(&to.to_le_bytes())?;
+            }
+        }
+        for writer in &mut writers {
+            writer.flush()?;
+        }
+        drop(writers);
+
+        let mut offsets = vec![0; n + 1];
+        for i in 0..chunks {
+            let mut arcs: Vec<(Vertex, Vertex)> = read_vertices(&unsorted(i))?
+                .chunks(2)
+                .map(|arc| (arc[0], arc[1]))
+                .collect();
+            arcs.sort();
+            let mut writer = BufWriter::new(File::create(directory.join(format!("chunk-{}", i)))?);
+            for (from, to) in arcs {
+                offsets[from as usize] += 1;
+                writer.write_all(&to.to_le_bytes())?;
+            }
+            writer.flush()?;
+            fs::remove_file(unsorted(i))?;
+        }
+        for v in 0..n {
+            offsets[v + 1] += offsets[v];
+        }
+
+        let cache = ChunkCache { capacity: cache_chunks.max(1), chunks: HashMap::new(), clock: 0, loads: 0 };
+        Ok(DiskGraph { directory: directory.to_path_buf(), offsets, chunk_size, cache: RefCell::new(cache) })
+    }
+
+    pub fn order(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    // the number of adjacency list entries, i.e. undirected edges count twice
+    pub fn arc_count(&self) -> u64 {
+        self.offsets[self.order()]
+    }
+
+    // the number of neighbours of v, taken from the offsets without reading from disk
+    pub fn degree(&self, v: Vertex) -> usize {
+        (self.offsets[v as usize] - self.offsets[v as usize - 1]) as usize
+    }
+
+    // the number of chunks read from disk so far
+    pub fn chunk_loads(&self) -> usize {
+        self.cache.borrow().loads
+    }
+
+    // the neighbours of v in ascending order, reading their chunk from disk unless cached
+    pub fn neighbours(&self, v: Vertex) -> io::Result<Vec<Vertex>> {
+        let i = (v as usize - 1) / self.chunk_size;
+        let chunk = self.chunk(i)?;
+        let first = self.offsets[i * self.chunk_size];
+        let range = (self.offsets[v as usize - 1] - first) as usize..(self.offsets[v as usize] - first) as usize;
+        Ok(chunk[range].to_vec())
+    }
+
+    fn chunk(&self, i: usize) -> io::Result<Rc<Vec<Vertex>>> {
+        let mut cache = self.cache.borrow_mut();
+        cache.clock += 1;
+        let clock = cache.clock;
+        if let Some(entry) = cache.chunks.get_mut(&i) {
+            entry.1 = clock;
+            return Ok(entry.0.clone());
+        }
+        if cache.chunks.len() == cache.capacity {
+            let oldest = *cache.chunks.iter().min_by_key(|(_, entry)| entry.1).unwrap().0;
+            cache.chunks.remove(&oldest);
+        }
+        let chunk = Rc::new(read_vertices(&self.directory.join(format!("chunk-{}", i)))?);
+        cache.chunks.insert(i, (chunk.clone(), clock));
+        cache.loads += 1;
+        Ok(chunk)
+    }
+
+    // the number of edges on a shortest path from source to every vertex reachable from it,
+    // found by breadth first search
+    pub fn bfs_distances(&self, source: Vertex) -> io::Result<VertexMap<u32>> {
+        let mut distance = VertexMap::new((1..self.order() as Vertex + 1).collect());
+        distance.set(source, 0);
+        let mut queue = VecDeque::new();
+        queue.push_back((source, 0));
+        while let Some((v, d)) = queue.pop_front() {
+            for w in self.neighbours(v)? {
+                if distance.get(w).is_none() {
+                    distance.set(w, d + 1);
+                    queue.push_back((w, d + 1));
+                }
+            }
+        }
+        Ok(distance)
+    }
+
+    // the weakly connected component of every vertex, numbered from 0 in order of the
+    // smallest vertex of each component; the chunks are read one after the other
+    pub fn component_map(&self) -> io::Result<VertexMap<usize>> {
+        let mut sets = DisjointSets::new(self.order());
+        for v in 1..self.order() as Vertex + 1 {
+            for w in self.neighbours(v)? {
+                sets.union(v as usize - 1, w as usize - 1);
+            }
+        }
+        let mut components = VertexMap::new((1..self.order() as Vertex + 1).collect());
+        let mut numbers = HashMap::new();
+        for v in 1..self.order() as Vertex + 1 {
+            let count = numbers.len();
+            components.set(v, *numbers.entry(sets.find(v as usize - 1)).or_insert(count));
+        }
+        Ok(components)
+    }
+}
+
+// reads a file of little endian vertices
+fn read_vertices(path: &Path) -> io::Result<Vec<Vertex>> {
+    let mut bytes = vec![];
+    BufReader::new(File::open(path)?).read_to_end(&mut bytes)?;
+    Ok(bytes.chunks(size_of::<Vertex>()).map(|v| Vertex::from_le_bytes(v.try_into().unwrap())).collect())
+}
@@ -0,0 +1,165 @@
+use std::io;
+
+use checkpoint::{Checkpoint, Stage, State};
+use graph::{Graph, HashMap, Vertex, Weight};
+use landmarks::LandmarkIndex;
+use progress::{completed, Cancellation, Interrupted, Progress};
+use shortest_paths::distances;
+
+// graphs with more vertices than this get an approximate oracle by default, as the exact
+// distance matrix grows quadratically
+pub const EXACT_ORACLE_LIMIT: usize = 4096;
+
+// number of landmarks used by approximate oracles
+const ORACLE_LANDMARKS: usize = 16;
+
+enum Distances {
+    // the full distance matrix stored row by row, or only its lower triangle including
+    // the diagonal for undirected graphs; unreachable pairs are infinitely far apart
+    Matrix(Vec<Weight>),
+    Landmarks(LandmarkIndex)
+}
+
+// answers distance queries between any two vertices in constant time, either exactly from
+// a precomputed matrix of all-pairs distances or approximately from landmark distances
+pub struct DistanceOracle {
+    index: HashMap<Vertex, usize>,
+    directed: bool,
+    distances: Distances
+}
+
+impl DistanceOracle {
+
+    pub fn is_exact(&self) -> bool {
+        match self.distances {
+            Distances::Matrix(_) => true,
+            Distances::Landmarks(_) => false
+        }
+    }
+
+    // returns the distance from u to v, or infinity if v cannot be reached from u; for an
+    // approximate oracle this is an upper bound, the length of a shortest path from u to v
+    // through one of the landmarks
+    pub fn distance(&self, u: Vertex, v: Vertex) -> Weight {
+        let (i, j) = match (self.index.get(&u), self.index.get(&v)) {
+            (Some(i), Some(j)) => (*i, *j),
+            _ => return Weight::INFINITY
+        };
+        match self.distances {
+            Distances::Matrix(ref matrix) => matrix[self.position(i, j)],
+            Distances::Landmarks(ref landmarks) => landmarks.upper_bound(u, v)
+        }
+    }
+
+    // position of the distance from the i-th to the j-th vertex in the distance matrix
+    fn position(&self, i: usize, j: usize) -> usize {
+        if self.directed {
+            i * self.index.len() + j
+        } else {
+            let (i, j) = if i < j { (j, i) } else { (i, j) };
+            i * (i + 1) / 2 + j
+        }
+    }
+}
+
+impl Graph {
+
+    // precomputes a distance oracle, which is exact if the graph has at most max_exact
+    // vertices and otherwise estimates distances using landmarks; edge weights must not be
+    // negative
+    pub fn distance_oracle(&self, max_exact: usize) -> DistanceOracle {
+        self.distance_oracle_with_progress(max_exact, |_| ())
+    }
+
+    // precomputes a distance oracle like distance_oracle, reporting the progress of an exact
+    // oracle after the distances from every vertex, with the vertices left as the active ones
+    pub fn distance_oracle_with_progress<F: FnMut(&Progress)>(&self, max_exact: usize, mut on_progress: F) -> DistanceOracle {
+        completed(self.distance_oracle_controlled(max_exact, (0, vec![]), |progress, _| { on_progress(progress); true }))
+    }
+
+    // precomputes a distance oracle like distance_oracle unless cancelled, which stops an
+    // exact oracle after the distances from the current vertex; there is no partial result,
+    // as distances missing from the matrix would pass for unreachable pairs
+    pub fn distance_oracle_cancellable(&self, max_exact: usize, cancellation: &Cancellation) -> Result<DistanceOracle, Interrupted<()>> {
+        self.distance_oracle_controlled(max_exact, (0, vec![]), |_, _| !cancellation.is_cancelled())
+    }
+
+    // precomputes a distance oracle like distance_oracle, saving the rows of the distance
+    // matrix of an exact oracle computed so far to the checkpoint, and resuming from the rows
+    // saved by an earlier run on the same graph that did not get to finish
+    pub fn distance_oracle_checkpointed(&self, max_exact: usize, checkpoint: &Checkpoint) -> io::Result<DistanceOracle> {
+        if self.vertices().len() > max_exact {
+            return Ok(self.distance_oracle(max_exact));
+        }
+        let (run, saved) = checkpoint.start(Stage::DistanceOracle, self)?;
+        let resumed = match saved {
+            Some(mut state) => {
+                let n = self.vertices().len();
+                let rows = state.take_index(n + 1)?;
+                let mut matrix = vec![];
+                for _ in 0..matrix_size(n, rows, self.is_directed()) {
+                    matrix.push(state.take_weight()?);
+                }
+                (rows, matrix)
+            },
+            None => (0, vec![])
+        };
+        let mut failure = None;
+        let result = self.distance_oracle_controlled(max_exact, resumed, |progress, matrix| {
+            if !run.is_due(progress.processed) || progress.active == 0 {
+                return true;
+            }
+            let mut state = State::new();
+            state.put_index(progress.processed);
+            for d in matrix {
+                state.put_weight(*d);
+            }
+            run.save(&state).map_err(|error| failure = Some(error)).is_ok()
+        });
+        match (result, failure) {
+            (Ok(oracle), _) => run.finish().map(|_| oracle),
+            (Err(_), Some(error)) => Err(error),
+            (Err(_), None) => unreachable!("distance oracle interrupted without an error")
+        }
+    }
+
+    // precomputes a distance oracle, resuming from the given number of rows of the distance
+    // matrix already computed, and passing the progress of an exact oracle and the rows
+    // computed so far after every vertex to go_on, which tells whether to carry on
+    fn distance_oracle_controlled<F>(&self, max_exact: usize, resumed: (usize, Vec<Weight>), mut go_on: F) -> Result<DistanceOracle, Interrupted<()>>
+        where F: FnMut(&Progress, &[Weight]) -> bool {
+        let vertices = self.sorted_vertices();
+        let index: HashMap<Vertex, usize> = vertices.iter().enumerate().map(|(i, v)| (*v, i)).collect();
+        let mut oracle = DistanceOracle {
+            index, directed: self.is_directed(), distances: Distances::Matrix(vec![])
+        };
+        if vertices.len() > max_exact {
+            oracle.distances = Distances::Landmarks(self.landmark_index(ORACLE_LANDMARKS));
+            return Ok(oracle);
+        }
+
+        let n = vertices.len();
+        let (rows, mut matrix) = resumed;
+        matrix.resize(matrix_size(n, n, oracle.directed), Weight::INFINITY);
+        let adj = self.weighted_adjacencies();
+        for (i, v) in vertices.iter().enumerate().skip(rows) {
+            for (w, d) in distances(&adj, *v) {
+                let j = oracle.index[&w];
+                if oracle.directed || j <= i {
+                    matrix[oracle.position(i, j)] = d;
+                }
+            }
+            let progress = Progress { round: 1, rounds: Some(1), processed: i + 1, total: n, active: n - i - 1 };
+            if !go_on(&progress, &matrix[..matrix_size(n, i + 1, oracle.directed)]) && i + 1 < n {
+                return Err(Interrupted { progress, partial: None });
+            }
+        }
+        oracle.distances = Distances::Matrix(matrix);
+        Ok(oracle)
+    }
+}
+
+// the number of distances in the first rows of the distance matrix of n vertices
+fn matrix_size(n: usize, rows: usize, directed: bool) -> usize {
+    if directed { rows * n } else { rows * (rows + 1) / 2 }
+}
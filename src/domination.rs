@@ -0,0 +1,39 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use graph::{Graph, HashSet, Vertex};
+
+impl Graph {
+
+    // computes a small dominating set using the greedy ln(n)-approximation:
+    // repeatedly picks the vertex that covers the most not yet dominated vertices
+    // (itself and its neighbours); ties are broken by picking the smallest vertex
+    pub fn dominating_set_greedy(&self) -> HashSet<Vertex> {
+        let adj = self.adjacencies();
+        let mut dominated = HashSet::new();
+        let mut dominating = HashSet::new();
+
+        // the gain of a vertex can only ever decrease, so stale heap entries are
+        // re-evaluated lazily when they are popped
+        let gain = |v: &Vertex, dominated: &HashSet<Vertex>| {
+            let own = if dominated.contains(v) { 0 } else { 1 };
+            own + adj[v].iter().filter(|w| !dominated.contains(*w)).count()
+        };
+        let mut heap: BinaryHeap<(usize, Reverse<Vertex>)> = self.vertices().iter()
+            .map(|v| (gain(v, &dominated), Reverse(*v)))
+            .collect();
+
+        while dominated.len() < self.vertices().len() {
+            let (old_gain, Reverse(v)) = heap.pop().unwrap();
+            let new_gain = gain(&v, &dominated);
+            if new_gain < old_gain {
+                heap.push((new_gain, Reverse(v)));
+                continue;
+            }
+            dominating.insert(v);
+            dominated.insert(v);
+            dominated.extend(&adj[&v]);
+        }
+        dominating
+    }
+}
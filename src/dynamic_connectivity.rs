@@ -0,0 +1,167 @@
+use std::collections::VecDeque;
+
+use graph::{Adjacencies, Graph, HashMap, HashSet, Vertex};
+
+// connectivity of an undirected graph under edge insertions and deletions: keeps a spanning
+// forest besides the remaining edges, plus a component label for every vertex so that
+// connectivity queries take constant time; merging components relabels the smaller one,
+// deleting a forest edge searches the smaller of the two resulting trees for a
+// replacement edge among the non-forest edges and relabels it if there is none
+pub struct DynamicConnectivity {
+    label: HashMap<Vertex, usize>,
+    members: HashMap<usize, HashSet<Vertex>>,
+    forest: Adjacencies,
+    others: Adjacencies,
+    next_label: usize
+}
+
+impl DynamicConnectivity {
+
+    pub fn new() -> DynamicConnectivity {
+        DynamicConnectivity {
+            label: HashMap::new(), members: HashMap::new(),
+            forest: HashMap::new(), others: HashMap::new(), next_label: 0
+        }
+    }
+
+    pub fn insert_vertex(&mut self, v: Vertex) {
+        if self.label.contains_key(&v) {
+            return;
+        }
+        self.label.insert(v, self.next_label);
+        self.members.insert(self.next_label, vec![v].into_iter().collect());
+        self.forest.insert(v, HashSet::new());
+        self.others.insert(v, HashSet::new());
+        self.next_label += 1;
+    }
+
+    // inserts the edge between u and v, adding any missing vertices
+    pub fn insert_edge(&mut self, u: Vertex, v: Vertex) {
+        self.insert_vertex(u);
+        self.insert_vertex(v);
+        if u == v || self.forest[&u].contains(&v) {
+            return;
+        }
+        let (lu, lv) = (self.label[&u], self.label[&v]);
+        if lu == lv {
+            self.others.get_mut(&u).unwrap().insert(v);
+            self.others.get_mut(&v).unwrap().insert(u);
+            return;
+        }
+        self.forest.get_mut(&u).unwrap().insert(v);
+        self.forest.get_mut(&v).unwrap().insert(u);
+        let (small, large) = if self.members[&lu].len() < self.members[&lv].len() { (lu, lv) } else { (lv, lu) };
+        let moved = self.members.remove(&small).unwrap();
+        for w in &moved {
+            self.label.insert(*w, large);
+        }
+        self.members.get_mut(&large).unwrap().extend(moved);
+    }
+
+    // deletes the edge between u and v; returns false if there was no such edge
+    pub fn delete_edge(&mut self, u: Vertex, v: Vertex) -> bool {
+        if self.others.get(&u).is_some_and(|adjacent| adjacent.contains(&v)) {
+            self.others.get_mut(&u).unwrap().remove(&v);
+            self.others.get_mut(&v).unwrap().remove(&u);
+            return true;
+        }
+        if !self.forest.get(&u).is_some_and(|adjacent| adjacent.contains(&v)) {
+            return false;
+        }
+        self.forest.get_mut(&u).unwrap().remove(&v);
+        self.forest.get_mut(&v).unwrap().remove(&u);
+
+        // explore both trees in lock step, so that the work is bounded by the smaller one
+        let mut searches = [Search::new(u), Search::new(v)];
+        let side = loop {
+            if searches[0].step(&self.forest) {
+                break 0;
+            }
+            if searches[1].step(&self.forest) {
+                break 1;
+            }
+        };
+        let tree = &searches[side].visited;
+
+        let replacement = tree.iter()
+            .flat_map(|x| self.others[x].iter().map(move |y| (*x, *y)))
+            .find(|(_, y)| !tree.contains(y));
+        match replacement {
+            Some((x, y)) => {
+                self.others.get_mut(&x).unwrap().remove(&y);
+                self.others.get_mut(&y).unwrap().remove(&x);
+                self.forest.get_mut(&x).unwrap().insert(y);
+                self.forest.get_mut(&y).unwrap().insert(x);
+            },
+            None => {
+                let old = self.label[&u];
+                let members = self.members.get_mut(&old).unwrap();
+                for w in tree {
+                    members.remove(w);
+                }
+                for w in tree {
+                    self.label.insert(*w, self.next_label);
+                }
+                self.members.insert(self.next_label, tree.clone());
+                self.next_label += 1;
+            }
+        }
+        true
+    }
+
+    // returns true if there is a path between u and v
+    pub fn connected(&self, u: Vertex, v: Vertex) -> bool {
+        match (self.label.get(&u), self.label.get(&v)) {
+            (Some(lu), Some(lv)) => lu == lv,
+            _ => false
+        }
+    }
+
+    pub fn component_count(&self) -> usize {
+        self.members.len()
+    }
+}
+
+// breadth first search of a tree of the spanning forest that can be advanced one vertex at a time
+struct Search {
+    visited: HashSet<Vertex>,
+    queue: VecDeque<Vertex>
+}
+
+impl Search {
+
+    fn new(root: Vertex) -> Search {
+        Search { visited: vec![root].into_iter().collect(), queue: vec![root].into_iter().collect() }
+    }
+
+    // explores the next vertex; returns true once the whole tree has been explored
+    fn step(&mut self, forest: &Adjacencies) -> bool {
+        match self.queue.pop_front() {
+            Some(v) => {
+                for w in &forest[&v] {
+                    if self.visited.insert(*w) {
+                        self.queue.push_back(*w);
+                    }
+                }
+                false
+            },
+            None => true
+        }
+    }
+}
+
+impl Graph {
+
+    // builds a dynamic connectivity structure initialized with the vertices and edges of the
+    // graph; edge directions are ignored
+    pub fn dynamic_connectivity(&self) -> DynamicConnectivity {
+        let mut connectivity = DynamicConnectivity::new();
+        for v in self.sorted_vertices() {
+            connectivity.insert_vertex(v);
+        }
+        for edge in self.edges() {
+            connectivity.insert_edge(edge.0, edge.1);
+        }
+        connectivity
+    }
+}
@@ -0,0 +1,182 @@
+use std::collections::BinaryHeap;
+use std::collections::VecDeque;
+
+use graph::{Graph, HashMap, HashSet, Vertex, Weight, WeightedAdjacencies};
+use preconditions::PreconditionError;
+use shortest_paths::{shortest_path_tree, trace_path, Path, QueueEntry};
+
+// the distances from a fixed source kept up to date as edges are inserted, removed or
+// change their weight, which must not be negative, in the manner of Ramalingam and Reps:
+// an edge getting lighter lets Dijkstra's algorithm run from its head over just the
+// vertices coming closer, and one getting heavier or removed only matters if it is in the
+// tree of shortest paths, in which case only the vertices below it are searched again,
+// starting from their best edges from the rest. Every update returns the vertices whose
+// distance changed, so that the routes through them can be updated in turn
+pub struct DynamicShortestPaths {
+    source: Vertex,
+    directed: bool,
+    out: WeightedAdjacencies,
+    inn: WeightedAdjacencies,
+    distance: HashMap<Vertex, Weight>,
+    parent: HashMap<Vertex, Vertex>
+}
+
+impl DynamicShortestPaths {
+
+    pub fn source(&self) -> Vertex {
+        self.source
+    }
+
+    // the distance of v from the source, or None if the source cannot reach it
+    pub fn distance(&self, v: Vertex) -> Option<Weight> {
+        self.distance.get(&v).cloned()
+    }
+
+    // a shortest path from the source to v, or None if the source cannot reach it
+    pub fn path_to(&self, v: Vertex) -> Option<Path> {
+        self.distance.get(&v)?;
+        Some(trace_path(&self.parent, v))
+    }
+
+    // inserts the edge from u to v, or u - v for an undirected graph, with the weight, or
+    // changes its weight if it exists, adding any missing vertices; returns the vertices
+    // whose distance changed in ascending order
+    // fails if the weight is negative
+    pub fn set_weight(&mut self, u: Vertex, v: Vertex, weight: Weight) -> Result<Vec<Vertex>, PreconditionError> {
+        if weight < 0.0 {
+            return Err(PreconditionError::NegativeWeight((u, v), weight));
+        }
+        let mut changed = self.update_arc(u, v, Some(weight));
+        if !self.directed {
+            changed.extend(self.update_arc(v, u, Some(weight)));
+        }
+        Ok(sorted(changed))
+    }
+
+    // removes the edge from u to v, or u - v for an undirected graph, but not its vertices;
+    // returns the vertices whose distance changed in ascending order, which are none if
+    // there is no such edge
+    pub fn remove_edge(&mut self, u: Vertex, v: Vertex) -> Vec<Vertex> {
+        let mut changed = self.update_arc(u, v, None);
+        if !self.directed {
+            changed.extend(self.update_arc(v, u, None));
+        }
+        sorted(changed)
+    }
+
+    // sets the weight of the arc from u to v, or removes it for None, and repairs the
+    // distances, returning the vertices whose distance changed
+    fn update_arc(&mut self, u: Vertex, v: Vertex, weight: Option<Weight>) -> Vec<Vertex> {
+        for w in &[u, v] {
+            self.out.entry(*w).or_default();
+            self.inn.entry(*w).or_default();
+        }
+        let old = match weight {
+            Some(weight) => {
+                self.inn.get_mut(&v).unwrap().insert(u, weight);
+                self.out.get_mut(&u).unwrap().insert(v, weight)
+            },
+            None => {
+                self.inn.get_mut(&v).unwrap().remove(&u);
+                self.out.get_mut(&u).unwrap().remove(&v)
+            }
+        };
+        match (old, weight) {
+            (_, Some(weight)) if old.is_none_or(|old| weight < old) => {
+                let alt = match self.distance.get(&u) {
+                    Some(d) => d + weight,
+                    None => return vec![]
+                };
+                if self.distance.get(&v).is_some_and(|current| *current <= alt) {
+                    return vec![];
+                }
+                self.distance.insert(v, alt);
+                self.parent.insert(v, u);
+                let mut changed = vec![v];
+                changed.extend(self.propagate(vec![QueueEntry { distance: alt, vertex: v }].into_iter().collect()));
+                changed
+            },
+            (Some(_), _) if self.parent.get(&v) == Some(&u) => self.reroute(v),
+            _ => vec![]
+        }
+    }
+
+    // the vertices below v in the tree of shortest paths, which lost the edge to v or saw
+    // it get heavier, get their distances from their best edges from the other vertices,
+    // from where Dijkstra's algorithm settles them again
+    fn reroute(&mut self, v: Vertex) -> Vec<Vertex> {
+        let mut affected = vec![v];
+        let mut queue = VecDeque::from(vec![v]);
+        while let Some(x) = queue.pop_front() {
+            for y in self.out[&x].keys() {
+                if self.parent.get(y) == Some(&x) {
+                    affected.push(*y);
+                    queue.push_back(*y);
+                }
+            }
+        }
+        let below: HashSet<Vertex> = affected.iter().cloned().collect();
+        let old: Vec<(Vertex, Weight)> = affected.iter().map(|x| (*x, self.distance[x])).collect();
+        for x in &affected {
+            self.distance.remove(x);
+            self.parent.remove(x);
+        }
+        let mut queue = BinaryHeap::new();
+        for x in &affected {
+            let best = self.inn[x].iter()
+                .filter(|(y, _)| !below.contains(y))
+                .filter_map(|(y, weight)| self.distance.get(y).map(|d| (d + weight, *y)))
+                .min_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+            if let Some((d, y)) = best {
+                self.distance.insert(*x, d);
+                self.parent.insert(*x, y);
+                queue.push(QueueEntry { distance: d, vertex: *x });
+            }
+        }
+        self.propagate(queue);
+        old.into_iter().filter(|(x, d)| self.distance.get(x) != Some(d)).map(|(x, _)| x).collect()
+    }
+
+    // Dijkstra's algorithm from the queued vertices, relaxing only edges that bring their
+    // heads closer; returns the vertices it brought closer
+    fn propagate(&mut self, mut queue: BinaryHeap<QueueEntry>) -> Vec<Vertex> {
+        let mut changed = vec![];
+        while let Some(QueueEntry { distance: d, vertex: x }) = queue.pop() {
+            if d > self.distance[&x] {
+                continue;
+            }
+            for (y, weight) in &self.out[&x] {
+                let alt = d + weight;
+                if self.distance.get(y).is_none_or(|current| alt < *current) {
+                    self.distance.insert(*y, alt);
+                    self.parent.insert(*y, x);
+                    changed.push(*y);
+                    queue.push(QueueEntry { distance: alt, vertex: *y });
+                }
+            }
+        }
+        changed
+    }
+}
+
+fn sorted(mut vertices: Vec<Vertex>) -> Vec<Vertex> {
+    vertices.sort();
+    vertices.dedup();
+    vertices
+}
+
+impl Graph {
+
+    // the distances from the source with the tree of shortest paths, to be kept up to date
+    // by DynamicShortestPaths as the edges change
+    // fails if there are edges with negative weights
+    pub fn dynamic_shortest_paths(&self, source: Vertex) -> Result<DynamicShortestPaths, PreconditionError> {
+        self.check_nonnegative_weights()?;
+        let mut out = self.weighted_adjacencies();
+        let mut inn = self.reverse_weighted_adjacencies();
+        out.entry(source).or_default();
+        inn.entry(source).or_default();
+        let (distance, parent) = shortest_path_tree(&out, source);
+        Ok(DynamicShortestPaths { source, directed: self.is_directed(), out, inn, distance, parent })
+    }
+}
@@ -0,0 +1,177 @@
+use graph::{Edge, Graph, HashMap, Vertex, Weight, WeightedAdjacencies};
+use preconditions::PreconditionError;
+use shortest_paths::{shortest_path_tree, trace_path, Path};
+
+// the largest number of odd degree vertices for which the Chinese postman solver computes an
+// exact minimum weight matching, which takes time and space exponential in their number;
+// beyond that the closest pairs are matched greedily
+const EXACT_MATCHING_LIMIT: usize = 20;
+
+impl Graph {
+
+    // finds a closed walk using every edge exactly once with Hierholzer's algorithm; edge
+    // directions are ignored
+    // fails unless all degrees are even and the edges are all connected
+    pub fn eulerian_circuit(&self) -> Result<Path, PreconditionError> {
+        self.check_eulerian()?;
+        let mut edges: Vec<Edge> = self.edges().iter().cloned().collect();
+        edges.sort();
+        Ok(circuit(&edges).unwrap())
+    }
+
+    // solves the route inspection problem: finds a shortest closed walk using every edge at
+    // least once, by adding a shortest path between each pair of a minimum weight perfect
+    // matching of the odd degree vertices and taking an Eulerian circuit of the result;
+    // edge directions are ignored and edge weights must not be negative
+    // returns the length of the walk, the edges traversed more than once (once for every
+    // additional traversal) and the walk itself, or None if the edges are not connected
+    pub fn chinese_postman(&self) -> Option<(Weight, Vec<Edge>, Path)> {
+        let mut edges: Vec<Edge> = self.edges().iter().cloned().collect();
+        edges.sort();
+        let mut degree: HashMap<Vertex, usize> = HashMap::new();
+        for edge in &edges {
+            *degree.entry(edge.0).or_default() += 1;
+            *degree.entry(edge.1).or_default() += 1;
+        }
+        let mut odd: Vec<Vertex> = degree.iter().filter(|(_, d)| *d % 2 == 1).map(|(v, _)| *v).collect();
+        odd.sort();
+
+        let adj = undirected_weights(self, &edges);
+        let trees: Vec<_> = odd.iter().map(|v| shortest_path_tree(&adj, *v)).collect();
+        let distance: Vec<Vec<Weight>> = trees.iter()
+            .map(|(distance, _)| odd.iter().map(|w| distance.get(w).cloned().unwrap_or(Weight::INFINITY)).collect())
+            .collect();
+        let pairs = if odd.len() <= EXACT_MATCHING_LIMIT { exact_matching(&distance) } else { greedy_matching(&distance) };
+
+        let mut added = vec![];
+        for (i, j) in pairs {
+            if distance[i][j] == Weight::INFINITY {
+                return None;
+            }
+            for hop in trace_path(&trees[i].1, odd[j]).windows(2) {
+                added.push(lightest_edge(self, hop[0], hop[1]));
+            }
+        }
+        edges.extend(&added);
+        let walk = circuit(&edges)?;
+        let length = edges.iter().map(|edge| self.weight(edge)).sum();
+        added.sort();
+        Some((length, added, walk))
+    }
+}
+
+// the adjacencies of the edges as if they were undirected, with the smallest weight of
+// the edges between two vertices in either direction
+fn undirected_weights(graph: &Graph, edges: &[Edge]) -> WeightedAdjacencies {
+    let mut adj: WeightedAdjacencies = graph.vertices().iter().map(|v| (*v, HashMap::new())).collect();
+    for edge in edges {
+        let weight = graph.weight(edge);
+        for (v, w) in [(edge.0, edge.1), (edge.1, edge.0)] {
+            let current = adj.get_mut(&v).unwrap().entry(w).or_insert(weight);
+            *current = current.min(weight);
+        }
+    }
+    adj
+}
+
+// the lightest of the edges between v and w in either direction
+fn lightest_edge(graph: &Graph, v: Vertex, w: Vertex) -> Edge {
+    vec![(v, w), (w, v)].into_iter()
+        .filter(|edge| graph.edges().contains(edge))
+        .min_by(|a, b| graph.weight(a).total_cmp(&graph.weight(b)))
+        .unwrap()
+}
+
+// Hierholzer's algorithm on the multigraph given by the list of edges, starting from the
+// smallest vertex with an edge and always following the first unused edge in the list
+fn circuit(edges: &[Edge]) -> Option<Path> {
+    let mut incident: HashMap<Vertex, Vec<usize>> = HashMap::new();
+    for (i, edge) in edges.iter().enumerate() {
+        incident.entry(edge.0).or_default().push(i);
+        incident.entry(edge.1).or_default().push(i);
+    }
+    if incident.values().any(|edges| edges.len() % 2 == 1) {
+        return None;
+    }
+    let start = match incident.keys().min() {
+        Some(v) => *v,
+        None => return Some(vec![])
+    };
+
+    let mut used = vec![false; edges.len()];
+    let mut next: HashMap<Vertex, usize> = HashMap::new();
+    let mut stack = vec![start];
+    let mut walk = vec![];
+    while let Some(&v) = stack.last() {
+        let position = next.entry(v).or_default();
+        while *position < incident[&v].len() && used[incident[&v][*position]] {
+            *position += 1;
+        }
+        match incident[&v].get(*position) {
+            Some(&i) => {
+                used[i] = true;
+                stack.push(if edges[i].0 == v { edges[i].1 } else { edges[i].0 });
+            },
+            None => {
+                walk.push(v);
+                stack.pop();
+            }
+        }
+    }
+    // a walk missing some of the edges means the edges are not connected
+    if walk.len() != edges.len() + 1 {
+        return None;
+    }
+    walk.reverse();
+    Some(walk)
+}
+
+// computes a minimum weight perfect matching on an even number of vertices by dynamic
+// programming over the subsets of vertices still to be matched, always matching the
+// first of them next
+fn exact_matching(distance: &[Vec<Weight>]) -> Vec<(usize, usize)> {
+    let n = distance.len();
+    let full = (1usize << n) - 1;
+    // weight[set] is the weight of a lightest matching of the vertices in set, choice[set]
+    // the partner of the first vertex of set in it
+    let mut weight = vec![Weight::INFINITY; full + 1];
+    let mut choice = vec![0; full + 1];
+    weight[0] = 0.0;
+    for set in (1..=full).filter(|set| set.count_ones() % 2 == 0) {
+        let i = set.trailing_zeros() as usize;
+        for j in (i + 1..n).filter(|j| set & (1 << j) != 0) {
+            let alt = weight[set & !(1 << i) & !(1 << j)] + distance[i][j];
+            if alt < weight[set] || choice[set] == 0 {
+                weight[set] = alt;
+                choice[set] = j;
+            }
+        }
+    }
+    let mut pairs = vec![];
+    let mut set = full;
+    while set != 0 {
+        let i = set.trailing_zeros() as usize;
+        pairs.push((i, choice[set]));
+        set &= !(1 << i) & !(1 << choice[set]);
+    }
+    pairs
+}
+
+// matches the closest pair of vertices still unmatched until all vertices are matched
+fn greedy_matching(distance: &[Vec<Weight>]) -> Vec<(usize, usize)> {
+    let n = distance.len();
+    let mut candidates: Vec<(Weight, usize, usize)> = (0..n)
+        .flat_map(|i| (i + 1..n).map(move |j| (distance[i][j], i, j)))
+        .collect();
+    candidates.sort_by(|a, b| a.0.total_cmp(&b.0).then_with(|| (a.1, a.2).cmp(&(b.1, b.2))));
+    let mut matched = vec![false; n];
+    let mut pairs = vec![];
+    for (_, i, j) in candidates {
+        if !matched[i] && !matched[j] {
+            matched[i] = true;
+            matched[j] = true;
+            pairs.push((i, j));
+        }
+    }
+    pairs
+}
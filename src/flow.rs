@@ -0,0 +1,674 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use graph::{Graph, HashMap, HashSet, Vertex, Weight};
+use random::Random;
+use shortest_paths::Path;
+
+const NONE: usize = usize::MAX;
+
+// an edge of a flow network together with the flow currently routed along it
+#[derive(Clone)]
+struct FlowEdge {
+    to: usize,
+    capacity: Weight,
+    flow: Weight
+}
+
+// a flow network over the nodes 0..n; every edge is stored right next to its reverse
+// residual edge, so the reverse of edge i is always edge i ^ 1
+#[derive(Clone)]
+pub struct FlowNetwork {
+    edges: Vec<FlowEdge>,
+    adj: Vec<Vec<usize>>
+}
+
+// the algorithms max_flow_with can compute a maximum flow with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FlowAlgorithm {
+    // shortest augmenting paths one at a time, O(n m^2)
+    EdmondsKarp,
+    // augmenting paths of large residual capacity first, O(m^2 log U) for whole capacities
+    // up to U
+    CapacityScaling,
+    // blocking flows along shortest paths, O(n^2 m), or O(m sqrt(n)) for unit capacities
+    Dinic,
+    // FIFO push-relabel with global relabelling and the gap heuristic, O(n^3)
+    PushRelabel,
+    // one of the above, picked by choose_algorithm
+    Auto
+}
+
+// the engines the benchmark compares, all but Auto, which it also runs
+pub const FLOW_ALGORITHMS: [FlowAlgorithm; 4] =
+    [FlowAlgorithm::EdmondsKarp, FlowAlgorithm::CapacityScaling, FlowAlgorithm::Dinic, FlowAlgorithm::PushRelabel];
+
+// the time an algorithm took on a network of the benchmark, averaged over the repetitions,
+// with the value of the maximum flow it found
+pub struct FlowTiming {
+    pub network: &'static str,
+    pub algorithm: FlowAlgorithm,
+    pub value: Weight,
+    pub time: Duration
+}
+
+impl FlowNetwork {
+
+    pub fn new(n: usize) -> FlowNetwork {
+        FlowNetwork { edges: vec![], adj: (0..n).map(|_| vec![]).collect() }
+    }
+
+    // adds an edge and returns its number, counting the edges from 0 in the order added
+    pub fn add_edge(&mut self, from: usize, to: usize, capacity: Weight) -> usize {
+        self.adj[from].push(self.edges.len());
+        self.edges.push(FlowEdge { to, capacity, flow: 0.0 });
+        self.adj[to].push(self.edges.len());
+        self.edges.push(FlowEdge { to: from, capacity: 0.0, flow: 0.0 });
+        self.edges.len() / 2 - 1
+    }
+
+    // lists the edges in the order added with their ends, capacities and current flows
+    pub fn flows(&self) -> Vec<(usize, usize, Weight, Weight)> {
+        self.edges.chunks(2)
+            .map(|pair| (pair[1].to, pair[0].to, pair[0].capacity, pair[0].flow))
+            .collect()
+    }
+
+    // the net flow currently leaving s
+    pub fn flow_value(&self, s: usize) -> Weight {
+        self.adj[s].iter().filter(|e| *e % 2 == 0).map(|e| self.edges[*e].flow).sum::<Weight>()
+            - self.adj[s].iter().filter(|e| *e % 2 == 1).map(|e| self.edges[*e ^ 1].flow).sum::<Weight>()
+    }
+
+    // lists the edges of the residual network, i.e. every pair of nodes between which more
+    // flow can be sent, either along an edge below capacity or back along an edge carrying
+    // flow, with the amount that can be sent
+    pub fn residual_edges(&self) -> Vec<(usize, usize, Weight)> {
+        (0..self.edges.len())
+            .filter(|e| self.edges[*e].capacity - self.edges[*e].flow > 0.0)
+            .map(|e| (self.edges[e ^ 1].to, self.edges[e].to, self.edges[e].capacity - self.edges[e].flow))
+            .collect()
+    }
+
+    // changes the capacity of the edge with the given number, keeping its flow; a capacity
+    // below the current flow would make the flow infeasible and is rejected by a panic
+    // after raising capacities, max_flow augments the existing flow further
+    pub fn set_capacity(&mut self, edge: usize, capacity: Weight) {
+        assert!(capacity >= self.edges[2 * edge].flow, "Capacity below flow!");
+        self.edges[2 * edge].capacity = capacity;
+    }
+
+    // finds a shortest augmenting path from s to t in the residual network and returns its
+    // nodes, or None if there is none, which proves the current flow maximal
+    pub fn augmenting_path(&self, s: usize, t: usize) -> Option<Vec<usize>> {
+        let path = self.augmenting_edges(s, t)?;
+        let mut nodes: Vec<usize> = path.iter().map(|e| self.edges[*e].to).collect();
+        nodes.push(s);
+        nodes.reverse();
+        Some(nodes)
+    }
+
+    // searches for an augmenting path by breadth first search, remembering the edge used to
+    // reach each node, and returns its edges from t back to s
+    fn augmenting_edges(&self, s: usize, t: usize) -> Option<Vec<usize>> {
+        self.wide_augmenting_edges(s, t, 0.0)
+    }
+
+    // like augmenting_edges, but only using edges with at least the threshold of residual
+    // capacity
+    fn wide_augmenting_edges(&self, s: usize, t: usize, threshold: Weight) -> Option<Vec<usize>> {
+        let mut via: Vec<Option<usize>> = vec![None; self.adj.len()];
+        let mut queue = VecDeque::new();
+        queue.push_back(s);
+        while let Some(u) = queue.pop_front() {
+            if u == t {
+                break;
+            }
+            for &e in &self.adj[u] {
+                let edge = &self.edges[e];
+                let residual = edge.capacity - edge.flow;
+                if edge.to != s && via[edge.to].is_none() && residual > 0.0 && residual >= threshold {
+                    via[edge.to] = Some(e);
+                    queue.push_back(edge.to);
+                }
+            }
+        }
+        via[t]?;
+
+        let mut path = vec![];
+        let mut node = t;
+        while let Some(e) = via[node] {
+            path.push(e);
+            node = self.edges[e ^ 1].to;
+        }
+        Some(path)
+    }
+
+    // computes the maximum flow from s to t using the Edmonds-Karp algorithm, i.e. by
+    // repeatedly augmenting the flow along shortest paths in the residual network, starting
+    // from the current flow; returns by how much the flow was increased
+    pub fn max_flow(&mut self, s: usize, t: usize) -> Weight {
+        let mut total = 0.0;
+        loop {
+            let path = match self.augmenting_edges(s, t) {
+                Some(path) => path,
+                None => return total
+            };
+            total += self.augment(&path);
+        }
+    }
+
+    // computes the maximum flow from s to t with the given algorithm, starting from the
+    // current flow like max_flow, and returns by how much the flow was increased; all of them
+    // find the same value, but how fast depends on the shape of the network
+    pub fn max_flow_with(&mut self, s: usize, t: usize, algorithm: FlowAlgorithm) -> Weight {
+        if s == t {
+            return 0.0;
+        }
+        match algorithm {
+            FlowAlgorithm::EdmondsKarp => self.max_flow(s, t),
+            FlowAlgorithm::CapacityScaling => self.capacity_scaling(s, t),
+            FlowAlgorithm::Dinic => self.dinic(s, t),
+            FlowAlgorithm::PushRelabel => self.push_relabel(s, t),
+            FlowAlgorithm::Auto => {
+                let algorithm = self.choose_algorithm();
+                self.max_flow_with(s, t, algorithm)
+            }
+        }
+    }
+
+    // the algorithm Auto runs, picked by the shape of the network as the benchmark suggests:
+    // push-relabel did the least work on sparse, dense, widely ranging and grid networks of
+    // all sizes, and on unit capacity ones from about 200 edges on, while Dinic was slightly
+    // ahead on smaller unit capacity networks, where its blocking flows saturate whole paths
+    // at once; neither Edmonds-Karp nor capacity scaling, which needs a round of searches
+    // for every threshold, ever came out ahead
+    pub fn choose_algorithm(&self) -> FlowAlgorithm {
+        let unit = self.edges.iter().step_by(2).all(|edge| edge.capacity == 1.0);
+        if unit && self.edges.len() / 2 < 200 {
+            FlowAlgorithm::Dinic
+        } else {
+            FlowAlgorithm::PushRelabel
+        }
+    }
+
+    // capacity scaling: augments along shortest paths whose edges all have at least a
+    // threshold of residual capacity, starting with the largest power of two not above the
+    // largest finite capacity and halving it once there are none left, which needs
+    // O(m log U) augmentations for whole capacities up to U; a last round without threshold
+    // picks up what fractional capacities leave
+    fn capacity_scaling(&mut self, s: usize, t: usize) -> Weight {
+        let residuals: Vec<Weight> = self.edges.iter()
+            .map(|edge| edge.capacity - edge.flow)
+            .filter(|residual| *residual > 0.0 && residual.is_finite())
+            .collect();
+        let smallest = residuals.iter().cloned().fold(Weight::INFINITY, Weight::min);
+        let largest = residuals.iter().cloned().fold(0.0, Weight::max);
+        let mut total = 0.0;
+        if largest > 0.0 {
+            let mut threshold = (2.0 as Weight).powi(largest.log2().floor() as i32);
+            while threshold >= smallest {
+                while let Some(path) = self.wide_augmenting_edges(s, t, threshold) {
+                    total += self.augment(&path);
+                }
+                threshold /= 2.0;
+            }
+        }
+        total + self.max_flow(s, t)
+    }
+
+    // Dinic's algorithm: finds the levels of the nodes, their distances from s in the
+    // residual network, and sends a blocking flow along edges going one level up until t
+    // is out of reach, in O(n^2 m) time, as t gets farther away with every round
+    fn dinic(&mut self, s: usize, t: usize) -> Weight {
+        let n = self.adj.len();
+        let mut total = 0.0;
+        loop {
+            let mut level = vec![NONE; n];
+            level[s] = 0;
+            let mut queue = VecDeque::from(vec![s]);
+            while let Some(u) = queue.pop_front() {
+                for &e in &self.adj[u] {
+                    let edge = &self.edges[e];
+                    if level[edge.to] == NONE && edge.capacity - edge.flow > 0.0 {
+                        level[edge.to] = level[u] + 1;
+                        queue.push_back(edge.to);
+                    }
+                }
+            }
+            if level[t] == NONE {
+                return total;
+            }
+
+            // a depth first search along the levels, with the edges of the path so far and
+            // the next edge to try at every node; nodes that lead nowhere leave the levels
+            let mut next = vec![0; n];
+            let mut path: Vec<usize> = vec![];
+            loop {
+                let u = path.last().map_or(s, |e| self.edges[*e].to);
+                if u == t {
+                    total += self.augment(&path);
+                    let saturated = path.iter().position(|e| self.edges[*e].capacity - self.edges[*e].flow <= 0.0);
+                    path.truncate(saturated.unwrap_or(0));
+                    continue;
+                }
+                while next[u] < self.adj[u].len() {
+                    let edge = &self.edges[self.adj[u][next[u]]];
+                    if level[edge.to] == level[u] + 1 && edge.capacity - edge.flow > 0.0 {
+                        break;
+                    }
+                    next[u] += 1;
+                }
+                if next[u] < self.adj[u].len() {
+                    path.push(self.adj[u][next[u]]);
+                } else if path.pop().is_some() {
+                    level[u] = NONE;
+                } else {
+                    break;
+                }
+            }
+        }
+    }
+
+    // the FIFO push-relabel algorithm: floods the edges leaving s and moves the excess of
+    // every node along edges leading one height down, raising a node once there is none,
+    // until all excess has reached t or gone back to s, in O(n^3) time. Heights start as
+    // the distances to t, or to s for nodes that lost t, and are recomputed like that after
+    // every n raises; once no node is left at a height, the nodes above it cannot reach t
+    // and are raised above s right away. Infinite capacities out of s are only flooded with
+    // the total of the finite capacities, which bounds any finite maximum flow
+    fn push_relabel(&mut self, s: usize, t: usize) -> Weight {
+        let n = self.adj.len();
+        let before = self.flow_value(s);
+        let residual = |edge: &FlowEdge| edge.capacity - edge.flow;
+        let limit = self.edges.iter().map(residual).filter(|r| *r > 0.0 && r.is_finite()).sum::<Weight>().max(1.0);
+        let mut excess: Vec<Weight> = vec![0.0; n];
+        let mut queue = VecDeque::new();
+        for i in 0..self.adj[s].len() {
+            let e = self.adj[s][i];
+            let amount = residual(&self.edges[e]).min(limit);
+            if amount > 0.0 {
+                self.push(e, amount, &mut excess);
+                let to = self.edges[e].to;
+                if to != t && to != s && excess[to] == amount {
+                    queue.push_back(to);
+                }
+            }
+        }
+
+        let mut height = self.heights(s, t);
+        // the number of nodes below n at every height, and the next edge to try at every node
+        let mut count = vec![0; 2 * n + 1];
+        let recount = |height: &[usize], count: &mut [usize]| {
+            count.iter_mut().for_each(|c| *c = 0);
+            for h in height {
+                count[*h] += 1;
+            }
+        };
+        recount(&height, &mut count);
+        let mut next = vec![0; n];
+        let mut raises: usize = 0;
+        while let Some(u) = queue.pop_front() {
+            while excess[u] > 0.0 {
+                if next[u] == self.adj[u].len() {
+                    let old = height[u];
+                    height[u] = self.adj[u].iter()
+                        .filter(|e| residual(&self.edges[**e]) > 0.0)
+                        .map(|e| height[self.edges[*e].to] + 1)
+                        .min().unwrap_or(2 * n).min(2 * n);
+                    count[old] -= 1;
+                    count[height[u]] += 1;
+                    next[u] = 0;
+                    if old < n && count[old] == 0 {
+                        for h in height.iter_mut().filter(|h| **h > old && **h < n) {
+                            count[*h] -= 1;
+                            *h = n + 1;
+                            count[n + 1] += 1;
+                        }
+                    }
+                    raises += 1;
+                    if raises.is_multiple_of(n) {
+                        height = self.heights(s, t);
+                        recount(&height, &mut count);
+                        next.iter_mut().for_each(|i| *i = 0);
+                    }
+                    continue;
+                }
+                let e = self.adj[u][next[u]];
+                let to = self.edges[e].to;
+                let available = residual(&self.edges[e]);
+                if available > 0.0 && height[u] == height[to] + 1 {
+                    let amount = excess[u].min(available);
+                    let empty = excess[to] == 0.0;
+                    self.push(e, amount, &mut excess);
+                    if empty && to != s && to != t {
+                        queue.push_back(to);
+                    }
+                } else {
+                    next[u] += 1;
+                }
+            }
+        }
+        self.flow_value(s) - before
+    }
+
+    // sends the amount along edge e, moving it from the excess of its tail to its head
+    fn push(&mut self, e: usize, amount: Weight, excess: &mut [Weight]) {
+        self.edges[e].flow += amount;
+        self.edges[e ^ 1].flow -= amount;
+        excess[self.edges[e].to] += amount;
+        excess[self.edges[e ^ 1].to] -= amount;
+    }
+
+    // the heights push_relabel starts from: the distance to t in the residual network, n
+    // plus the distance to s for nodes that cannot reach t, and 2n for nodes reaching neither
+    fn heights(&self, s: usize, t: usize) -> Vec<usize> {
+        let n = self.adj.len();
+        let mut height = vec![2 * n; n];
+        let mut queue = VecDeque::new();
+        for (target, base) in [(t, 0), (s, n)] {
+            height[target] = base;
+            queue.push_back(target);
+            while let Some(v) = queue.pop_front() {
+                for &e in &self.adj[v] {
+                    // the reverse of an edge from v leads to it, with the residual capacity
+                    // of that edge
+                    let u = self.edges[e].to;
+                    let into = &self.edges[e ^ 1];
+                    if height[u] == 2 * n && u != s && into.capacity - into.flow > 0.0 {
+                        height[u] = height[v] + 1;
+                        queue.push_back(u);
+                    }
+                }
+            }
+        }
+        height
+    }
+
+    // sends the bottleneck capacity along the edges of an augmenting path and returns it
+    fn augment(&mut self, path: &[usize]) -> Weight {
+        let bottleneck = path.iter()
+            .map(|e| self.edges[*e].capacity - self.edges[*e].flow)
+            .fold(Weight::INFINITY, Weight::min);
+        for e in path {
+            self.edges[*e].flow += bottleneck;
+            self.edges[*e ^ 1].flow -= bottleneck;
+        }
+        bottleneck
+    }
+
+    // augments the flow from s to t along at most k shortest augmenting paths by one unit
+    // each, for networks whose capacities are whole numbers
+    fn augment_units(&mut self, s: usize, t: usize, k: usize) {
+        for _ in 0..k {
+            match self.augmenting_edges(s, t) {
+                Some(path) => for e in path {
+                    self.edges[e].flow += 1.0;
+                    self.edges[e ^ 1].flow -= 1.0;
+                },
+                None => return
+            }
+        }
+    }
+
+    // splits a flow of whole units from s to t into paths by following edges carrying flow
+    // from s until reaching t, cutting out any cycle a walk runs into, and returns the nodes
+    // of each path
+    fn unit_paths(&self, s: usize, t: usize) -> Vec<Vec<usize>> {
+        let mut remaining: Vec<i64> = self.edges.iter().step_by(2).map(|edge| edge.flow.round() as i64).collect();
+        let mut paths = vec![];
+        for _ in 0..self.flow_value(s).round() as usize {
+            let mut path = vec![s];
+            let mut position: HashMap<usize, usize> = vec![(s, 0)].into_iter().collect();
+            while path[path.len() - 1] != t {
+                let u = path[path.len() - 1];
+                let e = self.adj[u].iter().cloned().find(|e| e % 2 == 0 && remaining[e / 2] > 0).unwrap();
+                remaining[e / 2] -= 1;
+                let w = self.edges[e].to;
+                if let Some(&i) = position.get(&w) {
+                    for node in path.drain(i + 1..) {
+                        position.remove(&node);
+                    }
+                } else {
+                    position.insert(w, path.len());
+                    path.push(w);
+                }
+            }
+            paths.push(path);
+        }
+        paths
+    }
+
+    // returns the nodes reachable from s in the residual network; after computing a maximum
+    // flow these form the source side of a minimum cut
+    pub fn residual_reachable(&self, s: usize) -> Vec<bool> {
+        let mut reachable = vec![false; self.adj.len()];
+        reachable[s] = true;
+        let mut stack = vec![s];
+        while let Some(u) = stack.pop() {
+            for &e in &self.adj[u] {
+                let edge = &self.edges[e];
+                if !reachable[edge.to] && edge.capacity - edge.flow > 0.0 {
+                    reachable[edge.to] = true;
+                    stack.push(edge.to);
+                }
+            }
+        }
+        reachable
+    }
+}
+
+impl Graph {
+
+    // computes the vertex connectivity of the graph, i.e. the smallest number of vertices
+    // whose removal disconnects the remaining graph (n - 1 for complete graphs)
+    pub fn vertex_connectivity(&self) -> usize {
+        let mut vertices: Vec<Vertex> = self.vertices().iter().cloned().collect();
+        vertices.sort();
+        let adj = self.adjacencies();
+        let mut connectivity = vertices.len().saturating_sub(1);
+
+        // any minimum vertex cut misses one of the first connectivity + 1 vertices, and
+        // that vertex is separated from some later vertex by the cut (Even's algorithm)
+        let mut i = 0;
+        while i <= connectivity && i < vertices.len() {
+            let v = vertices[i];
+            for w in &vertices[i+1..] {
+                let pairs = if self.is_directed() { vec![(v, *w), (*w, v)] } else { vec![(v, *w)] };
+                for (s, t) in pairs {
+                    if !adj[&s].contains(&t) {
+                        let cut = self.min_vertex_cut(s, t).unwrap();
+                        connectivity = connectivity.min(cut.len());
+                    }
+                }
+            }
+            i += 1;
+        }
+        connectivity
+    }
+
+    // finds up to k paths from s to t that share no edge, by sending k units of flow along
+    // augmenting paths with every edge, in both directions if undirected, of capacity 1
+    // and following the flow; fewer paths are returned only if no more exist, which
+    // takes removing as many edges to separate t from s, by Menger's theorem. Paths are
+    // simple unless s and t are the same vertex, for which there are none
+    pub fn edge_disjoint_paths(&self, s: Vertex, t: Vertex, k: usize) -> Vec<Path> {
+        if s == t || !self.vertices().contains(&s) || !self.vertices().contains(&t) {
+            return vec![];
+        }
+        let (vertices, index) = self.indexed_vertices();
+        let mut network = FlowNetwork::new(vertices.len());
+        let mut edges: Vec<_> = self.edges().iter().filter(|edge| edge.0 != edge.1).cloned().collect();
+        edges.sort();
+        for (v, w) in edges {
+            network.add_edge(index[&v], index[&w], 1.0);
+            if !self.is_directed() {
+                network.add_edge(index[&w], index[&v], 1.0);
+            }
+        }
+        network.augment_units(index[&s], index[&t], k);
+        if !self.is_directed() {
+            // flow going both ways along an undirected edge cancels out
+            for pair in network.edges.chunks_mut(4) {
+                if pair[0].flow > 0.5 && pair[2].flow > 0.5 {
+                    pair[0].flow = 0.0;
+                    pair[1].flow = 0.0;
+                    pair[2].flow = 0.0;
+                    pair[3].flow = 0.0;
+                }
+            }
+        }
+        network.unit_paths(index[&s], index[&t]).into_iter()
+            .map(|nodes| nodes.into_iter().map(|node| vertices[node]).collect())
+            .collect()
+    }
+
+    // finds up to k paths from s to t that share no vertex other than s and t, like
+    // edge_disjoint_paths but with every vertex split into an in- and an out-node joined by
+    // an edge of capacity 1, as for min_vertex_cut; if s and t are adjacent, the edge
+    // between them counts as one of the paths
+    pub fn vertex_disjoint_paths(&self, s: Vertex, t: Vertex, k: usize) -> Vec<Path> {
+        if s == t || !self.vertices().contains(&s) || !self.vertices().contains(&t) {
+            return vec![];
+        }
+        let (vertices, index) = self.indexed_vertices();
+        // vertex i is split into the in-node 2i and the out-node 2i + 1
+        let mut network = FlowNetwork::new(2 * vertices.len());
+        for (i, v) in vertices.iter().enumerate() {
+            network.add_edge(2 * i, 2 * i + 1, if *v == s || *v == t { k as Weight } else { 1.0 });
+        }
+        let mut edges: Vec<_> = self.edges().iter().filter(|edge| edge.0 != edge.1).cloned().collect();
+        edges.sort();
+        for (v, w) in edges {
+            network.add_edge(2 * index[&v] + 1, 2 * index[&w], 1.0);
+            if !self.is_directed() {
+                network.add_edge(2 * index[&w] + 1, 2 * index[&v], 1.0);
+            }
+        }
+        network.augment_units(2 * index[&s] + 1, 2 * index[&t], k);
+        network.unit_paths(2 * index[&s] + 1, 2 * index[&t]).into_iter()
+            .map(|nodes| {
+                let mut path = vec![s];
+                path.extend(nodes.into_iter().filter(|node| node % 2 == 0).map(|node| vertices[node / 2]));
+                path
+            })
+            .collect()
+    }
+
+    // computes a minimum set of vertices (other than s and t) whose removal leaves t
+    // unreachable from s, using a maximum flow on the graph with every vertex split into
+    // an in- and an out-node joined by an edge of capacity 1
+    // returns None if s and t are the same or adjacent vertices, as no such set exists, or
+    // if either is not in the graph
+    pub fn min_vertex_cut(&self, s: Vertex, t: Vertex) -> Option<HashSet<Vertex>> {
+        let adj = self.adjacencies();
+        if s == t || adj.get(&s)?.contains(&t) || !adj.contains_key(&t) {
+            return None;
+        }
+        let vertices: Vec<Vertex> = self.vertices().iter().cloned().collect();
+        let index: HashMap<Vertex, usize> = vertices.iter().enumerate().map(|(i, v)| (*v, i)).collect();
+
+        // vertex i is split into the in-node 2i and the out-node 2i + 1
+        let mut network = FlowNetwork::new(2 * vertices.len());
+        for (i, v) in vertices.iter().enumerate() {
+            let capacity = if *v == s || *v == t { Weight::INFINITY } else { 1.0 };
+            network.add_edge(2 * i, 2 * i + 1, capacity);
+            for w in &adj[v] {
+                network.add_edge(2 * i + 1, 2 * index[w], Weight::INFINITY);
+            }
+        }
+        network.max_flow_with(2 * index[&s] + 1, 2 * index[&t], FlowAlgorithm::Auto);
+
+        let reachable = network.residual_reachable(2 * index[&s] + 1);
+        let cut = vertices.iter().enumerate()
+            .filter(|&(i, _)| reachable[2 * i] && !reachable[2 * i + 1])
+            .map(|(_, v)| *v)
+            .collect();
+        Some(cut)
+    }
+}
+
+// times every algorithm, and Auto, on random networks of about n nodes of the shapes that
+// tell them apart, running each the given number of times from a zero flow: sparse ones with
+// small capacities, dense ones, bipartite matchings with unit capacities, sparse ones with
+// capacities spanning six orders of magnitude, and grids as in image segmentation
+pub fn benchmark(n: usize, seed: u64, repetitions: usize) -> Vec<FlowTiming> {
+    let mut random = Random::new(seed);
+    let n = n.max(4);
+    let mut networks: Vec<(&'static str, FlowNetwork)> = vec![];
+
+    let mut sparse = FlowNetwork::new(n);
+    for _ in 0..4 * n {
+        sparse.add_edge(random.below(n), random.below(n), (1 + random.below(100)) as Weight);
+    }
+    networks.push(("sparse", sparse));
+
+    let dense_nodes = n.min(400);
+    let mut dense = FlowNetwork::new(dense_nodes);
+    for u in 0..dense_nodes {
+        for v in 0..dense_nodes {
+            if u != v && random.below(2) == 0 {
+                dense.add_edge(u, v, (1 + random.below(100)) as Weight);
+            }
+        }
+    }
+    networks.push(("dense", dense));
+
+    // the source 0, the left side 1..=half, the right side after it and the target n - 1
+    let half = (n - 2) / 2;
+    let mut matching = FlowNetwork::new(n);
+    for left in 1..=half {
+        matching.add_edge(0, left, 1.0);
+        for _ in 0..3 {
+            matching.add_edge(left, half + 1 + random.below(half), 1.0);
+        }
+    }
+    for right in half + 1..=2 * half {
+        matching.add_edge(right, n - 1, 1.0);
+    }
+    networks.push(("unit", matching));
+
+    let mut wide = FlowNetwork::new(n);
+    for _ in 0..4 * n {
+        let capacity = (10.0 as Weight).powf(6.0 * random.next_f64()).floor();
+        wide.add_edge(random.below(n), random.below(n), capacity);
+    }
+    networks.push(("wide", wide));
+
+    // a side by side grid between the source 0 and the target n - 1, with the source joined
+    // to the first column and the last column to the target
+    let side = ((n - 2) as f64).sqrt() as usize;
+    let node = |row: usize, column: usize| 1 + row * side + column;
+    let mut grid = FlowNetwork::new(n);
+    for row in 0..side {
+        grid.add_edge(0, node(row, 0), Weight::INFINITY);
+        grid.add_edge(node(row, side - 1), n - 1, Weight::INFINITY);
+        for column in 0..side {
+            if column + 1 < side {
+                grid.add_edge(node(row, column), node(row, column + 1), (1 + random.below(20)) as Weight);
+            }
+            if row + 1 < side {
+                grid.add_edge(node(row, column), node(row + 1, column), (1 + random.below(20)) as Weight);
+                grid.add_edge(node(row + 1, column), node(row, column), (1 + random.below(20)) as Weight);
+            }
+        }
+    }
+    networks.push(("grid", grid));
+
+    let mut timings = vec![];
+    for (name, network) in networks {
+        let target = network.adj.len() - 1;
+        for algorithm in FLOW_ALGORITHMS.iter().cloned().chain([FlowAlgorithm::Auto]) {
+            let mut time = Duration::ZERO;
+            let mut value = 0.0;
+            for _ in 0..repetitions.max(1) {
+                let mut run = network.clone();
+                let start = Instant::now();
+                value = run.max_flow_with(0, target, algorithm);
+                time += start.elapsed();
+            }
+            timings.push(FlowTiming { network: name, algorithm, value, time: time / repetitions.max(1) as u32 });
+        }
+    }
+    timings
+}
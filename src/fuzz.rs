@@ -0,0 +1,294 @@
+use std::io::Cursor;
+use std::panic;
+
+use compressed::CompressedGraph;
+use graph::{Component, Graph, HashSet, Vertex, Weight};
+use parse::ParseOptions;
+use random::Random;
+use shortest_paths::distances;
+use testing::{assert_proper_coloring, assert_same_coloring, assert_same_partition, assert_valid_path};
+
+// an algorithm run on arbitrary graphs, by name
+type GraphTarget = (&'static str, fn(&Graph));
+
+// a loader run on malformed input, ignoring its errors
+type Loader = fn(&[u8]);
+
+// a loader run on malformed text, by name, with the line of its header in valid input
+type TextLoader = (&'static str, usize, Loader);
+
+// a fuzz target that panicked, with the input that made it panic: the text or bytes given
+// to a loader, or the graph in the binary format of write_binary for the algorithms
+pub struct Failure {
+    pub target: &'static str,
+    pub input: Vec<u8>,
+    pub message: String
+}
+
+impl Graph {
+
+    // a random graph with up to max_vertices vertices meant to trip up the algorithms: its
+    // vertex numbers include 0 and the largest vertices, it may contain self-loops and, if
+    // undirected, edges in both directions, and its weights range from 0 to huge
+    pub fn arbitrary(random: &mut Random, max_vertices: usize) -> Graph {
+        let n = random.below(max_vertices + 1);
+        let mut vertices = HashSet::new();
+        while vertices.len() < n {
+            vertices.insert(match random.below(4) {
+                0 => random.below(n + 1) as Vertex,
+                1 => Vertex::MAX - random.below(n + 1) as Vertex,
+                2 => Vertex::MAX / (1 + random.below(1 << 16) as Vertex),
+                _ => 1 + random.below(4 * n + 1) as Vertex
+            });
+        }
+        let mut sorted: Vec<Vertex> = vertices.iter().cloned().collect();
+        sorted.sort();
+
+        let mut graph = Graph::new(vertices, HashSet::new(), random.below(2) == 0);
+        let weighted = random.below(2) == 0;
+        let edges = if n == 0 { 0 } else { random.below(3 * n + 1) };
+        for _ in 0..edges {
+            let u = sorted[random.below(n)];
+            let v = if random.below(8) == 0 { u } else { sorted[random.below(n)] };
+            let weight = match random.below(4) {
+                0 => 0.0,
+                1 => 1e300,
+                _ => (random.below(100) + 1) as Weight
+            };
+            if weighted {
+                graph.add_weighted_edge(u, v, weight);
+            } else {
+                graph.add_edge(u, v);
+            }
+            // add_edge takes an undirected edge only once, the loaders do not
+            if !graph.is_directed() && random.below(8) == 0 {
+                let mut edges = graph.edges().clone();
+                edges.insert((v, u));
+                let mut weights = graph.weights().clone();
+                if weighted {
+                    weights.insert((v, u), weight);
+                }
+                graph = Graph::with_weights(graph.vertices().clone(), edges, weights, false);
+            }
+        }
+        graph
+    }
+}
+
+// runs the loaders on malformed files and the algorithms on arbitrary graphs for the given
+// number of rounds, returning the targets that panicked; inputs are derived from the seed,
+// so a failure can be reproduced by running the same round again. Only the headers of the
+// text formats are never made huge, as the loaders allocate every vertex they announce
+pub fn fuzz(rounds: usize, seed: u64) -> Vec<Failure> {
+    let mut random = Random::new(seed);
+    let mut failures = vec![];
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    for _ in 0..rounds {
+        let graph = Graph::arbitrary(&mut random, 12);
+        let mut binary = vec![];
+        graph.write_binary(&mut binary).unwrap();
+        for (target, check) in graph_targets() {
+            if let Err(message) = run(|| check(&graph)) {
+                failures.push(Failure { target, input: binary.clone(), message });
+            }
+        }
+
+        let text = loader_input(&mut random);
+        for (target, header, load) in text_loaders() {
+            let input = mutate_text(&mut random, &text[target], header).into_bytes();
+            if let Err(message) = run(|| load(&input)) {
+                failures.push(Failure { target, input, message });
+            }
+        }
+
+        let mut compressed = vec![];
+        graph.compress().write(&mut compressed).unwrap();
+        let binary_loaders: [(&'static str, Vec<u8>, Loader); 2] = [
+            ("read_binary", binary, |input| { let _ = Graph::read_binary(&mut Cursor::new(input)); }),
+            ("compressed read", compressed, |input| { let _ = CompressedGraph::read(&mut Cursor::new(input)).map(|graph| graph.decompress()); })
+        ];
+        for (target, valid, load) in binary_loaders {
+            let input = mutate_bytes(&mut random, &valid);
+            if let Err(message) = run(|| load(&input)) {
+                failures.push(Failure { target, input, message });
+            }
+        }
+    }
+    panic::set_hook(hook);
+    failures
+}
+
+// calls the target, turning a panic into its message
+fn run<F: FnOnce()>(target: F) -> Result<(), String> {
+    panic::catch_unwind(panic::AssertUnwindSafe(target)).map_err(|payload| {
+        match payload.downcast_ref::<&str>() {
+            Some(message) => message.to_string(),
+            None => payload.downcast_ref::<String>().cloned().unwrap_or_else(|| "panic".to_string())
+        }
+    })
+}
+
+// the algorithms run on every arbitrary graph, checking a few properties of their results
+fn graph_targets() -> Vec<GraphTarget> {
+    vec![
+        ("components", |graph| {
+            let n = graph.vertices().len();
+            for components in [graph.weakly_connected_components(), graph.strongly_connected_components()] {
+                assert_eq!(components.iter().map(|component| component.len()).sum::<usize>(), n, "components do not partition the vertices");
+            }
+            let subgraphs: Vec<Component> = graph.component_subgraphs().iter().map(|subgraph| subgraph.vertices().clone()).collect();
+            assert_same_partition(&graph.weakly_connected_components(), &subgraphs);
+        }),
+        ("shortest paths", |graph| {
+            let vertices = graph.sorted_vertices();
+            if let (Some(source), Some(target)) = (vertices.first(), vertices.last()) {
+                let found = graph.shortest_path(*source, *target).unwrap();
+                if let Some(found) = &found {
+                    assert_valid_path(graph, &found.path, *source, *target);
+                }
+                let both = graph.bidirectional_shortest_path(*source, *target);
+                assert_eq!(found.map(|found| found.distance), both.map(|both| both.distance), "searches find different distances");
+            }
+        }),
+        ("coloring", |graph| {
+            let coloring = graph.dsatur_coloring().coloring;
+            assert_proper_coloring(graph, &coloring);
+            // the vertices are taken up in the same order however they are numbered
+            let (dense, _, original) = graph.relabel_dense();
+            let renumbered = dense.dsatur_coloring().coloring.into_iter().map(|(v, color)| (original[v as usize], color)).collect();
+            assert_same_coloring(&coloring, &renumbered);
+        }),
+        ("distances", |graph| {
+            if let Some(source) = graph.sorted_vertices().first() {
+                let hops = graph.hop_distance_map(*source);
+                let distance = distances(&graph.weighted_adjacencies(), *source);
+                assert_eq!(hops.len(), distance.len(), "searches reach different vertices");
+            }
+        }),
+        ("spanning forest", |graph| {
+            if !graph.is_directed() {
+                graph.minimum_spanning_forest();
+            }
+        }),
+        ("topological order", |graph| {
+            if graph.is_directed() {
+                if let Ok(order) = graph.topological_order() {
+                    assert_eq!(order.len(), graph.vertices().len(), "order misses vertices");
+                }
+            }
+        }),
+        ("triangles", |graph| { graph.triangle_count(2); }),
+        ("diff", |graph| assert!(graph.diff(graph).is_empty(), "graph differs from itself")),
+        ("binary round trip", |graph| {
+            let mut bytes = vec![];
+            graph.write_binary(&mut bytes).unwrap();
+            assert!(Graph::read_binary(&mut Cursor::new(bytes)).unwrap() == *graph, "graph changed");
+        }),
+        ("compressed round trip", |graph| {
+            let mut bytes = vec![];
+            graph.compress().write(&mut bytes).unwrap();
+            let read = CompressedGraph::read(&mut Cursor::new(bytes)).unwrap().decompress();
+            assert!(read.adjacencies() == graph.adjacencies(), "adjacencies changed");
+        })
+    ]
+}
+
+// the text loaders, named like their valid inputs by loader_input
+fn text_loaders() -> Vec<TextLoader> {
+    vec![
+        ("parse", 0, |input| {
+            for strict in [false, true] {
+                let options = ParseOptions { strict, ..ParseOptions::default() };
+                let _ = Graph::parse(&mut Cursor::new(input), false, true, &options);
+            }
+        }),
+        ("parse_edge_list", usize::MAX, |input| {
+            let _ = Graph::parse_edge_list(&mut Cursor::new(input), false, true, &ParseOptions::default());
+        }),
+        ("load_dimacs", 0, |input| { let _ = Graph::load_dimacs(Cursor::new(input)); }),
+        ("load_matrix_market", 1, |input| { let _ = Graph::load_matrix_market(Cursor::new(input)); })
+    ]
+}
+
+// a valid input for each text loader, describing the same small random graph
+fn loader_input(random: &mut Random) -> ::std::collections::HashMap<&'static str, String> {
+    let n = random.below(8) + 1;
+    let edges: Vec<(usize, usize, usize)> = (0..random.below(2 * n + 1))
+        .map(|_| (random.below(n) + 1, random.below(n) + 1, random.below(10)))
+        .collect();
+    let lines = |format: &dyn Fn(&(usize, usize, usize)) -> String| -> String {
+        edges.iter().map(|edge| format(edge) + "\n").collect()
+    };
+    vec![
+        ("parse", format!("{} {}\n", n, edges.len()) + &lines(&|&(u, v, w)| format!("{} {} {}", u, v, w))),
+        ("parse_edge_list", lines(&|&(u, v, w)| format!("{} {} {}", u, v, w))),
+        ("load_dimacs", format!("p sp {} {}\n", n, edges.len()) + &lines(&|&(u, v, w)| format!("a {} {} {}", u, v, w))),
+        ("load_matrix_market", format!("%%MatrixMarket matrix coordinate real general\n{} {} {}\n", n, n, edges.len())
+            + &lines(&|&(u, v, w)| format!("{} {} {}", u, v, w)))
+    ].into_iter().collect()
+}
+
+// applies a few random changes to the lines of the text: replacing a token by an
+// unexpected one, deleting, repeating or inserting a line, or cutting the text short;
+// the header line stays where it is and its tokens are only replaced by small numbers
+fn mutate_text(random: &mut Random, text: &str, header: usize) -> String {
+    const TOKENS: [&str; 12] = [
+        "0", "-1", "18446744073709551616", "4294967295", "18446744073709551615", "1e309",
+        "NaN", "-inf", "x", "", "%", "a"
+    ];
+    let mut lines: Vec<String> = text.lines().map(|line| line.to_string()).collect();
+    for _ in 0..random.below(4) + 1 {
+        if lines.is_empty() {
+            lines.push(TOKENS[random.below(TOKENS.len())].to_string());
+        }
+        let i = random.below(lines.len());
+        let below_header = header == usize::MAX || i > header;
+        match random.below(5) {
+            0 | 1 => {
+                let mut tokens: Vec<String> = lines[i].split(' ').map(|token| token.to_string()).collect();
+                let j = random.below(tokens.len());
+                tokens[j] = if i == header { random.below(64).to_string() } else { TOKENS[random.below(TOKENS.len())].to_string() };
+                lines[i] = tokens.join(" ");
+            },
+            2 if below_header => { lines.remove(i); },
+            3 if below_header => {
+                let line = lines[i].clone();
+                lines.insert(i, line);
+            },
+            4 if below_header => {
+                let line = (0..random.below(4)).map(|_| TOKENS[random.below(TOKENS.len())]).collect::<Vec<_>>().join(" ");
+                lines.insert(i, line);
+            },
+            _ => ()
+        }
+    }
+    let mut text = lines.join("\n");
+    if random.below(4) == 0 {
+        let mut cut = random.below(text.len() + 1);
+        while !text.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        text.truncate(cut);
+    }
+    text
+}
+
+// flips, overwrites, deletes or inserts a few random bytes, or cuts the bytes short
+fn mutate_bytes(random: &mut Random, bytes: &[u8]) -> Vec<u8> {
+    let mut bytes = bytes.to_vec();
+    for _ in 0..random.below(4) + 1 {
+        if bytes.is_empty() {
+            break;
+        }
+        let i = random.below(bytes.len());
+        match random.below(5) {
+            0 => bytes[i] ^= 1 << random.below(8),
+            1 => bytes[i] = [0, 0x7f, 0x80, 0xff][random.below(4)],
+            2 => { bytes.remove(i); },
+            3 => bytes.insert(i, random.next_u64() as u8),
+            _ => bytes.truncate(i)
+        }
+    }
+    bytes
+}
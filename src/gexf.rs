@@ -0,0 +1,76 @@
+use std::io;
+use std::io::Write;
+
+use attributes::VertexMap;
+use graph::{Edge, Graph};
+
+// the values of a vertex attribute column, of the GEXF type integer or double
+pub enum Column<'a> {
+    Integer(&'a VertexMap<usize>),
+    Double(&'a VertexMap<f64>)
+}
+
+impl Graph {
+
+    // writes the graph in the GEXF format read by Gephi, with the given vertex attributes
+    // as named columns; vertices without a value in a column get no value for it, and edges
+    // carry their weights if the graph is weighted
+    pub fn write_gexf<W: Write>(&self, writer: &mut W, columns: &[(&str, Column)]) -> io::Result<()> {
+        writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(writer, r#"<gexf xmlns="http://gexf.net/1.3" version="1.3">"#)?;
+        let edge_type = if self.is_directed() { "directed" } else { "undirected" };
+        writeln!(writer, r#"  <graph mode="static" defaultedgetype="{}">"#, edge_type)?;
+        if !columns.is_empty() {
+            writeln!(writer, r#"    <attributes class="node">"#)?;
+            for (i, (title, column)) in columns.iter().enumerate() {
+                let kind = match *column {
+                    Column::Integer(_) => "integer",
+                    Column::Double(_) => "double"
+                };
+                writeln!(writer, r#"      <attribute id="{}" title="{}" type="{}"/>"#, i, escape(title), kind)?;
+            }
+            writeln!(writer, "    </attributes>")?;
+        }
+
+        writeln!(writer, "    <nodes>")?;
+        for v in self.sorted_vertices() {
+            let values: Vec<(usize, String)> = columns.iter().enumerate()
+                .filter_map(|(i, (_, column))| match *column {
+                    Column::Integer(values) => values.get(v).map(|value| (i, value.to_string())),
+                    Column::Double(values) => values.get(v).map(|value| (i, value.to_string()))
+                })
+                .collect();
+            if values.is_empty() {
+                writeln!(writer, r#"      <node id="{0}" label="{0}"/>"#, v)?;
+                continue;
+            }
+            writeln!(writer, r#"      <node id="{0}" label="{0}">"#, v)?;
+            writeln!(writer, "        <attvalues>")?;
+            for (i, value) in values {
+                writeln!(writer, r#"          <attvalue for="{}" value="{}"/>"#, i, value)?;
+            }
+            writeln!(writer, "        </attvalues>")?;
+            writeln!(writer, "      </node>")?;
+        }
+        writeln!(writer, "    </nodes>")?;
+
+        writeln!(writer, "    <edges>")?;
+        let mut edges: Vec<&Edge> = self.edges().iter().collect();
+        edges.sort();
+        for (i, edge) in edges.into_iter().enumerate() {
+            if self.is_weighted() {
+                writeln!(writer, r#"      <edge id="{}" source="{}" target="{}" weight="{}"/>"#, i, edge.0, edge.1, self.weight(edge))?;
+            } else {
+                writeln!(writer, r#"      <edge id="{}" source="{}" target="{}"/>"#, i, edge.0, edge.1)?;
+            }
+        }
+        writeln!(writer, "    </edges>")?;
+        writeln!(writer, "  </graph>")?;
+        writeln!(writer, "</gexf>")
+    }
+}
+
+// escapes the characters with a special meaning in XML attribute values
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
@@ -1,31 +1,61 @@
-use std::collections::HashMap;
-use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::fmt;
+use std::hash::{Hash, Hasher};
 
+use shortest_paths::{trace_path, Path};
+use traversal::sorted_adjacencies;
 use tuple_reader::TupleReader;
 
-// basic graph types: vertices & edges
+// basic graph types: vertices & edges; vertices are 64 bit wide with the wide_vertices
+// feature, for graphs with vertex ids that do not fit into 32 bits
+#[cfg(not(feature = "wide_vertices"))]
 pub type Vertex = u32;
+#[cfg(feature = "wide_vertices")]
+pub type Vertex = u64;
 pub type Edge = (Vertex, Vertex);
 
+// edge weights, e.g. distances; edges of unweighted graphs all have weight 1
+pub type Weight = f64;
+
+// the maps and sets the graph and its algorithms keep vertices and edges in: hashed by
+// default, or B-trees with the btree_core feature, which need no source of randomness, as
+// on an embedded router without one, and iterate in ascending order; the B-trees go by the
+// names of the hash tables, so that the modules read the same with either
+#[cfg(not(feature = "btree_core"))]
+pub use std::collections::{hash_map, HashMap, HashSet};
+#[cfg(feature = "btree_core")]
+pub use std::collections::{btree_map as hash_map, BTreeMap as HashMap, BTreeSet as HashSet};
+
 // adjacency map contains a list of adjacent vertices for each vertex in the graph
-type Adjacencies = HashMap<Vertex, HashSet<Vertex>>;
+pub type Adjacencies = HashMap<Vertex, HashSet<Vertex>>;
+
+// weighted adjacency map contains the weight of the edge to each adjacent vertex
+pub type WeightedAdjacencies = HashMap<Vertex, HashMap<Vertex, Weight>>;
 
 // connected components of a graph
 pub type Component = HashSet<Vertex>;
 
 // a graph consists of a list of edges
 // TODO: how to represent vertices that do not have any edges?
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Graph {
     vertices: HashSet<Vertex>,
     edges: HashSet<Edge>,
-    directed: bool
+    weights: HashMap<Edge, Weight>,
+    directed: bool,
+    // whether searches over the entire graph take their roots and neighbours in ascending
+    // order, so that their results come out in the same order on every run
+    deterministic: bool
 }
 
 impl Graph {
 
     pub fn new(vertices: HashSet<Vertex>, edges: HashSet<Edge>, directed: bool) -> Graph {
-        Graph { vertices: vertices, edges: edges, directed: directed }
+        Graph { vertices, edges, weights: HashMap::new(), directed, deterministic: false }
+    }
+
+    pub fn with_weights(vertices: HashSet<Vertex>, edges: HashSet<Edge>, weights: HashMap<Edge, Weight>, directed: bool) -> Graph {
+        Graph { vertices, edges, weights, directed, deterministic: false }
     }
 
     // loads a graph from an input stream:
@@ -42,8 +72,134 @@ impl Graph {
         Graph::new(vertices, edges, directed)
     }
 
+    // loads a weighted graph from an input stream:
+    // first line contains the number of vertices v and edges e
+    // next e lines contain pairs of vertices followed by the weight of the edge between them
+    pub fn load_weighted<T: TupleReader>(reader: &mut T, directed: bool) -> Graph {
+        let (v, e) = reader.next_tuple();
+        let vertices = (1..v+1).collect();
+        let mut edges = HashSet::new();
+        let mut weights = HashMap::new();
+        for _ in 0..e {
+            let (from, to, weight) = reader.next_weighted_tuple();
+            edges.insert((from, to));
+            weights.insert((from, to), weight);
+        }
+        Graph { vertices, edges, weights, directed, deterministic: false }
+    }
+
+    // the set of vertices of the graph
+    pub fn vertices(&self) -> &HashSet<Vertex> {
+        &self.vertices
+    }
+
+    // the vertices of the graph in ascending order
+    pub fn sorted_vertices(&self) -> Vec<Vertex> {
+        let mut vertices: Vec<Vertex> = self.vertices.iter().cloned().collect();
+        vertices.sort();
+        vertices
+    }
+
+    // the vertices of the graph in ascending order, together with the position of each
+    pub fn indexed_vertices(&self) -> (Vec<Vertex>, HashMap<Vertex, usize>) {
+        let vertices = self.sorted_vertices();
+        let index = vertices.iter().enumerate().map(|(i, v)| (*v, i)).collect();
+        (vertices, index)
+    }
+
+    // the set of edges of the graph
+    pub fn edges(&self) -> &HashSet<Edge> {
+        &self.edges
+    }
+
+    // the weights of the edges of a weighted graph, by the edge as it is stored
+    pub fn weights(&self) -> &HashMap<Edge, Weight> {
+        &self.weights
+    }
+
+    // releases capacity of the vertex and edge sets beyond what their contents need, of
+    // which B-trees hold none
+    pub fn shrink_to_fit(&mut self) {
+        #[cfg(not(feature = "btree_core"))]
+        {
+            self.vertices.shrink_to_fit();
+            self.edges.shrink_to_fit();
+            self.weights.shrink_to_fit();
+        }
+    }
+
+    // adds the edge from v to w together with any missing vertices; returns false if the
+    // graph already contains the edge, which for undirected graphs may be stored as w - v
+    pub fn add_edge(&mut self, v: Vertex, w: Vertex) -> bool {
+        if self.edges.contains(&(v, w)) || (!self.directed && self.edges.contains(&(w, v))) {
+            return false;
+        }
+        self.vertices.insert(v);
+        self.vertices.insert(w);
+        self.edges.insert((v, w))
+    }
+
+    // adds v as a vertex without edges; returns false if the graph already contains it
+    pub fn add_vertex(&mut self, v: Vertex) -> bool {
+        self.vertices.insert(v)
+    }
+
+    // adds the edge from v to w like add_edge, or changes its weight if it exists
+    pub fn add_weighted_edge(&mut self, v: Vertex, w: Vertex, weight: Weight) {
+        self.add_edge(v, w);
+        let edge = self.edge_between(v, w);
+        self.weights.insert(edge, weight);
+    }
+
+    // removes the edge from v to w (or w - v for undirected graphs) but not its vertices;
+    // returns false if there is no such edge
+    pub fn remove_edge(&mut self, v: Vertex, w: Vertex) -> bool {
+        let edge = self.edge_between(v, w);
+        if !self.directed || edge == (v, w) {
+            self.weights.remove(&edge);
+            return self.edges.remove(&edge);
+        }
+        false
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    // selects the deterministic mode, in which connected_components and the like list their
+    // components in the same order on every run: the components of an undirected graph by
+    // their smallest vertex, strongly connected components in a fixed reverse topological
+    // order; without it the order follows the hashing of the vertex sets, which is faster
+    pub fn set_deterministic(&mut self, deterministic: bool) {
+        self.deterministic = deterministic;
+    }
+
+    pub fn is_deterministic(&self) -> bool {
+        self.deterministic
+    }
+
+    // true if any edge weights were given, as for graphs loaded by load_weighted
+    pub fn is_weighted(&self) -> bool {
+        !self.weights.is_empty()
+    }
+
+    // returns the edge of the graph leading from v to w, which for undirected graphs may be
+    // stored the other way round
+    pub fn edge_between(&self, v: Vertex, w: Vertex) -> Edge {
+        if self.edges().contains(&(v, w)) { (v, w) } else { (w, v) }
+    }
+
+    // returns the weight of the given edge
+    pub fn weight(&self, edge: &Edge) -> Weight {
+        match self.weights.get(edge) {
+            Some(weight) => *weight,
+            None if !self.directed => *self.weights.get(&(edge.1, edge.0)).unwrap_or(&1.0),
+            None => 1.0
+        }
+    }
+
     // builds the adjacency map for the graph
-    fn adjacencies(&self) -> Adjacencies {
+    pub fn adjacencies(&self) -> Adjacencies {
         let mut adj = HashMap::new();
         for vertex in &self.vertices {
             adj.insert(*vertex, HashSet::new());
@@ -57,27 +213,123 @@ impl Graph {
         adj
     }
 
-    // depth first search of the entire graph
+    // builds the weighted adjacency map for the graph; if there are several edges between
+    // the same pair of vertices, the smallest weight is used
+    pub fn weighted_adjacencies(&self) -> WeightedAdjacencies {
+        let mut adj = HashMap::new();
+        for vertex in &self.vertices {
+            adj.insert(*vertex, HashMap::new());
+        }
+        let mut insert = |from: Vertex, to: Vertex, weight: Weight| {
+            let current = adj.get_mut(&from).unwrap().entry(to).or_insert(weight);
+            *current = current.min(weight);
+        };
+        for edge in &self.edges {
+            let weight = self.weight(edge);
+            insert(edge.0, edge.1, weight);
+            if !self.directed {
+                insert(edge.1, edge.0, weight);
+            }
+        }
+        adj
+    }
+
+    // builds the weighted adjacency map of the reversed graph, containing the weight
+    // of the edge from each predecessor
+    pub fn reverse_weighted_adjacencies(&self) -> WeightedAdjacencies {
+        let mut reverse: WeightedAdjacencies = self.vertices.iter().map(|v| (*v, HashMap::new())).collect();
+        for (v, adjacent) in self.weighted_adjacencies() {
+            for (w, weight) in adjacent {
+                reverse.get_mut(&w).unwrap().insert(v, weight);
+            }
+        }
+        reverse
+    }
+
+    // builds the adjacency map of the reversed graph, i.e. the list of
+    // predecessors of each vertex; same as the adjacency map for undirected graphs
+    pub fn reverse_adjacencies(&self) -> Adjacencies {
+        let mut adj = HashMap::new();
+        for vertex in &self.vertices {
+            adj.insert(*vertex, HashSet::new());
+        }
+        for edge in &self.edges {
+            adj.get_mut(&edge.1).unwrap().insert(edge.0);
+            if !self.directed {
+                adj.get_mut(&edge.0).unwrap().insert(edge.1);
+            }
+        }
+        adj
+    }
+
+    // builds the adjacency map ignoring edge directions, i.e. listing both the successors
+    // and the predecessors of each vertex; same as the adjacency map for undirected graphs
+    pub fn undirected_adjacencies(&self) -> Adjacencies {
+        let mut adj = self.adjacencies();
+        if self.directed {
+            for edge in &self.edges {
+                adj.get_mut(&edge.1).unwrap().insert(edge.0);
+            }
+        }
+        adj
+    }
+
+    // searches the adjacency map from start, stopping as soon as all vertices are reached
+    // returns the smallest vertex that cannot be reached, or None if all can
+    pub fn first_unreached(&self, adj: &Adjacencies, start: Vertex) -> Option<Vertex> {
+        let mut visited = HashSet::new();
+        visited.insert(start);
+        let mut stack = vec![start];
+        while let Some(v) = stack.pop() {
+            if visited.len() == self.vertices.len() {
+                return None;
+            }
+            for w in &adj[&v] {
+                if visited.insert(*w) {
+                    stack.push(*w);
+                }
+            }
+        }
+        self.vertices.iter().filter(|v| !visited.contains(v)).min().cloned()
+    }
+
+    // returns true if every vertex of a directed graph can be reached from every other one:
+    // that is the case if a search from one vertex reaches all vertices, and a second search
+    // from it along reversed edges does too; the second search is skipped if the first one
+    // fails; same as is_connected for undirected graphs
+    pub fn is_strongly_connected(&self) -> bool {
+        self.vertices.iter().next().is_none_or(|v| {
+            self.first_unreached(&self.adjacencies(), *v).is_none()
+                && (!self.directed || self.first_unreached(&self.reverse_adjacencies(), *v).is_none())
+        })
+    }
+
+    // depth first search of the entire graph along the given adjacency map
     // returns the set of connected components
-    fn depth_first_search(&self) -> Vec<Component> {
+    fn depth_first_search(&self, adj: &Adjacencies) -> Vec<Component> {
         let mut components = vec![];
         let mut visited = HashSet::new();
-        for v in &self.vertices {
+        for v in &self.roots() {
             if !visited.contains(v) {
                 let mut component = HashSet::new();
-                self.explore(v, &mut visited, &mut component);
+                self.explore(v, adj, &mut visited, &mut component);
                 components.push(component);
             }
         }
         components
     }
 
+    // the vertices in the order searches over the entire graph try them as roots
+    fn roots(&self) -> Vec<Vertex> {
+        if self.deterministic { self.sorted_vertices() } else { self.vertices.iter().cloned().collect() }
+    }
+
     // depth first search of the graph starting at vertex v
     // marks each vertex visited during the search and returns the list of visited vertices
-    fn explore(&self, v: &Vertex, visited: &mut HashSet<Vertex>, component: &mut Component) {
+    fn explore(&self, v: &Vertex, adj: &Adjacencies, visited: &mut HashSet<Vertex>, component: &mut Component) {
         fn visit(v: &Vertex, adj: &Adjacencies, visited: &mut HashSet<Vertex>, component: &mut Component) {
-            visited.insert(v.clone());
-            component.insert(v.clone());
+            visited.insert(*v);
+            component.insert(*v);
             if let Some(adjacent) = adj.get(v) {
                 for w in adjacent {
                     if !visited.contains(w) {
@@ -87,20 +339,315 @@ impl Graph {
             }
         }
 
-        let adj = &self.adjacencies();
-        visit(&v, &adj, visited, component);
+        visit(v, adj, visited, component);
     }
 
     // returns true if vertex w can be reached from vertex v
     pub fn is_reachable(&self, v: Vertex, w: Vertex) -> bool {
         let mut visited = HashSet::new();
         let mut component = HashSet::new();
-        self.explore(&v, &mut visited, &mut component);
+        self.explore(&v, &self.adjacencies(), &mut visited, &mut component);
         visited.contains(&w)
     }
 
+    // returns true if any vertex of to can be reached from any vertex of from, using a
+    // single search from all vertices of from at once that stops at the first vertex of to
+    pub fn any_reachable(&self, from: &HashSet<Vertex>, to: &HashSet<Vertex>) -> bool {
+        !self.reach_from(from, to, true).is_empty()
+    }
+
+    // returns the vertices of to that can be reached from any vertex of from, using a single
+    // search from all vertices of from at once that stops once all of to has been found
+    pub fn reachable_subset(&self, from: &HashSet<Vertex>, to: &HashSet<Vertex>) -> HashSet<Vertex> {
+        self.reach_from(from, to, false)
+    }
+
+    // searches from all vertices of from, collecting the vertices of to found on the way,
+    // until the first one if requested or otherwise until all have been found
+    fn reach_from(&self, from: &HashSet<Vertex>, to: &HashSet<Vertex>, first: bool) -> HashSet<Vertex> {
+        let adj = self.adjacencies();
+        let mut found = HashSet::new();
+        let mut visited: HashSet<Vertex> = from.iter().filter(|v| self.vertices.contains(v)).cloned().collect();
+        let mut stack: Vec<Vertex> = visited.iter().cloned().collect();
+        while let Some(v) = stack.pop() {
+            if to.contains(&v) {
+                found.insert(v);
+                if first || found.len() == to.len() {
+                    break;
+                }
+            }
+            for w in &adj[&v] {
+                if visited.insert(*w) {
+                    stack.push(*w);
+                }
+            }
+        }
+        found
+    }
+
+    // returns a path from vertex v to vertex w found by depth first search,
+    // or None if w cannot be reached from v or v is not in the graph
+    pub fn find_path(&self, v: Vertex, w: Vertex) -> Option<Path> {
+        fn visit(v: Vertex, target: Vertex, adj: &Adjacencies, parent: &mut HashMap<Vertex, Vertex>) -> bool {
+            if v == target {
+                return true;
+            }
+            for u in &adj[&v] {
+                if !parent.contains_key(u) {
+                    parent.insert(*u, v);
+                    if visit(*u, target, adj, parent) {
+                        return true;
+                    }
+                }
+            }
+            false
+        }
+
+        let adj = self.adjacencies();
+        adj.get(&v)?;
+        let mut parent = HashMap::new();
+        parent.insert(v, v);
+        if !visit(v, w, &adj, &mut parent) {
+            return None;
+        }
+        parent.remove(&v);
+        Some(trace_path(&parent, w))
+    }
+
+    // returns a path from vertex v to vertex w with the fewest edges, found by
+    // breadth first search, or None if w cannot be reached from v or v is not in the graph
+    pub fn find_shortest_path(&self, v: Vertex, w: Vertex) -> Option<Path> {
+        let adj = self.adjacencies();
+        adj.get(&v)?;
+        let mut parent = HashMap::new();
+        let mut queue = VecDeque::new();
+        parent.insert(v, v);
+        queue.push_back(v);
+        while let Some(u) = queue.pop_front() {
+            if u == w {
+                parent.remove(&v);
+                return Some(trace_path(&parent, w));
+            }
+            for x in &adj[&u] {
+                if !parent.contains_key(x) {
+                    parent.insert(*x, u);
+                    queue.push_back(*x);
+                }
+            }
+        }
+        None
+    }
+
+    // the edges in sorted order with the bits of their weights, with the edges of undirected
+    // graphs pointing from the smaller to the larger vertex, so that they can be compared
+    fn canonical_edges(&self) -> Vec<(Vertex, Vertex, u64)> {
+        let mut edges: Vec<(Vertex, Vertex, u64)> = self.edges.iter()
+            .map(|edge| {
+                let (v, w) = if self.directed { *edge } else { (edge.0.min(edge.1), edge.0.max(edge.1)) };
+                // adding zero turns negative zero into positive zero
+                (v, w, (self.weight(edge) + 0.0).to_bits())
+            })
+            .collect();
+        edges.sort();
+        // undirected graphs may contain both directions of an edge, which then count as one
+        edges.dedup_by_key(|edge| (edge.0, edge.1));
+        edges
+    }
+
+    // a one line description of the graph with the number of vertices, edges and components
+    // (strongly connected components for directed graphs)
+    pub fn summary(&self) -> String {
+        let (kind, components) = if self.directed {
+            ("directed", format!("{} strongly connected components", self.strongly_connected_components().len()))
+        } else {
+            ("undirected", format!("{} connected components", self.connected_components().len()))
+        };
+        format!("{} {}graph: {} vertices, {} edges, {}",
+                kind, if self.is_weighted() { "weighted " } else { "" }, self.vertices.len(), self.edges.len(), components)
+    }
+
     // returns the connected components for the graph
     pub fn connected_components(&self) -> Vec<Component> {
-        self.depth_first_search()
+        self.depth_first_search(&self.adjacencies())
+    }
+
+    // returns the weakly connected components of the graph, i.e. the connected components
+    // when edge directions are ignored; same as connected_components for undirected graphs
+    pub fn weakly_connected_components(&self) -> Vec<Component> {
+        self.depth_first_search(&self.undirected_adjacencies())
+    }
+
+    // labels every vertex with the number of its connected component, ignoring edge
+    // directions, in a single breadth first pass over dense indices: the i-th label belongs
+    // to the i-th vertex in ascending order, as in indexed_vertices, and the components are
+    // numbered from 0 in the order of their smallest vertices
+    pub fn component_labels(&self) -> Vec<usize> {
+        let (_, adj) = self.dense_undirected();
+        let mut labels = vec![usize::MAX; adj.len()];
+        let mut queue = VecDeque::new();
+        let mut count = 0;
+        for root in 0..adj.len() {
+            if labels[root] != usize::MAX {
+                continue;
+            }
+            labels[root] = count;
+            queue.push_back(root);
+            while let Some(v) = queue.pop_front() {
+                for &w in &adj[v] {
+                    if labels[w] == usize::MAX {
+                        labels[w] = count;
+                        queue.push_back(w);
+                    }
+                }
+            }
+            count += 1;
+        }
+        labels
+    }
+
+    // the vertices of the largest connected component, ignoring edge directions, or of the
+    // one with the smallest vertex among equally large ones; empty for the empty graph
+    pub fn largest_component(&self) -> Component {
+        let vertices = self.sorted_vertices();
+        let labels = self.component_labels();
+        let mut sizes = vec![];
+        for &label in &labels {
+            if label == sizes.len() {
+                sizes.push(0);
+            }
+            sizes[label] += 1;
+        }
+        // the first of the largest components, as max_by_key would take the last
+        let largest = (0..sizes.len()).rev().max_by_key(|c| sizes[*c]);
+        vertices.into_iter().zip(labels).filter(|(_, label)| Some(*label) == largest).map(|(v, _)| v).collect()
+    }
+
+    // returns the strongly connected components of the graph using Tarjan's algorithm;
+    // the components are listed in reverse topological order, i.e. no edge leads from a
+    // component to one listed after it
+    pub fn strongly_connected_components(&self) -> Vec<Component> {
+        struct State {
+            next_index: usize,
+            index: HashMap<Vertex, usize>,
+            lowlink: HashMap<Vertex, usize>,
+            stack: Vec<Vertex>,
+            on_stack: HashSet<Vertex>,
+            components: Vec<Component>
+        }
+
+        fn open(v: Vertex, state: &mut State) {
+            state.index.insert(v, state.next_index);
+            state.lowlink.insert(v, state.next_index);
+            state.next_index += 1;
+            state.stack.push(v);
+            state.on_stack.insert(v);
+        }
+
+        // the search keeps its own stack of the vertices being visited with the position of
+        // the next neighbour to look at, so that long paths cannot overflow the call stack
+        fn visit(root: Vertex, adj: &HashMap<Vertex, Vec<Vertex>>, state: &mut State) {
+            open(root, state);
+            let mut calls = vec![(root, 0)];
+            while let Some(&mut (v, ref mut next)) = calls.last_mut() {
+                if let Some(w) = adj[&v].get(*next) {
+                    *next += 1;
+                    if !state.index.contains_key(w) {
+                        open(*w, state);
+                        calls.push((*w, 0));
+                    } else if state.on_stack.contains(w) {
+                        let low = state.lowlink[&v].min(state.index[w]);
+                        state.lowlink.insert(v, low);
+                    }
+                    continue;
+                }
+                calls.pop();
+                if state.lowlink[&v] == state.index[&v] {
+                    let mut component = HashSet::new();
+                    loop {
+                        let w = state.stack.pop().unwrap();
+                        state.on_stack.remove(&w);
+                        component.insert(w);
+                        if w == v {
+                            break;
+                        }
+                    }
+                    state.components.push(component);
+                }
+                if let Some(&(parent, _)) = calls.last() {
+                    let low = state.lowlink[&parent].min(state.lowlink[&v]);
+                    state.lowlink.insert(parent, low);
+                }
+            }
+        }
+
+        // in deterministic mode the neighbours are visited in ascending order as well, which
+        // fixes the order of the components that are not ordered by any edge
+        let adj = if self.deterministic {
+            sorted_adjacencies(&self.adjacencies())
+        } else {
+            self.adjacencies().into_iter().map(|(v, adjacent)| (v, adjacent.into_iter().collect())).collect()
+        };
+        let mut state = State {
+            next_index: 0, index: HashMap::new(), lowlink: HashMap::new(),
+            stack: vec![], on_stack: HashSet::new(), components: vec![]
+        };
+        for v in &self.roots() {
+            if !state.index.contains_key(v) {
+                visit(*v, &adj, &mut state);
+            }
+        }
+        state.components
+    }
+}
+
+// turns the components into sorted lists of their vertices, keeping their order, so that
+// they print the same on every run
+pub fn sorted_members(components: Vec<Component>) -> Vec<Vec<Vertex>> {
+    components.into_iter()
+        .map(|component| {
+            let mut component: Vec<Vertex> = component.into_iter().collect();
+            component.sort();
+            component
+        })
+        .collect()
+}
+
+// lists every vertex with its sorted neighbours, one vertex per line in ascending order,
+// followed by the weight of the edge to each neighbour for weighted graphs
+impl fmt::Display for Graph {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let adj = self.weighted_adjacencies();
+        for v in self.sorted_vertices() {
+            let mut adjacent: Vec<(&Vertex, &Weight)> = adj[&v].iter().collect();
+            adjacent.sort_by_key(|(w, _)| **w);
+            write!(f, "{}:", v)?;
+            for (w, weight) in adjacent {
+                if self.is_weighted() {
+                    write!(f, " {} ({})", w, weight)?;
+                } else {
+                    write!(f, " {}", w)?;
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+// graphs are equal if they are both directed or both undirected and have the same vertices
+// and edges of the same weights, regardless of the direction of undirected edges
+impl PartialEq for Graph {
+    fn eq(&self, other: &Graph) -> bool {
+        self.directed == other.directed && self.vertices == other.vertices && self.canonical_edges() == other.canonical_edges()
+    }
+}
+
+impl Eq for Graph {}
+
+impl Hash for Graph {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.directed.hash(state);
+        self.sorted_vertices().hash(state);
+        self.canonical_edges().hash(state);
     }
 }
@@ -0,0 +1,95 @@
+use std::io::BufRead;
+
+use graph::{Edge, Graph, HashMap, HashSet, Vertex, Weight};
+use tuple_reader::TupleReader;
+
+// a hypergraph, whose hyperedges join any number of vertices, like the authors of a paper
+// or the members of a group; every hyperedge is kept as its distinct vertices in ascending
+// order, and the same set of vertices may form several hyperedges
+pub struct Hypergraph {
+    vertices: HashSet<Vertex>,
+    hyperedges: Vec<Vec<Vertex>>
+}
+
+impl Hypergraph {
+
+    pub fn new(vertices: HashSet<Vertex>) -> Hypergraph {
+        Hypergraph { vertices, hyperedges: vec![] }
+    }
+
+    // loads a hypergraph from an input stream:
+    // first line contains the number of vertices v and hyperedges e
+    // next e lines contain the vertices of each hyperedge
+    pub fn load<T: BufRead>(reader: &mut T) -> Hypergraph {
+        let (v, e) = reader.next_tuple();
+        let mut hypergraph = Hypergraph::new((1..v+1).collect());
+        for _ in 0..e {
+            let mut buffer = String::new();
+            reader.read_line(&mut buffer).unwrap();
+            hypergraph.add_hyperedge(buffer.split_whitespace().map(|s| s.parse().unwrap()).collect());
+        }
+        hypergraph
+    }
+
+    // adds a hyperedge together with any missing vertices and returns its index
+    pub fn add_hyperedge(&mut self, mut members: Vec<Vertex>) -> usize {
+        members.sort();
+        members.dedup();
+        self.vertices.extend(members.iter().cloned());
+        self.hyperedges.push(members);
+        self.hyperedges.len() - 1
+    }
+
+    pub fn vertices(&self) -> &HashSet<Vertex> {
+        &self.vertices
+    }
+
+    pub fn hyperedges(&self) -> &[Vec<Vertex>] {
+        &self.hyperedges
+    }
+
+    // the number of hyperedges every vertex belongs to
+    pub fn degrees(&self) -> HashMap<Vertex, usize> {
+        let mut degree: HashMap<Vertex, usize> = self.vertices.iter().map(|v| (*v, 0)).collect();
+        for v in self.hyperedges.iter().flatten() {
+            *degree.get_mut(v).unwrap() += 1;
+        }
+        degree
+    }
+
+    // the bipartite incidence graph, also known as the star expansion or König graph: the
+    // vertices keep their numbers, the i-th hyperedge becomes the vertex first + i, where
+    // first follows the largest vertex, and every vertex is joined to its hyperedges. Unlike
+    // the clique expansion it loses no information; returns the graph and first
+    pub fn incidence_graph(&self) -> (Graph, Vertex) {
+        let first = self.vertices.iter().max().map_or(1, |v| v.checked_add(1).expect("No vertex numbers left!"));
+        let mut graph = Graph::new(self.vertices.clone(), HashSet::new(), false);
+        for (i, members) in self.hyperedges.iter().enumerate() {
+            let hyperedge = first.checked_add(i as Vertex).expect("No vertex numbers left!");
+            graph.add_vertex(hyperedge);
+            for v in members {
+                graph.add_edge(*v, hyperedge);
+            }
+        }
+        (graph, first)
+    }
+
+    // the clique expansion: the graph on the same vertices in which two vertices are
+    // adjacent if they share a hyperedge, weighted by the number of hyperedges they share,
+    // or, if normalized, by the sum of 1 / (k - 1) over the hyperedges of k vertices they
+    // share, so that every vertex gets a weighted degree equal to its number of hyperedges
+    // of more than one vertex; either way large hyperedges dominate the edges
+    pub fn clique_expansion(&self, normalized: bool) -> Graph {
+        let mut weights: HashMap<Edge, Weight> = HashMap::new();
+        for members in &self.hyperedges {
+            let weight = if normalized { 1.0 / (members.len() as Weight - 1.0) } else { 1.0 };
+            for (i, v) in members.iter().enumerate() {
+                for w in &members[i + 1..] {
+                    *weights.entry((*v, *w)).or_insert(0.0) += weight;
+                }
+            }
+        }
+        let edges = weights.keys().cloned().collect();
+        Graph::with_weights(self.vertices.clone(), edges, weights, false)
+    }
+}
@@ -0,0 +1,213 @@
+use std::collections::BinaryHeap;
+use std::collections::VecDeque;
+
+use graph::{Graph, HashMap, HashSet, Vertex, Weight};
+use shortest_paths::{trace_path, Path, PathResult, QueueEntry};
+
+// a directed graph given by a function listing the successors of every vertex, such as the
+// states of a puzzle reachable by one move, encoded as vertex numbers; its vertices are only
+// discovered while searching it, so it may be far too large to hold as a Graph, or infinite.
+// Every edge counts as a step of weight 1, and the successors of a vertex are asked for
+// whenever a search expands it, so the function should be cheap and always give the same
+pub struct ImplicitGraph<F: Fn(Vertex) -> Vec<Vertex>> {
+    successors: F
+}
+
+// lazy breadth first search of an implicit graph, yielding the vertices reachable from the
+// start in order of their distance, with the distance
+pub struct ImplicitBfs<'a, F: Fn(Vertex) -> Vec<Vertex>> {
+    graph: &'a ImplicitGraph<F>,
+    discovered: HashSet<Vertex>,
+    queue: VecDeque<(Vertex, usize)>
+}
+
+// lazy depth first search of an implicit graph, yielding the vertices reachable from the
+// start in the order they are discovered, with their depth in the search tree
+pub struct ImplicitDfs<'a, F: Fn(Vertex) -> Vec<Vertex>> {
+    graph: &'a ImplicitGraph<F>,
+    discovered: HashSet<Vertex>,
+    // the vertices on the current path with their successors not looked at yet, reversed
+    stack: Vec<(Vertex, Vec<Vertex>)>,
+    start: Option<Vertex>
+}
+
+impl<F: Fn(Vertex) -> Vec<Vertex>> ImplicitGraph<F> {
+
+    pub fn new(successors: F) -> ImplicitGraph<F> {
+        ImplicitGraph { successors }
+    }
+
+    pub fn successors(&self, v: Vertex) -> Vec<Vertex> {
+        (self.successors)(v)
+    }
+
+    pub fn bfs(&self, start: Vertex) -> ImplicitBfs<'_, F> {
+        ImplicitBfs { graph: self, discovered: vec![start].into_iter().collect(), queue: vec![(start, 0)].into_iter().collect() }
+    }
+
+    pub fn dfs(&self, start: Vertex) -> ImplicitDfs<'_, F> {
+        ImplicitDfs { graph: self, discovered: HashSet::new(), stack: vec![], start: Some(start) }
+    }
+
+    // finds a path of the fewest steps from the source to a vertex meeting the goal by
+    // breadth first search; returns None if there is none among the first limit vertices
+    // discovered, which keeps the search finite when the graph is not
+    pub fn find_path<G: Fn(Vertex) -> bool>(&self, source: Vertex, goal: G, limit: usize) -> Option<Path> {
+        let mut previous = HashMap::new();
+        let mut discovered: HashSet<Vertex> = vec![source].into_iter().collect();
+        let mut queue = VecDeque::from(vec![source]);
+        while let Some(v) = queue.pop_front() {
+            if goal(v) {
+                return Some(trace_path(&previous, v));
+            }
+            for w in self.successors(v) {
+                if discovered.len() >= limit {
+                    return None;
+                }
+                if discovered.insert(w) {
+                    previous.insert(w, v);
+                    queue.push_back(w);
+                }
+            }
+        }
+        None
+    }
+
+    // finds a path of the fewest steps from source to target with the A* algorithm, as
+    // Graph::astar does, the heuristic never overestimating the number of steps left;
+    // returns the path with the number of steps as its distance, or None if target is not
+    // reached before limit vertices are discovered
+    pub fn astar<H: Fn(Vertex) -> Weight>(&self, source: Vertex, target: Vertex, heuristic: H, limit: usize) -> Option<PathResult> {
+        let mut distance = HashMap::new();
+        let mut previous = HashMap::new();
+        let mut settled = HashSet::new();
+        let mut queue = BinaryHeap::new();
+        let mut relaxations = 0;
+        distance.insert(source, 0.0);
+        queue.push(QueueEntry { distance: heuristic(source), vertex: source });
+        while let Some(QueueEntry { vertex: v, .. }) = queue.pop() {
+            if v == target {
+                return Some(PathResult::new(distance[&v], trace_path(&previous, target), relaxations, previous));
+            }
+            if !settled.insert(v) {
+                continue;
+            }
+            let alt = distance[&v] + 1.0;
+            for w in self.successors(v) {
+                relaxations += 1;
+                if distance.get(&w).is_none_or(|current| alt < *current) {
+                    if !distance.contains_key(&w) && distance.len() >= limit {
+                        return None;
+                    }
+                    distance.insert(w, alt);
+                    previous.insert(w, v);
+                    settled.remove(&w);
+                    queue.push(QueueEntry { distance: alt + heuristic(w), vertex: w });
+                }
+            }
+        }
+        None
+    }
+
+    // the part of the graph reachable from the start as a directed Graph, for the algorithms
+    // that need it whole, with the first limit vertices breadth first search discovers and
+    // the edges among them; returns whether that is all of the part
+    pub fn materialize(&self, start: Vertex, limit: usize) -> (Graph, bool) {
+        let vertices: Vec<Vertex> = self.bfs(start).map(|(v, _)| v).take(limit.saturating_add(1)).collect();
+        let complete = vertices.len() <= limit;
+        let vertices: HashSet<Vertex> = vertices.into_iter().take(limit).collect();
+        let edges = vertices.iter()
+            .flat_map(|v| self.successors(*v).into_iter().map(move |w| (*v, w)))
+            .filter(|(_, w)| vertices.contains(w))
+            .collect();
+        (Graph::new(vertices, edges, true), complete)
+    }
+}
+
+impl<'a, F: Fn(Vertex) -> Vec<Vertex>> Iterator for ImplicitBfs<'a, F> {
+    type Item = (Vertex, usize);
+
+    fn next(&mut self) -> Option<(Vertex, usize)> {
+        let (v, distance) = self.queue.pop_front()?;
+        for w in self.graph.successors(v) {
+            if self.discovered.insert(w) {
+                self.queue.push_back((w, distance + 1));
+            }
+        }
+        Some((v, distance))
+    }
+}
+
+impl<'a, F: Fn(Vertex) -> Vec<Vertex>> Iterator for ImplicitDfs<'a, F> {
+    type Item = (Vertex, usize);
+
+    fn next(&mut self) -> Option<(Vertex, usize)> {
+        if let Some(start) = self.start.take() {
+            return Some(self.discover(start));
+        }
+        while let Some((_, successors)) = self.stack.last_mut() {
+            match successors.pop() {
+                Some(w) if !self.discovered.contains(&w) => return Some(self.discover(w)),
+                Some(_) => {},
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+        None
+    }
+}
+
+impl<'a, F: Fn(Vertex) -> Vec<Vertex>> ImplicitDfs<'a, F> {
+
+    fn discover(&mut self, v: Vertex) -> (Vertex, usize) {
+        self.discovered.insert(v);
+        let mut successors = self.graph.successors(v);
+        successors.reverse();
+        self.stack.push((v, successors));
+        (v, self.stack.len() - 1)
+    }
+}
+
+// the states of the 8-puzzle one move away: a state lists the tiles of the 3 x 3 board row
+// by row as the digits of a nine digit number, 0 being the blank, which swaps places with
+// a tile next to it
+pub fn sliding_puzzle_moves(state: Vertex) -> Vec<Vertex> {
+    let digits = puzzle_digits(state);
+    let blank = digits.iter().position(|d| *d == 0).expect("No blank in the puzzle!");
+    let (row, column) = (blank / 3, blank % 3);
+    let mut neighbours = vec![];
+    if row > 0 { neighbours.push(blank - 3); }
+    if row < 2 { neighbours.push(blank + 3); }
+    if column > 0 { neighbours.push(blank - 1); }
+    if column < 2 { neighbours.push(blank + 1); }
+    neighbours.into_iter().map(|tile| {
+        let mut moved = digits;
+        moved.swap(blank, tile);
+        moved.iter().fold(0, |state, d| state * 10 + *d as Vertex)
+    }).collect()
+}
+
+// the sum over the tiles of the 8-puzzle of how many rows and columns they are away from
+// where the goal has them, which never overestimates the moves left as every move shifts
+// one tile by one
+pub fn sliding_puzzle_distance(state: Vertex, goal: Vertex) -> Weight {
+    let (digits, goal) = (puzzle_digits(state), puzzle_digits(goal));
+    (1..9).map(|tile| {
+        let (i, j) = (digits.iter().position(|d| *d == tile), goal.iter().position(|d| *d == tile));
+        match (i, j) {
+            (Some(i), Some(j)) => ((i / 3).abs_diff(j / 3) + (i % 3).abs_diff(j % 3)) as Weight,
+            _ => 0.0
+        }
+    }).sum()
+}
+
+fn puzzle_digits(state: Vertex) -> [u8; 9] {
+    let mut digits = [0; 9];
+    let mut rest = state;
+    for digit in digits.iter_mut().rev() {
+        *digit = (rest % 10) as u8;
+        rest /= 10;
+    }
+    digits
+}
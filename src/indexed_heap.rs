@@ -0,0 +1,113 @@
+use std::cmp::Ordering;
+
+const ABSENT: usize = usize::MAX;
+
+// a binary min-heap of the items 0 to capacity - 1, such as vertex indices, each held at
+// most once with a priority that can be lowered in place, which std's BinaryHeap cannot
+// do: Dijkstra's algorithm then keeps one entry per vertex instead of one per relaxation.
+// Of equal priorities the smaller item comes first; priorities that do not compare, such
+// as NaN, count as equal
+pub struct IndexedBinaryHeap<P> {
+    // the items in heap order with their priorities
+    heap: Vec<(usize, P)>,
+    // the position of every item in the heap, ABSENT if it is not in it
+    position: Vec<usize>
+}
+
+impl<P: PartialOrd + Copy> IndexedBinaryHeap<P> {
+
+    pub fn new(capacity: usize) -> IndexedBinaryHeap<P> {
+        IndexedBinaryHeap { heap: vec![], position: vec![ABSENT; capacity] }
+    }
+
+    // the priority of the item, or None if it is not in the heap
+    pub fn priority(&self, item: usize) -> Option<P> {
+        self.position.get(item).filter(|p| **p != ABSENT).map(|p| self.heap[*p].1)
+    }
+
+    // the item of the smallest priority with the priority, without removing it
+    pub fn peek(&self) -> Option<(usize, P)> {
+        self.heap.first().cloned()
+    }
+
+    // inserts the item with the priority or, if it is in the heap already, changes its
+    // priority to the given one, whether smaller or larger
+    // panics if the item is not below the capacity
+    pub fn push(&mut self, item: usize, priority: P) {
+        match self.position[item] {
+            ABSENT => {
+                self.position[item] = self.heap.len();
+                self.heap.push((item, priority));
+                self.sift_up(self.heap.len() - 1);
+            },
+            p => {
+                self.heap[p].1 = priority;
+                self.sift_up(p);
+                self.sift_down(self.position[item]);
+            }
+        }
+    }
+
+    // inserts the item with the priority, or lowers its priority to the given one if that
+    // is smaller; returns whether the heap changed, as the relaxation of an edge does
+    pub fn decrease_key(&mut self, item: usize, priority: P) -> bool {
+        if self.priority(item).is_some_and(|current| priority.partial_cmp(&current) != Some(Ordering::Less)) {
+            return false;
+        }
+        self.push(item, priority);
+        true
+    }
+
+    // removes the item of the smallest priority and returns it with the priority
+    pub fn pop(&mut self) -> Option<(usize, P)> {
+        if self.heap.is_empty() {
+            return None;
+        }
+        let last = self.heap.len() - 1;
+        self.swap(0, last);
+        let (item, priority) = self.heap.pop().unwrap();
+        self.position[item] = ABSENT;
+        if !self.heap.is_empty() {
+            self.sift_down(0);
+        }
+        Some((item, priority))
+    }
+
+    fn less(&self, i: usize, j: usize) -> bool {
+        let (a, b) = (&self.heap[i], &self.heap[j]);
+        match a.1.partial_cmp(&b.1) {
+            Some(Ordering::Less) => true,
+            Some(Ordering::Greater) => false,
+            _ => a.0 < b.0
+        }
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        self.heap.swap(i, j);
+        self.position[self.heap[i].0] = i;
+        self.position[self.heap[j].0] = j;
+    }
+
+    fn sift_up(&mut self, mut i: usize) {
+        while i > 0 && self.less(i, (i - 1) / 2) {
+            self.swap(i, (i - 1) / 2);
+            i = (i - 1) / 2;
+        }
+    }
+
+    fn sift_down(&mut self, mut i: usize) {
+        loop {
+            let mut smallest = i;
+            for child in [2 * i + 1, 2 * i + 2] {
+                if child < self.heap.len() && self.less(child, smallest) {
+                    smallest = child;
+                }
+            }
+            if smallest == i {
+                return;
+            }
+            self.swap(i, smallest);
+            i = smallest;
+        }
+    }
+}
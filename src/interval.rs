@@ -0,0 +1,258 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use graph::{Graph, HashMap, HashSet, Vertex};
+
+// a closed interval of positions, from its first to its last one
+pub type Interval = (usize, usize);
+
+impl Graph {
+
+    // finds intervals for the vertices such that two vertices are adjacent exactly if their
+    // intervals intersect, or None if the graph is not an interval graph; edge directions and
+    // self-loops are ignored. The graph must be chordal, which a lexicographic breadth first
+    // search tells, with its maximal cliques ordered so that the cliques of every vertex come
+    // one after the other, which the positions of the cliques then make its interval. The
+    // order is found by placing the sets of cliques of the vertices one at a time, refining
+    // an ordered partition of the cliques, which the sets overlapping each other fix up to
+    // reversal, while sets nested in others only need to stay together; this takes O(nk)
+    // time for k maximal cliques, no more than n
+    pub fn interval_representation(&self) -> Option<HashMap<Vertex, Interval>> {
+        let mut elimination = self.lex_bfs();
+        elimination.reverse();
+        let cliques = self.maximal_cliques_along(&elimination)?;
+        let mut sets: HashMap<Vertex, Vec<usize>> = self.vertices().iter().map(|v| (*v, vec![])).collect();
+        for (c, clique) in cliques.iter().enumerate() {
+            for v in clique {
+                sets.get_mut(v).unwrap().push(c);
+            }
+        }
+        let vertices = self.sorted_vertices();
+        let order = consecutive_order(cliques.len(), &vertices.iter().map(|v| sets[v].clone()).collect::<Vec<_>>())?;
+        let mut position = vec![0; cliques.len()];
+        for (i, c) in order.iter().enumerate() {
+            position[*c] = i;
+        }
+
+        let mut intervals = HashMap::new();
+        for (v, set) in sets {
+            let positions: Vec<usize> = set.iter().map(|c| position[*c]).collect();
+            let (first, last) = (*positions.iter().min().unwrap(), *positions.iter().max().unwrap());
+            // the cliques of every vertex must surely come one after the other
+            if last - first + 1 != positions.len() {
+                return None;
+            }
+            intervals.insert(v, (first, last));
+        }
+        Some(intervals)
+    }
+
+    // returns true if the graph is an interval graph, see interval_representation
+    pub fn is_interval_graph(&self) -> bool {
+        self.interval_representation().is_some()
+    }
+}
+
+// the interval graph of the intervals, in which two vertices are adjacent if their intervals
+// intersect, found by sweeping over the intervals in order of their first positions
+pub fn interval_graph(intervals: &HashMap<Vertex, Interval>) -> Graph {
+    let mut edges = HashSet::new();
+    let mut open: Vec<(Vertex, usize)> = vec![];
+    for (v, (first, last)) in sorted_intervals(intervals) {
+        open.retain(|&(_, end)| end >= first);
+        for &(w, _) in &open {
+            edges.insert((w, v));
+        }
+        open.push((v, last));
+    }
+    Graph::new(intervals.keys().cloned().collect(), edges, false)
+}
+
+// colors the intervals with as few colors as possible, so that intersecting intervals get
+// different ones, such as assigning the fewest resources to jobs running over the intervals:
+// taking the intervals in order of their first positions and giving each the smallest color
+// not used by an interval still open needs exactly as many colors as the most intervals
+// sharing a position, which is the size of a maximum clique of the interval graph
+pub fn interval_coloring(intervals: &HashMap<Vertex, Interval>) -> HashMap<Vertex, usize> {
+    let mut colors = HashMap::new();
+    // the color and last position of every open interval, and the colors free again
+    let mut open: Vec<(usize, usize)> = vec![];
+    let mut free = BinaryHeap::new();
+    let mut used = 0;
+    for (v, (first, last)) in sorted_intervals(intervals) {
+        open.retain(|&(color, end)| {
+            if end < first {
+                free.push(Reverse(color));
+            }
+            end >= first
+        });
+        let color = free.pop().map_or_else(|| {
+            used += 1;
+            used - 1
+        }, |Reverse(color)| color);
+        colors.insert(v, color);
+        open.push((color, last));
+    }
+    colors
+}
+
+// the largest set of intervals sharing a position, in ascending order, which is a maximum
+// clique of the interval graph
+pub fn interval_maximum_clique(intervals: &HashMap<Vertex, Interval>) -> Vec<Vertex> {
+    let mut best: Vec<Vertex> = vec![];
+    let mut open: Vec<(Vertex, usize)> = vec![];
+    for (v, (first, last)) in sorted_intervals(intervals) {
+        open.retain(|&(_, end)| end >= first);
+        open.push((v, last));
+        if open.len() > best.len() {
+            best = open.iter().map(|&(w, _)| w).collect();
+        }
+    }
+    best.sort();
+    best
+}
+
+// the intervals in order of their first positions, and of the vertices for equal ones
+fn sorted_intervals(intervals: &HashMap<Vertex, Interval>) -> Vec<(Vertex, Interval)> {
+    let mut sorted: Vec<(Vertex, Interval)> = intervals.iter().map(|(v, interval)| (*v, *interval)).collect();
+    sorted.sort_by_key(|&(v, (first, _))| (first, v));
+    sorted
+}
+
+// orders the elements 0..n so that every set, given with its elements in ascending order,
+// comes out consecutive, or returns None if there is no such order. Two sets overlap if they
+// share elements but neither contains the other; a group of sets connected by overlaps fixes
+// the order of the elements they cover up to reversal, found by placing them one by one, each
+// overlapping one placed before. The elements covered by such groups are nested or disjoint,
+// and a group covering fewer elements lies within elements that all groups covering more of
+// them treat alike, so the groups are placed from the largest one down, each into such a
+// class of elements of the partition refined so far
+fn consecutive_order(n: usize, sets: &[Vec<usize>]) -> Option<Vec<usize>> {
+    let mut containing: Vec<Vec<usize>> = vec![vec![]; n];
+    for (s, set) in sets.iter().enumerate() {
+        for e in set {
+            containing[*e].push(s);
+        }
+    }
+    let mut overlapping: Vec<Vec<usize>> = vec![vec![]; sets.len()];
+    for (s, set) in sets.iter().enumerate() {
+        let mut candidates: Vec<usize> = set.iter().flat_map(|e| containing[*e].iter().cloned()).filter(|t| *t > s).collect();
+        candidates.sort();
+        candidates.dedup();
+        for t in candidates {
+            if overlap(set, &sets[t]) {
+                overlapping[s].push(t);
+                overlapping[t].push(s);
+            }
+        }
+    }
+    let mut seen = vec![false; sets.len()];
+    let mut placed: Vec<(usize, bool, Vec<Vec<usize>>)> = vec![];
+    for s in 0..sets.len() {
+        if seen[s] {
+            continue;
+        }
+        // a breadth first search over the overlaps, so that every set overlaps one before it
+        seen[s] = true;
+        let mut group = vec![s];
+        let mut next = 0;
+        while next < group.len() {
+            for t in &overlapping[group[next]] {
+                if !seen[*t] {
+                    seen[*t] = true;
+                    group.push(*t);
+                }
+            }
+            next += 1;
+        }
+        let mut classes = vec![sets[s].clone()];
+        for t in &group[1..] {
+            classes = place(classes, &sets[*t])?;
+        }
+        placed.push((classes.iter().map(|class| class.len()).sum(), group.len() > 1, classes));
+    }
+    placed.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.cmp(&a.1)));
+
+    // the classes of the partition in order, with the class of every element
+    let mut classes: Vec<Vec<usize>> = vec![(0..n).collect()];
+    for (_, _, order) in placed {
+        let class_of = class_index(&classes, n);
+        let elements: Vec<usize> = order.iter().flatten().cloned().collect();
+        let c = class_of[elements[0]];
+        if elements.iter().any(|e| class_of[*e] != c) {
+            // a single set covering classes that groups covering as many elements made, which
+            // are consecutive already
+            continue;
+        }
+        let inside: HashSet<usize> = elements.into_iter().collect();
+        let rest: Vec<usize> = classes[c].iter().cloned().filter(|e| !inside.contains(e)).collect();
+        let mut replacement = if rest.is_empty() { vec![] } else { vec![rest] };
+        replacement.extend(order);
+        classes.splice(c..c + 1, replacement);
+    }
+    Some(classes.into_iter().flatten().collect())
+}
+
+// returns true if the sorted sets share elements but neither contains the other
+fn overlap(a: &[usize], b: &[usize]) -> bool {
+    let shared = a.iter().filter(|e| b.binary_search(e).is_ok()).count();
+    shared > 0 && shared < a.len() && shared < b.len()
+}
+
+// the class of every element of the classes, and n for elements in none of them
+fn class_index(classes: &[Vec<usize>], n: usize) -> Vec<usize> {
+    let mut class_of = vec![n; n];
+    for (c, class) in classes.iter().enumerate() {
+        for e in class {
+            class_of[*e] = c;
+        }
+    }
+    class_of
+}
+
+// places a set overlapping one of those that made the classes of elements in the same sets,
+// which only works if its elements placed already form a run of classes, of which only the
+// classes at the ends may lie partly outside the set, and its new elements can go at an end
+// of the partition; the classes at the ends of the run are split accordingly
+fn place(mut classes: Vec<Vec<usize>>, set: &[usize]) -> Option<Vec<Vec<usize>>> {
+    let n = classes.iter().flatten().chain(set.iter()).cloned().max().map_or(0, |e| e + 1);
+    let class_of = class_index(&classes, n);
+    let mut count = vec![0; classes.len()];
+    let mut new = vec![];
+    for e in set {
+        match class_of[*e] {
+            c if c == n => new.push(*e),
+            c => count[c] += 1
+        }
+    }
+    let touched: Vec<usize> = (0..classes.len()).filter(|c| count[*c] > 0).collect();
+    let (a, b) = (*touched.first()?, *touched.last()?);
+    let full = |c: usize| count[c] == classes[c].len();
+    if touched.len() != b - a + 1 || (a + 1..b).any(|c| !full(c)) {
+        return None;
+    }
+    let last = classes.len() - 1;
+    // splits class c into its elements outside and inside the set, in this order if the set
+    // lies after it
+    let split = |classes: &mut Vec<Vec<usize>>, c: usize, set_after: bool| {
+        let (inside, outside): (Vec<usize>, Vec<usize>) = classes[c].iter().partition(|e| set.binary_search(e).is_ok());
+        let parts = if set_after { vec![outside, inside] } else { vec![inside, outside] };
+        classes.splice(c..c + 1, parts.into_iter().filter(|part| !part.is_empty()));
+    };
+    if new.is_empty() {
+        if a == b {
+            return None;
+        }
+        split(&mut classes, b, false);
+        split(&mut classes, a, true);
+    } else if b == last && (a + 1..=b).all(full) {
+        split(&mut classes, a, true);
+        classes.push(new);
+    } else if a == 0 && (a..b).all(full) {
+        split(&mut classes, b, false);
+        classes.insert(0, new);
+    } else {
+        return None;
+    }
+    Some(classes)
+}
@@ -0,0 +1,145 @@
+use graph::{Edge, Graph, HashMap, HashSet, Vertex};
+use traversal::sorted_adjacencies;
+
+// an embedding of a pattern into a graph: the vertex of the graph every pattern vertex is
+// mapped to
+pub type Mapping = HashMap<Vertex, Vertex>;
+
+impl Graph {
+
+    // finds an embedding of the pattern into the graph: distinct pattern vertices go to
+    // distinct vertices accepted by compatible(pattern vertex, graph vertex), e.g. ones with
+    // the same label, and every pattern edge to an edge of the graph, in its direction if
+    // the graph is directed; other edges between the vertices found are allowed, so the
+    // pattern need not be an induced subgraph. Takes exponential time in the worst case
+    pub fn find_subgraph<F: Fn(Vertex, Vertex) -> bool>(&self, pattern: &Graph, compatible: F) -> Option<Mapping> {
+        let mut found = None;
+        self.embed(pattern, &compatible, &mut |mapping| {
+            found = Some(mapping.clone());
+            false
+        });
+        found
+    }
+
+    // finds up to limit embeddings of the pattern into the graph, see find_subgraph; a
+    // pattern with automorphisms is found once for each of them
+    pub fn subgraph_matches<F: Fn(Vertex, Vertex) -> bool>(&self, pattern: &Graph, compatible: F, limit: usize) -> Vec<Mapping> {
+        let mut matches = vec![];
+        if limit > 0 {
+            self.embed(pattern, &compatible, &mut |mapping| {
+                matches.push(mapping.clone());
+                matches.len() < limit
+            });
+        }
+        matches
+    }
+
+    // backtracking search in the style of VF2: the pattern vertices are mapped one after
+    // the other in breadth first order, so that every one after the first of its component
+    // only needs to be tried on the neighbours of the image of an earlier neighbour, and a
+    // candidate is rejected as soon as a pattern edge to an earlier vertex is missing;
+    // calls found with every embedding until it returns false
+    fn embed<F, G>(&self, pattern: &Graph, compatible: &F, found: &mut G)
+        where F: Fn(Vertex, Vertex) -> bool, G: FnMut(&Mapping) -> bool {
+        struct Search<'a, F, G> {
+            graph: &'a Graph,
+            adj: HashMap<Vertex, Vec<Vertex>>,
+            // the pattern vertices in the order they are mapped, each with an earlier
+            // neighbour if there is one, and its edges to itself and earlier vertices
+            order: Vec<(Vertex, Option<Vertex>, Vec<Edge>)>,
+            degree: HashMap<Vertex, usize>,
+            all: Vec<Vertex>,
+            compatible: &'a F,
+            found: &'a mut G,
+            mapping: Mapping,
+            used: HashSet<Vertex>
+        }
+
+        fn extend<F, G>(search: &mut Search<F, G>, i: usize) -> bool
+            where F: Fn(Vertex, Vertex) -> bool, G: FnMut(&Mapping) -> bool {
+            if i == search.order.len() {
+                return (search.found)(&search.mapping);
+            }
+            let (p, anchor, ref edges) = search.order[i];
+            let candidates = match anchor {
+                Some(q) => search.adj[&search.mapping[&q]].clone(),
+                None => search.all.clone()
+            };
+            let edges = edges.clone();
+            for v in candidates {
+                if search.used.contains(&v) || search.adj[&v].len() < search.degree[&p] || !(search.compatible)(p, v) {
+                    continue;
+                }
+                search.mapping.insert(p, v);
+                let fits = edges.iter().all(|&(a, b)| search.graph.has_edge(search.mapping[&a], search.mapping[&b]));
+                if fits {
+                    search.used.insert(v);
+                    let go_on = extend(search, i + 1);
+                    search.used.remove(&v);
+                    if !go_on {
+                        return false;
+                    }
+                }
+                search.mapping.remove(&p);
+            }
+            true
+        }
+
+        let pattern_adj = sorted_adjacencies(&pattern.undirected_adjacencies());
+        let degree: HashMap<Vertex, usize> = pattern_adj.iter()
+            .map(|(v, adjacent)| (*v, adjacent.iter().filter(|w| *w != v).count()))
+            .collect();
+        // breadth first from the vertex of largest degree of every component, which has the
+        // fewest candidates
+        let mut roots = pattern.sorted_vertices();
+        roots.sort_by_key(|v| std::cmp::Reverse(degree[v]));
+        let mut position = HashMap::new();
+        let mut order = vec![];
+        for root in roots {
+            if position.contains_key(&root) {
+                continue;
+            }
+            position.insert(root, order.len());
+            order.push((root, None));
+            let mut next = order.len() - 1;
+            while next < order.len() {
+                let v = order[next].0;
+                for w in &pattern_adj[&v] {
+                    if !position.contains_key(w) {
+                        position.insert(*w, order.len());
+                        order.push((*w, Some(v)));
+                    }
+                }
+                next += 1;
+            }
+        }
+        let order = order.into_iter()
+            .map(|(p, anchor)| {
+                let edges = pattern.edges().iter()
+                    .filter(|&&(a, b)| (a == p && position[&b] <= position[&p]) || (b == p && position[&a] < position[&p]))
+                    .cloned()
+                    .collect();
+                (p, anchor, edges)
+            })
+            .collect();
+
+        let adj = sorted_adjacencies(&self.undirected_adjacencies());
+        let mut search = Search {
+            graph: self,
+            adj,
+            order,
+            degree,
+            all: self.sorted_vertices(),
+            compatible,
+            found,
+            mapping: HashMap::new(),
+            used: HashSet::new()
+        };
+        extend(&mut search, 0);
+    }
+
+    // returns true if the graph contains the edge from v to w, or w - v if undirected
+    pub fn has_edge(&self, v: Vertex, w: Vertex) -> bool {
+        self.edges().contains(&(v, w)) || (!self.is_directed() && self.edges().contains(&(w, v)))
+    }
+}
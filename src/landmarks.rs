@@ -0,0 +1,168 @@
+use graph::{Graph, HashMap, Vertex, Weight};
+use shortest_paths::{distances, PathResult};
+
+// precomputed distances from and to a small set of landmark vertices, which yield lower
+// bounds on the distance between any two vertices by the triangle inequality; used as the
+// heuristic for A* searches (ALT: A*, landmarks, triangle inequality)
+pub struct LandmarkIndex {
+    landmarks: Vec<Vertex>,
+    from: Vec<HashMap<Vertex, Weight>>,
+    to: Vec<HashMap<Vertex, Weight>>
+}
+
+impl LandmarkIndex {
+
+    pub fn landmarks(&self) -> &[Vertex] {
+        &self.landmarks
+    }
+
+    // returns a lower bound on the distance from v to t: for every landmark l,
+    // d(l, t) <= d(l, v) + d(v, t) and d(v, l) <= d(v, t) + d(t, l)
+    pub fn lower_bound(&self, v: Vertex, t: Vertex) -> Weight {
+        let mut bound: Weight = 0.0;
+        for (from, to) in self.from.iter().zip(&self.to) {
+            if let (Some(lv), Some(lt)) = (from.get(&v), from.get(&t)) {
+                bound = bound.max(lt - lv);
+            }
+            if let (Some(vl), Some(tl)) = (to.get(&v), to.get(&t)) {
+                bound = bound.max(vl - tl);
+            }
+        }
+        bound
+    }
+
+    // returns an upper bound on the distance from v to t, the length of the shortest
+    // detour through a landmark, or infinity if no landmark lies on a path from v to t
+    pub fn upper_bound(&self, v: Vertex, t: Vertex) -> Weight {
+        let mut bound = if v == t { 0.0 } else { Weight::INFINITY };
+        for (from, to) in self.from.iter().zip(&self.to) {
+            if let (Some(vl), Some(lt)) = (to.get(&v), from.get(&t)) {
+                bound = bound.min(vl + lt);
+            }
+        }
+        bound
+    }
+
+    // finds a shortest path from source to target using A* with the landmark bounds
+    pub fn shortest_path(&self, graph: &Graph, source: Vertex, target: Vertex) -> Option<PathResult> {
+        graph.astar(source, target, |v| self.lower_bound(v, target))
+    }
+}
+
+// the distances of every vertex from and to the landmarks of a LandmarkIndex, laid out
+// densely as rows of one vertex after another, infinite where there is no path, so that an
+// estimate only reads the two rows of its ends; meant for answering very many rough
+// distance queries, each in time linear in the number of landmarks
+pub struct DistanceEstimator {
+    landmarks: usize,
+    index: HashMap<Vertex, usize>,
+    from: Vec<Weight>,
+    to: Vec<Weight>
+}
+
+// bounds on the distance between two vertices, the upper one infinite if no landmark lies
+// on a path between them, the lower one infinite if some landmark shows there is none
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Estimate {
+    pub lower: Weight,
+    pub upper: Weight
+}
+
+impl Estimate {
+
+    // whether the bounds coincide, as when a landmark lies on a shortest path
+    pub fn is_exact(&self) -> bool {
+        self.lower == self.upper
+    }
+}
+
+impl DistanceEstimator {
+
+    pub fn new(graph: &Graph, landmarks: &LandmarkIndex) -> DistanceEstimator {
+        let (vertices, index) = graph.indexed_vertices();
+        let k = landmarks.landmarks.len();
+        let mut from = vec![Weight::INFINITY; vertices.len() * k];
+        let mut to = vec![Weight::INFINITY; vertices.len() * k];
+        for (i, v) in vertices.iter().enumerate() {
+            for l in 0..k {
+                if let Some(d) = landmarks.from[l].get(v) {
+                    from[i * k + l] = *d;
+                }
+                if let Some(d) = landmarks.to[l].get(v) {
+                    to[i * k + l] = *d;
+                }
+            }
+        }
+        DistanceEstimator { landmarks: k, index, from, to }
+    }
+
+    // bounds on the distance from u to v by the triangle inequality for every landmark l,
+    // d(l, v) <= d(l, u) + d(u, v) and d(u, l) <= d(u, v) + d(v, l) giving lower bounds
+    // and d(u, v) <= d(u, l) + d(l, v) upper ones, as in LandmarkIndex, but also taking
+    // a landmark that reaches u but not v, or is reached from v but not from u, as proof
+    // that u cannot reach v; vertices not in the graph only get the trivial bounds
+    pub fn estimate(&self, u: Vertex, v: Vertex) -> Estimate {
+        if u == v {
+            return Estimate { lower: 0.0, upper: 0.0 };
+        }
+        let (i, j) = match (self.index.get(&u), self.index.get(&v)) {
+            (Some(i), Some(j)) => (*i, *j),
+            _ => return Estimate { lower: 0.0, upper: Weight::INFINITY }
+        };
+        let k = self.landmarks;
+        let (from_u, from_v) = (&self.from[i * k..(i + 1) * k], &self.from[j * k..(j + 1) * k]);
+        let (to_u, to_v) = (&self.to[i * k..(i + 1) * k], &self.to[j * k..(j + 1) * k]);
+        let (mut lower, mut upper): (Weight, Weight) = (0.0, Weight::INFINITY);
+        for l in 0..k {
+            if from_u[l].is_finite() {
+                lower = lower.max(from_v[l] - from_u[l]);
+            }
+            if to_v[l].is_finite() {
+                lower = lower.max(to_u[l] - to_v[l]);
+            }
+            upper = upper.min(to_u[l] + from_v[l]);
+        }
+        Estimate { lower, upper }
+    }
+}
+
+impl Graph {
+
+    // selects up to k landmarks by farthest point sampling, i.e. each further landmark is
+    // the vertex farthest away from the landmarks chosen so far (preferring vertices they
+    // cannot reach at all), and precomputes the distances from and to each landmark;
+    // edge weights must not be negative
+    pub fn landmark_index(&self, k: usize) -> LandmarkIndex {
+        let adj = self.weighted_adjacencies();
+        let reverse = self.reverse_weighted_adjacencies();
+
+        let vertices = self.sorted_vertices();
+        let mut index = LandmarkIndex { landmarks: vec![], from: vec![], to: vec![] };
+        let mut nearest: HashMap<Vertex, Weight> = vertices.iter().map(|v| (*v, Weight::INFINITY)).collect();
+        while index.landmarks.len() < k.min(vertices.len()) {
+            let landmark = if index.landmarks.is_empty() {
+                vertices[0]
+            } else {
+                *vertices.iter()
+                    .filter(|v| !index.landmarks.contains(v))
+                    .max_by(|a, b| nearest[a].total_cmp(&nearest[b]).then_with(|| b.cmp(a)))
+                    .unwrap()
+            };
+            let from = distances(&adj, landmark);
+            for (v, d) in &from {
+                let current = nearest.get_mut(v).unwrap();
+                *current = current.min(*d);
+            }
+            index.landmarks.push(landmark);
+            index.from.push(from);
+            index.to.push(distances(&reverse, landmark));
+        }
+        index
+    }
+
+    // picks up to k landmarks as landmark_index does and lays out their distances densely
+    // for estimating distances; edge weights must not be negative
+    pub fn distance_estimator(&self, k: usize) -> DistanceEstimator {
+        DistanceEstimator::new(self, &self.landmark_index(k))
+    }
+}
@@ -1,22 +1,218 @@
 use std::env;
 use std::fs::File;
+use std::io::BufRead;
 use std::io::BufReader;
 
+mod attributes;
+mod bellman_ford;
+mod betweenness;
+mod binary;
+mod cache;
+mod checkpoint;
+mod chordal;
+mod classics;
+mod closure;
+mod coarsen;
+mod coloring;
+mod communities;
+mod compressed;
+mod contraction;
+mod csr;
+mod cut;
+mod dag;
+mod degrees;
+mod delta_stepping;
+mod diameter;
+mod diff;
+mod dimacs;
+mod disjoint_sets;
+mod disk;
+mod distance_oracle;
+mod domination;
+mod dynamic_connectivity;
+mod dynamic_shortest_paths;
+mod eulerian;
+mod flow;
+mod fuzz;
+mod gexf;
 mod graph;
+mod hypergraph;
+mod implicit;
+mod indexed_heap;
+mod interval;
+mod isomorphism;
+mod landmarks;
+mod matching;
+mod matrix_market;
+mod maze;
+mod memory;
+mod merge;
+mod morphism;
+mod observe;
+mod ordering;
+mod orientation;
+mod pagerank;
+mod per_component;
+mod percolation;
+mod parse;
+mod partition;
+mod preconditions;
+mod progress;
+mod projection;
+mod random;
+mod report;
+mod rewrite;
+mod shortest_paths;
+mod signature;
+mod spanning_trees;
+mod sparsify;
+mod stats;
+mod streaming;
+mod temporal;
+mod testing;
+mod threshold;
+mod traversal;
+mod trace;
+mod tree;
+mod triangles;
+mod tsp;
 mod tuple_reader;
+mod two_sat;
+mod validation;
+mod view;
+mod viz;
+mod weights;
 
+use graph::{HashMap, HashSet};
 use tuple_reader::TupleReader;
 
 fn main() {
-    if env::args().count() != 3 {
+    if env::args().count() < 3 {
         let main = env::args().next().unwrap();
-        println!("Usage: {} <command> <graph file>", main);
+        println!("Usage: {} <command> <graph file> [<args>...]", main);
         std::process::exit(1);
     }
     let mut args = env::args();
     let command = args.nth(1).unwrap();
     let filename = args.next().unwrap();
-    let file = File::open(filename).expect("Cannot open file!");
+    if command == "classic" {
+        // takes the name of a classic graph instead of a graph file
+        let graph = classics::by_name(&filename).expect("Unknown graph!");
+        println!("{}", graph.summary());
+        println!("Triangles: {}", graph.triangle_count(1));
+        if filename == "karate" {
+            let instructor = classics::karate_club_split();
+            let administrator = graph.vertices().difference(&instructor).cloned().collect();
+            println!("Modularity of the split: {}", graph.modularity(&[instructor, administrator], 1.0));
+        }
+        print!("{}", graph);
+        return;
+    }
+    if command == "fuzz" {
+        // takes the number of rounds instead of a graph file, followed by a seed
+        let rounds = filename.parse().expect("Invalid number of rounds!");
+        let seed = args.next().map_or(1, |s| s.parse().expect("Invalid seed!"));
+        let failures = fuzz::fuzz(rounds, seed);
+        for failure in &failures {
+            println!("{}: {}", failure.target, failure.message);
+            match String::from_utf8(failure.input.clone()) {
+                Ok(text) => println!("  input: {:?}", text),
+                Err(_) => println!("  input: {:02x?}", failure.input)
+            }
+        }
+        println!("Failures: {}", failures.len());
+        return;
+    }
+    if command == "flowbench" {
+        // takes the number of nodes instead of a graph file, followed by a seed and the number
+        // of repetitions, and times the maximum flow algorithms on random networks
+        let n = filename.parse().expect("Invalid number of nodes!");
+        let seed = args.next().map_or(1, |s| s.parse().expect("Invalid seed!"));
+        let repetitions = args.next().map_or(3, |s| s.parse().expect("Invalid number of repetitions!"));
+        let timings = flow::benchmark(n, seed, repetitions);
+        for network in timings.chunks(flow::FLOW_ALGORITHMS.len() + 1) {
+            let value = network[0].value;
+            println!("{}: maximum flow {}", network[0].network, value);
+            for timing in network {
+                let agrees = (timing.value - value).abs() <= 1e-9 * value.abs().max(1.0);
+                println!("  {:?}: {:.3} ms{}", timing.algorithm, timing.time.as_secs_f64() * 1000.0,
+                         if agrees { String::new() } else { format!(", but found {}", timing.value) });
+            }
+        }
+        return;
+    }
+    if command == "generate" {
+        // takes the number of vertices instead of a graph file, followed by the number of
+        // edges, twice as many by default, the weights "unit", "uniform:<low>:<high>" or
+        // "exponential:<mean>", a seed and "directed" for a directed graph, and prints the
+        // random graph in the weighted input format
+        let n = filename.parse().expect("Invalid number of vertices!");
+        let m = args.next().map_or(2 * n, |s| s.parse().expect("Invalid number of edges!"));
+        let distribution = match args.next().as_ref().map(|s| s.split(':').collect::<Vec<_>>()).as_deref() {
+            None | Some(["unit"]) => weights::WeightDistribution::Unit,
+            Some(["uniform", low, high]) => weights::WeightDistribution::UniformInt(low.parse().expect("Invalid low weight!"), high.parse().expect("Invalid high weight!")),
+            Some(["exponential", mean]) => weights::WeightDistribution::Exponential(mean.parse().expect("Invalid mean weight!")),
+            Some(_) => panic!("Unknown weight distribution!")
+        };
+        let seed = args.next().map_or(1, |s| s.parse().expect("Invalid seed!"));
+        let directed = args.next().is_some_and(|s| s == "directed");
+        let graph = degrees::random_graph(n, m, directed, seed).random_weights(distribution, seed);
+        let mut edges: Vec<graph::Edge> = graph.edges().iter().cloned().collect();
+        edges.sort();
+        println!("{} {}", n, edges.len());
+        for edge in edges {
+            println!("{} {} {}", edge.0, edge.1, graph.weight(&edge));
+        }
+        return;
+    }
+    if command == "puzzle" {
+        // takes a state of the 8-puzzle instead of a graph file, its tiles row by row with 0
+        // for the blank, followed by the goal and the most states to discover, and solves it
+        // on the implicit graph of its moves
+        let start: graph::Vertex = filename.parse().expect("Invalid state!");
+        let goal: graph::Vertex = args.next().map_or(123456780, |s| s.parse().expect("Invalid goal!"));
+        let limit = args.next().map_or(1_000_000, |s| s.parse().expect("Invalid limit!"));
+        let puzzle = implicit::ImplicitGraph::new(implicit::sliding_puzzle_moves);
+        match puzzle.astar(start, goal, |v| implicit::sliding_puzzle_distance(v, goal), limit) {
+            Some(found) => {
+                for state in &found.path {
+                    println!("{:09}", state);
+                }
+                let bfs = puzzle.find_path(start, |v| v == goal, limit).map(|path| path.len());
+                println!("Moves: {} by A* with {} moves tried, {} by breadth first search", found.distance, found.relaxations,
+                         bfs.map_or("none".to_string(), |length| (length - 1).to_string()));
+            },
+            None => println!("Unsolvable within {} states", limit)
+        }
+        let reachable = puzzle.bfs(start).take(limit).last().map_or(0, |(_, distance)| distance);
+        println!("Farthest state discovered: {} moves", reachable);
+        println!("Depth first order: {:?}", puzzle.dfs(start).take(5).collect::<Vec<_>>());
+        let (graph, complete) = puzzle.materialize(start, 1000);
+        println!("Around the start: {}{}", graph.summary(), if complete { "" } else { ", cut off" });
+        return;
+    }
+    if command == "maze" {
+        // takes the number of rows instead of a graph file, followed by the number of
+        // columns, the generator "dfs" or "kruskal" and a seed, and prints the maze with the
+        // way from the top left to the bottom right corner
+        let rows = filename.parse().expect("Invalid number of rows!");
+        let columns = args.next().map_or(rows, |s| s.parse().expect("Invalid number of columns!"));
+        let generator = match args.next().as_ref().map(|s| s.as_ref()) {
+            None | Some("dfs") => maze::Generator::DepthFirst,
+            Some("kruskal") => maze::Generator::Kruskal,
+            Some(_) => panic!("Unknown generator!")
+        };
+        let seed = args.next().map_or(1, |s| s.parse().expect("Invalid seed!"));
+        let maze = maze::Maze::generate(rows, columns, &generator, seed);
+        let (from, to) = ((0, 0), (rows.max(1) - 1, columns.max(1) - 1));
+        let way = maze.solve_bfs(from, to).expect("Maze is not connected!");
+        print!("{}", maze.render(&way));
+        println!("{}", maze.graph().summary());
+        let astar = maze.solve_astar(from, to).expect("Maze is not connected!");
+        println!("Way of {} cells, {} by A*", way.len(), if astar == way { "confirmed" } else { "contradicted" });
+        return;
+    }
+    let file = File::open(&filename).expect("Cannot open file!");
     let mut reader = BufReader::new(&file);
     match command.as_ref() {
         "reach" => {
@@ -24,15 +220,2111 @@ fn main() {
             let (from, to) = reader.next_tuple();
             println!("Checking reachability {} -> {}: {}", from, to, graph.is_reachable(from, to));
         },
+        "reachsets" => {
+            // the directed graph is followed by two lines with the sets of vertices to reach
+            // from and to reach
+            let graph = graph::Graph::load(&mut reader, true);
+            let mut sets = reader.lines().map(|line| {
+                line.expect("Cannot read set!").split_whitespace().map(|s| s.parse().expect("Invalid vertex!")).collect::<HashSet<graph::Vertex>>()
+            });
+            let (from, to) = (sets.next().unwrap_or_default(), sets.next().unwrap_or_default());
+            println!("Any reachable: {}", graph.any_reachable(&from, &to));
+            let mut reached: Vec<_> = graph.reachable_subset(&from, &to).into_iter().collect();
+            reached.sort();
+            println!("Reachable: {:?}", reached);
+        },
+        "path" => {
+            let graph = graph::Graph::load(&mut reader, false);
+            let (from, to) = reader.next_tuple();
+            match (graph.find_path(from, to), graph.find_shortest_path(from, to)) {
+                (Some(path), Some(shortest)) => {
+                    println!("Path {} -> {}: {:?}", from, to, path);
+                    println!("Shortest path {} -> {}: {:?}", from, to, shortest);
+                },
+                _ => println!("No path {} -> {}", from, to)
+            }
+        },
         "comp" => {
+            // a "directed" graph has both weakly and strongly connected components; "sorted"
+            // lists the components and their vertices in the same order on every run, and
+            // "labels" lists the component of every vertex in ascending order
+            let flags: Vec<String> = args.collect();
+            let directed = flags.iter().any(|s| s == "directed");
+            let mut graph = graph::Graph::load(&mut reader, directed);
+            graph.set_deterministic(flags.iter().any(|s| s == "sorted"));
+            let show = |components: Vec<graph::Component>| if graph.is_deterministic() {
+                format!("{:?}", graph::sorted_members(components))
+            } else {
+                format!("{:?}", components)
+            };
+            if directed {
+                println!("Weakly connected components: {}", show(graph.weakly_connected_components()));
+                println!("Strongly connected components: {}", show(graph.strongly_connected_components()));
+            } else {
+                println!("Connected components: {}", show(graph.connected_components()));
+            }
+            if flags.iter().any(|s| s == "labels") {
+                println!("Component labels: {:?}", graph.component_labels());
+            }
+            println!("Largest component: {} vertices", graph.largest_component().len());
+        },
+        "connected" => {
+            // checks the graph for connectivity, as a "directed" graph if requested
+            let directed = args.next().is_some_and(|s| s == "directed");
+            let graph = graph::Graph::load(&mut reader, directed);
+            println!("Connected: {}", graph.is_connected().is_ok());
+            println!("Strongly connected: {}", graph.is_strongly_connected());
+        },
+        "diff" => {
+            // compares the graph with the one in the file given as second argument
+            let other = args.next().expect("Missing second graph file!");
+            let graph = graph::Graph::load_weighted(&mut reader, false);
+            let other = graph::Graph::load_weighted(&mut BufReader::new(File::open(other).expect("Cannot open file!")), false);
+            let diff = graph.diff(&other);
+            if diff.is_empty() {
+                println!("No changes");
+            }
+            print!("{}", diff);
+        },
+        "dom" => {
+            let graph = graph::Graph::load(&mut reader, false);
+            let mut set: Vec<_> = graph.dominating_set_greedy().into_iter().collect();
+            set.sort();
+            println!("Dominating set: {:?}", set);
+        },
+        "fas" => {
+            let graph = graph::Graph::load(&mut reader, true);
+            let (arcs, order) = graph.feedback_arc_set();
+            println!("Feedback arc set: {:?}", arcs);
+            println!("Topological order: {:?}", order);
+        },
+        "lprop" => {
+            let graph = graph::Graph::load(&mut reader, false);
+            let seed = args.next().map_or(0, |s| s.parse().expect("Invalid seed!"));
+            let max_iterations = args.next().map_or(100, |s| s.parse().expect("Invalid number of iterations!"));
+            let (report, cancellation) = run_flags(args);
+            let communities = match cancellation {
+                Some(cancellation) => partial(graph.communities_label_propagation_cancellable(seed, max_iterations, &cancellation)),
+                None => Some(graph.communities_label_propagation_with_progress(seed, max_iterations, |progress| if report { eprintln!("{}", progress) }))
+            };
+            println!("Communities: {:?}", communities.expect("No communities!"));
+        },
+        "louvain" => {
+            let graph = graph::Graph::load_weighted(&mut reader, false);
+            let resolution = args.next().map_or(1.0, |s| s.parse().expect("Invalid resolution!"));
+            let communities = match run_flags(args) {
+                (_, Some(cancellation)) => partial(graph.communities_louvain_cancellable(resolution, &cancellation)).expect("No communities!"),
+                (true, None) => graph.communities_louvain_with_progress(resolution, |progress| eprintln!("{}", progress)),
+                (false, None) => graph.communities_louvain(resolution)
+            };
+            println!("Communities: {:?}", communities);
+            println!("Modularity: {}", graph.modularity(&communities, resolution));
+        },
+        "betweenness" => {
+            // takes the number of pivots to sample, all vertices by default, a seed and
+            // "directed" for a directed graph, and prints the vertices of highest betweenness
+            // with the error bound of the estimate
+            let pivots = args.next().map_or(usize::MAX, |s| s.parse().expect("Invalid number of pivots!"));
+            let seed = args.next().map_or(1, |s| s.parse().expect("Invalid seed!"));
+            let directed = args.next().is_some_and(|s| s == "directed");
+            let graph = graph::Graph::load(&mut reader, directed);
+            let betweenness = if pivots >= graph.vertices().len() {
+                betweenness::Betweenness { scores: graph.betweenness_centrality(), pivots: graph.vertices().len(), error: 0.0 }
+            } else {
+                graph.approximate_betweenness(pivots, seed)
+            };
+            let mut top: Vec<_> = betweenness.scores.iter().collect();
+            top.sort_by(|a, b| b.1.partial_cmp(a.1).unwrap().then(a.0.cmp(b.0)));
+            for (v, score) in top.into_iter().take(10) {
+                println!("{}: {}", v, score);
+            }
+            println!("Pivots: {}, error at most {} with 95% probability", betweenness.pivots, betweenness.error);
+        },
+        "girvan" => {
+            // takes the largest number of splits, and prints the dendrogram and the level of
+            // highest modularity
+            let graph = graph::Graph::load(&mut reader, false);
+            let max_splits = args.next().map_or(usize::MAX, |s| s.parse().expect("Invalid number of splits!"));
+            let betweenness = graph.edge_betweenness();
+            let highest = betweenness.iter().max_by(|a, b| a.1.partial_cmp(b.1).unwrap().then(b.0.cmp(a.0)));
+            if let Some(((v, w), value)) = highest {
+                println!("Highest edge betweenness: {} - {} ({})", v, w, value);
+            }
+            let dendrogram = graph.girvan_newman(max_splits);
+            for split in &dendrogram.splits {
+                let size = |c: usize| dendrogram.clusters[c].len();
+                println!("After {} edges: {} ({}) -> {} ({}) + {} ({})", split.edges_removed,
+                         split.parent, size(split.parent), split.left, size(split.left), split.right, size(split.right));
+            }
+            let best = (0..dendrogram.splits.len() + 1)
+                .map(|k| (k, graph.modularity(&dendrogram.level(k), 1.0)))
+                .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap().then(b.0.cmp(&a.0)));
+            if let Some((k, modularity)) = best {
+                println!("Best level: {} splits, modularity {}", k, modularity);
+                println!("Communities: {:?}", graph::sorted_members(dendrogram.level(k)));
+            }
+        },
+        "bisect" => {
+            let graph = graph::Graph::load(&mut reader, false);
+            match graph.bisect_kernighan_lin() {
+                Ok((left, right, cut)) => {
+                    println!("Bisection: {:?} | {:?}", left, right);
+                    println!("Cut size: {}", cut);
+                },
+                Err(error) => println!("{}", error)
+            }
+        },
+        "coarsen" => {
+            // coarsens down to the given number of vertices, 100 unless given, bisects the
+            // coarsest graph and projects the bisection back onto the original vertices
+            let graph = graph::Graph::load_weighted(&mut reader, false);
+            let min_vertices = args.next().map_or(100, |s| s.parse().expect("Invalid number of vertices!"));
+            let seed = args.next().map_or(0, |s| s.parse().expect("Invalid seed!"));
+            let hierarchy = graph.coarsen(min_vertices, seed);
+            for (i, level) in hierarchy.levels.iter().enumerate() {
+                println!("Level {}: {}", i + 1, level.graph.summary());
+            }
+            match hierarchy.coarsest() {
+                None => println!("Graph has no more than {} vertices", min_vertices),
+                Some(coarsest) => match coarsest.bisect_kernighan_lin() {
+                    Ok((left, _, cut)) => {
+                        let coarse: HashMap<graph::Vertex, bool> = coarsest.vertices().iter().map(|v| (*v, left.contains(v))).collect();
+                        let side = hierarchy.prolong(&coarse);
+                        let projected: graph::Weight = graph.edges().iter()
+                            .filter(|&&(v, w)| left.contains(&hierarchy.project(v)) != left.contains(&hierarchy.project(w)))
+                            .map(|edge| graph.weight(edge))
+                            .sum();
+                        let size = side.values().filter(|left| **left).count();
+                        println!("Bisection after {} levels: {} | {} vertices, cut {} (coarsest {})", hierarchy.levels.len(), size, side.len() - size, projected, cut);
+                    },
+                    Err(error) => println!("{}", error)
+                }
+            }
+        },
+        "sparsify" => {
+            // the stretch factor t of the spanner is followed by the sampling probability,
+            // 0.5 unless given, and a seed
+            let graph = graph::Graph::load_weighted(&mut reader, false);
+            let t = args.next().map_or(3.0, |s| s.parse().expect("Invalid stretch factor!"));
+            let probability = args.next().map_or(0.5, |s| s.parse().expect("Invalid probability!"));
+            let seed = args.next().map_or(0, |s| s.parse().expect("Invalid seed!"));
+            let spanner = graph.t_spanner(t);
+            println!("{}-spanner: {} of {} edges, stretch {}", t, spanner.edges().len(), graph.edges().len(), graph.stretch(&spanner));
+            let sample = graph.sample_edges(probability, seed);
+            let (cut, side) = graph.global_min_cut();
+            println!("Sample: {} edges, stretch {}, minimum cut {} weighs {}", sample.edges().len(), graph.stretch(&sample), cut, sample.cut_weight(&side));
+        },
+        "vconn" => {
+            let graph = graph::Graph::load(&mut reader, false);
+            println!("Vertex connectivity: {}", graph.vertex_connectivity());
+        },
+        "vcut" => {
+            let graph = graph::Graph::load(&mut reader, false);
+            let (from, to) = reader.next_tuple();
+            match graph.min_vertex_cut(from, to) {
+                Some(cut) => println!("Minimum vertex cut {} -> {}: {:?}", from, to, cut),
+                None => println!("No vertex cut separates {} and {}", from, to)
+            }
+        },
+        "disjoint" => {
+            // takes the number of paths wanted, and "vertex" for paths sharing no vertices
+            let graph = graph::Graph::load(&mut reader, false);
+            let (from, to) = reader.next_tuple();
+            let k = args.next().map_or(usize::MAX, |s| s.parse().expect("Invalid number of paths!"));
+            let paths = if args.next().is_some_and(|s| s == "vertex") {
+                graph.vertex_disjoint_paths(from, to, k)
+            } else {
+                graph.edge_disjoint_paths(from, to, k)
+            };
+            for path in &paths {
+                println!("{:?}", path);
+            }
+            println!("{} disjoint paths {} -> {}", paths.len(), from, to);
+        },
+        "mincut" => {
+            // a number of trials and a seed may follow for the randomized algorithms
+            let graph = graph::Graph::load_weighted(&mut reader, false);
+            let (cut, side) = graph.global_min_cut();
+            println!("Minimum cut: {} {:?}", cut, side);
+            if let Some(trials) = args.next() {
+                let trials = trials.parse().expect("Invalid number of trials!");
+                let seed = args.next().map_or(0, |s| s.parse().expect("Invalid seed!"));
+                for (name, variant) in [("Karger", cut::KargerVariant::Plain), ("Karger-Stein", cut::KargerVariant::Stein)] {
+                    let cut = graph.randomized_min_cut(variant, trials, seed);
+                    println!("Minimum cut ({}): {} {:?}, success probability at least {:.4}", name, cut.weight, cut.side, cut.success_probability);
+                }
+            }
+        },
+        "dijkstra" => {
+            let graph = graph::Graph::load_weighted(&mut reader, true);
+            let (from, to) = reader.next_tuple();
+            match graph.shortest_path(from, to) {
+                Ok(Some(found)) => {
+                    println!("Shortest path {} -> {}: {} {:?}, {} edges relaxed", from, to, found.distance, found.path, found.relaxations);
+                    let mut labelled: Vec<graph::Vertex> = found.predecessors().keys().cloned().collect();
+                    labelled.sort();
+                    for v in labelled {
+                        println!("Reached {} from {} along {:?}", v, found.predecessor(v).unwrap(), found.path_to(v).unwrap());
+                    }
+                },
+                Ok(None) => println!("No path {} -> {}", from, to),
+                Err(error) => println!("{}", error)
+            }
+        },
+        "negcycle" => {
+            let graph = graph::Graph::load_weighted(&mut reader, true);
+            match graph.find_negative_cycle() {
+                Some(cycle) => println!("1\nNegative cycle: {:?}", cycle),
+                None => println!("0")
+            }
+        },
+        "exchange" => {
+            // the directed graph is followed by a line with the source; prints the distance
+            // of every vertex, "*" for minus infinity and "-" for unreachable ones
+            let graph = graph::Graph::load_weighted(&mut reader, true);
+            let source = reader.next_number();
+            let distances = graph.bellman_ford(source);
+            for v in graph.sorted_vertices() {
+                match distances.get(&v) {
+                    Some(distance) if *distance == graph::Weight::NEG_INFINITY => println!("*"),
+                    Some(distance) => println!("{}", distance),
+                    None => println!("-")
+                }
+            }
+            println!("Minus infinity: {:?}", graph.infinite_distance_vertices(source));
+        },
+        "arbitrage" => {
+            // the weights of the directed graph are exchange rates
+            let graph = graph::Graph::load_weighted(&mut reader, true);
+            match graph.find_arbitrage() {
+                Some((factor, cycle)) => println!("Arbitrage {:?} multiplies the money by {}", cycle, factor),
+                None => println!("No arbitrage")
+            }
+        },
+        "routes" => {
+            // takes the source, 1 by default, and "directed" for a directed network
+            let source = args.next().map_or(1, |s| s.parse().expect("Invalid source!"));
+            let directed = args.next().is_some_and(|s| s == "directed");
+            let graph = graph::Graph::load_weighted(&mut reader, directed);
+            let tree = graph.shortest_path_tree(source);
+            println!("Shortest path tree: {}", tree.summary());
+            let mut edges: Vec<_> = tree.edges().iter().cloned().collect();
+            edges.sort();
+            for (v, w) in edges {
+                println!("{} -> {} ({})", v, w, tree.weight(&(v, w)));
+            }
+            let table = graph.routing_table();
+            for (destination, hop, distance) in table.routes_from(source) {
+                println!("To {}: via {} at {}", destination, hop, distance);
+            }
+            if let Some(destination) = graph.sorted_vertices().last() {
+                println!("Next hop from {} to {}: {:?}", destination, source, table.next_hop(*destination, source));
+            }
+        },
+        "bidijkstra" => {
+            let graph = graph::Graph::load_weighted(&mut reader, true);
+            let (from, to) = reader.next_tuple();
+            match graph.bidirectional_shortest_path(from, to) {
+                Some(found) => println!("Shortest path {} -> {}: {} {:?}, {} edges relaxed", from, to, found.distance, found.path, found.relaxations),
+                None => println!("No path {} -> {}", from, to)
+            }
+        },
+        "widest" => {
+            let graph = graph::Graph::load_weighted(&mut reader, true);
+            let (from, to) = reader.next_tuple();
+            match graph.widest_path(from, to) {
+                Some(found) => println!("Widest path {} -> {}: {} {:?}", from, to, found.distance, found.path),
+                None => println!("No path {} -> {}", from, to)
+            }
+        },
+        "alt" => {
+            let graph = graph::Graph::load_weighted(&mut reader, true);
+            let (from, to) = reader.next_tuple();
+            let k = args.next().map_or(4, |s| s.parse().expect("Invalid number of landmarks!"));
+            let index = graph.landmark_index(k);
+            println!("Landmarks: {:?}", index.landmarks());
+            match index.shortest_path(&graph, from, to) {
+                Some(found) => println!("Shortest path {} -> {}: {} {:?}, {} edges relaxed", from, to, found.distance, found.path, found.relaxations),
+                None => println!("No path {} -> {}", from, to)
+            }
+        },
+        "estimate" => {
+            // takes the number of landmarks, 4 by default, the number of random pairs to
+            // estimate the distances of, 1000 by default, and a seed, and compares the bounds
+            // with the exact distances
+            let graph = graph::Graph::load_weighted(&mut reader, true);
+            let k = args.next().map_or(4, |s| s.parse().expect("Invalid number of landmarks!"));
+            let pairs = args.next().map_or(1000, |s| s.parse().expect("Invalid number of pairs!"));
+            let mut random = random::Random::new(args.next().map_or(1, |s| s.parse().expect("Invalid seed!")));
+            let estimator = graph.distance_estimator(k);
+            let vertices = graph.sorted_vertices();
+            if vertices.is_empty() {
+                return;
+            }
+            let queries: Vec<(graph::Vertex, graph::Vertex)> = (0..pairs)
+                .map(|_| (vertices[random.below(vertices.len())], vertices[random.below(vertices.len())]))
+                .collect();
+            let start = std::time::Instant::now();
+            let estimates: Vec<landmarks::Estimate> = queries.iter().map(|&(u, v)| estimator.estimate(u, v)).collect();
+            let elapsed = start.elapsed();
+            let (mut exact, mut violations, mut reachable, mut ratio) = (0, 0, 0, 0.0);
+            for (&(u, v), estimate) in queries.iter().zip(&estimates) {
+                let distance = graph.shortest_path(u, v).expect("Negative weights!").map_or(graph::Weight::INFINITY, |found| found.distance);
+                if estimate.lower > distance || estimate.upper < distance {
+                    violations += 1;
+                }
+                if estimate.is_exact() {
+                    exact += 1;
+                }
+                if distance.is_finite() && distance > 0.0 && estimate.upper.is_finite() {
+                    reachable += 1;
+                    ratio += (estimate.upper - estimate.lower) / distance;
+                }
+            }
+            println!("{} estimates in {:?}, {} exact, {} violating the bounds", estimates.len(), elapsed, exact, violations);
+            if reachable > 0 {
+                println!("Mean gap between the bounds: {:.3} of the distance", ratio / reachable as f64);
+            }
+        },
+        "oracle" => {
+            let graph = graph::Graph::load_weighted(&mut reader, true);
+            let max_exact = args.next().map_or(distance_oracle::EXACT_ORACLE_LIMIT, |s| s.parse().expect("Invalid vertex limit!"));
+            let oracle = match run_flags(args) {
+                (_, Some(cancellation)) => match graph.distance_oracle_cancellable(max_exact, &cancellation) {
+                    Ok(oracle) => oracle,
+                    Err(interrupted) => {
+                        eprintln!("{}", interrupted);
+                        return;
+                    }
+                },
+                (true, None) => graph.distance_oracle_with_progress(max_exact, |progress| eprintln!("{}", progress)),
+                (false, None) => graph.distance_oracle(max_exact)
+            };
+            println!("Exact: {}", oracle.is_exact());
+            for _ in 0..reader.next_number() {
+                let (from, to) = reader.next_tuple();
+                println!("Distance {} -> {}: {}", from, to, oracle.distance(from, to));
+            }
+        },
+        "checkpoint" => {
+            // takes the stage "ch", "closure" or "oracle", the checkpoint file and the number
+            // of contracted vertices or processed components or sources between saves, runs
+            // the stage, resuming from the checkpoint file if there is one, and answers the
+            // queries following the graph for "ch" and "oracle"
+            let stage = args.next().expect("Missing stage!");
+            let path = args.next().expect("Missing checkpoint file!");
+            let interval = args.next().map_or(1000, |s| s.parse().expect("Invalid interval!"));
+            let checkpoint = checkpoint::Checkpoint::new(std::path::Path::new(&path), interval);
+            if checkpoint.path().exists() {
+                println!("Resuming from {}", path);
+            }
+            let distance: Box<dyn Fn(graph::Vertex, graph::Vertex) -> String> = match stage.as_ref() {
+                "ch" => {
+                    let graph = graph::Graph::load_weighted(&mut reader, true);
+                    let hierarchy = graph.contraction_hierarchy_checkpointed(&checkpoint).expect("Cannot checkpoint!");
+                    println!("Shortcuts: {}", hierarchy.shortcut_count());
+                    Box::new(move |from, to| hierarchy.query(from, to).map_or("none".to_string(), |found| format!("{} {:?}", found.distance, found.path)))
+                },
+                "closure" => {
+                    let graph = graph::Graph::load(&mut reader, true);
+                    let closure = graph.transitive_closure_checkpointed(&checkpoint).expect("Cannot checkpoint!");
+                    Box::new(move |from, to| closure.reaches(from, to).to_string())
+                },
+                "oracle" => {
+                    let graph = graph::Graph::load_weighted(&mut reader, true);
+                    let oracle = graph.distance_oracle_checkpointed(distance_oracle::EXACT_ORACLE_LIMIT, &checkpoint).expect("Cannot checkpoint!");
+                    println!("Exact: {}", oracle.is_exact());
+                    Box::new(move |from, to| oracle.distance(from, to).to_string())
+                },
+                _ => panic!("Unknown stage!")
+            };
+            for _ in 0..reader.next_number() {
+                let (from, to) = reader.next_tuple();
+                println!("{} -> {}: {}", from, to, distance(from, to));
+            }
+        },
+        "ch" => {
+            let graph = graph::Graph::load_weighted(&mut reader, true);
+            let hierarchy = graph.contraction_hierarchy();
+            println!("Shortcuts: {}", hierarchy.shortcut_count());
+            for _ in 0..reader.next_number() {
+                let (from, to) = reader.next_tuple();
+                match hierarchy.query(from, to) {
+                    Some(found) => println!("Shortest path {} -> {}: {} {:?}, {} edges relaxed", from, to, found.distance, found.path, found.relaxations),
+                    None => println!("No path {} -> {}", from, to)
+                }
+            }
+        },
+        "kpaths" => {
+            let graph = graph::Graph::load_weighted(&mut reader, true);
+            let (from, to) = reader.next_tuple();
+            let k = args.next().map_or(3, |s| s.parse().expect("Invalid number of paths!"));
+            for (distance, path) in graph.k_shortest_paths(from, to, k) {
+                println!("{} {:?}", distance, path);
+            }
+        },
+        "dagsp" | "daglp" => {
+            let graph = graph::Graph::load_weighted(&mut reader, true);
+            let source = reader.next_number();
+            let paths = if command == "dagsp" { graph.dag_shortest_paths(source) } else { graph.dag_longest_paths(source) };
+            match paths {
+                Some((distances, _)) => {
+                    let mut vertices: Vec<_> = graph.vertices().iter().collect();
+                    vertices.sort();
+                    for v in vertices {
+                        match distances.get(v) {
+                            Some(distance) => println!("{}: {}", v, distance),
+                            None => println!("{}: unreachable", v)
+                        }
+                    }
+                },
+                None => println!("Graph contains a cycle")
+            }
+        },
+        "critical" => {
+            let graph = graph::Graph::load_weighted(&mut reader, true);
+            match graph.longest_path() {
+                Some((length, path)) => println!("Critical path: {} {:?}", length, path),
+                None => println!("Graph contains a cycle")
+            }
+        },
+        "cpm" => {
+            // schedules the tasks of the graph, with edges weighted by the time between the
+            // starts of two tasks
+            let graph = graph::Graph::load_weighted(&mut reader, true);
+            match graph.critical_path() {
+                Ok(schedule) => {
+                    for v in graph.sorted_vertices() {
+                        println!("{}: earliest {}, latest {}, slack {}", v, schedule.earliest[&v], schedule.latest[&v], schedule.slack[&v]);
+                    }
+                    println!("Length {}, critical path {:?}", schedule.length, schedule.critical_path);
+                },
+                Err(error) => println!("{}", error)
+            }
+        },
+        "closure" => {
+            let graph = graph::Graph::load(&mut reader, true);
+            println!("{:?}", graph.transitive_closure().to_graph());
+        },
+        "reduce" => {
+            let graph = graph::Graph::load(&mut reader, true);
+            match graph.transitive_reduction() {
+                Some(reduction) => println!("{:?}", reduction),
+                None => println!("Graph contains a cycle")
+            }
+        },
+        "reaches" => {
+            let graph = graph::Graph::load(&mut reader, true);
+            let closure = graph.transitive_closure();
+            for _ in 0..reader.next_number() {
+                let (from, to) = reader.next_tuple();
+                println!("{} -> {}: {}", from, to, closure.reaches(from, to));
+            }
+        },
+        "toposort" => {
+            let graph = graph::Graph::load(&mut reader, true);
+            match graph.topological_order_lex_smallest() {
+                Ok(order) => println!("Topological order: {:?}", order),
+                Err(error) => println!("{}", error)
+            }
+        },
+        "toposorts" => {
+            let graph = graph::Graph::load(&mut reader, true);
+            let limit = args.next().map_or(10, |s| s.parse().expect("Invalid limit!"));
+            if let Some(count) = graph.count_topological_orders() {
+                println!("Number of topological orders: {}", count);
+            }
+            for order in graph.topological_orders().take(limit) {
+                println!("{:?}", order);
+            }
+        },
+        "layers" => {
+            // the graph may be followed by lines "v layer" giving a layering to check instead
+            // of computing the longest-path layering
+            let graph = graph::Graph::load(&mut reader, true);
+            let given: HashMap<graph::Vertex, usize> = reader.lines()
+                .map(|line| line.expect("Cannot read layer!"))
+                .filter_map(|line| {
+                    let parts: Vec<&str> = line.split_whitespace().collect();
+                    match parts[..] {
+                        [v, layer] => Some((v.parse().expect("Invalid vertex!"), layer.parse().expect("Invalid layer!"))),
+                        _ => None
+                    }
+                })
+                .collect();
+            let layering = if given.is_empty() {
+                match graph.layering() {
+                    Ok(layering) => layering,
+                    Err(error) => {
+                        println!("{}", error);
+                        return;
+                    }
+                }
+            } else {
+                given
+            };
+            for (l, members) in dag::layers(&layering).iter().enumerate() {
+                println!("Layer {}: {:?}", l, members);
+            }
+            match graph.layering_violation(&layering) {
+                Some((v, w)) => println!("Edge {} -> {} does not lead to a higher layer", v, w),
+                None => println!("Every edge leads to a higher layer")
+            }
+        },
+        "trace" => {
+            // traces "bfs", "dfs" or "dijkstra" from the vertex given by "source=<v>", the
+            // smallest one by default, step by step; "json" writes the trace for replaying it
+            // and "directed" loads a directed graph
+            let flags: Vec<String> = args.collect();
+            let directed = flags.iter().any(|s| s == "directed");
+            let graph = graph::Graph::load_weighted(&mut reader, directed);
+            let source = flags.iter().find_map(|s| s.strip_prefix("source="))
+                .map_or_else(|| graph.sorted_vertices().first().cloned().unwrap_or(1), |s| s.parse().expect("Invalid source!"));
+            let trace = match flags.first().map(|s| s.as_ref()) {
+                None | Some("bfs") => graph.trace_bfs(),
+                Some("dfs") => graph.trace_dfs(),
+                Some("dijkstra") => graph.trace_dijkstra(source),
+                Some(_) => panic!("Unknown algorithm!")
+            };
+            if flags.iter().any(|s| s == "json") {
+                trace.write_json(&mut std::io::stdout()).expect("Cannot write trace!");
+            } else {
+                println!("{}: {} steps", trace.algorithm, trace.steps.len());
+                print!("{}", trace);
+            }
+        },
+        "distances" => {
+            let graph = graph::Graph::load(&mut reader, false);
+            let source = reader.next_number();
+            let distances = graph.distances_from(source);
+            for v in graph.sorted_vertices() {
+                match distances.get(&v) {
+                    Some(distance) => println!("{}: {}", v, distance),
+                    None => println!("{}: unreachable", v)
+                }
+            }
+        },
+        "bfsmulti" => {
+            // the graph is followed by a line with the sources
             let graph = graph::Graph::load(&mut reader, false);
-            let comps = graph.connected_components();
-            println!("Connected components: {:?}", comps);
+            let sources: Vec<graph::Vertex> = reader.lines().next().map_or(vec![], |line| {
+                line.expect("Cannot read sources!").split_whitespace().map(|s| s.parse().expect("Invalid vertex!")).collect()
+            });
+            let nearest = graph.bfs_multi(&sources);
+            for v in graph.sorted_vertices() {
+                match nearest.get(&v) {
+                    Some((distance, source)) => println!("{}: {} from {}", v, distance, source),
+                    None => println!("{}: unreachable", v)
+                }
+            }
+        },
+        "nearest" => {
+            // the graph is followed by a line with the sources and then by queries "v",
+            // asking for the source nearest to v, or "v k", asking for the k nearest ones
+            let graph = graph::Graph::load_weighted(&mut reader, false);
+            let mut lines = reader.lines().map(|line| line.expect("Cannot read line!"));
+            let sources: Vec<graph::Vertex> = lines.next().map_or(vec![], |line| {
+                line.split_whitespace().map(|s| s.parse().expect("Invalid vertex!")).collect()
+            });
+            let nearest = graph.multi_source_dijkstra(&sources);
+            if nearest.is_empty() {
+                println!("No sources");
+            } else {
+                println!("{} vertices reached", nearest.len());
+            }
+            let targets: HashSet<graph::Vertex> = sources.iter().cloned().collect();
+            for line in lines {
+                let query: Vec<usize> = line.split_whitespace().map(|s| s.parse().expect("Invalid number!")).collect();
+                match query[..] {
+                    [v] => match nearest.nearest(v as graph::Vertex) {
+                        Some((source, distance)) => println!("{}: nearest {} at {}", v, source, distance),
+                        None => println!("{}: no source reachable", v)
+                    },
+                    [v, k] => println!("{}: {:?}", v, graph.k_nearest_targets(v as graph::Vertex, &targets, k)),
+                    _ => ()
+                }
+            }
+        },
+        "knearest" => {
+            // "weighted" loads the edge weights, and "directed" follows the edge directions;
+            // the graph is followed by queries "v k" asking for the k vertices nearest to v
+            let weighted = args.next().is_some_and(|s| s == "weighted");
+            let directed = args.next().is_some_and(|s| s == "directed");
+            let graph = if weighted {
+                graph::Graph::load_weighted(&mut reader, directed)
+            } else {
+                graph::Graph::load(&mut reader, directed)
+            };
+            for line in reader.lines() {
+                let line = line.expect("Cannot read query!");
+                let query: Vec<usize> = line.split_whitespace().map(|s| s.parse().expect("Invalid number!")).collect();
+                if let [v, k] = query[..] {
+                    println!("{}: {:?}", v, graph.k_nearest(v as graph::Vertex, k));
+                }
+            }
+        },
+        "reweight" => {
+            // transforms the weights with "scale <factor>", "rescale <low> <high>", "log" or
+            // "negate", with "directed" last for a directed graph, and prints the graph; the
+            // graph may be followed by queries "s t" asking for the most reliable path from s
+            // to t for "log" and the longest one for "negate"
+            let flags: Vec<String> = args.collect();
+            let directed = flags.last().is_some_and(|s| s == "directed");
+            let graph = graph::Graph::load_weighted(&mut reader, directed);
+            let number = |i: usize| -> graph::Weight { flags.get(i).expect("Missing number!").parse().expect("Invalid number!") };
+            let transformed = match flags.first().map(|s| s.as_ref()) {
+                Some("scale") => Ok(graph.scale_weights(number(1))),
+                Some("rescale") => Ok(graph.rescale_weights(number(1), number(2))),
+                Some("log") => graph.log_transform(),
+                Some("negate") => graph.negate_weights(),
+                _ => panic!("Unknown transformation!")
+            };
+            let transformed = match transformed {
+                Ok(transformed) => transformed,
+                Err(error) => {
+                    println!("Cannot transform: {}", error);
+                    return;
+                }
+            };
+            print!("{}", transformed);
+            for line in reader.lines() {
+                let line = line.expect("Cannot read query!");
+                let query: Vec<graph::Vertex> = line.split_whitespace().map(|s| s.parse().expect("Invalid vertex!")).collect();
+                if let [s, t] = query[..] {
+                    match flags[0].as_ref() {
+                        "log" => match graph.most_reliable_path(s, t).unwrap() {
+                            Some(found) => println!("{} -> {}: probability {} along {:?}", s, t, found.distance, found.path),
+                            None => println!("{} -> {}: unreachable", s, t)
+                        },
+                        "negate" if directed => match transformed.dag_shortest_paths(s).unwrap().0.get(&t) {
+                            Some(distance) => println!("{} -> {}: longest path {}", s, t, -distance),
+                            None => println!("{} -> {}: unreachable", s, t)
+                        },
+                        _ => ()
+                    }
+                }
+            }
+        },
+        "sssp" => {
+            // distances from the source with Dijkstra's algorithm or, given "delta" followed
+            // by the bucket width (the average edge weight by default) and number of threads,
+            // by delta-stepping
+            let graph = graph::Graph::load_weighted(&mut reader, true);
+            let source = reader.next_number();
+            let (distances, previous) = match args.next().as_ref().map(|s| s.as_ref()) {
+                None | Some("dijkstra") => shortest_paths::shortest_path_tree(&graph.weighted_adjacencies(), source),
+                Some("delta") => {
+                    let average = graph.edges().iter().map(|edge| graph.weight(edge)).sum::<graph::Weight>() / graph.edges().len().max(1) as graph::Weight;
+                    let delta = args.next().map_or(average.max(1e-9), |s| s.parse().expect("Invalid delta!"));
+                    let threads = args.next().map_or(1, |s| s.parse().expect("Invalid number of threads!"));
+                    graph.delta_stepping(source, delta, threads)
+                },
+                Some(_) => panic!("Unknown algorithm!")
+            };
+            for v in graph.sorted_vertices() {
+                match (distances.get(&v), previous.get(&v)) {
+                    (Some(distance), Some(p)) => println!("{}: {} via {}", v, distance, p),
+                    (Some(distance), None) => println!("{}: {}", v, distance),
+                    _ => println!("{}: unreachable", v)
+                }
+            }
+        },
+        "within" => {
+            let graph = graph::Graph::load(&mut reader, false);
+            let (from, to) = reader.next_tuple();
+            let hops = args.next().map_or(2, |s| s.parse().expect("Invalid number of hops!"));
+            println!("Reachable {} -> {} within {} hops: {}", from, to, hops, graph.is_reachable_within(from, to, hops));
+            let mut ball: Vec<_> = graph.neighborhood(from, hops).into_iter().collect();
+            ball.sort();
+            println!("Neighborhood of {}: {:?}", from, ball);
+        },
+        "ego" => {
+            // takes the vertex, the radius, 1 by default, and "directed" for a directed graph
+            let v = args.next().expect("Missing vertex!").parse().expect("Invalid vertex!");
+            let radius = args.next().map_or(1, |s| s.parse().expect("Invalid radius!"));
+            let directed = args.next().is_some_and(|s| s == "directed");
+            let graph = graph::Graph::load_weighted(&mut reader, directed);
+            let (ego, hops) = graph.ego_network(v, radius);
+            println!("Ego network of {} within {} hops: {} vertices, {} edges", v, radius, ego.vertices().len(), ego.edges().len());
+            for w in ego.sorted_vertices() {
+                println!("{}: {} hops", w, hops[&w]);
+            }
+            let mut edges: Vec<_> = ego.edges().iter().map(|edge| (*edge, ego.weight(edge))).collect();
+            edges.sort_by_key(|a| a.0);
+            for ((a, b), weight) in edges {
+                println!("{} - {}: {}", a, b, weight);
+            }
+        },
+        "simplepaths" => {
+            let graph = graph::Graph::load(&mut reader, true);
+            let (from, to) = reader.next_tuple();
+            let max_len = args.next().map(|s| s.parse().expect("Invalid path length!"));
+            for path in graph.all_simple_paths(from, to, max_len) {
+                println!("{:?}", path);
+            }
+        },
+        "randomwalk" => {
+            // the graph is followed by the number of start vertices and the start vertices
+            let graph = graph::Graph::load(&mut reader, true);
+            let starts: Vec<_> = (0..reader.next_number()).map(|_| reader.next_number()).collect();
+            let steps = args.next().map_or(10, |s| s.parse().expect("Invalid number of steps!"));
+            let restart = args.next().map_or(0.0, |s| s.parse().expect("Invalid restart probability!"));
+            let seed = args.next().map_or(0, |s| s.parse().expect("Invalid seed!"));
+            if starts.len() == 1 {
+                println!("{:?}", graph.random_walk(starts[0], steps, restart, seed));
+            } else {
+                for walk in graph.random_walks(&starts, steps, restart, seed) {
+                    println!("{:?}", walk);
+                }
+            }
+        },
+        "ppr" => {
+            // the graph is followed by the number of seeds and the seeds
+            let graph = graph::Graph::load(&mut reader, true);
+            let seeds: Vec<_> = (0..reader.next_number()).map(|_| reader.next_number()).collect();
+            let alpha = args.next().map_or(0.15, |s| s.parse().expect("Invalid teleport probability!"));
+            let epsilon = args.next().map_or(1e-6, |s| s.parse().expect("Invalid tolerance!"));
+            let mut scores: Vec<_> = graph.personalized_pagerank(&seeds, alpha, epsilon).into_iter().collect();
+            scores.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            for (v, score) in scores {
+                println!("{}: {:.6}", v, score);
+            }
+        },
+        "pagerank" | "eigenvector" => {
+            // PageRank of a directed graph or eigenvector centrality of an undirected one
+            let graph = graph::Graph::load(&mut reader, command == "pagerank");
+            let iterations = args.next().map_or(100, |s| s.parse().expect("Invalid number of iterations!"));
+            let threads = args.next().map_or(1, |s| s.parse().expect("Invalid number of threads!"));
+            let on_progress = |progress: &progress::Progress| eprintln!("{}", progress);
+            let scores = match (command == "pagerank", run_flags(args)) {
+                (true, (_, Some(cancellation))) => partial(graph.pagerank_cancellable(0.85, iterations, threads, &cancellation)).expect("No scores!"),
+                (true, (true, None)) => graph.pagerank_with_progress(0.85, iterations, threads, on_progress),
+                (true, (false, None)) => graph.pagerank(0.85, iterations, threads),
+                (false, (_, Some(cancellation))) => partial(graph.eigenvector_centrality_cancellable(iterations, threads, &cancellation)).expect("No scores!"),
+                (false, (true, None)) => graph.eigenvector_centrality_with_progress(iterations, threads, on_progress),
+                (false, (false, None)) => graph.eigenvector_centrality(iterations, threads)
+            };
+            let mut scores: Vec<_> = scores.into_iter().collect();
+            scores.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            for (v, score) in scores {
+                println!("{}: {:.6}", v, score);
+            }
+        },
+        "triangles" => {
+            let graph = graph::Graph::load(&mut reader, false);
+            let threads = args.next().map_or(1, |s| s.parse().expect("Invalid number of threads!"));
+            println!("Triangles: {}", graph.triangle_count(threads));
+            let coefficients = graph.clustering_coefficients(threads);
+            let average = coefficients.iter().map(|(_, c)| c).sum::<f64>() / coefficients.len().max(1) as f64;
+            println!("Average clustering coefficient: {:.6}", average);
+            for (v, coefficient) in coefficients.iter() {
+                println!("{}: {:.6}", v, coefficient);
+            }
+        },
+        "walk" => {
+            // lists the first vertices reachable from the start vertex given after the graph
+            let graph = graph::Graph::load(&mut reader, true);
+            let start = reader.next_number();
+            let limit = args.next().map_or(usize::MAX, |s| s.parse().expect("Invalid number of vertices!"));
+            println!("DFS: {:?}", traversal::Dfs::new(&graph, start).take(limit).collect::<Vec<_>>());
+            println!("BFS: {:?}", traversal::Bfs::new(&graph, start).take(limit).collect::<Vec<_>>());
+        },
+        "iddfs" => {
+            // takes the source, the target and the largest number of edges to look at
+            let graph = graph::Graph::load(&mut reader, true);
+            let source = args.next().map_or(1, |s| s.parse().expect("Invalid source!"));
+            let target = args.next().map_or(source, |s| s.parse().expect("Invalid target!"));
+            let max_depth = args.next().map_or(graph.vertices().len(), |s| s.parse().expect("Invalid depth!"));
+            match graph.iddfs(source, target, max_depth) {
+                Some(path) => println!("Path {} -> {}: {:?}", source, target, path),
+                None => println!("No path {} -> {} within {} edges", source, target, max_depth)
+            }
+            let entered = traversal::DepthLimited::new(&graph, source, max_depth.min(3)).count();
+            println!("Vertices entered within {} edges: {}", max_depth.min(3), entered);
+        },
+        "classify" => {
+            // "labeled" lists every edge with its class in the order of the search instead
+            let graph = graph::Graph::load(&mut reader, true);
+            if args.next().is_some_and(|s| s == "labeled") {
+                for ((v, w), class) in graph.labeled_edges() {
+                    println!("{} {} {}", v, w, class);
+                }
+            } else {
+                let classes = graph.classify_edges();
+                println!("Tree edges: {:?}", classes.tree);
+                println!("Back edges: {:?}", classes.back);
+                println!("Forward edges: {:?}", classes.forward);
+                println!("Cross edges: {:?}", classes.cross);
+            }
+        },
+        "filtered" => {
+            // the graph is followed by a pair of vertices, the number of disabled vertices,
+            // the disabled vertices, the number of disabled edges and the disabled edges
+            let graph = graph::Graph::load_weighted(&mut reader, true);
+            let (from, to) = reader.next_tuple();
+            let vertices: HashSet<_> = (0..reader.next_number()).map(|_| reader.next_number()).collect();
+            let edges: HashSet<_> = (0..reader.next_number()).map(|_| reader.next_tuple()).collect();
+            let reached = graph.traverse_filtered(from, |v| !vertices.contains(&v), |e| !edges.contains(&e));
+            println!("Reachable from {}: {:?}", from, reached);
+            match graph.shortest_path_filtered(from, to, |v| !vertices.contains(&v), |e| !edges.contains(&e)) {
+                Some(found) => println!("Shortest path {} -> {}: {} {:?}, {} edges relaxed", from, to, found.distance, found.path, found.relaxations),
+                None => println!("No path {} -> {}", from, to)
+            }
+        },
+        "dfs" | "bfs" => {
+            // two vertices given after the file name are checked for being ancestor and
+            // descendant in the forest
+            let graph = graph::Graph::load(&mut reader, true);
+            let forest = if command == "dfs" { graph.dfs_forest() } else { graph.bfs_forest() };
+            for v in &forest.order {
+                let parent = forest.parent.get(v).map_or("-".to_string(), |p| p.to_string());
+                println!("{}: parent {}, discovered {}, finished {}", v, parent, forest.discovered[v], forest.finished[v]);
+            }
+            println!("Reverse postorder: {:?}", forest.reverse_postorder());
+            let pair: Vec<graph::Vertex> = args.map(|s| s.parse().expect("Invalid vertex!")).collect();
+            if let [u, v] = pair[..] {
+                println!("{} is an ancestor of {}: {}", u, v, forest.is_ancestor(u, v));
+            }
+        },
+        "degrees" => {
+            // takes a seed for the configuration model
+            let graph = graph::Graph::load(&mut reader, false);
+            let seed = args.next().map_or(1, |s| s.parse().expect("Invalid seed!"));
+            let sequence = graph.degree_sequence();
+            println!("Degree sequence: {:?}", sequence);
+            println!("Graphical: {}", degrees::is_graphical(&sequence));
+            match degrees::realize_degree_sequence(&sequence) {
+                Some(realization) => {
+                    let matches = realization.degree_sequence() == sequence;
+                    println!("Havel-Hakimi: {}, {}", realization.summary(), if matches { "same degrees" } else { "different degrees" });
+                },
+                None => println!("Havel-Hakimi: not graphical")
+            }
+            match degrees::configuration_model(&sequence, seed) {
+                Some(random) => println!("Configuration model: {}, degree sequence {:?}", random.summary(), random.degree_sequence()),
+                None => println!("Configuration model: odd degree sum")
+            }
+        },
+        "robustness" => {
+            // takes the removal "random", "edges", "degree" or "pagerank" and a seed for the
+            // random ones, and prints the curve at ten evenly spaced points
+            let graph = graph::Graph::load(&mut reader, false);
+            let kind = args.next().unwrap_or("random".to_string());
+            let seed = args.next().map_or(1, |s| s.parse().expect("Invalid seed!"));
+            let removal = match kind.as_ref() {
+                "random" => percolation::Removal::RandomVertices(seed),
+                "edges" => percolation::Removal::RandomEdges(seed),
+                "degree" => percolation::Removal::HighestDegree,
+                "pagerank" => percolation::Removal::HighestPagerank,
+                _ => panic!("Unknown removal!")
+            };
+            let curve = graph.robustness(&removal);
+            let removals = curve.largest.len() - 1;
+            for step in 0..11 {
+                let i = removals * step / 10;
+                println!("{} removed: largest component {}", i, curve.largest[i]);
+            }
+            println!("Robustness: {:.4}", curve.index());
+        },
+        "report" => {
+            // takes "directed", "no" followed by a metric among components, degrees,
+            // diameter, clustering and top to leave it out, "searches=N" for the diameter,
+            // "top=K" and "threads=N", and writes the report as JSON
+            let flags: Vec<String> = args.collect();
+            let graph = graph::Graph::load(&mut reader, flags.iter().any(|s| s == "directed"));
+            let mut options = report::ReportOptions::default();
+            for flag in flags.iter().filter(|s| *s != "directed") {
+                if let Some(value) = flag.strip_prefix("searches=") {
+                    options.diameter_searches = Some(value.parse().expect("Invalid number of searches!"));
+                } else if let Some(value) = flag.strip_prefix("top=") {
+                    options.top_k = value.parse().expect("Invalid number of vertices!");
+                } else if let Some(value) = flag.strip_prefix("threads=") {
+                    options.threads = value.parse().expect("Invalid number of threads!");
+                } else {
+                    match flag.as_ref() {
+                        "nocomponents" => options.components = false,
+                        "nodegrees" => options.degrees = false,
+                        "nodiameter" => options.diameter_searches = None,
+                        "noclustering" => options.clustering = false,
+                        "notop" => options.top_k = 0,
+                        _ => panic!("Unknown flag!")
+                    }
+                }
+            }
+            let report = graph.analysis_report(&options);
+            report.write_json(&mut std::io::stdout()).expect("Cannot write report!");
+        },
+        "rewire" => {
+            // takes the number of swaps, ten per edge by default, and a seed
+            let graph = graph::Graph::load(&mut reader, false);
+            let iterations = args.next().map_or(10 * graph.edges().len(), |s| s.parse().expect("Invalid number of swaps!"));
+            let seed = args.next().map_or(1, |s| s.parse().expect("Invalid seed!"));
+            let rewired = graph.rewire(iterations, seed);
+            println!("{}", rewired.summary());
+            println!("Same degrees: {}", rewired.degree_sequence() == graph.degree_sequence());
+            let common = rewired.edges().iter().filter(|&&(v, w)| graph.has_edge(v, w)).count();
+            println!("Edges kept: {} of {}", common, graph.edges().len());
+            println!("Triangles: {} before, {} after", graph.triangle_count(1), rewired.triangle_count(1));
+        },
+        "partite" => {
+            // takes the number of parts, 2 by default
+            let graph = graph::Graph::load(&mut reader, false);
+            let k = args.next().map_or(2, |s| s.parse().expect("Invalid number of parts!"));
+            match graph.k_coloring(k) {
+                Some(coloring) => {
+                    for v in graph.sorted_vertices() {
+                        println!("{}: {}", v, coloring[&v]);
+                    }
+                    println!("{}-partite", k);
+                },
+                None => println!("Not {}-partite", k)
+            }
+            println!("Bipartite: {}", graph.is_k_partite(2));
+            println!("Double cover: {}", graph.bipartite_double_cover().summary());
+        },
+        "coloring" => {
+            // takes the heuristic, dsatur by default or smallest-last, and prints the colors with
+            // the lower bounds on how many are needed
+            let graph = graph::Graph::load(&mut reader, false);
+            let bounds = match args.next().as_ref().map(|s| s.as_ref()) {
+                None | Some("dsatur") => graph.dsatur_coloring(),
+                Some("smallest-last") => graph.smallest_last_coloring(),
+                Some(_) => panic!("Unknown coloring heuristic!")
+            };
+            for v in graph.sorted_vertices() {
+                println!("{}: {}", v, bounds.coloring[&v]);
+            }
+            let proper = graph.edges().iter().all(|(v, w)| v == w || bounds.coloring[v] != bounds.coloring[w]);
+            println!("Colors: {} (proper: {})", bounds.colors, proper);
+            println!("Clique: {:?}", bounds.clique);
+            println!("Odd cycle: {}", bounds.odd_cycle);
+            println!("Clique cover bound: {}", bounds.cover_bound);
+            println!("Degeneracy: {}", bounds.degeneracy);
+            println!("Lower bound: {}, gap: {}", bounds.lower_bound, bounds.gap());
+            let order = graph.smallest_last_order();
+            println!("Greedy along smallest-last order: {} colors", graph.greedy_coloring(&order).values().map(|c| c + 1).max().unwrap_or(0));
+        },
+        "merge" => {
+            // takes a second graph file to merge into the graph, followed by "v=w" for every
+            // vertex v of the second graph that is the vertex w of the first, and "weighted"
+            // for loading the weights; without any the graphs are put side by side
+            let flags: Vec<String> = args.collect();
+            let weighted = flags.iter().any(|flag| flag == "weighted");
+            let load = |reader: &mut BufReader<&File>| if weighted { graph::Graph::load_weighted(reader, false) } else { graph::Graph::load(reader, false) };
+            let graph = load(&mut reader);
+            let file = File::open(flags.first().expect("Missing second graph file!")).expect("Cannot open file!");
+            let other = load(&mut BufReader::new(&file));
+            let mapping: HashMap<graph::Vertex, graph::Vertex> = flags.iter().filter_map(|flag| flag.split_once('=')).map(|(v, w)| {
+                (v.parse().expect("Invalid vertex!"), w.parse().expect("Invalid vertex!"))
+            }).collect();
+            let (merged, number) = if mapping.is_empty() { graph.disjoint_union(&other) } else { graph.merge_with_mapping(&other, &mapping) };
+            println!("Merged: {}", merged.summary());
+            for v in other.sorted_vertices() {
+                println!("{} -> {}", v, number[&v]);
+            }
+            print!("{}", merged);
+        },
+        "percomponent" => {
+            // takes the number of threads, 4 by default, and prints the size, triangles and
+            // diameter of every connected component, computed for the components in parallel
+            let graph = graph::Graph::load(&mut reader, false);
+            let threads = args.next().map_or(4, |s| s.parse().expect("Invalid number of threads!"));
+            let results = graph.per_component(threads, |component| {
+                (component.edges().len(), component.triangle_count(1), component.diameter())
+            });
+            for (component, (edges, triangles, diameter)) in results {
+                let smallest = component.iter().min().unwrap();
+                println!("{}: {} vertices, {} edges, {} triangles, diameter {}", smallest, component.len(), edges, triangles, diameter);
+            }
+        },
+        "fingerprint" => {
+            // takes a second graph file to compare the graph with
+            let graph = graph::Graph::load(&mut reader, false);
+            let fingerprint = graph.fingerprint();
+            println!("Fingerprint: {}", fingerprint);
+            if let Some(other) = args.next() {
+                let other = graph::Graph::load(&mut BufReader::new(File::open(other).expect("Cannot open file!")), false);
+                println!("Same fingerprint: {}", other.fingerprint() == fingerprint);
+            }
+            let labels = graph.component_labels();
+            let mut by_label: Vec<graph::Component> = vec![HashSet::new(); labels.iter().map(|label| label + 1).max().unwrap_or(0)];
+            for (v, label) in graph.sorted_vertices().into_iter().zip(labels) {
+                by_label[label].insert(v);
+            }
+            println!("Same components: {}", signature::same_partition(&graph.connected_components(), &by_label));
+            let vertices = graph.sorted_vertices();
+            if let (Some(first), Some(last)) = (vertices.first(), vertices.last()) {
+                let bfs = graph.find_shortest_path(*first, *last);
+                let dijkstra = graph.shortest_path(*first, *last).expect("Negative weights!").map(|found| found.path);
+                if let (Some(bfs), Some(dijkstra)) = (bfs, dijkstra) {
+                    println!("Equivalent shortest paths {} -> {}: {}", first, last, signature::equivalent_paths(&graph, &bfs, &dijkstra));
+                }
+            }
+        },
+        "rewrite" => {
+            // the directed graph is followed by its labels, and takes the file of rules, its
+            // first line holding their number, and the maximum number of steps
+            let graph = graph::Graph::load(&mut reader, true);
+            let labels = rewrite::load_labels(&mut reader);
+            let rules_file = File::open(args.next().expect("No rules file!")).expect("Cannot open rules file!");
+            let mut rules_reader = BufReader::new(&rules_file);
+            let rules: Vec<_> = (0..rules_reader.next_number()).map(|_| rewrite::RewriteRule::load(&mut rules_reader, true)).collect();
+            let max_steps = args.next().map_or(1000, |s| s.parse().expect("Invalid number of steps!"));
+            for (i, rule) in rules.iter().enumerate() {
+                let matches = graph.subgraph_matches(&rule.pattern, |p, v| {
+                    rule.pattern_labels.get(&p).is_none_or(|label| labels.get(&v) == Some(label))
+                }, max_steps);
+                println!("Rule {}: {} matches", i + 1, matches.len());
+            }
+            let rewriting = graph.rewrite(&labels, &rules, max_steps);
+            println!("{} steps, {}", rewriting.steps, if rewriting.fixpoint { "fixpoint reached" } else { "step limit reached" });
+            println!("{}", rewriting.graph.summary());
+            let mut edges: Vec<_> = rewriting.graph.edges().iter().cloned().collect();
+            edges.sort();
+            for (v, w) in edges {
+                let label = |v| rewriting.labels.get(&v).map_or("", |label: &String| label.as_str());
+                println!("{} {} -> {} {}", v, label(v), w, label(w));
+            }
+        },
+        "transform" => {
+            // takes the smallest weight of the edges to keep and a number to add to every
+            // vertex
+            let graph = graph::Graph::load_weighted(&mut reader, false);
+            let min_weight = args.next().map_or(0.0, |s| s.parse().expect("Invalid weight!"));
+            let offset: graph::Vertex = args.next().map_or(0, |s| s.parse().expect("Invalid offset!"));
+            let transformed = graph
+                .filter_map_edges(|_, weight| if weight >= min_weight { Some(weight) } else { None })
+                .map_vertices(|v| v.checked_add(offset).expect("No vertex numbers left!"));
+            println!("{}", transformed.summary());
+            print!("{}", transformed);
+        },
+        "hyper" => {
+            // the file holds a hypergraph, one hyperedge per line after the counts, and
+            // "normalized" weighs the clique expansion by the sizes of the hyperedges
+            let hypergraph = hypergraph::Hypergraph::load(&mut reader);
+            let normalized = args.next().is_some_and(|s| s == "normalized");
+            let degrees = hypergraph.degrees();
+            let largest = hypergraph.hyperedges().iter().map(|members| members.len()).max().unwrap_or(0);
+            println!("{} vertices, {} hyperedges of up to {} vertices, largest degree {}", hypergraph.vertices().len(),
+                     hypergraph.hyperedges().len(), largest, degrees.values().max().unwrap_or(&0));
+            let (incidence, first) = hypergraph.incidence_graph();
+            println!("Incidence graph: {}, hyperedges from {}", incidence.summary(), first);
+            let expansion = hypergraph.clique_expansion(normalized);
+            println!("Clique expansion: {}", expansion.summary());
+            print!("{}", expansion);
+        },
+        "project" => {
+            // takes the smallest and the largest vertex of the side of the bipartite graph to
+            // project onto, such as the users numbered before the items they picked
+            let graph = graph::Graph::load(&mut reader, false);
+            let low: graph::Vertex = args.next().expect("Missing smallest vertex!").parse().expect("Invalid vertex!");
+            let high: graph::Vertex = args.next().expect("Missing largest vertex!").parse().expect("Invalid vertex!");
+            let side = graph.vertices().iter().cloned().filter(|v| (low..=high).contains(v)).collect();
+            match graph.project_onto(&side) {
+                Ok(projection) => {
+                    println!("Projection: {}", projection.summary());
+                    print!("{}", projection);
+                },
+                Err(violation) => println!("Not bipartite along the sides: {}", violation)
+            }
+        },
+        "istree" => {
+            let graph = graph::Graph::load(&mut reader, false);
+            let checks = [("Tree", graph.is_tree()), ("Forest", graph.is_forest()), ("Connected", graph.is_connected())];
+            for (name, check) in &checks {
+                match check {
+                    Ok(()) => println!("{}: yes", name),
+                    Err(violation) => println!("{}: no, {}", name, violation)
+                }
+            }
+        },
+        "treeinfo" => {
+            let graph = graph::Graph::load(&mut reader, false);
+            match (graph.tree_diameter(), graph.tree_center(), graph.centroid_decomposition()) {
+                (Ok((length, path)), Ok(center), Ok(decomposition)) => {
+                    println!("Diameter: {} {:?}", length, path);
+                    println!("Center: {:?}", center);
+                    println!("Centroid roots: {:?}", decomposition.roots);
+                    for v in graph.sorted_vertices() {
+                        println!("{}: level {}, centroid ancestors {:?}", v, decomposition.level[&v], decomposition.ancestors(v));
+                    }
+                },
+                (Err(violation), _, _) | (_, Err(violation), _) | (_, _, Err(violation)) => println!("Forest: no, {}", violation)
+            }
+        },
+        "euler" => {
+            // the graph is followed by the root and a number of "u v" ancestor queries
+            let graph = graph::Graph::load(&mut reader, false);
+            let euler = graph.euler_tour(reader.next_number());
+            println!("Euler tour: {:?}", euler.tour);
+            println!("Depths: {:?}", euler.depth);
+            for _ in 0..reader.next_number() {
+                let (u, v) = reader.next_tuple();
+                println!("{} ancestor of {}: {} (subtree size {})", u, v, euler.is_ancestor(u, v), euler.subtree_size(u));
+            }
+        },
+        "lca" => {
+            // the graph is followed by the root and a number of "u v" queries
+            let graph = graph::Graph::load(&mut reader, false);
+            let index = graph.lca_index(reader.next_number());
+            for _ in 0..reader.next_number() {
+                let (u, v) = reader.next_tuple();
+                match (index.lca(u, v), index.distance_in_tree(u, v)) {
+                    (Some(lca), Some(distance)) => println!("LCA of {} and {}: {}, distance {}", u, v, lca, distance),
+                    _ => println!("{} and {} are not in the same tree", u, v)
+                }
+            }
+        },
+        "index" => {
+            // "chains" answers the queries with a chain decomposition index instead
+            let graph = graph::Graph::load(&mut reader, true);
+            let reachable: Box<dyn Fn(graph::Vertex, graph::Vertex) -> bool> = if args.next().is_some_and(|s| s == "chains") {
+                let index = graph.chain_index();
+                println!("{} chains, {} entries", index.chains(), index.entries());
+                Box::new(move |from, to| index.reachable(from, to))
+            } else {
+                let index = graph.reachability_index();
+                Box::new(move |from, to| index.reachable(from, to))
+            };
+            for _ in 0..reader.next_number() {
+                let (from, to) = reader.next_tuple();
+                println!("{} -> {}: {}", from, to, reachable(from, to));
+            }
+        },
+        "dynconn" => {
+            // the graph is followed by one operation per line: "+ u v" inserts an edge,
+            // "- u v" deletes an edge and "? u v" checks whether u and v are connected
+            let graph = graph::Graph::load(&mut reader, false);
+            let mut connectivity = graph.dynamic_connectivity();
+            for line in reader.lines() {
+                let line = line.expect("Cannot read operation!");
+                let parts: Vec<&str> = line.split_whitespace().collect();
+                if parts.len() != 3 {
+                    continue;
+                }
+                let u = parts[1].parse().expect("Invalid vertex!");
+                let v = parts[2].parse().expect("Invalid vertex!");
+                match parts[0] {
+                    "+" => connectivity.insert_edge(u, v),
+                    "-" => { connectivity.delete_edge(u, v); },
+                    "?" => println!("{} - {}: {}", u, v, connectivity.connected(u, v)),
+                    op => println!("Unknown operation: {}", op)
+                }
+            }
+            println!("Components: {}", connectivity.component_count());
+        },
+        "dynsp" => {
+            // takes the source, 1 by default, and "directed" for a directed graph, which is
+            // followed by one operation per line: "= u v w" sets the weight of an edge, adding
+            // it if need be, "- u v" removes an edge and "? v" asks for the distance of v
+            let source = args.next().map_or(1, |s| s.parse().expect("Invalid source!"));
+            let directed = args.next().is_some_and(|s| s == "directed");
+            let graph = graph::Graph::load_weighted(&mut reader, directed);
+            let mut paths = graph.dynamic_shortest_paths(source).expect("Negative weights!");
+            for line in reader.lines() {
+                let line = line.expect("Cannot read operation!");
+                let parts: Vec<&str> = line.split_whitespace().collect();
+                let vertex = |i: usize| parts[i].parse().expect("Invalid vertex!");
+                match parts[..] {
+                    ["=", _, _, weight] => match paths.set_weight(vertex(1), vertex(2), weight.parse().expect("Invalid weight!")) {
+                        Ok(changed) => println!("Changed: {:?}", changed),
+                        Err(error) => println!("{}", error)
+                    },
+                    ["-", _, _] => println!("Changed: {:?}", paths.remove_edge(vertex(1), vertex(2))),
+                    ["?", _] => match paths.distance(vertex(1)) {
+                        Some(distance) => println!("{} -> {}: {} {:?}", paths.source(), parts[1], distance, paths.path_to(vertex(1)).unwrap()),
+                        None => println!("{} -> {}: unreachable", paths.source(), parts[1])
+                    },
+                    _ => println!("Unknown operation: {}", line)
+                }
+            }
+        },
+        "cached" => {
+            // the directed graph is followed by one request per line: "c" counts the strongly
+            // connected components, "d u v" gives the distance from u to v, "n v k" the k
+            // vertices nearest to v, "+ u v [weight]" and "- u v" add and remove edges, "i"
+            // drops all cached results and "r" recomputes them
+            let graph = graph::Graph::load_weighted(&mut reader, true);
+            let mut cached = cache::CachedGraph::new(graph);
+            for line in reader.lines() {
+                let line = line.expect("Cannot read request!");
+                let parts: Vec<&str> = line.split_whitespace().collect();
+                let vertex = |i: usize| -> graph::Vertex { parts.get(i).expect("Missing vertex!").parse().expect("Invalid vertex!") };
+                match parts.first() {
+                    Some(&"c") => {
+                        let adj = cached.adjacencies();
+                        let isolated = adj.values().filter(|adjacent| adjacent.is_empty()).count();
+                        println!("Components: {}, vertices without successors: {}", cached.strongly_connected_components().len(), isolated);
+                    },
+                    Some(&"d") => match cached.distances(vertex(1)).get(&vertex(2)) {
+                        Some(distance) => println!("Distance: {}", distance),
+                        None => println!("Unreachable")
+                    },
+                    Some(&"n") => {
+                        let k = parts.get(2).expect("Missing number!").parse().expect("Invalid number!");
+                        println!("Nearest: {:?}", cached.k_nearest(vertex(1), k));
+                    },
+                    Some(&"+") => match parts.get(3) {
+                        Some(weight) => cached.add_weighted_edge(vertex(1), vertex(2), weight.parse().expect("Invalid weight!")),
+                        None => { cached.add_edge(vertex(1), vertex(2)); }
+                    },
+                    Some(&"-") => { cached.remove_edge(vertex(1), vertex(2)); },
+                    Some(&"i") => cached.invalidate(),
+                    Some(&"r") => cached.recompute(),
+                    Some(op) => println!("Unknown request: {}", op),
+                    None => ()
+                }
+            }
+            println!("Edges: {}, computations: {}", cached.graph().edges().len(), cached.computations());
+        },
+        "observe" => {
+            // the graph is followed by one change per line: "+ u v [weight]" adds an edge or
+            // changes its weight and "- u v" removes it; every change is printed by one
+            // observer, while the components are kept up to date from the returned changes
+            use observe::Observer;
+            let graph = graph::Graph::load_weighted(&mut reader, false);
+            let mut connectivity = graph.dynamic_connectivity();
+            let mut observed = observe::ObservedGraph::new(graph);
+            observed.observe(|change: &observe::Change| println!("{:?}", change));
+            for line in reader.lines() {
+                let line = line.expect("Cannot read change!");
+                let parts: Vec<&str> = line.split_whitespace().collect();
+                if parts.len() < 3 {
+                    continue;
+                }
+                let u = parts[1].parse().expect("Invalid vertex!");
+                let v = parts[2].parse().expect("Invalid vertex!");
+                let changes = match (parts[0], parts.get(3)) {
+                    ("+", Some(weight)) => observed.add_weighted_edge(u, v, weight.parse().expect("Invalid weight!")),
+                    ("+", None) => observed.add_edge(u, v),
+                    ("-", _) => observed.remove_edge(u, v),
+                    (op, _) => {
+                        println!("Unknown operation: {}", op);
+                        continue;
+                    }
+                };
+                for change in &changes {
+                    connectivity.changed(change);
+                }
+                println!("Components: {}", connectivity.component_count());
+            }
+            println!("Vertices: {}", observed.graph().vertices().len());
+            println!("Edges: {}", observed.into_graph().edges().len());
+        },
+        "sample" => {
+            // takes the number of edges to sample, a seed and "vertices" to sample as many
+            // vertices with the edges among them instead; the edges are sampled while they
+            // are read, without loading the graph
+            let k = args.next().map_or(100, |s| s.parse().expect("Invalid sample size!"));
+            let seed = args.next().map_or(1, |s| s.parse().expect("Invalid seed!"));
+            let (v, e) = reader.next_tuple();
+            let edges = (0..e).map(|_| reader.next_tuple());
+            let sample = if args.next().is_some_and(|s| s == "vertices") {
+                streaming::sample_vertices_with_induced(1..v+1, edges, k, seed, false)
+            } else {
+                streaming::sample_edges(edges, k, seed, false)
+            };
+            println!("{}", sample.summary());
+            print!("{}", sample);
+        },
+        "stream" => {
+            // the graph is followed by queries "u v", listing the size of u's component and
+            // whether v is in the same component
+            let (v, e) = reader.next_tuple();
+            let mut graph = streaming::StreamingGraph::new();
+            for vertex in 1..v+1 {
+                graph.add_vertex(vertex);
+            }
+            graph.extend((0..e).map(|_| reader.next_tuple()), |merge| {
+                println!("Edge {:?} merged components of sizes {} and {}", merge.edge, merge.sizes.0, merge.sizes.1);
+            });
+            println!("{} vertices, {} edges, {} components", graph.vertex_count(), graph.edge_count(), graph.component_count());
+            for line in reader.lines() {
+                let line = line.expect("Cannot read query!");
+                let query: Vec<graph::Vertex> = line.split_whitespace().map(|s| s.parse().expect("Invalid vertex!")).collect();
+                if query.len() == 2 {
+                    println!("{}: component of size {}, connected to {}: {}",
+                             query[0], graph.component_size(query[0]), query[1], graph.same_component(query[0], query[1]));
+                }
+            }
+        },
+        "maxflow" => {
+            // the directed graph of capacities is followed by source and target and then by
+            // lines "u v c" setting the capacity of the edge from u to v to c, after each of
+            // which the flow is augmented further; the algorithm is "edmonds-karp" (default),
+            // "scaling", "dinic", "push-relabel" or "auto"
+            let algorithm = match args.next().as_ref().map(|s| s.as_ref()) {
+                None | Some("edmonds-karp") => flow::FlowAlgorithm::EdmondsKarp,
+                Some("scaling") => flow::FlowAlgorithm::CapacityScaling,
+                Some("dinic") => flow::FlowAlgorithm::Dinic,
+                Some("push-relabel") => flow::FlowAlgorithm::PushRelabel,
+                Some("auto") => flow::FlowAlgorithm::Auto,
+                Some(_) => panic!("Unknown algorithm!")
+            };
+            let graph = graph::Graph::load_weighted(&mut reader, true);
+            let (s, t) = reader.next_tuple();
+            let (vertices, index) = graph.indexed_vertices();
+            let mut network = flow::FlowNetwork::new(vertices.len());
+            let mut edges: Vec<&graph::Edge> = graph.edges().iter().collect();
+            edges.sort();
+            let numbers: HashMap<graph::Edge, usize> = edges.into_iter()
+                .map(|edge| (*edge, network.add_edge(index[&edge.0], index[&edge.1], graph.weight(edge))))
+                .collect();
+            let (source, target) = (index[&s], index[&t]);
+            let report = |network: &flow::FlowNetwork| {
+                println!("Maximum flow {} -> {}: {}", s, t, network.flow_value(source));
+                for (from, to, capacity, flow) in network.flows() {
+                    println!("  {} -> {}: {} / {}", vertices[from], vertices[to], flow, capacity);
+                }
+                let residual: Vec<String> = network.residual_edges().into_iter()
+                    .map(|(from, to, capacity)| format!("{} -> {} ({})", vertices[from], vertices[to], capacity))
+                    .collect();
+                println!("  Residual edges: {}", residual.join(", "));
+                match network.augmenting_path(source, target) {
+                    Some(path) => println!("  Augmenting path left: {:?}", path.iter().map(|i| vertices[*i]).collect::<Vec<_>>()),
+                    None => println!("  No augmenting path left")
+                }
+            };
+            network.max_flow_with(source, target, algorithm);
+            report(&network);
+            for line in reader.lines() {
+                let line = line.expect("Cannot read change!");
+                let parts: Vec<&str> = line.split_whitespace().collect();
+                if parts.len() != 3 {
+                    continue;
+                }
+                let edge = (parts[0].parse().expect("Invalid vertex!"), parts[1].parse().expect("Invalid vertex!"));
+                match numbers.get(&edge) {
+                    Some(number) => {
+                        network.set_capacity(*number, parts[2].parse().expect("Invalid capacity!"));
+                        println!("Augmented by {}", network.max_flow_with(source, target, algorithm));
+                        report(&network);
+                    },
+                    None => println!("No edge {} -> {}", edge.0, edge.1)
+                }
+            }
+        },
+        "matching" => {
+            // "cardinality" asks for the heaviest of the matchings with the most edges
+            let graph = graph::Graph::load_weighted(&mut reader, false);
+            let max_cardinality = args.next().is_some_and(|s| s == "cardinality");
+            let (total, matching) = graph.maximum_weight_matching(max_cardinality);
+            for (v, w) in &matching {
+                println!("{} - {} ({})", v, w, graph.weight(&graph.edge_between(*v, *w)));
+            }
+            println!("Total weight: {}, {} edges", total, matching.len());
+            println!("Maximum matching: {} edges", graph.maximum_matching().len());
+        },
+        "assignment" => {
+            // the file holds a cost matrix, one row per line
+            let cost: Vec<Vec<graph::Weight>> = reader.lines()
+                .map(|line| line.expect("Cannot read row!"))
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| line.split_whitespace().map(|s| s.parse().expect("Invalid cost!")).collect())
+                .collect();
+            if cost.iter().any(|row| row.len() != cost[0].len()) {
+                panic!("Rows of different lengths!");
+            }
+            let (total, pairs) = matching::min_cost_assignment(&cost);
+            for (row, column) in pairs {
+                println!("{} -> {} ({})", row, column, cost[row][column]);
+            }
+            println!("Total cost: {}", total);
+        },
+        "stable" => {
+            // the numbers of proposers p and receivers r are followed by p and then r lines
+            // of preferences, listing the other side numbered from 1 in order of preference
+            // (an empty line finds no one acceptable)
+            let (p, r) = reader.next_tuple();
+            let mut lists: Vec<Vec<usize>> = reader.lines()
+                .take((p + r) as usize)
+                .map(|line| line.expect("Cannot read preferences!"))
+                .map(|line| line.split_whitespace().map(|s| s.parse::<usize>().expect("Invalid preference!") - 1).collect())
+                .collect();
+            if lists.len() != (p + r) as usize {
+                panic!("Expected {} preference lists!", p + r);
+            }
+            let receivers = lists.split_off(p as usize);
+            let pairs = matching::acceptable_pairs(&lists, &receivers);
+            println!("{} acceptable pairs", pairs.edges().len());
+            for (proposer, receiver) in matching::stable_matching(&lists, &receivers).into_iter().enumerate() {
+                match receiver {
+                    Some(receiver) => println!("{} - {}", proposer + 1, receiver + 1),
+                    None => println!("{} unmatched", proposer + 1)
+                }
+            }
+        },
+        "twosat" => {
+            // the numbers of variables and clauses are followed by the clauses "a b", with -i
+            // the negation of variable i
+            let (n, m) = reader.next_tuple();
+            let clauses: Vec<(two_sat::Literal, two_sat::Literal)> = reader.lines()
+                .take(m as usize)
+                .map(|line| {
+                    let line = line.expect("Cannot read clause!");
+                    let literals: Vec<two_sat::Literal> = line.split_whitespace().map(|s| s.parse().expect("Invalid literal!")).collect();
+                    if literals.len() != 2 {
+                        panic!("Clause without two literals!");
+                    }
+                    (literals[0], literals[1])
+                })
+                .collect();
+            let graph = two_sat::implication_graph(n as usize, &clauses);
+            println!("Implication graph: {}", graph.summary());
+            match two_sat::two_sat(n as usize, &clauses) {
+                Ok(values) => {
+                    let values: Vec<String> = values.iter().enumerate().map(|(i, value)| format!("x{}={}", i + 1, value)).collect();
+                    println!("Satisfiable: {}", values.join(" "));
+                },
+                Err(i) => println!("Unsatisfiable: x{} implies its negation and vice versa", i)
+            }
+        },
+        "temporal" => {
+            // the first line holds the number of connections c, followed by c connections
+            // "u v departure arrival" and queries "source target start"
+            let count: usize = reader.next_number() as usize;
+            let mut graph = temporal::TemporalGraph::new();
+            let mut lines = reader.lines().map(|line| line.expect("Cannot read line!"));
+            for line in lines.by_ref().take(count) {
+                let parts: Vec<&str> = line.split_whitespace().collect();
+                if parts.len() != 4 {
+                    panic!("Invalid connection!");
+                }
+                graph.add_connection(parts[0].parse().expect("Invalid vertex!"), parts[1].parse().expect("Invalid vertex!"),
+                                     parts[2].parse().expect("Invalid time!"), parts[3].parse().expect("Invalid time!"));
+            }
+            if graph.is_empty() {
+                println!("No connections");
+            } else {
+                println!("{} connections", graph.len());
+            }
+            for line in lines {
+                let parts: Vec<&str> = line.split_whitespace().collect();
+                if parts.len() != 3 {
+                    continue;
+                }
+                let (source, target) = (parts[0].parse().expect("Invalid vertex!"), parts[1].parse().expect("Invalid vertex!"));
+                let start = parts[2].parse().expect("Invalid time!");
+                let reachable = graph.earliest_arrivals(source, start).len();
+                match graph.earliest_arrival_path(source, target, start) {
+                    Some((time, journey)) => {
+                        println!("{} -> {} from {}: arrives at {}, {} vertices reachable", source, target, start, time, reachable);
+                        for c in journey {
+                            println!("  {} -> {}: {} - {}", c.from, c.to, c.departure, c.arrival);
+                        }
+                    },
+                    None => println!("{} -> {} from {}: unreachable", source, target, start)
+                }
+            }
+        },
+        "mst" => {
+            let graph = graph::Graph::load_weighted(&mut reader, false);
+            let tree = match args.next().as_ref().map(|s| s.as_ref()) {
+                None | Some("kruskal") => graph.minimum_spanning_tree(),
+                Some("prim") => graph.minimum_spanning_tree_prim(),
+                Some("boruvka") => {
+                    let threads = args.next().map_or(1, |s| s.parse().expect("Invalid number of threads!"));
+                    graph.minimum_spanning_tree_boruvka(threads)
+                },
+                Some(_) => panic!("Unknown algorithm!")
+            };
+            match (tree, graph.second_mst()) {
+                (Some((weight, edges)), second) => {
+                    println!("Minimum spanning tree: {} {:?}", weight, edges);
+                    match second {
+                        Some((weight, edges)) => println!("Second best spanning tree: {} {:?}", weight, edges),
+                        None => println!("No other spanning tree")
+                    }
+                },
+                (None, _) => println!("Graph is not connected")
+            }
+        },
+        "msf" => {
+            let graph = graph::Graph::load_weighted(&mut reader, false);
+            for (component, weight, edges) in graph.minimum_spanning_forest() {
+                let mut vertices: Vec<_> = component.into_iter().collect();
+                vertices.sort();
+                println!("Component {:?}: {} {:?}", vertices, weight, edges);
+            }
+        },
+        "clustering" => {
+            let graph = graph::Graph::load_weighted(&mut reader, false);
+            let k = args.next().map_or(2, |s| s.parse().expect("Invalid number of clusters!"));
+            let clustering = graph.clustering_max_spacing(k);
+            println!("Spacing: {}", clustering.spacing);
+            for v in graph.sorted_vertices() {
+                println!("{}: cluster {}", v, clustering.assignment[&v]);
+            }
+        },
+        "threshold" => {
+            // takes the threshold, the edges of at least whose weight are present, or "at-most"
+            // after it for those of at most it, and prints the components with the profile of
+            // the number of components over all thresholds
+            let graph = graph::Graph::load_weighted(&mut reader, false);
+            let threshold: graph::Weight = args.next().expect("Missing threshold!").parse().expect("Invalid threshold!");
+            let keep = if args.next().is_some_and(|s| s == "at-most") { threshold::Keep::AtMost } else { threshold::Keep::AtLeast };
+            let profile = graph.merge_profile(keep);
+            for component in graph.components_at_threshold(threshold, keep) {
+                let mut component: Vec<graph::Vertex> = component.into_iter().collect();
+                component.sort();
+                println!("{:?}", component);
+            }
+            println!("Components: {}", profile.component_count(threshold));
+            for (weight, components) in profile.steps() {
+                println!("At {}: {} components", weight, components);
+            }
+            for merge in profile.merges_at(threshold) {
+                println!("Merge {} and {} at {}", merge.left, merge.right, merge.threshold);
+            }
+        },
+        "steiner" => {
+            // the graph is followed by the number of terminals and the terminals
+            let graph = graph::Graph::load_weighted(&mut reader, false);
+            let terminals: Vec<_> = (0..reader.next_number()).map(|_| reader.next_number()).collect();
+            match graph.steiner_tree(&terminals) {
+                Some((weight, edges)) => println!("Steiner tree: {} {:?}", weight, edges),
+                None => println!("Terminals are not connected")
+            }
+        },
+        "spantrees" => {
+            let graph = graph::Graph::load(&mut reader, false);
+            match graph.count_spanning_trees() {
+                Some(count) => println!("Spanning trees: {}", count),
+                None => println!("Too many spanning trees to count exactly")
+            }
+        },
+        "tsp" => {
+            let graph = graph::Graph::load_weighted(&mut reader, false);
+            let tour = graph.tsp_nearest_neighbor();
+            println!("Nearest neighbour tour: {} {:?}", tour.0, tour.1);
+            let improved = graph.tsp_improve(&tour);
+            println!("Improved tour: {} {:?}", improved.0, improved.1);
+            if graph.vertices().len() <= tsp::HELD_KARP_LIMIT {
+                match graph.tsp_held_karp() {
+                    Some((length, tour)) => println!("Shortest tour: {} {:?}", length, tour),
+                    None => println!("No tour")
+                }
+            }
+        },
+        "chordal" => {
+            // prints a perfect elimination ordering with a maximum clique and the number of
+            // colors needed if the graph is chordal, and a chordless cycle otherwise
+            let graph = graph::Graph::load(&mut reader, false);
+            println!("Chordal: {}", graph.is_chordal());
+            match graph.perfect_elimination_ordering() {
+                Ok(order) => {
+                    println!("Perfect elimination ordering: {:?}", order);
+                    println!("Maximum clique: {:?}", graph.chordal_maximum_clique(&order).unwrap());
+                    let colors = graph.chordal_coloring(&order).unwrap();
+                    let proper = graph.edges().iter().all(|(v, w)| v == w || colors[v] != colors[w]);
+                    println!("Colors: {} ({})", colors.values().map(|c| c + 1).max().unwrap_or(0), if proper { "proper" } else { "improper" });
+                },
+                Err(cycle) => println!("Chordless cycle: {:?}", cycle)
+            }
+        },
+        "interval" => {
+            // prints intervals representing the graph if it is an interval graph, with a
+            // coloring by the fewest colors and a maximum clique
+            let graph = graph::Graph::load(&mut reader, false);
+            println!("Interval graph: {}", graph.is_interval_graph());
+            match graph.interval_representation() {
+                Some(intervals) => {
+                    let mut sorted: Vec<_> = intervals.iter().collect();
+                    sorted.sort();
+                    for (v, (first, last)) in sorted {
+                        println!("{}: [{}, {}]", v, first, last);
+                    }
+                    let represented = interval::interval_graph(&intervals);
+                    let edges = graph.edges().iter().filter(|edge| edge.0 != edge.1);
+                    let same = edges.clone().all(|edge| represented.has_edge(edge.0, edge.1))
+                        && represented.edges().len() == edges.map(|&(v, w)| (v.min(w), v.max(w))).collect::<std::collections::HashSet<_>>().len();
+                    println!("Representation confirmed: {}", same);
+                    let colors = interval::interval_coloring(&intervals);
+                    println!("Colors: {}", colors.values().map(|c| c + 1).max().unwrap_or(0));
+                    println!("Maximum clique: {:?}", interval::interval_maximum_clique(&intervals));
+                },
+                None => println!("No intervals")
+            }
+        },
+        "orient" => {
+            // takes the orientation "acyclic" along the ascending degree order, "eulerian" or
+            // "strong", the default, and prints the oriented graph
+            let graph = graph::Graph::load(&mut reader, false);
+            let oriented = match args.next().as_ref().map_or("strong", |s| s.as_ref()) {
+                "acyclic" => Ok(graph.orient_acyclic(&graph.degree_order(false))),
+                "eulerian" => graph.orient_eulerian(),
+                "strong" => graph.orient_strongly(),
+                _ => panic!("Unknown orientation!")
+            };
+            match oriented {
+                Ok(oriented) => {
+                    let strong = oriented.strongly_connected_components().iter().filter(|component| component.len() > 1).count();
+                    println!("{:?}", oriented);
+                    println!("Acyclic: {}, strongly connected components with edges: {}", oriented.topological_order().is_ok(), strong);
+                },
+                Err(error) => println!("No orientation: {}", error)
+            }
+        },
+        "euleriancircuit" => {
+            let graph = graph::Graph::load(&mut reader, false);
+            match graph.eulerian_circuit() {
+                Ok(circuit) => println!("Eulerian circuit: {:?}", circuit),
+                Err(error) => println!("No Eulerian circuit: {}", error)
+            }
+        },
+        "postman" => {
+            // "directed" loads a directed graph, whose edge directions the route ignores
+            let directed = args.next().is_some_and(|s| s == "directed");
+            let graph = graph::Graph::load_weighted(&mut reader, directed);
+            match graph.chinese_postman() {
+                Some((length, added, walk)) => {
+                    println!("Repeated edges: {:?}", added);
+                    println!("Route: {} {:?}", length, walk);
+                },
+                None => println!("Edges are not connected")
+            }
+        },
+        "tobinary" => {
+            // converts a text graph, weighted if the optional third argument is "weighted", into
+            // the binary format, written to the file given as second argument
+            let output = args.next().expect("Missing output file!");
+            let graph = match args.next().as_ref().map(|s| s.as_ref()) {
+                Some("weighted") => graph::Graph::load_weighted(&mut reader, false),
+                _ => graph::Graph::load(&mut reader, false)
+            };
+            let mut writer = std::io::BufWriter::new(File::create(output).expect("Cannot create file!"));
+            graph.write_binary(&mut writer).expect("Cannot write graph!");
+        },
+        "frombinary" => {
+            let graph = graph::Graph::read_binary(&mut reader).expect("Cannot read graph!");
+            println!("{}", graph.summary());
+            print!("{}", graph)
+        },
+        "same" => {
+            // compares the graph with the one in the file given as second argument
+            let other = args.next().expect("Missing second graph file!");
+            let mut other_reader = BufReader::new(File::open(other).expect("Cannot open file!"));
+            let graph = graph::Graph::load_weighted(&mut reader, false);
+            let other = graph::Graph::load_weighted(&mut other_reader, false);
+            let hash = |graph: &graph::Graph| {
+                let mut hasher = std::collections::hash_map::DefaultHasher::new();
+                std::hash::Hash::hash(graph, &mut hasher);
+                std::hash::Hasher::finish(&hasher)
+            };
+            println!("Equal: {}, same hash: {}", graph == other, hash(&graph) == hash(&other));
+        },
+        "stats" => {
+            let graph = graph::Graph::load(&mut reader, false);
+            print!("{}", graph.stats());
+        },
+        "diameter" => {
+            // iFUB stops after the given number of breadth first searches, if any
+            let graph = graph::Graph::load(&mut reader, false);
+            let max_searches = args.next().map_or(usize::MAX, |s| s.parse().expect("Invalid number of searches!"));
+            let report = |name: &str, bounds: diameter::Diameter| {
+                if bounds.is_exact() {
+                    println!("{}: {} ({} searches)", name, bounds.lower, bounds.searches);
+                } else {
+                    println!("{}: {}..{} ({} searches)", name, bounds.lower, bounds.upper, bounds.searches);
+                }
+            };
+            report("Double sweep", graph.diameter_double_sweep());
+            report("iFUB", graph.diameter_ifub(max_searches));
+            if graph.vertices().len() <= diameter::EXACT_DIAMETER_LIMIT {
+                println!("Exact: {}", graph.diameter());
+            }
+        },
+        "validate" => {
+            // self-loops and parallel edges are reported unless "lenient" is given
+            let graph = graph::Graph::load_weighted(&mut reader, false);
+            let lenient = args.next().is_some_and(|s| s == "lenient");
+            let policy = validation::ValidationPolicy { allow_self_loops: lenient, allow_parallel_edges: lenient };
+            let violations = graph.validate(&policy);
+            for violation in &violations {
+                println!("{}", violation);
+            }
+            println!("{} violations", violations.len());
+        },
+        "normalize" => {
+            let graph = graph::Graph::load_weighted(&mut reader, false);
+            let normalized = graph.normalize(args.next().is_some_and(|s| s == "keeploops"));
+            println!("{}", normalized.summary());
+            print!("{}", normalized)
+        },
+        "parse" => {
+            // any of "strict", "zerobased" and "weighted" may follow the file name
+            let flags: Vec<String> = args.collect();
+            let has = |flag: &str| flags.iter().any(|s| s == flag);
+            let options = parse::ParseOptions { strict: has("strict"), zero_based: has("zerobased"), ..Default::default() };
+            match graph::Graph::parse(&mut reader, false, has("weighted"), &options) {
+                Ok(graph) => {
+                    println!("{}", graph.summary());
+                    print!("{}", graph)
+                },
+                Err(error) => println!("{}", error)
+            }
+        },
+        "edgelist" => {
+            // loads a headerless edge list; "directed", "strict" and "weighted" may follow
+            let flags: Vec<String> = args.collect();
+            let has = |flag: &str| flags.iter().any(|s| s == flag);
+            let options = parse::ParseOptions { strict: has("strict"), ..Default::default() };
+            match graph::Graph::parse_edge_list(&mut reader, has("directed"), has("weighted"), &options) {
+                Ok(graph) => println!("{}", graph.summary()),
+                Err(error) => println!("{}", error)
+            }
+        },
+        "fromfile" => {
+            // loads the graph by path instead of through the already opened file, "weighted"
+            // with the weights and "mapped" by mapping the file into memory
+            let flags: Vec<String> = args.collect();
+            let has = |flag: &str| flags.iter().any(|s| s == flag);
+            let loaded = if has("mapped") {
+                graph::Graph::from_mapped_file(&filename, false, has("weighted"))
+            } else {
+                graph::Graph::from_file(&filename, false, has("weighted"))
+            };
+            match loaded {
+                Ok(graph) => println!("{}", graph.summary()),
+                Err(error) => println!("{}", error)
+            }
+        },
+        "csr" => {
+            // loads the graph into compressed sparse row form, reading the file twice
+            let weighted = args.next().is_some_and(|s| s == "weighted");
+            let graph = csr::CsrGraph::load(&mut reader, false, weighted).expect("Cannot read graph!");
+            println!("{} vertices, {} edges", graph.order(), graph.size());
+            for v in 1..graph.order() as graph::Vertex + 1 {
+                let neighbours: Vec<String> = graph.neighbours(v)
+                    .map(|(w, weight)| if weighted { format!(" {} ({})", w, weight) } else { format!(" {}", w) })
+                    .collect();
+                println!("{}:{}", v, neighbours.concat());
+            }
+        },
+        "tocsr" => {
+            // converts the graph into the CSR file given as second argument
+            let out = args.next().expect("Missing output file!");
+            let weighted = args.next().is_some_and(|s| s == "weighted");
+            let graph = csr::CsrGraph::load(&mut reader, false, weighted).expect("Cannot read graph!");
+            let mut writer = std::io::BufWriter::new(File::create(&out).expect("Cannot create file!"));
+            graph.write_file(&mut writer).expect("Cannot write graph!");
+            println!("Wrote {} vertices, {} edges to {}", graph.order(), graph.size(), out);
+        },
+        "mapcsr" => {
+            // maps a CSR file written by tocsr and lists the neighbours of every vertex
+            let graph = csr::MappedCsr::open(&filename).expect("Cannot map graph!");
+            println!("{} {} vertices, {} adjacency entries",
+                     if graph.is_directed() { "directed" } else { "undirected" }, graph.order(), graph.arc_count());
+            for v in 1..graph.order() as graph::Vertex + 1 {
+                let neighbours: Vec<String> = graph.neighbours(v).map(|(w, weight)| format!(" {} ({})", w, weight)).collect();
+                println!("{}:{}", v, neighbours.concat());
+            }
+        },
+        "compress" => {
+            // reports the size of the compressed adjacency lists and, given a file name as
+            // second argument, writes them there and checks that they read back unchanged
+            let graph = graph::Graph::load(&mut reader, false);
+            let mut compressed = graph.compress();
+            println!("{} vertices, {} adjacency entries in {} bytes ({:.2} bits per entry)", compressed.order(),
+                     compressed.arc_count(), compressed.encoded_size(),
+                     8.0 * compressed.encoded_size() as f64 / compressed.arc_count().max(1) as f64);
+            if let Some(out) = args.next() {
+                let mut writer = std::io::BufWriter::new(File::create(&out).expect("Cannot create file!"));
+                compressed.write(&mut writer).expect("Cannot write graph!");
+                drop(writer);
+                compressed = compressed::CompressedGraph::read(&mut File::open(&out).expect("Cannot open file!"))
+                    .expect("Cannot read graph!");
+                println!("Wrote {}", out);
+            }
+            let adj = graph.adjacencies();
+            let same = graph.sorted_vertices().iter().all(|v| {
+                let mut expected: Vec<graph::Vertex> = adj[v].iter().cloned().collect();
+                expected.sort();
+                compressed.neighbours(*v).eq(expected)
+            });
+            println!("{}", if same && compressed.decompress().adjacencies() == adj { "Round trip ok" } else { "Round trip failed" });
+        },
+        "memory" => {
+            // reports the memory used before and after releasing unused capacity
+            let mut graph = graph::Graph::load_weighted(&mut reader, false);
+            print!("{}", graph.memory_usage());
+            graph.shrink_to_fit();
+            println!("After shrinking: {} bytes", graph.memory_usage().total());
+        },
+        "reorder" => {
+            // the ordering "bfs", "rcm" (the default) or "degree" is followed by "descending"
+            // for the degree ordering to put the vertices of largest degree first
+            let graph = graph::Graph::load(&mut reader, false);
+            let order = match args.next().as_deref() {
+                Some("bfs") => graph.bfs_order(),
+                Some("degree") => graph.degree_order(args.next().is_some_and(|s| s == "descending")),
+                None | Some("rcm") => graph.reverse_cuthill_mckee(),
+                Some(other) => panic!("Unknown ordering {}!", other)
+            };
+            println!("Order: {:?}", order);
+            println!("Bandwidth: {} before, {} after", graph.bandwidth(&graph.sorted_vertices()), graph.bandwidth(&order));
+            print!("{}", graph.reordered(&order))
+        },
+        "relabel" => {
+            let graph = graph::Graph::load_weighted(&mut reader, false);
+            let (relabeled, dense, original) = graph.relabel_dense();
+            for v in &original {
+                println!("{} -> {}", v, dense[v]);
+            }
+            println!("{} vertices, {} of them only ends of edges", original.len(), original.len() - graph.vertices().len());
+            print!("{}", relabeled)
+        },
+        "labels" => {
+            // every edge is labeled with its weight, followed by commands "set u v label",
+            // "append u v text", "remove u v" and "get u v" changing and showing the labels
+            let graph = graph::Graph::load_weighted(&mut reader, false);
+            let mut labels = graph.edge_map_from(|_, weight| weight.to_string());
+            for line in reader.lines() {
+                let line = line.expect("Cannot read command!");
+                let words: Vec<&str> = line.split_whitespace().collect();
+                if words.len() < 3 {
+                    continue;
+                }
+                let v = words[1].parse().expect("Invalid vertex!");
+                let w = words[2].parse().expect("Invalid vertex!");
+                let text = words[3..].join(" ");
+                match words[0] {
+                    "set" => { labels.insert(v, w, text); },
+                    "append" => labels.get_mut(v, w).map_or((), |label| label.push_str(&text)),
+                    "remove" => { labels.remove(v, w); },
+                    _ => println!("{} - {}: {}", v, w, labels.get(v, w).map_or("none", |label| label.as_str()))
+                }
+            }
+            let mut labeled: Vec<_> = labels.iter().collect();
+            labeled.sort();
+            for ((v, w), label) in labeled {
+                println!("{} - {}: {}", v, w, label);
+            }
+            if labels.is_empty() {
+                println!("No labeled edges");
+            } else {
+                println!("{} labeled edges", labels.len());
+            }
+        },
+        "vertexmap" => {
+            // lists the component of every vertex and its distance from the source
+            let graph = graph::Graph::load(&mut reader, false);
+            let source = reader.next_number();
+            let components = graph.component_map();
+            let distances = graph.hop_distance_map(source);
+            for (v, component) in components.iter() {
+                match distances.get(v) {
+                    Some(distance) => println!("{}: component {}, distance {}", v, component, distance),
+                    None => println!("{}: component {}, unreachable", v, component)
+                }
+            }
+            if components.is_empty() {
+                println!("Empty graph");
+            } else {
+                println!("{} of {} vertices reachable", distances.len(), components.len());
+            }
+        },
+        "dimacs" => {
+            // the .gr file is followed by source and target, and optionally the .co file
+            let graph = graph::Graph::load_dimacs(reader).expect("Cannot read graph!");
+            let source = args.next().expect("Missing source!").parse().expect("Invalid vertex!");
+            let target = args.next().expect("Missing target!").parse().expect("Invalid vertex!");
+            println!("{}", graph.summary());
+            match graph.bidirectional_shortest_path(source, target) {
+                Some(found) => println!("Length {} with {} edges", found.distance, found.edges.len()),
+                None => println!("Unreachable")
+            }
+            if let Some(co) = args.next() {
+                let co = BufReader::new(File::open(co).expect("Cannot open file!"));
+                let coordinates = graph.load_dimacs_coordinates(co).expect("Cannot read coordinates!");
+                for v in [source, target].iter() {
+                    match coordinates.get(*v) {
+                        Some((x, y)) => println!("{} at {} {}", v, x, y),
+                        None => println!("{} has no coordinates", v)
+                    }
+                }
+            }
+        },
+        "mtx" => {
+            // reads a Matrix Market file and optionally writes it back to the file given as
+            // second argument
+            let graph = graph::Graph::load_matrix_market(reader).expect("Cannot read matrix!");
+            println!("{}", graph.summary());
+            if let Some(out) = args.next() {
+                let mut writer = std::io::BufWriter::new(File::create(&out).expect("Cannot create file!"));
+                graph.write_matrix_market(&mut writer).expect("Cannot write matrix!");
+                println!("Wrote {}", out);
+            }
+        },
+        "concurrent" => {
+            // the graph is followed by queries "u v", answered by several threads sharing
+            // one view of the graph; the answers are listed in the order of the queries
+            let graph = graph::Graph::load_weighted(&mut reader, false);
+            let threads: usize = args.next().map_or(4, |s| s.parse().expect("Invalid number of threads!"));
+            let queries: Vec<(graph::Vertex, graph::Vertex)> = reader.lines()
+                .map(|line| line.expect("Cannot read query!"))
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| {
+                    let mut query = line.split_whitespace().map(|s| s.parse().expect("Invalid vertex!"));
+                    (query.next().expect("Missing vertex!"), query.next().expect("Missing vertex!"))
+                })
+                .collect();
+            let view = graph.view();
+            let chunk = queries.len().div_ceil(threads.max(1)).max(1);
+            let answers: Vec<String> = std::thread::scope(|scope| {
+                let workers: Vec<_> = queries.chunks(chunk)
+                    .map(|queries| {
+                        let view = view.clone();
+                        scope.spawn(move || queries.iter().map(|&(u, v)| {
+                            if !view.is_reachable(u, v) {
+                                return format!("{} -> {}: unreachable", u, v);
+                            }
+                            let found = view.shortest_path(u, v).unwrap();
+                            format!("{} -> {}: {} {:?}", u, v, found.distance, found.path)
+                        }).collect::<Vec<String>>())
+                    })
+                    .collect();
+                workers.into_iter().flat_map(|worker| worker.join().unwrap()).collect()
+            });
+            println!("{}", view.graph().summary());
+            for answer in answers {
+                println!("{}", answer);
+            }
+        },
+        "snapshot" => {
+            // the graph is followed by changes "+ u v [weight]" and "- u v", applied to a
+            // thawed copy while another thread keeps analyzing the frozen original
+            let graph = graph::Graph::load_weighted(&mut reader, false).freeze();
+            let changes: Vec<String> = reader.lines().map(|line| line.expect("Cannot read change!")).collect();
+            let analysis = graph.clone();
+            let (before, after) = std::thread::scope(|scope| {
+                let before = scope.spawn(move || analysis.graph().summary());
+                let mut changed = graph.thaw();
+                for change in &changes {
+                    let words: Vec<&str> = change.split_whitespace().collect();
+                    if words.len() < 3 {
+                        continue;
+                    }
+                    let (v, w) = (words[1].parse().expect("Invalid vertex!"), words[2].parse().expect("Invalid vertex!"));
+                    match (words[0], words.get(3)) {
+                        ("+", Some(weight)) => changed.add_weighted_edge(v, w, weight.parse().expect("Invalid weight!")),
+                        ("+", None) => { changed.add_edge(v, w); },
+                        _ => { changed.remove_edge(v, w); }
+                    }
+                }
+                (before.join().unwrap(), changed.freeze().graph().summary())
+            });
+            println!("Before: {}", before);
+            println!("After: {}", after);
+        },
+        "disk" => {
+            // builds the graph in the directory given as second argument, which must exist,
+            // with the given number of vertices per chunk and of chunks cached in memory
+            let directory = args.next().expect("Missing directory!");
+            let chunk_size = args.next().map_or(1024, |s| s.parse().expect("Invalid chunk size!"));
+            let cache_chunks = args.next().map_or(16, |s| s.parse().expect("Invalid cache size!"));
+            let graph = disk::DiskGraph::build(&mut reader, std::path::Path::new(&directory), false, chunk_size, cache_chunks)
+                .expect("Cannot build graph!");
+            let max_degree = (1..graph.order() as graph::Vertex + 1).map(|v| graph.degree(v)).max().unwrap_or(0);
+            println!("{} vertices, {} adjacency entries, maximum degree {}", graph.order(), graph.arc_count(), max_degree);
+            let components = graph.component_map().expect("Cannot read graph!");
+            println!("{} connected components", components.iter().map(|(_, c)| *c + 1).max().unwrap_or(0));
+            if graph.order() > 0 {
+                let distances = graph.bfs_distances(1).expect("Cannot read graph!");
+                let farthest = distances.iter().map(|(_, d)| *d).max().unwrap_or(0);
+                println!("{} vertices reachable from 1, the farthest at distance {}", distances.len(), farthest);
+            }
+            println!("{} chunks read", graph.chunk_loads());
+        },
+        "svg" => {
+            // draws the graph into the SVG file given as second argument, optionally colored
+            // by "components" or "communities", after the given number of layout iterations
+            let out = args.next().expect("Missing output file!");
+            let coloring = match args.next().as_ref().map(|s| s.as_ref()) {
+                None | Some("uniform") => viz::Coloring::Uniform,
+                Some("components") => viz::Coloring::Components,
+                Some("communities") => viz::Coloring::Communities(1),
+                Some(_) => panic!("Unknown coloring!")
+            };
+            let iterations = args.next().map_or(200, |s| s.parse().expect("Invalid number of iterations!"));
+            let graph = graph::Graph::load(&mut reader, false);
+            let layout = graph.layout(iterations, 1);
+            let mut writer = std::io::BufWriter::new(File::create(&out).expect("Cannot create file!"));
+            graph.write_svg(&mut writer, &layout, &coloring).expect("Cannot write drawing!");
+            println!("Wrote {}", out);
+        },
+        "gexf" => {
+            // writes the graph into the GEXF file given as second argument, with the component
+            // and the PageRank of every vertex as attributes
+            let out = args.next().expect("Missing output file!");
+            let graph = graph::Graph::load(&mut reader, false);
+            let components = graph.component_map();
+            let mut pagerank = graph.vertex_map();
+            for (v, rank) in graph.pagerank(0.85, 100, 1) {
+                pagerank.set(v, rank);
+            }
+            let columns = [("component", gexf::Column::Integer(&components)), ("pagerank", gexf::Column::Double(&pagerank))];
+            let mut writer = std::io::BufWriter::new(File::create(&out).expect("Cannot create file!"));
+            graph.write_gexf(&mut writer, &columns).expect("Cannot write graph!");
+            println!("Wrote {}", out);
+        },
+        "ascii" => {
+            // prints the adjacency "matrix", a "drawing" of the given number of columns and
+            // rows, or both
+            let graph = graph::Graph::load(&mut reader, false);
+            let mode = args.next();
+            if mode.as_ref().is_none_or(|mode| mode == "matrix") {
+                print!("{}", graph.adjacency_matrix_text());
+            }
+            if mode.as_ref().is_none_or(|mode| mode == "drawing") {
+                let columns = args.next().map_or(60, |s| s.parse().expect("Invalid number of columns!"));
+                let rows = args.next().map_or(20, |s| s.parse().expect("Invalid number of rows!"));
+                print!("{}", graph.text_drawing(&graph.layout(200, 1), columns, rows));
+            }
         },
         "print" => {
             let graph = graph::Graph::load(&mut reader, false);
-            println!("{:?}", graph)
+            println!("{}", graph.summary());
+            print!("{}", graph)
         },
         _ => println!("Unknown command: {}", command)
     }
 }
+
+// parses the flags following the arguments of a long-running command: "progress" reports its
+// progress on standard error, "timeout=<milliseconds>" stops it after that time or once a
+// line is entered on standard input; returns whether to report and the cancellation if any
+fn run_flags<I: Iterator<Item = String>>(flags: I) -> (bool, Option<progress::Cancellation>) {
+    let (mut report, mut cancellation) = (false, None);
+    for flag in flags {
+        if flag == "progress" {
+            report = true;
+        } else if let Some(timeout) = flag.strip_prefix("timeout=") {
+            let timeout = std::time::Duration::from_millis(timeout.parse().expect("Invalid timeout!"));
+            let token = progress::Cancellation::with_timeout(timeout);
+            let stopper = token.clone();
+            std::thread::spawn(move || {
+                let mut line = String::new();
+                if std::io::stdin().read_line(&mut line).is_ok_and(|read| read > 0) {
+                    stopper.cancel();
+                }
+            });
+            cancellation = Some(token);
+        } else {
+            panic!("Unknown flag!");
+        }
+    }
+    (report, cancellation)
+}
+
+// the result of a cancellable computation, or its partial result if it was interrupted,
+// which is reported on standard error
+fn partial<T>(result: Result<T, progress::Interrupted<T>>) -> Option<T> {
+    match result {
+        Ok(result) => Some(result),
+        Err(interrupted) => {
+            eprintln!("{}", interrupted);
+            interrupted.partial
+        }
+    }
+}
@@ -0,0 +1,678 @@
+use std::collections::VecDeque;
+
+use graph::{Edge, Graph, HashMap, HashSet, Vertex, Weight};
+
+// solves the assignment problem for the cost matrix with the Hungarian algorithm (Kuhn and
+// Munkres) in O(n^2 m) time for n rows and m >= n columns: the rows are added one at a
+// time, each by a Dijkstra-like search for a cheapest augmenting path with respect to the
+// reduced costs, after which the potentials of rows and columns are updated so that all
+// reduced costs stay non-negative; for more rows than columns the matrix is transposed,
+// so every row or every column, whichever there are fewer of, is assigned
+// returns the total cost and the assigned pairs (row, column) in order of rows
+pub fn min_cost_assignment(cost: &[Vec<Weight>]) -> (Weight, Vec<(usize, usize)>) {
+    let n = cost.len();
+    let m = cost.first().map_or(0, |row| row.len());
+    if n > m {
+        let transposed: Vec<Vec<Weight>> = (0..m).map(|j| (0..n).map(|i| cost[i][j]).collect()).collect();
+        let (total, pairs) = min_cost_assignment(&transposed);
+        let mut pairs: Vec<(usize, usize)> = pairs.into_iter().map(|(j, i)| (i, j)).collect();
+        pairs.sort();
+        return (total, pairs);
+    }
+
+    // rows and columns are numbered from 1 here, column 0 holding the row being added;
+    // row_of[j] is the row assigned to column j, or 0
+    let mut u = vec![0.0; n + 1];
+    let mut v = vec![0.0; m + 1];
+    let mut row_of = vec![0; m + 1];
+    let mut way = vec![0; m + 1];
+    for i in 1..n + 1 {
+        row_of[0] = i;
+        let mut j0 = 0;
+        let mut min = vec![Weight::INFINITY; m + 1];
+        let mut used = vec![false; m + 1];
+        loop {
+            used[j0] = true;
+            let i0 = row_of[j0];
+            let mut delta = Weight::INFINITY;
+            let mut j1 = 0;
+            for j in 1..m + 1 {
+                if !used[j] {
+                    let reduced = cost[i0 - 1][j - 1] - u[i0] - v[j];
+                    if reduced < min[j] {
+                        min[j] = reduced;
+                        way[j] = j0;
+                    }
+                    if min[j] < delta {
+                        delta = min[j];
+                        j1 = j;
+                    }
+                }
+            }
+            for j in 0..m + 1 {
+                if used[j] {
+                    u[row_of[j]] += delta;
+                    v[j] -= delta;
+                } else {
+                    min[j] -= delta;
+                }
+            }
+            j0 = j1;
+            if row_of[j0] == 0 {
+                break;
+            }
+        }
+        // flip the assignments along the augmenting path
+        while j0 != 0 {
+            let j1 = way[j0];
+            row_of[j0] = row_of[j1];
+            j0 = j1;
+        }
+    }
+
+    let mut pairs: Vec<(usize, usize)> = (1..m + 1)
+        .filter(|j| row_of[*j] != 0)
+        .map(|j| (row_of[j] - 1, j - 1))
+        .collect();
+    pairs.sort();
+    let total = pairs.iter().map(|&(i, j)| cost[i][j]).sum();
+    (total, pairs)
+}
+
+// finds the proposer-optimal stable matching for the preference lists with the algorithm of
+// Gale and Shapley: each free proposer proposes to the next receiver on its list, who holds
+// on to the best proposal so far and rejects the others; proposers and receivers are
+// numbered from 0, each list ranks the other side from most to least preferred, and a pair
+// can only be matched if each is on the other's list, so lists may be incomplete and the
+// two sides of different sizes, as in residency matching
+// returns for every proposer the receiver it is matched to, if any
+pub fn stable_matching(proposers: &[Vec<usize>], receivers: &[Vec<usize>]) -> Vec<Option<usize>> {
+    // rank[r][p] is the position of proposer p on the list of receiver r
+    let rank: Vec<Vec<Option<usize>>> = receivers.iter()
+        .map(|list| {
+            let mut rank = vec![None; proposers.len()];
+            for (position, p) in list.iter().enumerate() {
+                rank[*p] = Some(position);
+            }
+            rank
+        })
+        .collect();
+    let mut next = vec![0; proposers.len()];
+    let mut held: Vec<Option<usize>> = vec![None; receivers.len()];
+    let mut free: VecDeque<usize> = (0..proposers.len()).collect();
+    while let Some(p) = free.pop_front() {
+        let r = match proposers[p].get(next[p]) {
+            Some(r) => *r,
+            None => continue
+        };
+        next[p] += 1;
+        match (rank[r][p], held[r]) {
+            (None, _) => free.push_back(p),
+            (Some(_), None) => held[r] = Some(p),
+            (Some(position), Some(q)) => {
+                if position < rank[r][q].unwrap() {
+                    held[r] = Some(p);
+                    free.push_back(q);
+                } else {
+                    free.push_back(p);
+                }
+            }
+        }
+    }
+    let mut matched = vec![None; proposers.len()];
+    for (r, p) in held.iter().enumerate() {
+        if let Some(p) = p {
+            matched[*p] = Some(r);
+        }
+    }
+    matched
+}
+
+// the bipartite graph of the mutually acceptable pairs of the preference lists of
+// stable_matching, with the proposers as the vertices 1..p and the receivers as p+1..p+r
+pub fn acceptable_pairs(proposers: &[Vec<usize>], receivers: &[Vec<usize>]) -> Graph {
+    let p = proposers.len();
+    let vertices = (1..(p + receivers.len()) as Vertex + 1).collect();
+    let wanted: HashSet<(usize, usize)> = receivers.iter().enumerate()
+        .flat_map(|(r, list)| list.iter().map(move |p| (*p, r)))
+        .collect();
+    let edges = proposers.iter().enumerate()
+        .flat_map(|(i, list)| list.iter().map(move |r| (i, *r)))
+        .filter(|pair| wanted.contains(pair))
+        .map(|(i, r)| ((i + 1) as Vertex, (p + r + 1) as Vertex))
+        .collect();
+    Graph::new(vertices, edges, false)
+}
+
+impl Graph {
+
+    // finds a matching of maximum total weight in a graph that need not be bipartite, with
+    // the blossom algorithm of Edmonds in the O(n^3) primal-dual form of Galil, following
+    // the implementation of Van Rantwijk: starting from free vertices, alternating trees
+    // are grown along the edges that are tight with respect to the dual variables, odd
+    // cycles are shrunk into blossoms, and the duals are adjusted whenever no tight edge is
+    // left to grow on. Edge directions, self-loops and edges of NaN weight are ignored,
+    // only the heaviest of parallel edges counts, and edges of negative weight are only
+    // matched to get more edges; with max_cardinality the matching is the heaviest among
+    // those with the most edges. The dual variables are halved along the way, so the
+    // result is only guaranteed exact for integer weights
+    // returns the total weight and the matched edges, smaller vertex first, in order
+    pub fn maximum_weight_matching(&self, max_cardinality: bool) -> (Weight, Vec<Edge>) {
+        let (vertices, index) = self.indexed_vertices();
+        let mut heaviest: HashMap<(usize, usize), Weight> = HashMap::new();
+        for edge in self.edges().iter().filter(|&&(v, w)| v != w && !self.weight(&(v, w)).is_nan()) {
+            let (i, j) = (index[&edge.0], index[&edge.1]);
+            let weight = heaviest.entry((i.min(j), i.max(j))).or_insert(Weight::NEG_INFINITY);
+            *weight = weight.max(self.weight(edge));
+        }
+        let mut edges: Vec<(usize, usize, Weight)> = heaviest.into_iter().map(|((i, j), weight)| (i, j, weight)).collect();
+        edges.sort_by(|a, b| (a.0, a.1).cmp(&(b.0, b.1)).then(a.2.total_cmp(&b.2)));
+
+        let mate = Blossoms::new(vertices.len(), &edges, max_cardinality).solve();
+        let pairs: HashSet<(usize, usize)> = (0..vertices.len())
+            .filter(|v| mate[*v] != NONE && *v < mate[*v])
+            .map(|v| (v, mate[v]))
+            .collect();
+        let total = edges.iter().filter(|&&(i, j, _)| pairs.contains(&(i, j))).map(|edge| edge.2).sum();
+        let mut matching: Vec<Edge> = pairs.into_iter().map(|(i, j)| (vertices[i], vertices[j])).collect();
+        matching.sort();
+        (total, matching)
+    }
+
+    // finds a matching with the most edges in a graph that need not be bipartite, as the
+    // maximum weight matching of the graph with all weights 1
+    pub fn maximum_matching(&self) -> Vec<Edge> {
+        let unweighted = Graph::new(self.vertices().clone(), self.edges().clone(), self.is_directed());
+        unweighted.maximum_weight_matching(true).1
+    }
+}
+
+// marks the absence of a vertex, edge, endpoint or blossom
+const NONE: usize = usize::MAX;
+
+// the state of the weighted blossom algorithm on n vertices, numbered from 0, where the
+// blossoms get the numbers n..2n and the endpoints of edge k are numbered 2k and 2k + 1
+struct Blossoms<'a> {
+    edges: &'a [(usize, usize, Weight)],
+    n: usize,
+    max_cardinality: bool,
+    // the vertex of every endpoint, and the remote endpoints of the edges of every vertex
+    endpoint: Vec<usize>,
+    neighbours: Vec<Vec<usize>>,
+    // the remote endpoint of the matched edge of every vertex
+    mate: Vec<usize>,
+    // the label of every top-level blossom and vertex: 0 for none, 1 for an outer (S) and 2
+    // for an inner (T) one, with 5 temporarily marking outer ones during scan_blossom; and
+    // the remote endpoint of the edge through which the label was assigned
+    label: Vec<u8>,
+    label_end: Vec<usize>,
+    // the top-level blossom every vertex is in
+    in_blossom: Vec<usize>,
+    blossom_parent: Vec<usize>,
+    // the sub-blossoms of every blossom in order around its cycle, starting at the base,
+    // with the endpoints of the edges joining each sub-blossom to the next
+    blossom_children: Vec<Vec<usize>>,
+    blossom_endpoints: Vec<Vec<usize>>,
+    blossom_base: Vec<usize>,
+    // for every vertex and top-level blossom not labeled inner, the edge of least slack to
+    // another outer blossom, and for outer blossoms such edges to every other outer blossom
+    best_edge: Vec<usize>,
+    blossom_best_edges: Vec<Option<Vec<usize>>>,
+    unused_blossoms: Vec<usize>,
+    // the dual variables of the vertices followed by those of the blossoms
+    dual: Vec<Weight>,
+    // the edges known to be tight, which the trees may grow along
+    allowed: Vec<bool>,
+    // the outer vertices whose edges are still to be scanned
+    queue: Vec<usize>
+}
+
+impl<'a> Blossoms<'a> {
+
+    fn new(n: usize, edges: &'a [(usize, usize, Weight)], max_cardinality: bool) -> Blossoms<'a> {
+        let max_weight = edges.iter().map(|edge| edge.2).fold(0.0, Weight::max);
+        let mut neighbours = vec![vec![]; n];
+        for (k, &(i, j, _)) in edges.iter().enumerate() {
+            neighbours[i].push(2 * k + 1);
+            neighbours[j].push(2 * k);
+        }
+        Blossoms {
+            edges,
+            n,
+            max_cardinality,
+            endpoint: edges.iter().flat_map(|&(i, j, _)| [i, j]).collect(),
+            neighbours,
+            mate: vec![NONE; n],
+            label: vec![0; 2 * n],
+            label_end: vec![NONE; 2 * n],
+            in_blossom: (0..n).collect(),
+            blossom_parent: vec![NONE; 2 * n],
+            blossom_children: vec![vec![]; 2 * n],
+            blossom_endpoints: vec![vec![]; 2 * n],
+            blossom_base: (0..n).chain((0..n).map(|_| NONE)).collect(),
+            best_edge: vec![NONE; 2 * n],
+            blossom_best_edges: vec![None; 2 * n],
+            unused_blossoms: (n..2 * n).collect(),
+            dual: (0..2 * n).map(|i| if i < n { max_weight } else { 0.0 }).collect(),
+            allowed: vec![false; edges.len()],
+            queue: vec![]
+        }
+    }
+
+    // runs one stage per augmentation, returning the vertex every vertex is matched to
+    fn solve(mut self) -> Vec<usize> {
+        let n = self.n;
+        for _ in 0..n {
+            self.label = vec![0; 2 * n];
+            self.best_edge = vec![NONE; 2 * n];
+            for b in n..2 * n {
+                self.blossom_best_edges[b] = None;
+            }
+            self.allowed = vec![false; self.edges.len()];
+            self.queue.clear();
+            for v in 0..n {
+                if self.mate[v] == NONE && self.label[self.in_blossom[v]] == 0 {
+                    self.assign_label(v, 1, NONE);
+                }
+            }
+            if !self.augment() {
+                break;
+            }
+            // blossoms with a dual of 0 are no longer needed
+            for b in n..2 * n {
+                if self.blossom_parent[b] == NONE && self.blossom_base[b] != NONE && self.label[b] == 1 && self.dual[b] == 0.0 {
+                    self.expand_blossom(b, true);
+                }
+            }
+        }
+        (0..n).map(|v| if self.mate[v] == NONE { NONE } else { self.endpoint[self.mate[v]] }).collect()
+    }
+
+    // grows the trees and adjusts the duals until the matching is augmented, returning
+    // false if it is already optimal
+    fn augment(&mut self) -> bool {
+        let n = self.n;
+        loop {
+            while let Some(v) = self.queue.pop() {
+                for p in self.neighbours[v].clone() {
+                    let k = p / 2;
+                    let w = self.endpoint[p];
+                    if self.in_blossom[v] == self.in_blossom[w] {
+                        continue;
+                    }
+                    let mut slack = 0.0;
+                    if !self.allowed[k] {
+                        slack = self.slack(k);
+                        if slack <= 0.0 {
+                            self.allowed[k] = true;
+                        }
+                    }
+                    if self.allowed[k] {
+                        if self.label[self.in_blossom[w]] == 0 {
+                            self.assign_label(w, 2, p ^ 1);
+                        } else if self.label[self.in_blossom[w]] == 1 {
+                            // either a new blossom or an augmenting path between two trees
+                            let base = self.scan_blossom(v, w);
+                            if base != NONE {
+                                self.add_blossom(base, k);
+                            } else {
+                                self.augment_matching(k);
+                                return true;
+                            }
+                        } else if self.label[w] == 0 {
+                            // w is in an inner blossom but not yet labeled itself
+                            self.label[w] = 2;
+                            self.label_end[w] = p ^ 1;
+                        }
+                    } else if self.label[self.in_blossom[w]] == 1 {
+                        let b = self.in_blossom[v];
+                        if self.best_edge[b] == NONE || slack < self.slack(self.best_edge[b]) {
+                            self.best_edge[b] = k;
+                        }
+                    } else if self.label[w] == 0 && (self.best_edge[w] == NONE || slack < self.slack(self.best_edge[w])) {
+                        self.best_edge[w] = k;
+                    }
+                }
+            }
+
+            // no tight edge is left, so change the duals by the largest delta that keeps them
+            // feasible: making a vertex dual 0 (1), an edge from an outer blossom to a free
+            // vertex (2) or between outer blossoms (3) tight, or an inner blossom dual 0 (4)
+            let (mut kind, mut delta, mut delta_edge, mut delta_blossom) = (0, 0.0, NONE, NONE);
+            if !self.max_cardinality {
+                kind = 1;
+                delta = self.dual[..n].iter().cloned().fold(Weight::INFINITY, Weight::min);
+            }
+            for v in 0..n {
+                if self.label[self.in_blossom[v]] == 0 && self.best_edge[v] != NONE {
+                    let d = self.slack(self.best_edge[v]);
+                    if kind == 0 || d < delta {
+                        (kind, delta, delta_edge) = (2, d, self.best_edge[v]);
+                    }
+                }
+            }
+            for b in 0..2 * n {
+                if self.blossom_parent[b] == NONE && self.label[b] == 1 && self.best_edge[b] != NONE {
+                    let d = self.slack(self.best_edge[b]) / 2.0;
+                    if kind == 0 || d < delta {
+                        (kind, delta, delta_edge) = (3, d, self.best_edge[b]);
+                    }
+                }
+            }
+            for b in n..2 * n {
+                if self.blossom_base[b] != NONE && self.blossom_parent[b] == NONE && self.label[b] == 2
+                    && (kind == 0 || self.dual[b] < delta) {
+                    (kind, delta, delta_blossom) = (4, self.dual[b], b);
+                }
+            }
+            if kind == 0 {
+                // only possible with max_cardinality: no further improvement can be made
+                kind = 1;
+                delta = self.dual[..n].iter().cloned().fold(Weight::INFINITY, Weight::min).max(0.0);
+            }
+
+            for v in 0..n {
+                match self.label[self.in_blossom[v]] {
+                    1 => self.dual[v] -= delta,
+                    2 => self.dual[v] += delta,
+                    _ => ()
+                }
+            }
+            for b in n..2 * n {
+                if self.blossom_base[b] != NONE && self.blossom_parent[b] == NONE {
+                    match self.label[b] {
+                        1 => self.dual[b] += delta,
+                        2 => self.dual[b] -= delta,
+                        _ => ()
+                    }
+                }
+            }
+            match kind {
+                1 => return false,
+                2 | 3 => {
+                    self.allowed[delta_edge] = true;
+                    let (i, j, _) = self.edges[delta_edge];
+                    self.queue.push(if self.label[self.in_blossom[i]] == 0 { j } else { i });
+                },
+                _ => self.expand_blossom(delta_blossom, false)
+            }
+        }
+    }
+
+    fn slack(&self, k: usize) -> Weight {
+        let (i, j, weight) = self.edges[k];
+        self.dual[i] + self.dual[j] - 2.0 * weight
+    }
+
+    // the vertices in the blossom, which may be a single vertex
+    fn leaves(&self, b: usize) -> Vec<usize> {
+        if b < self.n {
+            return vec![b];
+        }
+        self.blossom_children[b].iter().flat_map(|child| self.leaves(*child)).collect()
+    }
+
+    // labels the top-level blossom containing w, reached through endpoint p, as outer (1)
+    // or inner (2); an inner blossom makes the vertex matched to its base outer
+    fn assign_label(&mut self, w: usize, label: u8, p: usize) {
+        let b = self.in_blossom[w];
+        self.label[w] = label;
+        self.label[b] = label;
+        self.label_end[w] = p;
+        self.label_end[b] = p;
+        self.best_edge[w] = NONE;
+        self.best_edge[b] = NONE;
+        if label == 1 {
+            let leaves = self.leaves(b);
+            self.queue.extend(leaves);
+        } else {
+            let m = self.mate[self.blossom_base[b]];
+            self.assign_label(self.endpoint[m], 1, m ^ 1);
+        }
+    }
+
+    // traces the trees of the outer vertices v and w back to their roots, alternately, and
+    // returns the base of the new blossom where the paths meet, or NONE if they reach two
+    // different roots, which makes for an augmenting path
+    fn scan_blossom(&mut self, mut v: usize, mut w: usize) -> usize {
+        let mut path = vec![];
+        let mut base = NONE;
+        while v != NONE {
+            let b = self.in_blossom[v];
+            if self.label[b] & 4 != 0 {
+                base = self.blossom_base[b];
+                break;
+            }
+            path.push(b);
+            self.label[b] = 5;
+            if self.label_end[b] == NONE {
+                // the root of the tree
+                v = NONE;
+            } else {
+                // up through the inner blossom to the next outer one
+                let t = self.in_blossom[self.endpoint[self.label_end[b]]];
+                v = self.endpoint[self.label_end[t]];
+            }
+            if w != NONE {
+                std::mem::swap(&mut v, &mut w);
+            }
+        }
+        for b in path {
+            self.label[b] = 1;
+        }
+        base
+    }
+
+    // shrinks the cycle closed by edge k, through the outer blossoms up to the one with the
+    // given base, into a new outer blossom
+    fn add_blossom(&mut self, base: usize, k: usize) {
+        let (v, w, _) = self.edges[k];
+        let bb = self.in_blossom[base];
+        let (mut bv, mut bw) = (self.in_blossom[v], self.in_blossom[w]);
+        let b = self.unused_blossoms.pop().unwrap();
+        self.blossom_base[b] = base;
+        self.blossom_parent[b] = NONE;
+        self.blossom_parent[bb] = b;
+        // the sub-blossoms from v's side back to the base, then those from w's side
+        let (mut path, mut endpoints) = (vec![], vec![]);
+        while bv != bb {
+            self.blossom_parent[bv] = b;
+            path.push(bv);
+            endpoints.push(self.label_end[bv]);
+            bv = self.in_blossom[self.endpoint[self.label_end[bv]]];
+        }
+        path.push(bb);
+        path.reverse();
+        endpoints.reverse();
+        endpoints.push(2 * k);
+        while bw != bb {
+            self.blossom_parent[bw] = b;
+            path.push(bw);
+            endpoints.push(self.label_end[bw] ^ 1);
+            bw = self.in_blossom[self.endpoint[self.label_end[bw]]];
+        }
+        self.label[b] = 1;
+        self.label_end[b] = self.label_end[bb];
+        self.dual[b] = 0.0;
+        self.blossom_children[b] = path.clone();
+        self.blossom_endpoints[b] = endpoints;
+        for v in self.leaves(b) {
+            // the formerly inner vertices become outer and need to be scanned
+            if self.label[self.in_blossom[v]] == 2 {
+                self.queue.push(v);
+            }
+            self.in_blossom[v] = b;
+        }
+
+        // the least slack edges from the new blossom to every other outer blossom
+        let mut best_to = vec![NONE; 2 * self.n];
+        for bv in path {
+            let lists: Vec<Vec<usize>> = match self.blossom_best_edges[bv].take() {
+                Some(list) => vec![list],
+                None => self.leaves(bv).iter().map(|v| self.neighbours[*v].iter().map(|p| p / 2).collect()).collect()
+            };
+            for k in lists.into_iter().flatten() {
+                let (i, j, _) = self.edges[k];
+                let bj = if self.in_blossom[j] == b { self.in_blossom[i] } else { self.in_blossom[j] };
+                if bj != b && self.label[bj] == 1 && (best_to[bj] == NONE || self.slack(k) < self.slack(best_to[bj])) {
+                    best_to[bj] = k;
+                }
+            }
+            self.best_edge[bv] = NONE;
+        }
+        let best: Vec<usize> = best_to.into_iter().filter(|k| *k != NONE).collect();
+        self.best_edge[b] = NONE;
+        for &k in &best {
+            if self.best_edge[b] == NONE || self.slack(k) < self.slack(self.best_edge[b]) {
+                self.best_edge[b] = k;
+            }
+        }
+        self.blossom_best_edges[b] = Some(best);
+    }
+
+    // turns the sub-blossoms of the top-level blossom b into top-level blossoms; in the
+    // middle of a stage an inner blossom is expanded, and its sub-blossoms on the even
+    // length path from the entry to the base are relabeled to keep the tree alternating
+    fn expand_blossom(&mut self, b: usize, end_stage: bool) {
+        let children = self.blossom_children[b].clone();
+        let endpoints = self.blossom_endpoints[b].clone();
+        for &s in &children {
+            self.blossom_parent[s] = NONE;
+            if s < self.n {
+                self.in_blossom[s] = s;
+            } else if end_stage && self.dual[s] == 0.0 {
+                self.expand_blossom(s, end_stage);
+            } else {
+                for v in self.leaves(s) {
+                    self.in_blossom[v] = s;
+                }
+            }
+        }
+
+        if !end_stage && self.label[b] == 2 {
+            let len = children.len() as isize;
+            let at = |list: &[usize], i: isize| list[i.rem_euclid(len) as usize];
+            // walk from the sub-blossom the tree enters through to the base, the short way
+            let entry = self.in_blossom[self.endpoint[self.label_end[b] ^ 1]];
+            let mut j = children.iter().position(|child| *child == entry).unwrap() as isize;
+            let (step, trick) = if j & 1 == 1 {
+                j -= len;
+                (1, 0)
+            } else {
+                (-1, 1)
+            };
+            let mut p = self.label_end[b];
+            while j != 0 {
+                self.label[self.endpoint[p ^ 1]] = 0;
+                self.label[self.endpoint[at(&endpoints, j - trick as isize) ^ trick ^ 1]] = 0;
+                self.assign_label(self.endpoint[p ^ 1], 2, p);
+                self.allowed[at(&endpoints, j - trick as isize) / 2] = true;
+                j += step;
+                p = at(&endpoints, j - trick as isize) ^ trick;
+                self.allowed[p / 2] = true;
+                j += step;
+            }
+            // the base becomes inner without relabeling its mate
+            let bv = at(&children, j);
+            let e = self.endpoint[p ^ 1];
+            self.label[e] = 2;
+            self.label[bv] = 2;
+            self.label_end[e] = p;
+            self.label_end[bv] = p;
+            self.best_edge[bv] = NONE;
+            // the sub-blossoms on the other way round may be reached by the tree again
+            j += step;
+            while at(&children, j) != entry {
+                let bv = at(&children, j);
+                j += step;
+                if self.label[bv] == 1 {
+                    continue;
+                }
+                if let Some(v) = self.leaves(bv).into_iter().find(|v| self.label[*v] != 0) {
+                    self.label[v] = 0;
+                    self.label[self.endpoint[self.mate[self.blossom_base[bv]]]] = 0;
+                    let p = self.label_end[v];
+                    self.assign_label(v, 2, p);
+                }
+            }
+        }
+
+        self.label[b] = 0;
+        self.label_end[b] = NONE;
+        self.blossom_children[b] = vec![];
+        self.blossom_endpoints[b] = vec![];
+        self.blossom_base[b] = NONE;
+        self.blossom_best_edges[b] = None;
+        self.best_edge[b] = NONE;
+        self.unused_blossoms.push(b);
+    }
+
+    // swaps matched and unmatched edges along the even length path through blossom b from
+    // its base to vertex v, which becomes the new base
+    fn augment_blossom(&mut self, b: usize, v: usize) {
+        let mut t = v;
+        while self.blossom_parent[t] != b {
+            t = self.blossom_parent[t];
+        }
+        if t >= self.n {
+            self.augment_blossom(t, v);
+        }
+        let children = self.blossom_children[b].clone();
+        let endpoints = self.blossom_endpoints[b].clone();
+        let len = children.len() as isize;
+        let at = |list: &[usize], i: isize| list[i.rem_euclid(len) as usize];
+        let i = children.iter().position(|child| *child == t).unwrap();
+        let mut j = i as isize;
+        let (step, trick) = if i & 1 == 1 {
+            j -= len;
+            (1, 0)
+        } else {
+            (-1, 1)
+        };
+        while j != 0 {
+            j += step;
+            let t = at(&children, j);
+            let p = at(&endpoints, j - trick as isize) ^ trick;
+            if t >= self.n {
+                self.augment_blossom(t, self.endpoint[p]);
+            }
+            j += step;
+            let t = at(&children, j);
+            if t >= self.n {
+                self.augment_blossom(t, self.endpoint[p ^ 1]);
+            }
+            self.mate[self.endpoint[p]] = p ^ 1;
+            self.mate[self.endpoint[p ^ 1]] = p;
+        }
+        self.blossom_children[b].rotate_left(i);
+        self.blossom_endpoints[b].rotate_left(i);
+        self.blossom_base[b] = self.blossom_base[self.blossom_children[b][0]];
+    }
+
+    // swaps matched and unmatched edges along the augmenting path through edge k between
+    // the roots of two trees
+    fn augment_matching(&mut self, k: usize) {
+        let (v, w, _) = self.edges[k];
+        for (mut s, mut p) in [(v, 2 * k + 1), (w, 2 * k)] {
+            loop {
+                let bs = self.in_blossom[s];
+                if bs >= self.n {
+                    self.augment_blossom(bs, s);
+                }
+                self.mate[s] = p;
+                if self.label_end[bs] == NONE {
+                    // reached the root
+                    break;
+                }
+                let bt = self.in_blossom[self.endpoint[self.label_end[bs]]];
+                s = self.endpoint[self.label_end[bt]];
+                let j = self.endpoint[self.label_end[bt] ^ 1];
+                if bt >= self.n {
+                    self.augment_blossom(bt, j);
+                }
+                self.mate[j] = self.label_end[bt];
+                p = self.label_end[bt] ^ 1;
+            }
+        }
+    }
+}
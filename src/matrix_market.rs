@@ -0,0 +1,113 @@
+use std::io;
+use std::io::{BufRead, Write};
+
+use graph::{Edge, Graph, HashMap, HashSet, Vertex, Weight};
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+impl Graph {
+
+    // loads a graph from a sparse matrix in the Matrix Market coordinate format, taking the
+    // square matrix of n rows as the adjacency matrix of a graph over the vertices 1..n:
+    // every entry "i j [value]" is an edge from i to j, weighted with the value unless the
+    // matrix is a pattern; symmetric matrices, which list only one triangle, are undirected
+    // graphs and general matrices directed ones; of several entries for the same edge, the
+    // lightest one is kept
+    pub fn load_matrix_market<R: BufRead>(reader: R) -> io::Result<Graph> {
+        let mut lines = reader.lines();
+        let header = lines.next().unwrap_or_else(|| Ok(String::new()))?.to_lowercase();
+        let header: Vec<&str> = header.split_whitespace().collect();
+        if header.len() != 5 || header[0] != "%%matrixmarket" || header[1] != "matrix" || header[2] != "coordinate" {
+            return Err(invalid("not a Matrix Market coordinate file"));
+        }
+        let weighted = match header[3] {
+            "real" | "integer" => true,
+            "pattern" => false,
+            _ => return Err(invalid("unsupported field type"))
+        };
+        let directed = match header[4] {
+            "general" => true,
+            "symmetric" => false,
+            _ => return Err(invalid("unsupported symmetry"))
+        };
+
+        let mut size = None;
+        let mut count = 0;
+        let mut edges = HashSet::new();
+        let mut weights: HashMap<Edge, Weight> = HashMap::new();
+        for line in lines {
+            let line = line?;
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.is_empty() || fields[0].starts_with('%') {
+                continue;
+            }
+            let number = |i: usize| fields.get(i).and_then(|s| s.parse::<u64>().ok()).ok_or_else(|| invalid("invalid line"));
+            let (n, entries) = match size {
+                Some(size) => size,
+                None => {
+                    if number(0)? != number(1)? {
+                        return Err(invalid("not a square matrix"));
+                    }
+                    size = Some((number(0)?, number(2)?));
+                    continue;
+                }
+            };
+            let (i, j) = (number(0)?, number(1)?);
+            if i < 1 || i > n || j < 1 || j > n {
+                return Err(invalid("entry outside of the matrix"));
+            }
+            count += 1;
+            if count > entries {
+                return Err(invalid("more entries than declared"));
+            }
+            let edge = (i as Vertex, j as Vertex);
+            edges.insert(edge);
+            if weighted {
+                let weight: Weight = fields.get(2).and_then(|s| s.parse().ok()).ok_or_else(|| invalid("invalid value"))?;
+                let lightest = weights.entry(edge).or_insert(weight);
+                *lightest = lightest.min(weight);
+            }
+        }
+        let (n, entries) = size.ok_or_else(|| invalid("missing size line"))?;
+        if count < entries {
+            return Err(invalid("fewer entries than declared"));
+        }
+        let vertices = (1..n as Vertex + 1).collect();
+        if weighted {
+            Ok(Graph::with_weights(vertices, edges, weights, directed))
+        } else {
+            Ok(Graph::new(vertices, edges, directed))
+        }
+    }
+
+    // writes the adjacency matrix of the graph in the Matrix Market coordinate format, with
+    // as many rows and columns as the largest vertex; undirected graphs are written as
+    // symmetric matrices listing their lower triangle, and unweighted graphs as patterns
+    pub fn write_matrix_market<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        if self.vertices().contains(&0) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "vertex 0 has no row in the matrix"));
+        }
+        let field = if self.is_weighted() { "real" } else { "pattern" };
+        let symmetry = if self.is_directed() { "general" } else { "symmetric" };
+        writeln!(writer, "%%MatrixMarket matrix coordinate {} {}", field, symmetry)?;
+        let n = self.vertices().iter().max().cloned().unwrap_or(0);
+        writeln!(writer, "{} {} {}", n, n, self.edges().len())?;
+        let mut edges: Vec<(Edge, Weight)> = self.edges().iter()
+            .map(|edge| {
+                let entry = if self.is_directed() { *edge } else { (edge.0.max(edge.1), edge.0.min(edge.1)) };
+                (entry, self.weight(edge))
+            })
+            .collect();
+        edges.sort_by_key(|entry| entry.0);
+        for ((i, j), weight) in edges {
+            if self.is_weighted() {
+                writeln!(writer, "{} {} {}", i, j, weight)?;
+            } else {
+                writeln!(writer, "{} {}", i, j)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,132 @@
+use disjoint_sets::DisjointSets;
+use graph::{Graph, HashSet, Vertex};
+use random::Random;
+
+// a cell of the grid as its row and column, counted from the top left corner
+pub type Cell = (usize, usize);
+
+// how Maze::generate carves the passages
+pub enum Generator {
+    // randomized depth first search, which yields long winding corridors
+    DepthFirst,
+    // randomized Kruskal, which yields many short dead ends
+    Kruskal
+}
+
+// a perfect maze on a grid, i.e. one with exactly one way between any two cells, stored as
+// the undirected graph of its cells with an edge for every passage between neighbouring
+// cells; the cell (row, column) is the vertex row * columns + column + 1
+pub struct Maze {
+    rows: usize,
+    columns: usize,
+    graph: Graph
+}
+
+impl Maze {
+
+    // generates a maze of the given size with the generator, reproducibly for a given seed
+    pub fn generate(rows: usize, columns: usize, generator: &Generator, seed: u64) -> Maze {
+        let (rows, columns) = (rows.max(1), columns.max(1));
+        let mut random = Random::new(seed);
+        let n = rows * columns;
+        let neighbours = |i: usize| {
+            let (row, column) = (i / columns, i % columns);
+            let mut neighbours = vec![];
+            if row > 0 { neighbours.push(i - columns); }
+            if row + 1 < rows { neighbours.push(i + columns); }
+            if column > 0 { neighbours.push(i - 1); }
+            if column + 1 < columns { neighbours.push(i + 1); }
+            neighbours
+        };
+        let mut passages = HashSet::new();
+        match *generator {
+            Generator::DepthFirst => {
+                let mut visited = vec![false; n];
+                let mut stack = vec![0];
+                visited[0] = true;
+                while let Some(&i) = stack.last() {
+                    let unvisited: Vec<usize> = neighbours(i).into_iter().filter(|j| !visited[*j]).collect();
+                    if unvisited.is_empty() {
+                        stack.pop();
+                        continue;
+                    }
+                    let j = unvisited[random.below(unvisited.len())];
+                    visited[j] = true;
+                    passages.insert((i.min(j), i.max(j)));
+                    stack.push(j);
+                }
+            },
+            Generator::Kruskal => {
+                let mut walls: Vec<(usize, usize)> = (0..n)
+                    .flat_map(|i| neighbours(i).into_iter().filter(move |j| i < *j).map(move |j| (i, j)))
+                    .collect();
+                random.shuffle(&mut walls);
+                let mut sets = DisjointSets::new(n);
+                for (i, j) in walls {
+                    if sets.union(i, j) {
+                        passages.insert((i, j));
+                    }
+                }
+            }
+        }
+        let vertex = |i: usize| i as Vertex + 1;
+        let edges = passages.into_iter().map(|(i, j)| (vertex(i), vertex(j))).collect();
+        Maze { rows, columns, graph: Graph::new((0..n).map(vertex).collect(), edges, false) }
+    }
+
+    pub fn graph(&self) -> &Graph {
+        &self.graph
+    }
+
+    pub fn vertex(&self, cell: Cell) -> Vertex {
+        (cell.0 * self.columns + cell.1) as Vertex + 1
+    }
+
+    pub fn cell(&self, v: Vertex) -> Cell {
+        let i = v as usize - 1;
+        (i / self.columns, i % self.columns)
+    }
+
+    fn has_passage(&self, a: Cell, b: Cell) -> bool {
+        let (v, w) = (self.vertex(a), self.vertex(b));
+        self.graph.edges().contains(&(v.min(w), v.max(w)))
+    }
+
+    // finds the way from one cell to another by breadth first search
+    pub fn solve_bfs(&self, from: Cell, to: Cell) -> Option<Vec<Cell>> {
+        self.graph.find_shortest_path(self.vertex(from), self.vertex(to))
+            .map(|path| path.into_iter().map(|v| self.cell(v)).collect())
+    }
+
+    // finds the way from one cell to another with A*, estimating the remaining distance by
+    // the Manhattan distance on the grid, which never overestimates as every passage leads
+    // to a neighbouring cell
+    pub fn solve_astar(&self, from: Cell, to: Cell) -> Option<Vec<Cell>> {
+        let heuristic = |v: Vertex| {
+            let cell = self.cell(v);
+            (cell.0.abs_diff(to.0) + cell.1.abs_diff(to.1)) as f64
+        };
+        self.graph.astar(self.vertex(from), self.vertex(to), heuristic)
+            .map(|found| found.path.into_iter().map(|v| self.cell(v)).collect())
+    }
+
+    // draws the maze with +, - and | for the walls, marking the cells of the path with *
+    pub fn render(&self, path: &[Cell]) -> String {
+        let on_path: HashSet<&Cell> = path.iter().collect();
+        let mut text = "+".to_string() + &"---+".repeat(self.columns) + "\n";
+        for row in 0..self.rows {
+            let mut cells = "|".to_string();
+            let mut walls = "+".to_string();
+            for column in 0..self.columns {
+                let cell = (row, column);
+                cells += if on_path.contains(&cell) { " * " } else { "   " };
+                let open_right = column + 1 < self.columns && self.has_passage(cell, (row, column + 1));
+                cells += if open_right { " " } else { "|" };
+                let open_below = row + 1 < self.rows && self.has_passage(cell, (row + 1, column));
+                walls += if open_below { "   +" } else { "---+" };
+            }
+            text += &(cells + "\n" + &walls + "\n");
+        }
+        text
+    }
+}
@@ -0,0 +1,78 @@
+use std::fmt;
+use std::mem::size_of;
+
+use graph::{Edge, Graph, HashSet, Vertex, Weight};
+
+// estimated number of bytes of heap memory held by a graph; the adjacency maps are not
+// stored but built by the algorithms, adjacencies is the size of one such map
+pub struct MemoryUsage {
+    pub vertices: usize,
+    pub edges: usize,
+    pub weights: usize,
+    pub adjacencies: usize
+}
+
+impl MemoryUsage {
+
+    pub fn total(&self) -> usize {
+        self.vertices + self.edges + self.weights
+    }
+}
+
+impl fmt::Display for MemoryUsage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Vertices: {} bytes", self.vertices)?;
+        writeln!(f, "Edges: {} bytes", self.edges)?;
+        writeln!(f, "Weights: {} bytes", self.weights)?;
+        writeln!(f, "Total: {} bytes", self.total())?;
+        writeln!(f, "Adjacencies: {} bytes when built", self.adjacencies)
+    }
+}
+
+// the heap memory of a std hash table with room for capacity entries of the given size:
+// the number of buckets is a power of two kept at most 7/8 full, and every bucket has a
+// control byte besides the entry
+#[cfg(not(feature = "btree_core"))]
+fn table_bytes(capacity: usize, entry: usize) -> usize {
+    let buckets = match capacity {
+        0 => return 0,
+        1..=3 => 4,
+        4..=7 => 8,
+        _ => (capacity * 8).div_ceil(7).next_power_of_two()
+    };
+    buckets * (entry + 1)
+}
+
+// the heap memory of a std B-tree of len entries of the given size: nodes have room for 11
+// entries besides a parent link and two counters, and are taken to be two thirds full
+#[cfg(feature = "btree_core")]
+fn table_bytes(len: usize, entry: usize) -> usize {
+    len.div_ceil(7) * (11 * entry + 16)
+}
+
+impl Graph {
+
+    pub fn memory_usage(&self) -> MemoryUsage {
+        let mut degrees = vec![0; self.vertices().len()];
+        let (_, index) = self.indexed_vertices();
+        for edge in self.edges() {
+            degrees[index[&edge.0]] += 1;
+            if !self.is_directed() && edge.0 != edge.1 {
+                degrees[index[&edge.1]] += 1;
+            }
+        }
+        let adjacencies = table_bytes(degrees.len(), size_of::<(Vertex, HashSet<Vertex>)>())
+            + degrees.iter().map(|d| table_bytes(*d, size_of::<Vertex>())).sum::<usize>();
+        // B-trees have no room beyond their entries
+        #[cfg(not(feature = "btree_core"))]
+        let room = (self.vertices().capacity(), self.edges().capacity(), self.weights().capacity());
+        #[cfg(feature = "btree_core")]
+        let room = (self.vertices().len(), self.edges().len(), self.weights().len());
+        MemoryUsage {
+            vertices: table_bytes(room.0, size_of::<Vertex>()),
+            edges: table_bytes(room.1, size_of::<Edge>()),
+            weights: table_bytes(room.2, size_of::<(Edge, Weight)>()),
+            adjacencies
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use graph::{Graph, HashMap, Vertex};
+
+impl Graph {
+
+    // the disjoint union of the graph with another one, such as one loaded from a further
+    // file: the vertices of the graph keep their numbers and those of the other one are
+    // renumbered after the largest of them, see merge_with_mapping; returns the union with
+    // the new number of every vertex of the other graph
+    pub fn disjoint_union(&self, other: &Graph) -> (Graph, HashMap<Vertex, Vertex>) {
+        self.merge_with_mapping(other, &HashMap::new())
+    }
+
+    // merges another graph into the graph, identifying the vertices of the other one in the
+    // mapping with the vertices of the graph they map to, such as the same person in two
+    // social networks; the other vertices of the other graph get new numbers after the
+    // largest vertex of the graph and of the mapping, in ascending order, so that they
+    // collide with none. An edge in both graphs keeps the weight it has in the graph, and
+    // the merged graph is directed if the graph is, with undirected edges of the other graph
+    // turned into edges both ways; returns the merged graph with the number of every vertex
+    // of the other graph in it
+    pub fn merge_with_mapping(&self, other: &Graph, mapping: &HashMap<Vertex, Vertex>) -> (Graph, HashMap<Vertex, Vertex>) {
+        let largest = self.vertices().iter().chain(mapping.values()).max().cloned();
+        let mut next = largest.map_or(Some(0), |v| v.checked_add(1));
+        let mut number: HashMap<Vertex, Vertex> = HashMap::new();
+        for v in other.sorted_vertices() {
+            let merged = match mapping.get(&v) {
+                Some(w) => *w,
+                None => {
+                    let fresh = next.expect("No vertex numbers left!");
+                    next = fresh.checked_add(1);
+                    fresh
+                }
+            };
+            number.insert(v, merged);
+        }
+
+        let mut merged = self.clone();
+        let weighted = self.is_weighted() || other.is_weighted();
+        if weighted && !self.is_weighted() {
+            for edge in self.edges() {
+                merged.add_weighted_edge(edge.0, edge.1, 1.0);
+            }
+        }
+        for v in number.values() {
+            merged.add_vertex(*v);
+        }
+        let mut edges: Vec<_> = other.edges().iter().collect();
+        edges.sort();
+        for edge in edges {
+            let (v, w) = (number[&edge.0], number[&edge.1]);
+            let mut ends = vec![(v, w)];
+            if self.is_directed() && !other.is_directed() {
+                ends.push((w, v));
+            }
+            for (v, w) in ends {
+                if merged.has_edge(v, w) {
+                    continue;
+                }
+                if weighted {
+                    merged.add_weighted_edge(v, w, other.weight(edge));
+                } else {
+                    merged.add_edge(v, w);
+                }
+            }
+        }
+        (merged, number)
+    }
+}
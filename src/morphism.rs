@@ -0,0 +1,68 @@
+use graph::{Edge, Graph, HashMap, HashSet, Vertex, Weight};
+
+impl Graph {
+
+    // the image of the graph under a mapping of its vertices, called once for every vertex:
+    // every edge v - w becomes an edge f(v) - f(w), so vertices mapped to the same one are
+    // merged, with the edges between them turning into self-loops, and of several edges
+    // merged into one the lightest keeps its weight; a one-to-one mapping just renumbers
+    // the vertices
+    pub fn map_vertices<F: FnMut(Vertex) -> Vertex>(&self, mut f: F) -> Graph {
+        let image: HashMap<Vertex, Vertex> = self.sorted_vertices().into_iter().map(|v| (v, f(v))).collect();
+        let mut mapped = Graph::new(image.values().cloned().collect(), HashSet::new(), self.is_directed());
+        mapped.set_deterministic(self.is_deterministic());
+        let mut edges: Vec<(Weight, Edge)> = self.edges().iter().map(|edge| (self.weight(edge), *edge)).collect();
+        edges.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        for (weight, (v, w)) in edges {
+            let (a, b) = (image[&v], image[&w]);
+            if !mapped.add_edge(a, b) {
+                continue;
+            }
+            if self.is_weighted() {
+                mapped.add_weighted_edge(a, b, weight);
+            }
+        }
+        mapped
+    }
+
+    // the graph with the edges the function returns a weight for, given every edge and its
+    // weight, 1 for unweighted graphs, e.g. to drop light edges by returning None for them
+    // or to transform the weights; keeps all vertices, and the result is weighted if this
+    // graph is or a weight other than 1 was returned
+    pub fn filter_map_edges<F: FnMut(Edge, Weight) -> Option<Weight>>(&self, mut f: F) -> Graph {
+        let mut filtered = Graph::new(self.vertices().clone(), HashSet::new(), self.is_directed());
+        filtered.set_deterministic(self.is_deterministic());
+        let mut edges: Vec<Edge> = self.edges().iter().cloned().collect();
+        edges.sort();
+        let mut kept = vec![];
+        for edge in edges {
+            if let Some(weight) = f(edge, self.weight(&edge)) {
+                kept.push((edge, weight));
+            }
+        }
+        let weighted = self.is_weighted() || kept.iter().any(|(_, weight)| *weight != 1.0);
+        for ((v, w), weight) in kept {
+            if weighted {
+                filtered.add_weighted_edge(v, w, weight);
+            } else {
+                filtered.add_edge(v, w);
+            }
+        }
+        filtered
+    }
+
+    // the subgraph induced by the vertices, with the edges of the graph between them and
+    // their weights; vertices not in the graph are left out, and the numbers are kept
+    pub fn induced_subgraph(&self, vertices: &HashSet<Vertex>) -> Graph {
+        let kept: HashSet<Vertex> = vertices.iter().filter(|v| self.vertices().contains(v)).cloned().collect();
+        let edges: HashSet<Edge> = self.edges().iter().filter(|(v, w)| kept.contains(v) && kept.contains(w)).cloned().collect();
+        let mut induced = if self.is_weighted() {
+            let weights = edges.iter().map(|edge| (*edge, self.weight(edge))).collect();
+            Graph::with_weights(kept, edges, weights, self.is_directed())
+        } else {
+            Graph::new(kept, edges, self.is_directed())
+        };
+        induced.set_deterministic(self.is_deterministic());
+        induced
+    }
+}
@@ -0,0 +1,126 @@
+use dynamic_connectivity::DynamicConnectivity;
+use graph::{Edge, Graph, Vertex, Weight};
+
+// a change made to a graph through an ObservedGraph; edges are given as they are stored,
+// which for undirected graphs may be the other way round than they were passed in
+#[derive(Clone, Debug, PartialEq)]
+pub enum Change {
+    VertexAdded(Vertex),
+    EdgeAdded(Edge, Weight),
+    // an edge that was removed, with the weight it had
+    EdgeRemoved(Edge, Weight),
+    // an existing edge given a new weight, with the old and the new weight
+    EdgeReweighted(Edge, Weight, Weight)
+}
+
+// a structure derived from a graph that follows its changes, so that it can be updated
+// incrementally instead of being rebuilt after every change
+pub trait Observer {
+    fn changed(&mut self, change: &Change);
+}
+
+impl<F: FnMut(&Change)> Observer for F {
+    fn changed(&mut self, change: &Change) {
+        self(change)
+    }
+}
+
+// keeps the components up to date, ignoring edge directions; reweighting makes no difference
+impl Observer for DynamicConnectivity {
+    fn changed(&mut self, change: &Change) {
+        match *change {
+            Change::VertexAdded(v) => self.insert_vertex(v),
+            Change::EdgeAdded((u, v), _) => self.insert_edge(u, v),
+            Change::EdgeRemoved((u, v), _) => { self.delete_edge(u, v); },
+            Change::EdgeReweighted(..) => ()
+        }
+    }
+}
+
+// a graph that reports every change made through it to the registered observers, in the
+// order the changes happen; each mutation also returns its changes, for callers that keep
+// their derived structures themselves
+pub struct ObservedGraph<'a> {
+    graph: Graph,
+    observers: Vec<Box<dyn Observer + 'a>>
+}
+
+impl<'a> ObservedGraph<'a> {
+
+    pub fn new(graph: Graph) -> ObservedGraph<'a> {
+        ObservedGraph { graph, observers: vec![] }
+    }
+
+    // registers an observer for all further changes; it is not told about the graph as it
+    // is, so it should be built from graph() first
+    pub fn observe<O: Observer + 'a>(&mut self, observer: O) {
+        self.observers.push(Box::new(observer));
+    }
+
+    pub fn graph(&self) -> &Graph {
+        &self.graph
+    }
+
+    // gives up the observers and returns the graph
+    pub fn into_graph(self) -> Graph {
+        self.graph
+    }
+
+    // adds the edge from v to w like Graph::add_edge; there are no changes if the graph
+    // already contains the edge
+    pub fn add_edge(&mut self, v: Vertex, w: Vertex) -> Vec<Change> {
+        let mut changes = self.added_vertices(v, w);
+        if self.graph.add_edge(v, w) {
+            changes.push(Change::EdgeAdded((v, w), 1.0));
+        }
+        self.notify(changes)
+    }
+
+    // adds the edge from v to w with the given weight, or changes the weight of an existing
+    // edge like Graph::add_weighted_edge; there are no changes if the weight stays the same
+    pub fn add_weighted_edge(&mut self, v: Vertex, w: Vertex, weight: Weight) -> Vec<Change> {
+        let mut changes = self.added_vertices(v, w);
+        let edge = self.graph.edge_between(v, w);
+        let existing = self.graph.edges().contains(&edge) && (!self.graph.is_directed() || edge == (v, w));
+        if existing {
+            let old = self.graph.weight(&edge);
+            self.graph.add_weighted_edge(v, w, weight);
+            if old != weight {
+                changes.push(Change::EdgeReweighted(edge, old, weight));
+            }
+        } else {
+            self.graph.add_weighted_edge(v, w, weight);
+            changes.push(Change::EdgeAdded((v, w), weight));
+        }
+        self.notify(changes)
+    }
+
+    // removes the edge from v to w like Graph::remove_edge; there are no changes if there
+    // is no such edge
+    pub fn remove_edge(&mut self, v: Vertex, w: Vertex) -> Vec<Change> {
+        let edge = self.graph.edge_between(v, w);
+        let weight = self.graph.weight(&edge);
+        let changes = if self.graph.remove_edge(v, w) { vec![Change::EdgeRemoved(edge, weight)] } else { vec![] };
+        self.notify(changes)
+    }
+
+    // the vertices an edge between v and w would add to the graph
+    fn added_vertices(&self, v: Vertex, w: Vertex) -> Vec<Change> {
+        let mut changes = vec![];
+        for u in if v == w { vec![v] } else { vec![v, w] } {
+            if !self.graph.vertices().contains(&u) {
+                changes.push(Change::VertexAdded(u));
+            }
+        }
+        changes
+    }
+
+    fn notify(&mut self, changes: Vec<Change>) -> Vec<Change> {
+        for observer in &mut self.observers {
+            for change in &changes {
+                observer.changed(change);
+            }
+        }
+        changes
+    }
+}
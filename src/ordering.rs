@@ -0,0 +1,188 @@
+use std::collections::BTreeSet;
+use std::collections::VecDeque;
+
+use graph::{Graph, HashMap, HashSet, Vertex};
+use traversal::sorted_adjacencies;
+
+impl Graph {
+
+    // the vertices in breadth first order, ignoring edge directions: each component is
+    // searched from its smallest vertex, the components by their smallest vertex, and the
+    // neighbours of each vertex in ascending order, so that vertices close in the graph end
+    // up close in the order
+    pub fn bfs_order(&self) -> Vec<Vertex> {
+        let adj = sorted_adjacencies(&self.undirected_adjacencies());
+        let mut order = vec![];
+        let mut discovered = HashSet::new();
+        for root in self.sorted_vertices() {
+            if discovered.insert(root) {
+                order.extend(breadth_first(&adj, root, &mut discovered));
+            }
+        }
+        order
+    }
+
+    // the vertices in reverse Cuthill-McKee order, ignoring edge directions: each component
+    // is searched breadth first from a pseudo-peripheral vertex, a vertex of about the
+    // largest eccentricity found by the heuristic of George and Liu, visiting the neighbours
+    // of each vertex by ascending degree, and the whole order is reversed; this keeps the
+    // nonzeros of the adjacency matrix close to the diagonal, i.e. its bandwidth low
+    pub fn reverse_cuthill_mckee(&self) -> Vec<Vertex> {
+        let undirected = self.undirected_adjacencies();
+        let degree = |v: &Vertex| undirected[v].iter().filter(|w| *w != v).count();
+        let adj: HashMap<Vertex, Vec<Vertex>> = sorted_adjacencies(&undirected).into_iter()
+            .map(|(v, mut adjacent)| {
+                adjacent.sort_by_key(|w| (degree(w), *w));
+                (v, adjacent)
+            })
+            .collect();
+
+        let mut vertices = self.sorted_vertices();
+        vertices.sort_by_key(|v| (degree(v), *v));
+        let mut order = vec![];
+        let mut discovered = HashSet::new();
+        for v in vertices {
+            if discovered.contains(&v) {
+                continue;
+            }
+            // when the farthest level holds a vertex of smallest degree with a larger
+            // eccentricity, start from that instead
+            let mut root = v;
+            let mut levels = levels_from(&adj, root);
+            loop {
+                let candidate = *levels.last().unwrap().iter().min_by_key(|w| (degree(w), **w)).unwrap();
+                let candidate_levels = levels_from(&adj, candidate);
+                if candidate_levels.len() <= levels.len() {
+                    break;
+                }
+                (root, levels) = (candidate, candidate_levels);
+            }
+            discovered.insert(root);
+            order.extend(breadth_first(&adj, root, &mut discovered));
+        }
+        order.reverse();
+        order
+    }
+
+    // the vertices by degree, ignoring edge directions and self-loops, in ascending or
+    // descending order and between vertices of the same degree in ascending order; putting
+    // the hubs first keeps their long adjacency lists together
+    pub fn degree_order(&self, descending: bool) -> Vec<Vertex> {
+        let adj = self.undirected_adjacencies();
+        let degree = |v: &Vertex| adj[v].iter().filter(|w| *w != v).count();
+        let mut order = self.sorted_vertices();
+        if descending {
+            order.sort_by_key(|v| (std::cmp::Reverse(degree(v)), *v));
+        } else {
+            order.sort_by_key(|v| (degree(v), *v));
+        }
+        order
+    }
+
+    // the vertices in smallest-last order, ignoring edge directions and self-loops: a vertex
+    // of smallest degree among those left is removed over and over, the smallest of them on
+    // ties, and the order lists them from the last removed to the first, so that no vertex
+    // has more neighbours before it than the degeneracy; coloring greedily along it thus
+    // needs at most degeneracy + 1 colors
+    pub fn smallest_last_order(&self) -> Vec<Vertex> {
+        self.smallest_last().0
+    }
+
+    // the degeneracy of the graph, the largest k such that some subgraph has no vertex of
+    // degree below k, which is the largest degree at which smallest_last_order removes a vertex
+    pub fn degeneracy(&self) -> usize {
+        self.smallest_last().1
+    }
+
+    // removes vertices of smallest degree one by one, keeping the vertices left of every
+    // degree in a bucket, in O(m log n) time
+    fn smallest_last(&self) -> (Vec<Vertex>, usize) {
+        let (vertices, adj) = self.simple_undirected();
+        let n = vertices.len();
+        let mut degree: Vec<usize> = adj.iter().map(|adjacent| adjacent.len()).collect();
+        let mut buckets = vec![BTreeSet::new(); n.max(1)];
+        for v in 0..n {
+            buckets[degree[v]].insert(v);
+        }
+        let mut removed = vec![false; n];
+        let mut order = Vec::with_capacity(n);
+        let (mut smallest, mut degeneracy) = (0, 0);
+        for _ in 0..n {
+            while buckets[smallest].is_empty() {
+                smallest += 1;
+            }
+            let v = buckets[smallest].pop_first().unwrap();
+            degeneracy = degeneracy.max(smallest);
+            removed[v] = true;
+            order.push(vertices[v]);
+            for &w in adj[v].iter().filter(|w| !removed[**w]) {
+                buckets[degree[w]].remove(&w);
+                degree[w] -= 1;
+                buckets[degree[w]].insert(w);
+            }
+            smallest = smallest.saturating_sub(1);
+        }
+        order.reverse();
+        (order, degeneracy)
+    }
+
+    // the graph with the vertices renumbered 1..n in the given order, which must list every
+    // vertex once, e.g. for converting into a CsrGraph whose vertex numbers are positions
+    pub fn reordered(&self, order: &[Vertex]) -> Graph {
+        let position = positions(order);
+        let vertices = (1..order.len() as Vertex + 1).collect();
+        let edges = self.edges().iter().map(|edge| (position[&edge.0], position[&edge.1])).collect();
+        let weights = self.weights().iter().map(|(edge, weight)| ((position[&edge.0], position[&edge.1]), *weight)).collect();
+        Graph::with_weights(vertices, edges, weights, self.is_directed())
+    }
+
+    // the bandwidth of the adjacency matrix with the vertices in the given order: the
+    // largest distance in the order between the ends of an edge
+    pub fn bandwidth(&self, order: &[Vertex]) -> usize {
+        let position = positions(order);
+        self.edges().iter()
+            .map(|edge| (position[&edge.0] as i64 - position[&edge.1] as i64).unsigned_abs() as usize)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+// the number of every vertex in the order, counting from 1
+fn positions(order: &[Vertex]) -> HashMap<Vertex, Vertex> {
+    order.iter().enumerate().map(|(i, v)| (*v, (i + 1) as Vertex)).collect()
+}
+
+// the vertices reached from the discovered root in breadth first order, marking them as
+// discovered
+fn breadth_first(adj: &HashMap<Vertex, Vec<Vertex>>, root: Vertex, discovered: &mut HashSet<Vertex>) -> Vec<Vertex> {
+    let mut order = vec![];
+    let mut queue = VecDeque::new();
+    queue.push_back(root);
+    while let Some(v) = queue.pop_front() {
+        order.push(v);
+        for w in &adj[&v] {
+            if discovered.insert(*w) {
+                queue.push_back(*w);
+            }
+        }
+    }
+    order
+}
+
+// the vertices reachable from the root grouped by their distance from it
+fn levels_from(adj: &HashMap<Vertex, Vec<Vertex>>, root: Vertex) -> Vec<Vec<Vertex>> {
+    let mut discovered = HashSet::new();
+    discovered.insert(root);
+    let mut levels = vec![vec![root]];
+    loop {
+        let next: Vec<Vertex> = levels.last().unwrap().iter()
+            .flat_map(|v| adj[v].iter())
+            .filter(|w| discovered.insert(**w))
+            .cloned()
+            .collect();
+        if next.is_empty() {
+            return levels;
+        }
+        levels.push(next);
+    }
+}
@@ -0,0 +1,109 @@
+use graph::{Edge, Graph, HashMap, HashSet, Vertex};
+use preconditions::PreconditionError;
+
+impl Graph {
+
+    // orients every edge from the end that comes first in the order to the other one, which
+    // gives a directed acyclic graph with the order as a topological order; vertices missing
+    // from the order come after it in ascending order, self-loops are dropped as they would
+    // be cycles, and edge directions of directed graphs are ignored. Orienting along the
+    // ascending degree_order leaves no vertex more than sqrt(2m) outgoing edges, which is
+    // what makes counting triangles fast
+    pub fn orient_acyclic(&self, order: &[Vertex]) -> Graph {
+        let mut position: HashMap<Vertex, usize> = HashMap::new();
+        for v in order.iter().chain(self.sorted_vertices().iter()) {
+            let next = position.len();
+            position.entry(*v).or_insert(next);
+        }
+        let arcs = self.edges().iter()
+            .filter(|edge| edge.0 != edge.1)
+            .map(|&(v, w)| if position[&v] < position[&w] { (v, w) } else { (w, v) })
+            .collect();
+        self.oriented(arcs)
+    }
+
+    // orients every edge along an Eulerian circuit, so that every vertex gets as many
+    // incoming as outgoing edges and the vertices with edges are strongly connected
+    // fails unless all degrees are even and the edges are all connected
+    pub fn orient_eulerian(&self) -> Result<Graph, PreconditionError> {
+        let circuit = self.eulerian_circuit()?;
+        Ok(self.oriented(circuit.windows(2).map(|step| (step[0], step[1])).collect()))
+    }
+
+    // finds a strongly connected orientation, which by Robbins' theorem exists exactly if the
+    // graph is connected and has no bridge: the edges of a depth first search tree are
+    // oriented away from the root and all others, which lead back to an ancestor, towards
+    // the root, so that every vertex reaches the root through a back edge from its subtree;
+    // edge directions of directed graphs are ignored and isolated vertices stay isolated
+    // fails with a bridge, found the same way as a missing back edge, or two vertices whose
+    // edges are not connected
+    pub fn orient_strongly(&self) -> Result<Graph, PreconditionError> {
+        let (vertices, mut adj) = self.dense_undirected();
+        for adjacent in &mut adj {
+            adjacent.sort();
+            adjacent.dedup();
+        }
+        let n = vertices.len();
+        let unvisited = usize::MAX;
+        let mut discovered = vec![unvisited; n];
+        let mut low = vec![0; n];
+        let mut arcs: HashSet<Edge> = self.edges().iter().filter(|edge| edge.0 == edge.1).cloned().collect();
+        let mut root = None;
+        let mut time = 0;
+        for start in 0..n {
+            if discovered[start] != unvisited || adj[start].is_empty() {
+                continue;
+            }
+            if let Some(first) = root {
+                return Err(PreconditionError::Disconnected(vertices[first], vertices[start]));
+            }
+            root = Some(start);
+            discovered[start] = time;
+            low[start] = time;
+            time += 1;
+            // the path of the search, with the parent of every vertex on it and the position
+            // of its next neighbour
+            let mut stack = vec![(start, unvisited, 0)];
+            while let Some(&(v, parent, next)) = stack.last() {
+                if next < adj[v].len() {
+                    let top = stack.len() - 1;
+                    stack[top].2 += 1;
+                    let w = adj[v][next];
+                    if discovered[w] == unvisited {
+                        discovered[w] = time;
+                        low[w] = time;
+                        time += 1;
+                        arcs.insert((vertices[v], vertices[w]));
+                        stack.push((w, v, 0));
+                    } else if w != parent && discovered[w] < discovered[v] {
+                        low[v] = low[v].min(discovered[w]);
+                        arcs.insert((vertices[v], vertices[w]));
+                    }
+                } else {
+                    stack.pop();
+                    if parent != unvisited {
+                        // nothing in the subtree of v leads back above it
+                        if low[v] > discovered[parent] {
+                            return Err(PreconditionError::Bridge(vertices[parent], vertices[v]));
+                        }
+                        low[parent] = low[parent].min(low[v]);
+                    }
+                }
+            }
+        }
+        Ok(self.oriented(arcs.into_iter().collect()))
+    }
+
+    // the directed graph on the same vertices with the given orientations of the edges,
+    // each keeping the weight of its edge
+    fn oriented(&self, arcs: Vec<Edge>) -> Graph {
+        let weights = if self.is_weighted() {
+            arcs.iter().map(|&(v, w)| ((v, w), self.weight(&self.edge_between(v, w)))).collect()
+        } else {
+            HashMap::new()
+        };
+        let mut graph = Graph::with_weights(self.vertices().clone(), arcs.into_iter().collect(), weights, true);
+        graph.set_deterministic(self.is_deterministic());
+        graph
+    }
+}
@@ -0,0 +1,172 @@
+use std::collections::VecDeque;
+use std::thread;
+
+use graph::{Graph, HashMap, Vertex};
+use progress::{completed, Cancellation, Interrupted, Progress};
+
+impl Graph {
+
+    // approximates the personalized PageRank of every vertex with respect to the seeds, i.e.
+    // the probability of ending at the vertex when walking randomly from a random seed and
+    // stopping with probability alpha after every step, using the local push algorithm of
+    // Andersen, Chung and Lang: the residual probability mass is pushed from vertex to
+    // vertex until what is left at each vertex is below epsilon times its degree, so only
+    // the region around the seeds is explored; a walk at a vertex without outgoing edges
+    // jumps back to the seeds
+    // returns the scores of the vertices reached, each below its exact value by at most
+    // epsilon times the degree of the vertex
+    pub fn personalized_pagerank(&self, seeds: &[Vertex], alpha: f64, epsilon: f64) -> HashMap<Vertex, f64> {
+        let adj = self.adjacencies();
+        let mut seeds = seeds.to_vec();
+        seeds.sort();
+        seeds.dedup();
+        let mut score: HashMap<Vertex, f64> = HashMap::new();
+        let mut residual: HashMap<Vertex, f64> = HashMap::new();
+        let mut queue = VecDeque::new();
+        for seed in &seeds {
+            residual.insert(*seed, 1.0 / seeds.len() as f64);
+            queue.push_back(*seed);
+        }
+
+        let threshold = |v: Vertex| epsilon * adj[&v].len().max(1) as f64;
+        while let Some(v) = queue.pop_front() {
+            let mass = residual[&v];
+            if mass < threshold(v) {
+                continue;
+            }
+            residual.insert(v, 0.0);
+            *score.entry(v).or_default() += alpha * mass;
+            let targets: Vec<Vertex> = if adj[&v].is_empty() { seeds.clone() } else { adj[&v].iter().cloned().collect() };
+            let share = (1.0 - alpha) * mass / targets.len() as f64;
+            for w in targets {
+                let r = residual.entry(w).or_default();
+                let before = *r;
+                *r += share;
+                // queue vertices once their residual crosses the threshold
+                if before < threshold(w) && *r >= threshold(w) {
+                    queue.push_back(w);
+                }
+            }
+        }
+        score
+    }
+
+    // computes the PageRank of every vertex by power iteration with the given damping factor,
+    // i.e. the probability of being at the vertex when walking randomly and jumping to a
+    // random vertex with probability 1 - damping before every step, and always at vertices
+    // without outgoing edges; the vertices are split between the threads in every iteration,
+    // each score summed in a fixed order so the result does not depend on their number
+    pub fn pagerank(&self, damping: f64, iterations: usize, threads: usize) -> HashMap<Vertex, f64> {
+        self.pagerank_with_progress(damping, iterations, threads, |_| ())
+    }
+
+    // computes the PageRank like pagerank, reporting the progress after every iteration with
+    // the number of vertices whose score changed as the active ones
+    pub fn pagerank_with_progress<F>(&self, damping: f64, iterations: usize, threads: usize, mut on_progress: F) -> HashMap<Vertex, f64>
+        where F: FnMut(&Progress) {
+        completed(self.pagerank_controlled(damping, iterations, threads, |progress| { on_progress(progress); true }))
+    }
+
+    // computes the PageRank like pagerank unless cancelled, which stops it after the current
+    // iteration with the scores reached so far as the partial result
+    pub fn pagerank_cancellable(&self, damping: f64, iterations: usize, threads: usize, cancellation: &Cancellation) -> Result<HashMap<Vertex, f64>, Interrupted<HashMap<Vertex, f64>>> {
+        self.pagerank_controlled(damping, iterations, threads, |_| !cancellation.is_cancelled())
+    }
+
+    // computes the PageRank, passing the progress after every iteration to go_on, which
+    // tells whether to carry on
+    fn pagerank_controlled<F>(&self, damping: f64, iterations: usize, threads: usize, go_on: F) -> Result<HashMap<Vertex, f64>, Interrupted<HashMap<Vertex, f64>>>
+        where F: FnMut(&Progress) -> bool {
+        let (vertices, predecessors) = self.indexed_predecessors();
+        let n = vertices.len();
+        let mut out_degree = vec![0; n];
+        for u in predecessors.iter().flatten() {
+            out_degree[*u] += 1;
+        }
+        let score = vec![1.0 / n as f64; n];
+        power_iteration(vertices, score, iterations, go_on, |score| {
+            let dangling: f64 = (0..n).filter(|u| out_degree[*u] == 0).map(|u| score[u]).sum();
+            let base = (1.0 - damping) / n as f64 + damping * dangling / n as f64;
+            parallel_map(n, threads, |v| {
+                base + damping * predecessors[v].iter().map(|u| score[*u] / out_degree[*u] as f64).sum::<f64>()
+            })
+        })
+    }
+
+    // computes the eigenvector centrality of every vertex by power iteration, scoring each
+    // vertex by the sum of the scores of the vertices with edges to it; iterating with the
+    // adjacency matrix plus the identity, which has the same leading eigenvector, keeps the
+    // scores from oscillating on bipartite graphs; the scores are scaled to unit length and
+    // do not depend on the number of threads
+    pub fn eigenvector_centrality(&self, iterations: usize, threads: usize) -> HashMap<Vertex, f64> {
+        self.eigenvector_centrality_with_progress(iterations, threads, |_| ())
+    }
+
+    // computes the eigenvector centrality like eigenvector_centrality, reporting the progress
+    // after every iteration like pagerank_with_progress
+    pub fn eigenvector_centrality_with_progress<F>(&self, iterations: usize, threads: usize, mut on_progress: F) -> HashMap<Vertex, f64>
+        where F: FnMut(&Progress) {
+        completed(self.eigenvector_centrality_controlled(iterations, threads, |progress| { on_progress(progress); true }))
+    }
+
+    // computes the eigenvector centrality unless cancelled, like pagerank_cancellable
+    pub fn eigenvector_centrality_cancellable(&self, iterations: usize, threads: usize, cancellation: &Cancellation) -> Result<HashMap<Vertex, f64>, Interrupted<HashMap<Vertex, f64>>> {
+        self.eigenvector_centrality_controlled(iterations, threads, |_| !cancellation.is_cancelled())
+    }
+
+    fn eigenvector_centrality_controlled<F>(&self, iterations: usize, threads: usize, go_on: F) -> Result<HashMap<Vertex, f64>, Interrupted<HashMap<Vertex, f64>>>
+        where F: FnMut(&Progress) -> bool {
+        let (vertices, predecessors) = self.indexed_predecessors();
+        let n = vertices.len();
+        let score = vec![1.0 / (n as f64).sqrt(); n];
+        power_iteration(vertices, score, iterations, go_on, |score| {
+            let next = parallel_map(n, threads, |v| score[v] + predecessors[v].iter().map(|u| score[*u]).sum::<f64>());
+            let norm = next.iter().map(|x| x * x).sum::<f64>().sqrt();
+            next.into_iter().map(|x| if norm > 0.0 { x / norm } else { x }).collect()
+        })
+    }
+
+    // the vertices in ascending order with the indices of the vertices with an edge to each,
+    // in ascending order as well; undirected edges lead both ways
+    fn indexed_predecessors(&self) -> (Vec<Vertex>, Vec<Vec<usize>>) {
+        let (vertices, index) = self.indexed_vertices();
+        let mut predecessors: Vec<Vec<usize>> = vec![vec![]; vertices.len()];
+        for (v, adjacent) in self.reverse_adjacencies() {
+            predecessors[index[&v]] = adjacent.iter().map(|u| index[u]).collect();
+            predecessors[index[&v]].sort();
+        }
+        (vertices, predecessors)
+    }
+}
+
+// applies the step to the scores of the vertices the given number of times, passing the
+// progress after every iteration to go_on and stopping early with the scores reached so far
+// as the partial result if it says no
+fn power_iteration<F, S>(vertices: Vec<Vertex>, mut score: Vec<f64>, iterations: usize, mut go_on: F, step: S) -> Result<HashMap<Vertex, f64>, Interrupted<HashMap<Vertex, f64>>>
+    where F: FnMut(&Progress) -> bool, S: Fn(&[f64]) -> Vec<f64> {
+    for iteration in 0..iterations {
+        let next = step(&score);
+        let progress = Progress {
+            round: iteration + 1, rounds: Some(iterations),
+            processed: next.len(), total: next.len(),
+            active: score.iter().zip(&next).filter(|(x, y)| x != y).count()
+        };
+        score = next;
+        if !go_on(&progress) && iteration + 1 < iterations {
+            return Err(Interrupted { progress, partial: Some(vertices.into_iter().zip(score).collect()) });
+        }
+    }
+    Ok(vertices.into_iter().zip(score).collect())
+}
+
+// computes f for 0..n, splitting the range into consecutive chunks for the threads
+fn parallel_map<F: Fn(usize) -> f64 + Sync>(n: usize, threads: usize, f: F) -> Vec<f64> {
+    let chunk = n.div_ceil(threads.max(1)).max(1);
+    thread::scope(|scope| {
+        let f = &f;
+        let workers: Vec<_> = (0..n).step_by(chunk)
+            .map(|start| scope.spawn(move || (start..n.min(start + chunk)).map(f).collect::<Vec<f64>>()))
+            .collect();
+        workers.into_iter().flat_map(|worker| worker.join().unwrap()).collect()
+    })
+}
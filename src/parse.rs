@@ -0,0 +1,207 @@
+use std::fmt;
+use std::fs::File;
+use std::io;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use csr::Mapping;
+use graph::{Edge, Graph, HashMap, HashSet, Vertex, Weight};
+
+// size of the read buffer used by from_file, large enough to keep the number of reads
+// from big edge lists low
+const FILE_BUFFER_SIZE: usize = 1 << 20;
+
+// how parse reads a graph in the format of load and load_weighted
+pub struct ParseOptions {
+    // whether lines starting with # are skipped
+    pub comments: bool,
+    // whether empty lines are skipped
+    pub blank_lines: bool,
+    // whether the vertices are numbered 0..v - 1 instead of 1..v
+    pub zero_based: bool,
+    // whether any anomaly is an error; otherwise malformed lines and edges with unknown
+    // vertices are skipped, extra fields ignored, a repeated edge replaces the earlier one
+    // and missing edge lines at the end of the input are tolerated
+    pub strict: bool
+}
+
+impl Default for ParseOptions {
+    fn default() -> ParseOptions {
+        ParseOptions { comments: true, blank_lines: true, zero_based: false, strict: false }
+    }
+}
+
+// a problem found by parse, with the number of the line it was found on
+#[derive(Debug)]
+pub enum ParseError {
+    Io(io::Error),
+    // the input ended before the line with the number of vertices and edges
+    MissingHeader,
+    // a line that does not consist of the expected number of fields
+    Malformed(usize),
+    VertexOutOfRange(usize, Vertex),
+    DuplicateEdge(usize, Edge),
+    // the input ended after the given number of edges out of the number announced
+    MissingEdges(Vertex, Vertex)
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParseError::Io(ref error) => write!(f, "read error: {}", error),
+            ParseError::MissingHeader => write!(f, "missing number of vertices and edges"),
+            ParseError::Malformed(line) => write!(f, "line {}: malformed", line),
+            ParseError::VertexOutOfRange(line, v) => write!(f, "line {}: vertex {} out of range", line, v),
+            ParseError::DuplicateEdge(line, (u, v)) => write!(f, "line {}: repeated edge {} - {}", line, u, v),
+            ParseError::MissingEdges(found, expected) => write!(f, "found {} of {} edges", found, expected)
+        }
+    }
+}
+
+// reads the lines of the input that are neither comments nor blank lines
+struct Lines<'a, R: 'a> {
+    reader: &'a mut R,
+    options: &'a ParseOptions,
+    number: usize
+}
+
+impl<'a, R: BufRead> Lines<'a, R> {
+
+    // the number and the fields of the next line, or None at the end of the input
+    fn next(&mut self) -> Result<Option<(usize, Vec<String>)>, ParseError> {
+        loop {
+            let mut buffer = String::new();
+            if self.reader.read_line(&mut buffer).map_err(ParseError::Io)? == 0 {
+                return Ok(None);
+            }
+            self.number += 1;
+            let line = buffer.trim();
+            if (self.options.comments && line.starts_with('#')) || (self.options.blank_lines && line.is_empty()) {
+                continue;
+            }
+            return Ok(Some((self.number, line.split_whitespace().map(String::from).collect())));
+        }
+    }
+}
+
+// parses the first count fields as numbers, if there are that many (exactly that many in
+// strict mode) and they are numbers
+fn fields<T: ::std::str::FromStr>(fields: &[String], count: usize, strict: bool) -> Option<Vec<T>> {
+    if fields.len() < count || (strict && fields.len() > count) {
+        return None;
+    }
+    fields[..count].iter().map(|field| field.parse().ok()).collect()
+}
+
+// parses the fields of an edge line, with a weight of 1 unless the graph is weighted
+fn edge(line: &[String], weighted: bool, strict: bool) -> Option<(Edge, Weight)> {
+    let ends = fields::<Vertex>(line, 2, strict && !weighted)?;
+    if !weighted {
+        return Some(((ends[0], ends[1]), 1.0));
+    }
+    fields::<Weight>(&line[2..], 1, strict).map(|weight| ((ends[0], ends[1]), weight[0]))
+}
+
+impl Graph {
+
+    // loads a graph like load or load_weighted, but configurable to skip comments and blank
+    // lines, numbered from 0 and to either reject or work around anomalies in the input
+    pub fn parse<R: BufRead>(reader: &mut R, directed: bool, weighted: bool, options: &ParseOptions) -> Result<Graph, ParseError> {
+        let mut lines = Lines { reader, options, number: 0 };
+        let (v, e) = loop {
+            match lines.next()? {
+                None => return Err(ParseError::MissingHeader),
+                Some((number, line)) => match fields::<Vertex>(&line, 2, options.strict) {
+                    Some(header) => break (header[0], header[1]),
+                    None if options.strict => return Err(ParseError::Malformed(number)),
+                    None => continue
+                }
+            }
+        };
+        let vertices: HashSet<Vertex> = if options.zero_based { (0..v).collect() } else { (1..v+1).collect() };
+
+        let mut edges = HashSet::new();
+        let mut weights: HashMap<Edge, Weight> = HashMap::new();
+        let mut found = 0;
+        while found < e {
+            let (number, line) = match lines.next()? {
+                Some(line) => line,
+                None if options.strict => return Err(ParseError::MissingEdges(found, e)),
+                None => break
+            };
+            let (edge, weight) = match edge(&line, weighted, options.strict) {
+                Some(parsed) => parsed,
+                None if options.strict => return Err(ParseError::Malformed(number)),
+                None => continue
+            };
+            if let Some(v) = [edge.0, edge.1].iter().find(|v| !vertices.contains(v)) {
+                if options.strict {
+                    return Err(ParseError::VertexOutOfRange(number, *v));
+                }
+                continue;
+            }
+            // an undirected edge repeats another one listed in either direction
+            let repeated = edges.contains(&edge) || (!directed && edges.contains(&(edge.1, edge.0)));
+            if repeated && options.strict {
+                return Err(ParseError::DuplicateEdge(number, edge));
+            }
+            edges.insert(edge);
+            if weighted {
+                weights.insert(edge, weight);
+            }
+            found += 1;
+        }
+        Ok(Graph::with_weights(vertices, edges, weights, directed))
+    }
+
+    // loads a graph from a plain edge list as distributed e.g. by SNAP, with one edge per line
+    // and no line announcing the numbers of vertices and edges: the vertices are those found
+    // at the ends of the edges, keeping their numbers, so zero_based has no effect; an
+    // undirected edge listed in both directions is taken once, and in strict mode only a
+    // repetition in the same direction is an error
+    pub fn parse_edge_list<R: BufRead>(reader: &mut R, directed: bool, weighted: bool, options: &ParseOptions) -> Result<Graph, ParseError> {
+        let mut lines = Lines { reader, options, number: 0 };
+        let mut vertices = HashSet::new();
+        let mut edges = HashSet::new();
+        let mut weights: HashMap<Edge, Weight> = HashMap::new();
+        while let Some((number, line)) = lines.next()? {
+            let (edge, weight) = match edge(&line, weighted, options.strict) {
+                Some(parsed) => parsed,
+                None if options.strict => return Err(ParseError::Malformed(number)),
+                None => continue
+            };
+            if !directed && edges.contains(&(edge.1, edge.0)) {
+                continue;
+            }
+            if !edges.insert(edge) && options.strict {
+                return Err(ParseError::DuplicateEdge(number, edge));
+            }
+            vertices.insert(edge.0);
+            vertices.insert(edge.1);
+            if weighted {
+                weights.insert(edge, weight);
+            }
+        }
+        Ok(Graph::with_weights(vertices, edges, weights, directed))
+    }
+
+    // loads a graph in the format of load or load_weighted from a buffered reader, skipping
+    // comments and blank lines
+    pub fn from_reader<R: BufRead>(mut reader: R, directed: bool, weighted: bool) -> Result<Graph, ParseError> {
+        Graph::parse(&mut reader, directed, weighted, &ParseOptions::default())
+    }
+
+    // loads a graph like from_reader from the file at the given path
+    pub fn from_file<P: AsRef<Path>>(path: P, directed: bool, weighted: bool) -> Result<Graph, ParseError> {
+        let file = File::open(path).map_err(ParseError::Io)?;
+        Graph::from_reader(BufReader::with_capacity(FILE_BUFFER_SIZE, file), directed, weighted)
+    }
+
+    // loads a graph like from_file, but maps the file into memory and parses it in place,
+    // which saves copying large files through a read buffer; see csr::Mapping
+    pub fn from_mapped_file<P: AsRef<Path>>(path: P, directed: bool, weighted: bool) -> Result<Graph, ParseError> {
+        let file = File::open(path).map_err(ParseError::Io)?;
+        let bytes = Mapping::new(&file).map_err(ParseError::Io)?;
+        Graph::from_reader(&bytes[..], directed, weighted)
+    }
+}
@@ -0,0 +1,96 @@
+use graph::{Graph, HashMap, HashSet, Vertex, Weight};
+use preconditions::PreconditionError;
+
+impl Graph {
+
+    // splits the vertices into two halves of (almost) equal size using the Kernighan-Lin
+    // heuristic, which repeatedly swaps the pairs of vertices that reduce the total weight of
+    // the edges between the halves the most; edge directions are ignored
+    // returns both halves and the weight of the cut between them, failing with the smallest
+    // edge of negative or NaN weight if there is one
+    pub fn bisect_kernighan_lin(&self) -> Result<(HashSet<Vertex>, HashSet<Vertex>, Weight), PreconditionError> {
+        self.check_nonnegative_weights()?;
+        let mut cost: HashMap<Vertex, HashMap<Vertex, Weight>> = HashMap::new();
+        for edge in self.edges().iter().filter(|&&(v, w)| v != w) {
+            let weight = self.weight(edge);
+            *cost.entry(edge.0).or_default().entry(edge.1).or_insert(0.0) += weight;
+            *cost.entry(edge.1).or_default().entry(edge.0).or_insert(0.0) += weight;
+        }
+        let c = |v: &Vertex, w: &Vertex| cost.get(v).and_then(|adjacent| adjacent.get(w)).cloned().unwrap_or(0.0);
+
+        let mut vertices: Vec<Vertex> = self.vertices().iter().cloned().collect();
+        vertices.sort();
+        let mut side: HashMap<Vertex, bool> = vertices.iter().enumerate()
+            .map(|(i, v)| (*v, i >= vertices.len() / 2))
+            .collect();
+
+        loop {
+            // d is the reduction of the cut weight when moving a vertex to the other side
+            let mut d: HashMap<Vertex, Weight> = HashMap::new();
+            for v in &vertices {
+                let gain = cost.get(v).map_or(0.0, |adjacent| adjacent.iter()
+                    .map(|(w, weight)| if side[v] == side[w] { -weight } else { *weight })
+                    .sum());
+                d.insert(*v, gain);
+            }
+
+            let mut left: Vec<Vertex> = vertices.iter().filter(|v| !side[v]).cloned().collect();
+            let mut right: Vec<Vertex> = vertices.iter().filter(|v| side[v]).cloned().collect();
+            let mut swaps = vec![];
+            while !left.is_empty() && !right.is_empty() {
+                // as edge weights are non-negative, the gain of a pair is bounded by the sum of
+                // its d values, which allows cutting the search short once sorted by d
+                left.sort_by(|a, b| d[b].total_cmp(&d[a]));
+                right.sort_by(|a, b| d[b].total_cmp(&d[a]));
+                let mut best: Option<(usize, usize, Weight)> = None;
+                for (i, a) in left.iter().enumerate() {
+                    if best.is_some_and(|(_, _, gain)| d[a] + d[&right[0]] <= gain) {
+                        break;
+                    }
+                    for (j, b) in right.iter().enumerate() {
+                        if best.is_some_and(|(_, _, gain)| d[a] + d[b] <= gain) {
+                            break;
+                        }
+                        let gain = d[a] + d[b] - 2.0 * c(a, b);
+                        if best.is_none_or(|(_, _, best)| gain > best) {
+                            best = Some((i, j, gain));
+                        }
+                    }
+                }
+                let (i, j, gain) = best.unwrap();
+                let (a, b) = (left.remove(i), right.remove(j));
+                for x in &left {
+                    *d.get_mut(x).unwrap() += 2.0 * c(x, &a) - 2.0 * c(x, &b);
+                }
+                for y in &right {
+                    *d.get_mut(y).unwrap() += 2.0 * c(y, &b) - 2.0 * c(y, &a);
+                }
+                swaps.push((a, b, gain));
+            }
+
+            // perform the prefix of the swaps with the largest total gain
+            let mut total = 0.0;
+            let mut best = (0, 0.0);
+            for (k, &(_, _, gain)) in swaps.iter().enumerate() {
+                total += gain;
+                if total > best.1 + 1e-9 {
+                    best = (k + 1, total);
+                }
+            }
+            if best.0 == 0 {
+                break;
+            }
+            for &(a, b, _) in &swaps[..best.0] {
+                side.insert(a, true);
+                side.insert(b, false);
+            }
+        }
+
+        let (right, left): (HashSet<Vertex>, HashSet<Vertex>) = vertices.iter().cloned().partition(|v| side[v]);
+        let cut = self.edges().iter()
+            .filter(|&&(v, w)| side[&v] != side[&w])
+            .map(|edge| self.weight(edge))
+            .sum();
+        Ok((left, right, cut))
+    }
+}
@@ -0,0 +1,71 @@
+use std::cmp::Reverse;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use graph::{Component, Graph};
+
+impl Graph {
+
+    // the subgraphs induced by the connected components, ignoring edge directions for
+    // finding them but keeping them, the weights and the deterministic mode in the
+    // subgraphs, ordered by their smallest vertices, in a single pass over the edges
+    pub fn component_subgraphs(&self) -> Vec<Graph> {
+        let (vertices, index) = self.indexed_vertices();
+        let labels = self.component_labels();
+        let count = labels.iter().map(|label| label + 1).max().unwrap_or(0);
+        let mut subgraphs: Vec<Graph> = (0..count).map(|_| {
+            let mut subgraph = Graph::new(Component::new(), Default::default(), self.is_directed());
+            subgraph.set_deterministic(self.is_deterministic());
+            subgraph
+        }).collect();
+        for (v, label) in vertices.iter().zip(&labels) {
+            subgraphs[*label].add_vertex(*v);
+        }
+        for edge in self.edges() {
+            let subgraph = &mut subgraphs[labels[index[&edge.0]]];
+            if self.is_weighted() {
+                subgraph.add_weighted_edge(edge.0, edge.1, self.weight(edge));
+            } else {
+                subgraph.add_edge(edge.0, edge.1);
+            }
+        }
+        subgraphs
+    }
+
+    // runs the analysis on the subgraph of every connected component, see
+    // component_subgraphs, on the given number of threads, which take up the components
+    // from the one with the most edges on, so that a giant component does not wait behind
+    // many small ones; returns the vertices of every component with its result, ordered by
+    // the smallest vertices. A panic of the analysis is passed on once all threads are done
+    pub fn per_component<R, F>(&self, threads: usize, analysis: F) -> Vec<(Component, R)>
+        where R: Send, F: Fn(&Graph) -> R + Sync {
+        let subgraphs = self.component_subgraphs();
+        let mut order: Vec<usize> = (0..subgraphs.len()).collect();
+        order.sort_by_key(|c| (Reverse(subgraphs[*c].edges().len()), *c));
+        let next = AtomicUsize::new(0);
+        let mut results: Vec<Option<R>> = (0..subgraphs.len()).map(|_| None).collect();
+        thread::scope(|scope| {
+            let (subgraphs, order, next, analysis) = (&subgraphs, &order, &next, &analysis);
+            let workers: Vec<_> = (0..threads.max(1).min(subgraphs.len().max(1)))
+                .map(|_| scope.spawn(move || {
+                    let mut done = vec![];
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        match order.get(i) {
+                            Some(c) => done.push((*c, analysis(&subgraphs[*c]))),
+                            None => return done
+                        }
+                    }
+                }))
+                .collect();
+            for worker in workers {
+                for (c, result) in worker.join().unwrap() {
+                    results[c] = Some(result);
+                }
+            }
+        });
+        subgraphs.into_iter().zip(results)
+            .map(|(subgraph, result)| (subgraph.vertices().clone(), result.unwrap()))
+            .collect()
+    }
+}
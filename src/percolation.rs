@@ -0,0 +1,132 @@
+use std::cmp::Reverse;
+
+use disjoint_sets::DisjointSets;
+use graph::{Edge, Graph, HashMap, Vertex};
+use random::Random;
+
+// the order in which a robustness experiment removes parts of the graph: random failures
+// of vertices or edges, using the seed, or targeted attacks on the vertices of highest
+// degree or PageRank, computed once on the intact graph
+pub enum Removal {
+    RandomVertices(u64),
+    RandomEdges(u64),
+    HighestDegree,
+    HighestPagerank
+}
+
+// the number of vertices of the largest connected component, ignoring edge directions,
+// before the first removal and after each one, so that largest[i] holds it after i removals
+pub struct RobustnessCurve {
+    pub vertices: usize,
+    pub largest: Vec<usize>
+}
+
+impl RobustnessCurve {
+
+    // the robustness R of Schneider et al.: the average over all removals of the fraction of
+    // the original vertices left in the largest component, at most 1/2 for vertex removals;
+    // graphs that keep a giant component longer score higher
+    pub fn index(&self) -> f64 {
+        let removals = self.largest.len() - 1;
+        if removals == 0 || self.vertices == 0 {
+            return 0.0;
+        }
+        self.largest[1..].iter().sum::<usize>() as f64 / (removals * self.vertices) as f64
+    }
+}
+
+impl Graph {
+
+    // removes all vertices or all edges one after the other in the order of the removal and
+    // tracks the size of the largest component, see RobustnessCurve
+    pub fn robustness(&self, removal: &Removal) -> RobustnessCurve {
+        match *removal {
+            Removal::RandomEdges(seed) => {
+                let mut edges: Vec<Edge> = self.edges().iter().cloned().collect();
+                edges.sort();
+                Random::new(seed).shuffle(&mut edges);
+                self.edge_percolation(&edges)
+            },
+            Removal::RandomVertices(seed) => {
+                let mut order = self.sorted_vertices();
+                Random::new(seed).shuffle(&mut order);
+                self.vertex_percolation(&order)
+            },
+            Removal::HighestDegree => {
+                let degree = self.degree_sequence();
+                let (vertices, position) = self.indexed_vertices();
+                let mut order = vertices.clone();
+                order.sort_by_key(|v| (Reverse(degree[position[v]]), *v));
+                self.vertex_percolation(&order)
+            },
+            Removal::HighestPagerank => {
+                let rank = self.pagerank(0.85, 50, 1);
+                let mut order = self.sorted_vertices();
+                order.sort_by(|v, w| rank[w].partial_cmp(&rank[v]).unwrap().then(v.cmp(w)));
+                self.vertex_percolation(&order)
+            }
+        }
+    }
+
+    // removes the vertices in the given order, which must list every vertex once; the
+    // curve is computed backwards, adding the vertices from the last one removed to the
+    // first one and merging components with disjoint sets, so that it takes near linear
+    // time instead of a search after every removal
+    pub fn vertex_percolation(&self, order: &[Vertex]) -> RobustnessCurve {
+        let position: HashMap<Vertex, usize> = order.iter().enumerate().map(|(i, v)| (*v, i)).collect();
+        let mut adj = vec![vec![]; order.len()];
+        for edge in self.edges() {
+            let (v, w) = (position[&edge.0], position[&edge.1]);
+            // every edge is added with the later of its ends
+            adj[v.min(w)].push(v.max(w));
+        }
+        let mut components = Components::new(order.len());
+        let mut largest = vec![0; order.len() + 1];
+        for v in (0..order.len()).rev() {
+            for &w in &adj[v] {
+                components.union(v, w);
+            }
+            components.largest = components.largest.max(1);
+            largest[v] = components.largest;
+        }
+        RobustnessCurve { vertices: order.len(), largest }
+    }
+
+    // removes the edges in the given order, which must list every edge once, like
+    // vertex_percolation; the vertices stay, so the curve ends at 1
+    pub fn edge_percolation(&self, order: &[Edge]) -> RobustnessCurve {
+        let (vertices, index) = self.indexed_vertices();
+        let mut components = Components::new(vertices.len());
+        components.largest = usize::from(!vertices.is_empty());
+        let mut largest = vec![0; order.len() + 1];
+        largest[order.len()] = components.largest;
+        for (i, edge) in order.iter().enumerate().rev() {
+            components.union(index[&edge.0], index[&edge.1]);
+            largest[i] = components.largest;
+        }
+        RobustnessCurve { vertices: vertices.len(), largest }
+    }
+}
+
+// disjoint sets that know the size of every set and of the largest one
+struct Components {
+    sets: DisjointSets,
+    size: Vec<usize>,
+    largest: usize
+}
+
+impl Components {
+
+    fn new(n: usize) -> Components {
+        Components { sets: DisjointSets::new(n), size: vec![1; n], largest: 0 }
+    }
+
+    fn union(&mut self, v: usize, w: usize) {
+        let size = self.size[self.sets.find(v)] + self.size[self.sets.find(w)];
+        if self.sets.union(v, w) {
+            let root = self.sets.find(v);
+            self.size[root] = size;
+            self.largest = self.largest.max(size);
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use std::fmt;
+
+use disjoint_sets::DisjointSets;
+use graph::{Edge, Graph, HashMap, Vertex, Weight};
+use shortest_paths::Path;
+
+// a violated precondition of an algorithm, with a witness of the violation
+#[derive(Debug)]
+pub enum PreconditionError {
+    // an edge whose weight is negative or NaN
+    NegativeWeight(Edge, Weight),
+    // a cycle, listing its vertices with the first one repeated at the end
+    Cycle(Path),
+    OddDegree(Vertex),
+    // two vertices with edges that are not connected to each other
+    Disconnected(Vertex, Vertex),
+    // an edge whose removal would disconnect its ends
+    Bridge(Vertex, Vertex),
+    // an edge whose weight should be a probability but lies outside 0 to 1
+    NotProbability(Edge, Weight),
+    // an edge of a bipartite graph that does not join its two sides
+    SameSide(Edge)
+}
+
+impl fmt::Display for PreconditionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PreconditionError::NegativeWeight((u, v), weight) if weight.is_nan() => write!(f, "edge {} - {} has weight NaN", u, v),
+            PreconditionError::NegativeWeight((u, v), weight) => write!(f, "edge {} - {} has negative weight {}", u, v, weight),
+            PreconditionError::Cycle(ref cycle) => {
+                let cycle: Vec<String> = cycle.iter().map(|v| v.to_string()).collect();
+                write!(f, "graph contains the cycle {}", cycle.join(" -> "))
+            },
+            PreconditionError::OddDegree(v) => write!(f, "vertex {} has odd degree", v),
+            PreconditionError::Disconnected(u, v) => write!(f, "edges at {} and {} are not connected", u, v),
+            PreconditionError::Bridge(u, v) => write!(f, "edge {} - {} is a bridge", u, v),
+            PreconditionError::NotProbability((u, v), weight) => write!(f, "edge {} - {} has weight {}, which is no probability", u, v, weight),
+            PreconditionError::SameSide((u, v)) => write!(f, "edge {} - {} does not join the two sides", u, v)
+        }
+    }
+}
+
+impl Graph {
+
+    // checks that no edge has a negative or NaN weight, reporting the smallest such edge
+    // otherwise
+    pub fn check_nonnegative_weights(&self) -> Result<(), PreconditionError> {
+        let mut edges: Vec<&Edge> = self.edges().iter().collect();
+        edges.sort();
+        match edges.into_iter().find(|edge| self.weight(edge) < 0.0 || self.weight(edge).is_nan()) {
+            Some(edge) => Err(PreconditionError::NegativeWeight(*edge, self.weight(edge))),
+            None => Ok(())
+        }
+    }
+
+    // checks that the graph has an Eulerian circuit when edge directions are ignored: all
+    // degrees are even and all edges are connected
+    pub fn check_eulerian(&self) -> Result<(), PreconditionError> {
+        let (vertices, index) = self.indexed_vertices();
+        let mut degree = vec![0; vertices.len()];
+        let mut sets = DisjointSets::new(vertices.len());
+        for edge in self.edges() {
+            let (u, v) = (index[&edge.0], index[&edge.1]);
+            degree[u] += 1;
+            degree[v] += 1;
+            sets.union(u, v);
+        }
+        if let Some(i) = (0..vertices.len()).find(|i| degree[*i] % 2 == 1) {
+            return Err(PreconditionError::OddDegree(vertices[i]));
+        }
+        let mut with_edges = (0..vertices.len()).filter(|i| degree[*i] > 0);
+        if let Some(first) = with_edges.next() {
+            let root = sets.find(first);
+            if let Some(other) = with_edges.find(|i| sets.find(*i) != root) {
+                return Err(PreconditionError::Disconnected(vertices[first], vertices[other]));
+            }
+        }
+        Ok(())
+    }
+
+    // finds a cycle among the vertices Kahn's algorithm could not remove, i.e. those left
+    // with positive in-degree: each of them has a predecessor among them, so following
+    // predecessors from any of them eventually runs into a cycle
+    pub fn cycle_among(&self, in_degree: &HashMap<Vertex, usize>) -> Path {
+        let reverse = self.reverse_adjacencies();
+        let remaining = |v: &Vertex| in_degree[v] > 0;
+        let mut walk: Vec<Vertex> = vec![*in_degree.keys().filter(|v| remaining(v)).min().unwrap()];
+        let mut position: HashMap<Vertex, usize> = HashMap::new();
+        loop {
+            let v = walk[walk.len() - 1];
+            if let Some(i) = position.get(&v) {
+                let mut cycle = walk[*i..].to_vec();
+                cycle.reverse();
+                return cycle;
+            }
+            position.insert(v, walk.len() - 1);
+            walk.push(*reverse[&v].iter().filter(|u| remaining(u)).min().unwrap());
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+// how far a long-running algorithm has got, passed to the callback of its _with_progress
+// variant whenever a round or a part of one is done, which is also when its _cancellable
+// variant checks whether to stop
+#[derive(Clone, Debug)]
+pub struct Progress {
+    // the current round, counting from 1: an iteration of a power iteration, a pass of
+    // label propagation or a level of the Louvain method; and the number of rounds if it is
+    // known beforehand
+    pub round: usize,
+    pub rounds: Option<usize>,
+    // the vertices (or nodes of a Louvain level) processed so far in the round, out of all
+    // the round processes
+    pub processed: usize,
+    pub total: usize,
+    // the vertices still to be worked on, e.g. the sources left for all pairs shortest paths
+    // or the vertices that changed their label in the last pass of label propagation; the
+    // algorithm is done once there are none
+    pub active: usize
+}
+
+impl fmt::Display for Progress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.rounds {
+            Some(rounds) => write!(f, "round {}/{}", self.round, rounds)?,
+            None => write!(f, "round {}", self.round)?
+        }
+        write!(f, ": {}/{} processed, {} active", self.processed, self.total, self.active)
+    }
+}
+
+// asks long-running algorithms to stop, either explicitly or once a deadline has passed;
+// clones share the request, so one can be handed to another thread to cancel from there
+#[derive(Clone, Default)]
+pub struct Cancellation {
+    cancelled: Arc<AtomicBool>,
+    deadline: Option<Instant>
+}
+
+impl Cancellation {
+
+    pub fn new() -> Cancellation {
+        Cancellation::default()
+    }
+
+    // a cancellation that takes effect on its own after the given time
+    pub fn with_timeout(timeout: Duration) -> Cancellation {
+        Cancellation { deadline: Some(Instant::now() + timeout), ..Cancellation::new() }
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed) || self.deadline.is_some_and(|deadline| Instant::now() >= deadline)
+    }
+}
+
+// a computation stopped by a Cancellation, with its progress when it stopped and, where
+// meaningful, its partial result: the result of the last round completed
+pub struct Interrupted<T> {
+    pub progress: Progress,
+    pub partial: Option<T>
+}
+
+impl<T> fmt::Display for Interrupted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "interrupted after {}", self.progress)
+    }
+}
+
+// the result of a computation that was never asked to stop
+pub fn completed<T, P>(result: Result<T, Interrupted<P>>) -> T {
+    match result {
+        Ok(result) => result,
+        Err(_) => unreachable!("computation interrupted without a cancellation")
+    }
+}
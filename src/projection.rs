@@ -0,0 +1,42 @@
+use graph::{Edge, Graph, HashMap, HashSet, Vertex, Weight};
+use preconditions::PreconditionError;
+
+impl Graph {
+
+    // the projection of a bipartite graph, such as of users and the items they picked, onto
+    // one of its sides: the graph on the vertices of that side in which two of them are
+    // adjacent if they share a neighbour on the other side, weighted by the number of
+    // neighbours they share. Rather than intersecting the neighbourhoods of all pairs, the
+    // shared neighbours of every vertex are counted by walking two steps from it to the
+    // vertices after it, which takes time in the number of these walks, the sum of the
+    // squared degrees on the other side. Edge directions and weights are ignored
+    // fails with the smallest edge whose ends are both on the side or both off it
+    pub fn project_onto(&self, side: &HashSet<Vertex>) -> Result<Graph, PreconditionError> {
+        let mut edges: Vec<&Edge> = self.edges().iter().collect();
+        edges.sort();
+        if let Some(edge) = edges.into_iter().find(|(v, w)| side.contains(v) == side.contains(w)) {
+            return Err(PreconditionError::SameSide(*edge));
+        }
+        let (vertices, adj) = self.simple_undirected();
+        let mut shared = vec![0; vertices.len()];
+        let mut touched = vec![];
+        let mut weights: HashMap<Edge, Weight> = HashMap::new();
+        for v in (0..vertices.len()).filter(|v| side.contains(&vertices[*v])) {
+            for w in &adj[v] {
+                for u in adj[*w].iter().cloned().filter(|u| *u > v) {
+                    if shared[u] == 0 {
+                        touched.push(u);
+                    }
+                    shared[u] += 1;
+                }
+            }
+            for u in touched.drain(..) {
+                weights.insert((vertices[v], vertices[u]), shared[u] as Weight);
+                shared[u] = 0;
+            }
+        }
+        let vertices = self.vertices().iter().cloned().filter(|v| side.contains(v)).collect();
+        let edges = weights.keys().cloned().collect();
+        Ok(Graph::with_weights(vertices, edges, weights, false))
+    }
+}
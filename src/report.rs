@@ -0,0 +1,184 @@
+use std::cmp::Reverse;
+use std::io;
+use std::io::Write;
+
+use diameter::Diameter;
+use graph::{Graph, Vertex};
+use stats::GraphStats;
+
+// which metrics analysis_report computes beyond the basic statistics, which are cheap; the
+// default computes all of them, with the diameter bounded by a limited number of searches
+pub struct ReportOptions {
+    pub components: bool,
+    pub degrees: bool,
+    // the number of breadth first searches iFUB may take, or None to skip the diameter
+    pub diameter_searches: Option<usize>,
+    pub clustering: bool,
+    // the number of vertices of highest PageRank and degree to list
+    pub top_k: usize,
+    pub threads: usize
+}
+
+impl Default for ReportOptions {
+    fn default() -> ReportOptions {
+        ReportOptions { components: true, degrees: true, diameter_searches: Some(100), clustering: true, top_k: 10, threads: 1 }
+    }
+}
+
+// the sizes of the connected components, ignoring edge directions
+pub struct ComponentSummary {
+    pub count: usize,
+    pub largest: usize,
+    pub isolated: usize
+}
+
+// the clustering of the graph: its triangles, the average local clustering coefficient and
+// the global one, the fraction of the paths of length two closed by a triangle
+pub struct ClusteringSummary {
+    pub triangles: u64,
+    pub average: f64,
+    pub transitivity: f64
+}
+
+// the results of analysis_report, with the metrics not asked for left out
+pub struct AnalysisReport {
+    pub stats: GraphStats,
+    pub components: Option<ComponentSummary>,
+    // the number of vertices of every degree from 0 to the largest one, where degrees are
+    // counted as in degree_sequence
+    pub degree_histogram: Option<Vec<usize>>,
+    pub diameter: Option<Diameter>,
+    pub clustering: Option<ClusteringSummary>,
+    pub top_pagerank: Vec<(Vertex, f64)>,
+    pub top_degree: Vec<(Vertex, usize)>
+}
+
+impl Graph {
+
+    // runs the metrics chosen by the options on the graph, for writing them to a dashboard
+    // with AnalysisReport::write_json
+    pub fn analysis_report(&self, options: &ReportOptions) -> AnalysisReport {
+        let vertices = self.sorted_vertices();
+        let degree = self.degree_sequence();
+
+        let components = if options.components {
+            let labels = self.component_labels();
+            let mut sizes = vec![0; labels.iter().map(|label| label + 1).max().unwrap_or(0)];
+            for label in labels {
+                sizes[label] += 1;
+            }
+            Some(ComponentSummary {
+                count: sizes.len(),
+                largest: sizes.iter().cloned().max().unwrap_or(0),
+                isolated: sizes.iter().filter(|size| **size == 1).count()
+            })
+        } else {
+            None
+        };
+
+        let degree_histogram = if options.degrees {
+            let mut histogram = vec![0; degree.iter().map(|d| d + 1).max().unwrap_or(0)];
+            for d in &degree {
+                histogram[*d] += 1;
+            }
+            Some(histogram)
+        } else {
+            None
+        };
+
+        let clustering = if options.clustering {
+            let coefficients = self.clustering_coefficients(options.threads);
+            let triangles = self.triangle_count(options.threads);
+            let average = if vertices.is_empty() {
+                0.0
+            } else {
+                coefficients.iter().fold(0.0, |sum, (_, c)| sum + c) / vertices.len() as f64
+            };
+            // the paths of length two, through distinct neighbours
+            let (_, adj) = self.dense_undirected();
+            let paths: u64 = adj.into_iter()
+                .map(|mut adjacent| {
+                    adjacent.sort();
+                    adjacent.dedup();
+                    let d = adjacent.len() as u64;
+                    d * d.saturating_sub(1) / 2
+                })
+                .sum();
+            let transitivity = if paths == 0 { 0.0 } else { 3.0 * triangles as f64 / paths as f64 };
+            Some(ClusteringSummary { triangles, average, transitivity })
+        } else {
+            None
+        };
+
+        let top_pagerank = if options.top_k > 0 {
+            let rank = self.pagerank(0.85, 50, options.threads);
+            let mut top: Vec<(Vertex, f64)> = vertices.iter().map(|v| (*v, rank[v])).collect();
+            top.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap().then(a.0.cmp(&b.0)));
+            top.truncate(options.top_k);
+            top
+        } else {
+            vec![]
+        };
+        let mut top_degree: Vec<(Vertex, usize)> = vertices.iter().cloned().zip(degree.iter().cloned()).collect();
+        top_degree.sort_by_key(|&(v, d)| (Reverse(d), v));
+        top_degree.truncate(options.top_k);
+
+        AnalysisReport {
+            stats: self.stats(),
+            components,
+            degree_histogram,
+            diameter: options.diameter_searches.map(|searches| self.diameter_ifub(searches)),
+            clustering,
+            top_pagerank,
+            top_degree
+        }
+    }
+}
+
+impl AnalysisReport {
+
+    // writes the report as a JSON object, leaving out the metrics not computed; numbers
+    // that JSON cannot represent, like infinite ones, are written as null
+    pub fn write_json<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let stats = &self.stats;
+        writeln!(writer, "{{")?;
+        write!(writer, r#"  "directed": {},"#, stats.directed)?;
+        write!(writer, r#" "vertices": {}, "edges": {}, "density": {},"#, stats.order, stats.size, number(stats.density))?;
+        writeln!(writer, r#" "self_loops": {}, "parallel_edges": {},"#, stats.self_loops, stats.parallel_edges)?;
+        write!(writer, r#"  "degree": {{"min": {}, "max": {}, "average": {}}}"#, stats.min_degree, stats.max_degree, number(stats.average_degree))?;
+        if let Some(ref components) = self.components {
+            write!(writer, ",\n  \"components\": {{\"count\": {}, \"largest\": {}, \"isolated\": {}}}",
+                   components.count, components.largest, components.isolated)?;
+        }
+        if let Some(ref histogram) = self.degree_histogram {
+            let counts: Vec<String> = histogram.iter().map(|count| count.to_string()).collect();
+            write!(writer, ",\n  \"degree_histogram\": [{}]", counts.join(", "))?;
+        }
+        if let Some(ref diameter) = self.diameter {
+            write!(writer, ",\n  \"diameter\": {{\"lower\": {}, \"upper\": {}, \"exact\": {}, \"searches\": {}}}",
+                   diameter.lower, diameter.upper, diameter.is_exact(), diameter.searches)?;
+        }
+        if let Some(ref clustering) = self.clustering {
+            write!(writer, ",\n  \"clustering\": {{\"triangles\": {}, \"average\": {}, \"transitivity\": {}}}",
+                   clustering.triangles, number(clustering.average), number(clustering.transitivity))?;
+        }
+        if !self.top_pagerank.is_empty() {
+            let top: Vec<String> = self.top_pagerank.iter()
+                .map(|(v, rank)| format!("{{\"vertex\": {}, \"pagerank\": {}}}", v, number(*rank)))
+                .collect();
+            write!(writer, ",\n  \"top_pagerank\": [{}]", top.join(", "))?;
+        }
+        if !self.top_degree.is_empty() {
+            let top: Vec<String> = self.top_degree.iter()
+                .map(|(v, degree)| format!("{{\"vertex\": {}, \"degree\": {}}}", v, degree))
+                .collect();
+            write!(writer, ",\n  \"top_degree\": [{}]", top.join(", "))?;
+        }
+        writeln!(writer, "\n}}")
+    }
+}
+
+// a number in JSON, which has no infinite or undefined numbers
+pub fn number(x: f64) -> String {
+    if x.is_finite() { x.to_string() } else { "null".to_string() }
+}
@@ -0,0 +1,143 @@
+use std::io::BufRead;
+
+use graph::hash_map::Entry;
+use graph::{Graph, HashMap, HashSet, Vertex};
+use isomorphism::Mapping;
+use tuple_reader::TupleReader;
+
+// labels of the vertices of a graph, such as the kinds of gates of a circuit; vertices
+// without a label are unlabeled
+pub type Labels = HashMap<Vertex, String>;
+
+// a rule replacing every occurrence of the pattern by the replacement: the vertices the
+// two graphs have in common are kept with all their other edges, the vertices only in the
+// pattern are deleted with all their edges, the vertices only in the replacement are added,
+// and the edges of the pattern are replaced by those of the replacement. Pattern vertices
+// with a label only match vertices with the same label, the others match any vertex; a
+// label of the replacement is given to the vertex, kept or added
+pub struct RewriteRule {
+    pub pattern: Graph,
+    pub pattern_labels: Labels,
+    pub replacement: Graph,
+    pub replacement_labels: Labels
+}
+
+// the result of rewriting a graph: the final graph and labels, the number of rules
+// applied, and whether no rule matched any more, as opposed to running out of steps
+pub struct Rewriting {
+    pub graph: Graph,
+    pub labels: Labels,
+    pub steps: usize,
+    pub fixpoint: bool
+}
+
+// loads labels from an input stream: first line contains their number k, next k lines
+// contain a vertex followed by its label
+pub fn load_labels<T: BufRead>(reader: &mut T) -> Labels {
+    let mut labels = HashMap::new();
+    for _ in 0..reader.next_number() {
+        let mut buffer = String::new();
+        reader.read_line(&mut buffer).unwrap();
+        let mut iter = buffer.split_whitespace();
+        let v = iter.next().unwrap().parse().unwrap();
+        labels.insert(v, iter.next().unwrap().to_string());
+    }
+    labels
+}
+
+impl RewriteRule {
+
+    // loads a rule from an input stream: the pattern as a directed or undirected graph in
+    // the format of Graph::load followed by its labels in the format of load_labels, then
+    // the replacement and its labels; vertices of the same number in both are kept
+    pub fn load<T: BufRead>(reader: &mut T, directed: bool) -> RewriteRule {
+        let pattern = Graph::load(reader, directed);
+        let pattern_labels = load_labels(reader);
+        let replacement = Graph::load(reader, directed);
+        let replacement_labels = load_labels(reader);
+        RewriteRule { pattern, pattern_labels, replacement, replacement_labels }
+    }
+
+    // finds an occurrence of the pattern in the labeled graph
+    pub fn find(&self, graph: &Graph, labels: &Labels) -> Option<Mapping> {
+        graph.find_subgraph(&self.pattern, |p, v| {
+            self.pattern_labels.get(&p).is_none_or(|label| labels.get(&v) == Some(label))
+        })
+    }
+
+    // replaces the occurrence of the pattern given by the mapping; added vertices get the
+    // numbers following the largest vertex of the graph
+    pub fn apply(&self, graph: &Graph, labels: &Labels, mapping: &Mapping) -> (Graph, Labels) {
+        let deleted: HashSet<Vertex> = self.pattern.vertices().iter()
+            .filter(|p| !self.replacement.vertices().contains(p))
+            .map(|p| mapping[p])
+            .collect();
+        let mut rewritten = graph.clone();
+        for &(a, b) in self.pattern.edges() {
+            rewritten.remove_edge(mapping[&a], mapping[&b]);
+        }
+        if !deleted.is_empty() {
+            let vertices = rewritten.vertices().iter().filter(|v| !deleted.contains(v)).cloned().collect();
+            let edges = rewritten.edges().iter().filter(|&&(v, w)| !deleted.contains(&v) && !deleted.contains(&w)).cloned().collect();
+            let weights = rewritten.weights().iter().filter(|(&(v, w), _)| !deleted.contains(&v) && !deleted.contains(&w))
+                .map(|(edge, weight)| (*edge, *weight))
+                .collect();
+            rewritten = Graph::with_weights(vertices, edges, weights, graph.is_directed());
+            rewritten.set_deterministic(graph.is_deterministic());
+        }
+        let mut labels: Labels = labels.iter().filter(|(v, _)| !deleted.contains(v)).map(|(v, label)| (*v, label.clone())).collect();
+
+        let mut image = mapping.clone();
+        let mut next = graph.vertices().iter().max().map_or(1, |v| v.checked_add(1).expect("No vertex numbers left!"));
+        for r in self.replacement.sorted_vertices() {
+            if let Entry::Vacant(entry) = image.entry(r) {
+                entry.insert(next);
+                rewritten.add_vertex(next);
+                next = next.checked_add(1).expect("No vertex numbers left!");
+            }
+            if let Some(label) = self.replacement_labels.get(&r) {
+                labels.insert(image[&r], label.clone());
+            }
+        }
+        let mut edges: Vec<_> = self.replacement.edges().iter().cloned().collect();
+        edges.sort();
+        for edge in edges {
+            if self.replacement.is_weighted() {
+                rewritten.add_weighted_edge(image[&edge.0], image[&edge.1], self.replacement.weight(&edge));
+            } else {
+                rewritten.add_edge(image[&edge.0], image[&edge.1]);
+            }
+        }
+        (rewritten, labels)
+    }
+}
+
+impl Graph {
+
+    // applies the rules to the labeled graph until none of them matches or max_steps rules
+    // have been applied, which guards against rules that keep matching their own results;
+    // every step applies the first rule that matches, at its first occurrence
+    pub fn rewrite(&self, labels: &Labels, rules: &[RewriteRule], max_steps: usize) -> Rewriting {
+        let mut rewriting = Rewriting { graph: self.clone(), labels: labels.clone(), steps: 0, fixpoint: false };
+        while rewriting.steps < max_steps {
+            let step = rules.iter().find_map(|rule| {
+                rule.find(&rewriting.graph, &rewriting.labels).map(|mapping| rule.apply(&rewriting.graph, &rewriting.labels, &mapping))
+            });
+            match step {
+                Some((graph, labels)) => {
+                    rewriting.graph = graph;
+                    rewriting.labels = labels;
+                    rewriting.steps += 1;
+                },
+                None => {
+                    rewriting.fixpoint = true;
+                    break;
+                }
+            }
+        }
+        if !rewriting.fixpoint {
+            rewriting.fixpoint = rules.iter().all(|rule| rule.find(&rewriting.graph, &rewriting.labels).is_none());
+        }
+        rewriting
+    }
+}
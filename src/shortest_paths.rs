@@ -0,0 +1,582 @@
+use std::cmp::Ordering;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use graph::{Edge, Graph, HashMap, HashSet, Vertex, Weight, WeightedAdjacencies};
+use indexed_heap::IndexedBinaryHeap;
+use preconditions::PreconditionError;
+
+// a path through the graph, listing the vertices in the order they are visited
+pub type Path = Vec<Vertex>;
+
+// entry of the priority queue used by Dijkstra's algorithm, ordered such that
+// the std max-heap pops the vertex (or vertex index) with the smallest distance first
+#[derive(PartialEq)]
+pub struct QueueEntry<T = Vertex> {
+    pub distance: Weight,
+    pub vertex: T
+}
+
+impl<T: Ord> Eq for QueueEntry<T> {}
+
+impl<T: Ord> Ord for QueueEntry<T> {
+    fn cmp(&self, other: &QueueEntry<T>) -> Ordering {
+        other.distance.total_cmp(&self.distance).then_with(|| other.vertex.cmp(&self.vertex))
+    }
+}
+
+impl<T: Ord> PartialOrd for QueueEntry<T> {
+    fn partial_cmp(&self, other: &QueueEntry<T>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// a path found by a search from a source to a target: its length (its width for
+// widest_path), the vertices and the edges along it, the number of edges the search
+// relaxed, i.e. looked at from a vertex it took up, as a measure of its work, and the
+// predecessors of the vertices it labelled on the way, through which the paths to these
+// can be followed too, shortest for those the search was done with before the target
+#[derive(Clone, Debug)]
+pub struct PathResult {
+    pub distance: Weight,
+    pub path: Path,
+    pub edges: Vec<Edge>,
+    pub relaxations: usize,
+    previous: HashMap<Vertex, Vertex>
+}
+
+impl PathResult {
+
+    // the result for the path, with the predecessors of the vertices labelled on the way,
+    // which are made to agree with the path
+    pub fn new(distance: Weight, path: Path, relaxations: usize, mut previous: HashMap<Vertex, Vertex>) -> PathResult {
+        let edges: Vec<Edge> = path.windows(2).map(|edge| (edge[0], edge[1])).collect();
+        previous.extend(edges.iter().map(|(v, w)| (*w, *v)));
+        if let Some(source) = path.first() {
+            previous.remove(source);
+        }
+        PathResult { distance, path, edges, relaxations, previous }
+    }
+
+    // the vertex before v on the way to it from the source, or None if v is the source or
+    // the search did not label it
+    pub fn predecessor(&self, v: Vertex) -> Option<Vertex> {
+        self.previous.get(&v).cloned()
+    }
+
+    pub fn predecessors(&self) -> &HashMap<Vertex, Vertex> {
+        &self.previous
+    }
+
+    // the path from the source to v through the predecessors, or None if the search did not
+    // label v
+    pub fn path_to(&self, v: Vertex) -> Option<Path> {
+        if self.path.first() != Some(&v) && !self.previous.contains_key(&v) {
+            return None;
+        }
+        Some(trace_path(&self.previous, v))
+    }
+}
+
+// the nearest of several sources for every vertex reachable from them, as computed by
+// multi_source_dijkstra
+pub struct NearestSources {
+    nearest: HashMap<Vertex, (Vertex, Weight)>
+}
+
+impl NearestSources {
+
+    // the source nearest to v and its distance, or None if v cannot be reached from any
+    pub fn nearest(&self, v: Vertex) -> Option<(Vertex, Weight)> {
+        self.nearest.get(&v).cloned()
+    }
+
+    // the number of vertices reachable from the sources
+    pub fn len(&self) -> usize {
+        self.nearest.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nearest.is_empty()
+    }
+}
+
+// the routing tables of link-state routing: for every source the neighbour to forward to
+// on a shortest path to every destination reachable from it, with the distance, as
+// computed by routing_table
+pub struct RoutingTable {
+    routes: HashMap<Vertex, HashMap<Vertex, (Vertex, Weight)>>
+}
+
+impl RoutingTable {
+
+    // the neighbour of source a packet for destination goes to next, or None if source
+    // cannot reach destination or is destination itself
+    pub fn next_hop(&self, source: Vertex, destination: Vertex) -> Option<Vertex> {
+        self.route(source, destination).map(|(hop, _)| hop)
+    }
+
+    // the next hop from source to destination together with the distance between them
+    pub fn route(&self, source: Vertex, destination: Vertex) -> Option<(Vertex, Weight)> {
+        self.routes.get(&source).and_then(|routes| routes.get(&destination)).cloned()
+    }
+
+    // the routes of source to all destinations it reaches, in ascending order of the
+    // destinations
+    pub fn routes_from(&self, source: Vertex) -> Vec<(Vertex, Vertex, Weight)> {
+        let mut routes: Vec<_> = self.routes.get(&source).map_or(vec![], |routes| {
+            routes.iter().map(|(destination, (hop, distance))| (*destination, *hop, *distance)).collect()
+        });
+        routes.sort_by_key(|route| route.0);
+        routes
+    }
+}
+
+impl Graph {
+
+    // finds a shortest path from source to target using Dijkstra's algorithm; fails if
+    // there are edges with negative weights
+    // returns the path, or None if target is unreachable
+    pub fn shortest_path(&self, source: Vertex, target: Vertex) -> Result<Option<PathResult>, PreconditionError> {
+        self.check_nonnegative_weights()?;
+        Ok(dijkstra(&self.weighted_adjacencies(), source, target, |_| true, |_| true))
+    }
+
+    // finds a shortest path from source to target like shortest_path, but only through the
+    // vertices and along the edges accepted by the given predicates
+    pub fn shortest_path_filtered<V, E>(&self, source: Vertex, target: Vertex, vertex_filter: V, edge_filter: E) -> Option<PathResult>
+        where V: Fn(Vertex) -> bool, E: Fn(Edge) -> bool {
+        if !vertex_filter(source) {
+            return None;
+        }
+        dijkstra(&self.weighted_adjacencies(), source, target, vertex_filter, edge_filter)
+    }
+
+    // finds a shortest path from source to target using the A* algorithm, which explores
+    // vertices in order of their distance from the source plus the heuristic's estimate of
+    // their distance to the target; the estimate must never exceed the actual distance, and
+    // ought to be consistent (h(v) <= w(v, u) + h(u) for every edge) for best performance
+    // returns None if target is unreachable or either end is not in the graph
+    pub fn astar<H: Fn(Vertex) -> Weight>(&self, source: Vertex, target: Vertex, heuristic: H) -> Option<PathResult> {
+        let adj = self.weighted_adjacencies();
+        let (vertices, index) = self.indexed_vertices();
+        if !index.contains_key(&source) || !index.contains_key(&target) {
+            return None;
+        }
+        let mut distance = HashMap::new();
+        let mut previous = HashMap::new();
+        let mut queue = IndexedBinaryHeap::new(vertices.len());
+        let mut relaxations = 0;
+        distance.insert(source, 0.0);
+        queue.push(index[&source], heuristic(source));
+        // a vertex reached by a shorter path after it was taken up goes back onto the queue,
+        // as it may be with an inconsistent heuristic
+        while let Some((i, _)) = queue.pop() {
+            let v = vertices[i];
+            if v == target {
+                return Some(PathResult::new(distance[&v], trace_path(&previous, target), relaxations, previous));
+            }
+            let d = distance[&v];
+            relaxations += adj[&v].len();
+            for (w, weight) in &adj[&v] {
+                let alt = d + weight;
+                if distance.get(w).is_none_or(|current| alt < *current) {
+                    distance.insert(*w, alt);
+                    previous.insert(*w, v);
+                    queue.push(index[w], alt + heuristic(*w));
+                }
+            }
+        }
+        None
+    }
+
+    // finds a shortest path from source to target using bidirectional Dijkstra, searching
+    // forwards from the source and backwards from the target at the same time; mu is the
+    // length of the best path found where the searches touch, and once the smallest
+    // distances left in both queues add up to at least mu no shorter path can exist
+    // returns None if target is unreachable or either end is not in the graph
+    pub fn bidirectional_shortest_path(&self, source: Vertex, target: Vertex) -> Option<PathResult> {
+        let adj = [self.weighted_adjacencies(), self.reverse_weighted_adjacencies()];
+        let (vertices, index) = self.indexed_vertices();
+        if !index.contains_key(&source) || !index.contains_key(&target) {
+            return None;
+        }
+        let mut distance = [HashMap::new(), HashMap::new()];
+        let mut previous = [HashMap::new(), HashMap::new()];
+        let mut queue = [IndexedBinaryHeap::new(vertices.len()), IndexedBinaryHeap::new(vertices.len())];
+        for (side, start) in [source, target].iter().enumerate() {
+            distance[side].insert(*start, 0.0);
+            queue[side].push(index[start], 0.0);
+        }
+        let mut mu = if source == target { Some((0.0, source)) } else { None };
+        let mut relaxations = 0;
+
+        loop {
+            let top = |side: usize| queue[side].peek().map(|(_, d): (usize, Weight)| d);
+            let side = match (top(0), top(1)) {
+                (Some(f), Some(b)) => {
+                    if mu.is_some_and(|(mu, _)| f + b >= mu) {
+                        break;
+                    }
+                    if f <= b { 0 } else { 1 }
+                },
+                _ => break
+            };
+            let (i, d) = queue[side].pop().unwrap();
+            let v = vertices[i];
+            relaxations += adj[side][&v].len();
+            for (w, weight) in &adj[side][&v] {
+                let alt = d + weight;
+                if distance[side].get(w).is_none_or(|current| alt < *current) {
+                    distance[side].insert(*w, alt);
+                    previous[side].insert(*w, v);
+                    queue[side].decrease_key(index[w], alt);
+                }
+                if let Some(other) = distance[1 - side].get(w) {
+                    let length = alt + other;
+                    if mu.is_none_or(|(mu, _)| length < mu) {
+                        mu = Some((length, *w));
+                    }
+                }
+            }
+        }
+
+        let (length, meeting) = mu?;
+        let mut path = trace_path(&previous[0], meeting);
+        let mut vertex = meeting;
+        while let Some(next) = previous[1].get(&vertex) {
+            path.push(*next);
+            vertex = *next;
+        }
+        let [forward, _] = previous;
+        Some(PathResult::new(length, path, relaxations, forward))
+    }
+
+    // finds a widest path from source to target, whose lightest edge is as heavy as possible,
+    // using Dijkstra's algorithm with the width of the widest path found so far in place of
+    // the distance, exploring the vertex reached by the widest path first, and of equal
+    // widths the smallest vertex
+    // returns the path with its width as the distance, infinite when source and target
+    // coincide, or None if target is unreachable or source is not in the graph
+    pub fn widest_path(&self, source: Vertex, target: Vertex) -> Option<PathResult> {
+        let adj = self.weighted_adjacencies();
+        let (vertices, index) = self.indexed_vertices();
+        let mut width = HashMap::new();
+        let mut previous = HashMap::new();
+        let mut queue = IndexedBinaryHeap::new(vertices.len());
+        let mut relaxations = 0;
+        width.insert(source, Weight::INFINITY);
+        queue.push(*index.get(&source)?, Reverse(Weight::INFINITY));
+        while let Some((i, Reverse(b))) = queue.pop() {
+            let v = vertices[i];
+            if v == target {
+                return Some(PathResult::new(b, trace_path(&previous, target), relaxations, previous));
+            }
+            relaxations += adj[&v].len();
+            for (w, weight) in &adj[&v] {
+                let alt = b.min(*weight);
+                if *w != source && width.get(w).is_none_or(|current| alt > *current) {
+                    width.insert(*w, alt);
+                    previous.insert(*w, v);
+                    queue.decrease_key(index[w], Reverse(alt));
+                }
+            }
+        }
+        None
+    }
+
+    // finds the nearest of the sources for every vertex with a single run of Dijkstra's
+    // algorithm that starts from all sources at once, each at distance 0 and labeling the
+    // vertices it reaches first; of several sources at the same distance, the smallest one
+    // is taken; edge weights must not be negative, and sources not in the graph are ignored
+    pub fn multi_source_dijkstra(&self, sources: &[Vertex]) -> NearestSources {
+        let adj = self.weighted_adjacencies();
+        let mut nearest: HashMap<Vertex, (Vertex, Weight)> = HashMap::new();
+        let mut queue = BinaryHeap::new();
+        for s in sources.iter().filter(|s| adj.contains_key(s)) {
+            if nearest.get(s).is_none_or(|(source, _)| s < source) {
+                nearest.insert(*s, (*s, 0.0));
+                queue.push(QueueEntry { distance: 0.0, vertex: (*s, *s) });
+            }
+        }
+        // entries are ordered by distance and then by source, so the first one popped for
+        // a vertex is final
+        while let Some(QueueEntry { distance: d, vertex: (source, v) }) = queue.pop() {
+            if nearest[&v] != (source, d) {
+                continue;
+            }
+            for (w, weight) in &adj[&v] {
+                let alt = d + weight;
+                let better = nearest.get(w).is_none_or(|&(current, distance)| alt < distance || (alt == distance && source < current));
+                if better {
+                    nearest.insert(*w, (source, alt));
+                    queue.push(QueueEntry { distance: alt, vertex: (source, *w) });
+                }
+            }
+        }
+        NearestSources { nearest }
+    }
+
+    // the tree of the shortest paths from source found by Dijkstra's algorithm, as a
+    // directed graph with an edge from the predecessor of every vertex reachable from source
+    // to it, which keeps its weight; edge weights must not be negative, and the tree is
+    // empty if source is not in the graph
+    pub fn shortest_path_tree(&self, source: Vertex) -> Graph {
+        let (_, previous) = shortest_path_tree(&self.weighted_adjacencies(), source);
+        let mut tree = Graph::new(HashSet::new(), HashSet::new(), true);
+        if !self.vertices().contains(&source) {
+            return tree;
+        }
+        tree.add_vertex(source);
+        for (w, v) in previous {
+            tree.add_weighted_edge(v, w, self.weight(&self.edge_between(v, w)));
+        }
+        tree
+    }
+
+    // computes the routing table of every vertex from its shortest path tree, i.e. with a
+    // run of Dijkstra's algorithm from every vertex; the next hop to a destination is the
+    // first vertex after the source on the path to it in the tree, which the hops of the
+    // vertices on that path share
+    pub fn routing_table(&self) -> RoutingTable {
+        let adj = self.weighted_adjacencies();
+        let mut routes = HashMap::new();
+        for source in self.sorted_vertices() {
+            let (distance, previous) = shortest_path_tree(&adj, source);
+            let mut hops: HashMap<Vertex, Vertex> = HashMap::new();
+            for destination in previous.keys() {
+                let mut path = vec![*destination];
+                let mut hop = None;
+                while hop.is_none() {
+                    let v = path[path.len() - 1];
+                    if let Some(known) = hops.get(&v) {
+                        hop = Some(*known);
+                    } else if previous[&v] == source {
+                        hop = Some(v);
+                    } else {
+                        path.push(previous[&v]);
+                    }
+                }
+                for v in path {
+                    hops.insert(v, hop.unwrap());
+                }
+            }
+            let table = hops.into_iter().map(|(destination, hop)| (destination, (hop, distance[&destination]))).collect();
+            routes.insert(source, table);
+        }
+        RoutingTable { routes }
+    }
+
+    // the k vertices other than source nearest to it with their distances, in increasing
+    // order of distance and of the vertices for equal ones, settling only as many vertices as
+    // it takes instead of all of them: by breadth first search a layer at a time for
+    // unweighted graphs, counting edges, and by Dijkstra's algorithm stopped as soon as k
+    // vertices are settled otherwise, see shortest_paths::k_nearest; fewer are returned only
+    // if fewer are reachable. Edge weights must not be negative
+    pub fn k_nearest(&self, source: Vertex, k: usize) -> Vec<(Vertex, Weight)> {
+        if self.is_weighted() {
+            return k_nearest(&self.weighted_adjacencies(), source, k);
+        }
+        let adj = self.adjacencies();
+        let mut found = vec![];
+        if !adj.contains_key(&source) {
+            return found;
+        }
+        let mut seen: HashSet<Vertex> = vec![source].into_iter().collect();
+        let mut layer = vec![source];
+        let mut distance = 0.0;
+        while found.len() < k && !layer.is_empty() {
+            distance += 1.0;
+            let mut next: Vec<Vertex> = layer.iter().flat_map(|v| adj[v].iter().cloned()).filter(|w| seen.insert(*w)).collect();
+            next.sort();
+            found.extend(next.iter().take(k - found.len()).map(|w| (*w, distance)));
+            layer = next;
+        }
+        found
+    }
+
+    // finds the k targets nearest to source with their distances, in increasing order of
+    // distance and of the targets for equal ones, by Dijkstra's algorithm stopped as soon as
+    // k targets are settled, so that only the vicinity of source is explored; edge weights
+    // must not be negative
+    pub fn k_nearest_targets(&self, source: Vertex, targets: &HashSet<Vertex>, k: usize) -> Vec<(Vertex, Weight)> {
+        settle_nearest(&self.weighted_adjacencies(), source, k, |v| targets.contains(&v))
+    }
+
+    // finds up to k loopless paths from source to target in increasing order of length
+    // using Yen's algorithm: every further path branches off one of the paths found so
+    // far at some spur vertex, from which a shortest path is searched that avoids the
+    // prefix before the spur vertex and all edges already taken there by earlier paths
+    pub fn k_shortest_paths(&self, source: Vertex, target: Vertex, k: usize) -> Vec<(Weight, Path)> {
+        let adj = self.weighted_adjacencies();
+        let mut paths: Vec<(Weight, Path)> = vec![];
+        let mut candidates: Vec<(Weight, Path)> = vec![];
+        match dijkstra(&adj, source, target, |_| true, |_| true) {
+            Some(found) if k > 0 => paths.push((found.distance, found.path)),
+            _ => return paths
+        }
+
+        while paths.len() < k {
+            let previous = paths[paths.len() - 1].1.clone();
+            for i in 0..previous.len() - 1 {
+                let root = &previous[..i+1];
+                let mut excluded_edges = HashSet::new();
+                for (_, path) in &paths {
+                    if path.len() > i + 1 && &path[..i+1] == root {
+                        excluded_edges.insert((path[i], path[i+1]));
+                        if !self.is_directed() {
+                            excluded_edges.insert((path[i+1], path[i]));
+                        }
+                    }
+                }
+                let excluded_vertices: HashSet<Vertex> = root[..i].iter().cloned().collect();
+                let spur = dijkstra(&adj, root[i], target,
+                                    |v| !excluded_vertices.contains(&v), |e| !excluded_edges.contains(&e));
+                if let Some(spur) = spur {
+                    let root_length: Weight = root.windows(2).map(|edge| adj[&edge[0]][&edge[1]]).sum();
+                    let mut path = root.to_vec();
+                    path.extend_from_slice(&spur.path[1..]);
+                    let candidate = (root_length + spur.distance, path);
+                    if !candidates.contains(&candidate) && !paths.iter().any(|p| p.1 == candidate.1) {
+                        candidates.push(candidate);
+                    }
+                }
+            }
+            if candidates.is_empty() {
+                break;
+            }
+            // the shortest candidate (ties broken by comparing paths) becomes the next path
+            candidates.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| b.1.cmp(&a.1)));
+            paths.push(candidates.pop().unwrap());
+        }
+        paths
+    }
+}
+
+// the vertices of the adjacencies in ascending order with the position of every vertex
+// among them, which the indexed heaps of Dijkstra's algorithm hold
+fn indexed_vertices(adj: &WeightedAdjacencies) -> (Vec<Vertex>, HashMap<Vertex, usize>) {
+    let mut vertices: Vec<Vertex> = adj.keys().cloned().collect();
+    vertices.sort();
+    let index = vertices.iter().enumerate().map(|(i, v)| (*v, i)).collect();
+    (vertices, index)
+}
+
+// Dijkstra's algorithm from source to target, ignoring the vertices and edges rejected by
+// the filters; None if target is unreachable or source is not in the graph
+pub fn dijkstra<V, E>(adj: &WeightedAdjacencies, source: Vertex, target: Vertex,
+                  vertex_filter: V, edge_filter: E) -> Option<PathResult>
+    where V: Fn(Vertex) -> bool, E: Fn(Edge) -> bool {
+    let (vertices, index) = indexed_vertices(adj);
+    let mut distance = HashMap::new();
+    let mut previous = HashMap::new();
+    let mut queue = IndexedBinaryHeap::new(vertices.len());
+    let mut relaxations = 0;
+    distance.insert(source, 0.0);
+    queue.push(*index.get(&source)?, 0.0);
+    while let Some((i, d)) = queue.pop() {
+        let v = vertices[i];
+        if v == target {
+            return Some(PathResult::new(d, trace_path(&previous, target), relaxations, previous));
+        }
+        for (w, weight) in &adj[&v] {
+            if !vertex_filter(*w) || !edge_filter((v, *w)) {
+                continue;
+            }
+            relaxations += 1;
+            let alt = d + weight;
+            if distance.get(w).is_none_or(|current| alt < *current) {
+                distance.insert(*w, alt);
+                previous.insert(*w, v);
+                queue.decrease_key(index[w], alt);
+            }
+        }
+    }
+    None
+}
+
+// the k vertices other than source nearest to it with their distances, by Dijkstra's
+// algorithm stopped as soon as k vertices are settled, so that asking for the vicinity of
+// many sources in turn only builds the adjacencies once; see Graph::k_nearest
+pub fn k_nearest(adj: &WeightedAdjacencies, source: Vertex, k: usize) -> Vec<(Vertex, Weight)> {
+    settle_nearest(adj, source, k, |v| v != source)
+}
+
+// Dijkstra's algorithm from source until k of the vertices accepted by the filter are
+// settled, returning them with their distances in increasing order of distance and of the
+// vertices for equal ones; vertices as far as the k-th are settled too, as edges of weight
+// zero may still lead to smaller ones at the same distance
+fn settle_nearest<F>(adj: &WeightedAdjacencies, source: Vertex, k: usize, wanted: F) -> Vec<(Vertex, Weight)>
+    where F: Fn(Vertex) -> bool {
+    let mut found: Vec<(Vertex, Weight)> = vec![];
+    if k == 0 || !adj.contains_key(&source) {
+        return found;
+    }
+    let (vertices, index) = indexed_vertices(adj);
+    let mut distance = HashMap::new();
+    let mut queue = IndexedBinaryHeap::new(vertices.len());
+    distance.insert(source, 0.0);
+    queue.push(index[&source], 0.0);
+    while let Some((i, d)) = queue.pop() {
+        if found.len() >= k && d > found[k - 1].1 {
+            break;
+        }
+        let v = vertices[i];
+        if wanted(v) {
+            found.push((v, d));
+        }
+        for (w, weight) in &adj[&v] {
+            let alt = d + weight;
+            if distance.get(w).is_none_or(|current| alt < *current) {
+                distance.insert(*w, alt);
+                queue.decrease_key(index[w], alt);
+            }
+        }
+    }
+    found.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+    found.truncate(k);
+    found
+}
+
+// Dijkstra's algorithm computing the distances from source to all reachable vertices
+pub fn distances(adj: &WeightedAdjacencies, source: Vertex) -> HashMap<Vertex, Weight> {
+    shortest_path_tree(adj, source).0
+}
+
+// Dijkstra's algorithm computing the distances from source to all reachable vertices
+// together with the predecessor of every vertex on a shortest path to it; both are empty
+// if source is not in the graph
+pub fn shortest_path_tree(adj: &WeightedAdjacencies, source: Vertex) -> (HashMap<Vertex, Weight>, HashMap<Vertex, Vertex>) {
+    let (vertices, index) = indexed_vertices(adj);
+    let mut distance = HashMap::new();
+    let mut previous = HashMap::new();
+    let start = match index.get(&source) {
+        Some(start) => *start,
+        None => return (distance, previous)
+    };
+    let mut queue = IndexedBinaryHeap::new(vertices.len());
+    distance.insert(source, 0.0);
+    queue.push(start, 0.0);
+    while let Some((i, d)) = queue.pop() {
+        let v = vertices[i];
+        for (w, weight) in &adj[&v] {
+            let alt = d + weight;
+            if distance.get(w).is_none_or(|current| alt < *current) {
+                distance.insert(*w, alt);
+                previous.insert(*w, v);
+                queue.decrease_key(index[w], alt);
+            }
+        }
+    }
+    (distance, previous)
+}
+
+// reconstructs the path to target from the map of predecessors on the path
+pub fn trace_path(previous: &HashMap<Vertex, Vertex>, target: Vertex) -> Path {
+    let mut path = vec![target];
+    while let Some(p) = previous.get(&path[path.len() - 1]) {
+        path.push(*p);
+    }
+    path.reverse();
+    path
+}
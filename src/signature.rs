@@ -0,0 +1,155 @@
+use std::fmt;
+
+use graph::{Graph, HashMap, HashSet, Vertex, Weight};
+use shortest_paths::Path;
+
+// a summary of the structure of a graph that does not depend on how its vertices are
+// numbered, for checking that an algorithm produced the graph it should have: isomorphic
+// graphs always have the same fingerprint, while graphs with the same fingerprint are
+// very likely, though not certainly, isomorphic
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Fingerprint {
+    pub directed: bool,
+    // the degrees in descending order, counted as in degree_sequence
+    pub degrees: Vec<usize>,
+    // the numbers of vertices of the connected components, ignoring edge directions, in
+    // descending order
+    pub component_sizes: Vec<usize>,
+    // the hash of the colors the vertices end up with in the Weisfeiler-Lehman test
+    pub wl_hash: u64
+}
+
+impl fmt::Display for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut digest = mix(self.directed as u64);
+        for d in &self.degrees {
+            digest = combine(digest, *d as u64);
+        }
+        for size in &self.component_sizes {
+            digest = combine(digest, *size as u64);
+        }
+        write!(f, "{}:{}:{}:{:016x}:{:016x}", if self.directed { "d" } else { "u" },
+               self.degrees.len(), self.component_sizes.len(), digest, self.wl_hash)
+    }
+}
+
+impl Graph {
+
+    // the fingerprint of the graph; the Weisfeiler-Lehman hash starts out coloring every
+    // vertex by its out and in degree and repeatedly recolors it by its color and the
+    // multisets of the colors of its successors and predecessors, until that no longer
+    // splits any color class, and then hashes the sorted colors; weights are ignored, and
+    // the hash neither depends on the platform nor on the run
+    pub fn fingerprint(&self) -> Fingerprint {
+        let (vertices, index) = self.indexed_vertices();
+        let n = vertices.len();
+        let mut successors = vec![vec![]; n];
+        let mut predecessors = vec![vec![]; n];
+        for edge in self.edges() {
+            let (v, w) = (index[&edge.0], index[&edge.1]);
+            successors[v].push(w);
+            predecessors[w].push(v);
+        }
+        if !self.is_directed() {
+            // the neighbours of undirected graphs all count as successors
+            for v in 0..n {
+                let incoming = std::mem::take(&mut predecessors[v]);
+                successors[v].extend(incoming);
+            }
+        }
+
+        let mut colors: Vec<u64> = (0..n).map(|v| combine(mix(successors[v].len() as u64), predecessors[v].len() as u64)).collect();
+        let mut classes = distinct(&colors);
+        for _ in 0..n {
+            let recolored: Vec<u64> = (0..n)
+                .map(|v| {
+                    let mut color = mix(colors[v]);
+                    for neighbours in [&successors[v], &predecessors[v]] {
+                        let mut adjacent: Vec<u64> = neighbours.iter().map(|w| colors[*w]).collect();
+                        adjacent.sort();
+                        color = combine(color, adjacent.len() as u64);
+                        for c in adjacent {
+                            color = combine(color, c);
+                        }
+                    }
+                    color
+                })
+                .collect();
+            let refined = distinct(&recolored);
+            colors = recolored;
+            if refined == classes {
+                break;
+            }
+            classes = refined;
+        }
+        colors.sort();
+        let wl_hash = colors.into_iter().fold(mix(n as u64), combine);
+
+        let mut degrees = self.degree_sequence();
+        degrees.sort_by(|a, b| b.cmp(a));
+        let mut component_sizes: Vec<usize> = self.weakly_connected_components().iter().map(|component| component.len()).collect();
+        component_sizes.sort_by(|a, b| b.cmp(a));
+        Fingerprint { directed: self.is_directed(), degrees, component_sizes, wl_hash }
+    }
+
+    // the total weight of the edges along the path, or None if it is empty or two of its
+    // consecutive vertices are not joined by an edge in the direction of the path
+    pub fn path_weight(&self, path: &[Vertex]) -> Option<Weight> {
+        if path.is_empty() || !self.vertices().contains(&path[0]) {
+            return None;
+        }
+        path.windows(2).try_fold(0.0, |total, step| {
+            if self.has_edge(step[0], step[1]) {
+                Some(total + self.weight(&self.edge_between(step[0], step[1])))
+            } else {
+                None
+            }
+        })
+    }
+}
+
+// returns true if both lists divide the same vertices into the same parts, regardless of the
+// order of the parts, as for the components or communities found by different algorithms
+pub fn same_partition(a: &[HashSet<Vertex>], b: &[HashSet<Vertex>]) -> bool {
+    let part: HashMap<Vertex, usize> = a.iter().enumerate().flat_map(|(i, members)| members.iter().map(move |v| (*v, i))).collect();
+    let size = |members: &[HashSet<Vertex>]| members.iter().map(|members| members.len()).sum::<usize>();
+    if a.len() != b.len() || size(a) != part.len() || size(b) != part.len() {
+        return false;
+    }
+    // every part of b must lie within a single part of a, and no two parts in the same one
+    let mut matched = HashSet::new();
+    b.iter().all(|members| {
+        let first = members.iter().next().and_then(|v| part.get(v));
+        match first {
+            Some(i) => members.iter().all(|v| part.get(v) == Some(i)) && members.len() == a[*i].len() && matched.insert(*i),
+            None => false
+        }
+    })
+}
+
+// returns true if both paths are paths of the graph between the same ends with the same
+// weight, as for the shortest paths found by different algorithms when there are ties
+pub fn equivalent_paths(graph: &Graph, a: &Path, b: &Path) -> bool {
+    a.first() == b.first() && a.last() == b.last() && match (graph.path_weight(a), graph.path_weight(b)) {
+        (Some(x), Some(y)) => (x - y).abs() <= 1e-9 * x.abs().max(y.abs()).max(1.0),
+        _ => false
+    }
+}
+
+// the number of distinct colors, which only grows as the colors are refined
+fn distinct(colors: &[u64]) -> usize {
+    colors.iter().collect::<HashSet<_>>().len()
+}
+
+// scrambles a number with the finalizer of splitmix64
+pub fn mix(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+// hashes a value into a hash built so far, depending on the order of the values
+pub fn combine(hash: u64, value: u64) -> u64 {
+    mix(hash.rotate_left(5) ^ value)
+}
@@ -0,0 +1,439 @@
+use std::thread;
+
+use disjoint_sets::DisjointSets;
+use graph::{Component, Edge, Graph, HashMap, HashSet, Vertex, Weight};
+use indexed_heap::IndexedBinaryHeap;
+use shortest_paths::{shortest_path_tree, trace_path};
+
+// a clustering of the vertices into groups numbered from 0 in order of their smallest
+// vertex, with its spacing, the smallest weight of an edge between different clusters
+// (infinite if there is no such edge)
+pub struct Clustering {
+    pub spacing: Weight,
+    pub assignment: HashMap<Vertex, usize>
+}
+
+impl Graph {
+
+    // computes a minimum spanning tree using Kruskal's algorithm, i.e. by adding the edges
+    // in order of increasing weight unless they close a cycle; edge directions are ignored
+    // returns the weight of the tree and its edges, or None if the graph is not connected
+    pub fn minimum_spanning_tree(&self) -> Option<(Weight, Vec<Edge>)> {
+        let (weight, edges) = self.kruskal();
+        if edges.len() + 1 < self.vertices().len() {
+            return None;
+        }
+        Some((weight, edges))
+    }
+
+    // computes a minimum spanning tree of every connected component of the graph, which
+    // should be undirected; returns the vertices of each component together with the
+    // weight and the edges of its tree, ordered by the smallest vertex of the components
+    pub fn minimum_spanning_forest(&self) -> Vec<(Component, Weight, Vec<Edge>)> {
+        let mut components = self.connected_components();
+        components.sort_by_key(|component| component.iter().min().cloned());
+        let position: HashMap<Vertex, usize> = components.iter().enumerate()
+            .flat_map(|(i, component)| component.iter().map(move |v| (*v, i)))
+            .collect();
+        let mut forest: Vec<(Component, Weight, Vec<Edge>)> = components.into_iter()
+            .map(|component| (component, 0.0, vec![]))
+            .collect();
+        for edge in self.kruskal().1 {
+            let tree = &mut forest[position[&edge.0]];
+            tree.1 += self.weight(&edge);
+            tree.2.push(edge);
+        }
+        forest
+    }
+
+    // computes a minimum spanning tree using Prim's algorithm, i.e. by growing a single tree
+    // from the smallest vertex, always adding the lightest edge leaving it
+    pub fn minimum_spanning_tree_prim(&self) -> Option<(Weight, Vec<Edge>)> {
+        let (vertices, index) = self.indexed_vertices();
+        let edges = self.sorted_edges();
+        // edges are identified by their rank in sorted order, which breaks ties between
+        // edges of equal weight consistently
+        let mut incident = vec![vec![]; vertices.len()];
+        for (rank, (_, edge)) in edges.iter().enumerate() {
+            incident[index[&edge.0]].push(rank);
+            incident[index[&edge.1]].push(rank);
+        }
+        // every vertex outside the tree is queued with the lightest edge joining it to the
+        // tree, lowered as the tree grows
+        let mut in_tree = vec![false; vertices.len()];
+        let mut tree = (0.0, vec![]);
+        let mut queue = IndexedBinaryHeap::new(vertices.len());
+        let add = |i: usize, in_tree: &mut Vec<bool>, queue: &mut IndexedBinaryHeap<(Weight, usize)>| {
+            in_tree[i] = true;
+            for rank in &incident[i] {
+                let (weight, edge) = edges[*rank];
+                let j = if index[&edge.0] == i { index[&edge.1] } else { index[&edge.0] };
+                if !in_tree[j] {
+                    queue.decrease_key(j, (weight, *rank));
+                }
+            }
+        };
+        if !vertices.is_empty() {
+            add(0, &mut in_tree, &mut queue);
+        }
+        while let Some((i, (weight, rank))) = queue.pop() {
+            tree.0 += weight;
+            tree.1.push(edges[rank].1);
+            add(i, &mut in_tree, &mut queue);
+        }
+        if tree.1.len() + 1 < vertices.len() {
+            return None;
+        }
+        Some(tree)
+    }
+
+    // computes a minimum spanning tree using Boruvka's algorithm: in every round each
+    // component picks the lightest edge leaving it, and all these edges are added at once,
+    // at least halving the number of components; the search for the lightest edges is
+    // split across the given number of threads, and edges that end up within a component
+    // are dropped after each round so that later rounds only scan the remaining ones
+    pub fn minimum_spanning_tree_boruvka(&self, threads: usize) -> Option<(Weight, Vec<Edge>)> {
+        let (vertices, index) = self.indexed_vertices();
+        let weights = self.sorted_edges();
+        let edges: Vec<(usize, usize)> = weights.iter()
+            .map(|(_, edge)| (index[&edge.0], index[&edge.1]))
+            .collect();
+        let mut sets = DisjointSets::new(vertices.len());
+        // ranks of the edges between different components
+        let mut remaining: Vec<usize> = (0..edges.len()).collect();
+        let mut tree = (0.0, vec![]);
+        let chunk = remaining.len().div_ceil(threads.max(1)).max(1);
+        while !remaining.is_empty() {
+            let component: Vec<usize> = (0..vertices.len()).map(|i| sets.find(i)).collect();
+            // the lightest edge leaving each component, i.e. the one with the smallest rank
+            let lightest = thread::scope(|scope| {
+                let workers: Vec<_> = remaining.chunks(chunk)
+                    .map(|ranks| {
+                        let (component, edges) = (&component, &edges);
+                        scope.spawn(move || {
+                            let mut lightest: HashMap<usize, usize> = HashMap::new();
+                            for rank in ranks {
+                                let (i, j) = edges[*rank];
+                                for c in [component[i], component[j]] {
+                                    let best = lightest.entry(c).or_insert(*rank);
+                                    *best = (*best).min(*rank);
+                                }
+                            }
+                            lightest
+                        })
+                    })
+                    .collect();
+                let mut lightest: HashMap<usize, usize> = HashMap::new();
+                for worker in workers {
+                    for (c, rank) in worker.join().unwrap() {
+                        let best = lightest.entry(c).or_insert(rank);
+                        *best = (*best).min(rank);
+                    }
+                }
+                lightest
+            });
+            let mut added: Vec<usize> = lightest.into_values().collect();
+            added.sort();
+            for rank in added {
+                if sets.union(edges[rank].0, edges[rank].1) {
+                    tree.0 += weights[rank].0;
+                    tree.1.push(weights[rank].1);
+                }
+            }
+            remaining.retain(|rank| sets.find(edges[*rank].0) != sets.find(edges[*rank].1));
+        }
+        if tree.1.len() + 1 < vertices.len() {
+            return None;
+        }
+        Some(tree)
+    }
+
+    // computes a second best spanning tree, a lightest spanning tree other than the minimum
+    // spanning tree: it differs from the minimum spanning tree by a single swap, adding one
+    // other edge and removing the heaviest tree edge on the cycle this closes
+    // returns None if the graph is not connected or has no other spanning tree
+    pub fn second_mst(&self) -> Option<(Weight, Vec<Edge>)> {
+        let (weight, tree) = self.minimum_spanning_tree()?;
+        let in_tree: HashSet<Edge> = tree.iter().cloned().collect();
+        let mut adj: HashMap<Vertex, Vec<(Vertex, Weight, Edge)>> = HashMap::new();
+        for edge in &tree {
+            let w = self.weight(edge);
+            adj.entry(edge.0).or_default().push((edge.1, w, *edge));
+            adj.entry(edge.1).or_default().push((edge.0, w, *edge));
+        }
+        let mut others: HashMap<Vertex, Vec<(Weight, Edge)>> = HashMap::new();
+        for (w, edge) in self.sorted_edges() {
+            if !in_tree.contains(&edge) {
+                others.entry(edge.0).or_default().push((w, edge));
+            }
+        }
+
+        // for every vertex with other edges, find the heaviest tree edge on the path to
+        // every other vertex by a search through the tree
+        let mut best: Option<(Weight, Edge, Edge)> = None;
+        let mut sources: Vec<&Vertex> = others.keys().collect();
+        sources.sort();
+        for source in sources {
+            let mut heaviest: HashMap<Vertex, Option<(Weight, Edge)>> = HashMap::new();
+            heaviest.insert(*source, None);
+            let mut stack = vec![*source];
+            while let Some(v) = stack.pop() {
+                for (u, w, edge) in adj.get(&v).into_iter().flatten() {
+                    if !heaviest.contains_key(u) {
+                        let max = match heaviest[&v] {
+                            Some(max) if max.0 >= *w => Some(max),
+                            _ => Some((*w, *edge))
+                        };
+                        heaviest.insert(*u, max);
+                        stack.push(*u);
+                    }
+                }
+            }
+            for (w, edge) in &others[source] {
+                if let Some((removed_weight, removed)) = heaviest[&edge.1] {
+                    let swapped = weight - removed_weight + w;
+                    if best.is_none_or(|b| swapped < b.0) {
+                        best = Some((swapped, *edge, removed));
+                    }
+                }
+            }
+        }
+
+        let (swapped, added, removed) = best?;
+        let mut edges: Vec<Edge> = tree.into_iter().filter(|edge| *edge != removed).collect();
+        edges.push(added);
+        Some((swapped, edges))
+    }
+
+    // clusters the vertices into k groups such that the spacing is as large as possible,
+    // by running Kruskal's algorithm until only k components remain; there are more
+    // clusters if the graph has more than k connected components
+    pub fn clustering_max_spacing(&self, k: usize) -> Clustering {
+        let (vertices, index) = self.indexed_vertices();
+        let mut sets = DisjointSets::new(vertices.len());
+        let mut components = vertices.len();
+        let mut spacing = Weight::INFINITY;
+        for (weight, edge) in self.sorted_edges() {
+            let (i, j) = (index[&edge.0], index[&edge.1]);
+            if sets.find(i) == sets.find(j) {
+                continue;
+            }
+            if components <= k {
+                spacing = weight;
+                break;
+            }
+            sets.union(i, j);
+            components -= 1;
+        }
+
+        let mut cluster: HashMap<usize, usize> = HashMap::new();
+        let mut assignment = HashMap::new();
+        for (i, v) in vertices.iter().enumerate() {
+            let next = cluster.len();
+            assignment.insert(*v, *cluster.entry(sets.find(i)).or_insert(next));
+        }
+        Clustering { spacing, assignment }
+    }
+
+    // approximates a lightest tree connecting the terminals to within a factor of two: a
+    // minimum spanning tree of the complete graph on the terminals, weighted by their
+    // distances, is expanded into shortest paths, of whose edges a minimum spanning tree is
+    // taken again, and vertices other than terminals are pruned while they are leaves
+    // returns the weight of the tree and its edges, or None if the terminals are not
+    // connected; edge weights must not be negative
+    pub fn steiner_tree(&self, terminals: &[Vertex]) -> Option<(Weight, Vec<Edge>)> {
+        let mut terminals = terminals.to_vec();
+        terminals.sort();
+        terminals.dedup();
+        let adj = self.weighted_adjacencies();
+        let trees: Vec<_> = terminals.iter().map(|t| shortest_path_tree(&adj, *t)).collect();
+
+        // Prim's algorithm on the distances between the terminals, adding the edges of the
+        // shortest path for every edge of the tree
+        let distance = |i: usize, j: usize| trees[i].0.get(&terminals[j]).cloned().unwrap_or(Weight::INFINITY);
+        let mut in_tree = vec![false; terminals.len()];
+        let mut nearest: Vec<(Weight, usize)> = (0..terminals.len()).map(|j| (distance(0, j), 0)).collect();
+        let mut paths: HashSet<Edge> = HashSet::new();
+        for k in 0..terminals.len() {
+            let next = (0..terminals.len())
+                .filter(|j| !in_tree[*j])
+                .min_by(|a, b| nearest[*a].0.total_cmp(&nearest[*b].0))
+                .unwrap();
+            let (d, from) = nearest[next];
+            if d == Weight::INFINITY {
+                return None;
+            }
+            in_tree[next] = true;
+            if k > 0 {
+                for hop in trace_path(&trees[from].1, terminals[next]).windows(2) {
+                    paths.insert(self.edge_between(hop[0], hop[1]));
+                }
+            }
+            for j in 0..terminals.len() {
+                if !in_tree[j] && distance(next, j) < nearest[j].0 {
+                    nearest[j] = (distance(next, j), next);
+                }
+            }
+        }
+
+        // the shortest paths may share vertices, so they are reduced to a spanning tree
+        let mut edges: Vec<(Weight, Edge)> = paths.into_iter().map(|edge| (self.weight(&edge), edge)).collect();
+        edges.sort_by(|a, b| a.0.total_cmp(&b.0).then_with(|| a.1.cmp(&b.1)));
+        let (vertices, index) = self.indexed_vertices();
+        let mut sets = DisjointSets::new(vertices.len());
+        edges.retain(|(_, edge)| sets.union(index[&edge.0], index[&edge.1]));
+
+        let mut degree: HashMap<Vertex, usize> = HashMap::new();
+        for (_, edge) in &edges {
+            *degree.entry(edge.0).or_default() += 1;
+            *degree.entry(edge.1).or_default() += 1;
+        }
+        loop {
+            let leaf = |v: &Vertex| degree[v] == 1 && terminals.binary_search(v).is_err();
+            let before = edges.len();
+            edges.retain(|(_, edge)| !leaf(&edge.0) && !leaf(&edge.1));
+            if edges.len() == before {
+                break;
+            }
+            degree.clear();
+            for (_, edge) in &edges {
+                *degree.entry(edge.0).or_default() += 1;
+                *degree.entry(edge.1).or_default() += 1;
+            }
+        }
+        Some((edges.iter().map(|edge| edge.0).sum(), edges.into_iter().map(|edge| edge.1).collect()))
+    }
+
+    // runs Kruskal's algorithm over all edges, returning the weight and the edges of a
+    // minimum spanning forest
+    fn kruskal(&self) -> (Weight, Vec<Edge>) {
+        let (vertices, index) = self.indexed_vertices();
+        let mut sets = DisjointSets::new(vertices.len());
+        let mut forest = (0.0, vec![]);
+        for (weight, edge) in self.sorted_edges() {
+            if sets.union(index[&edge.0], index[&edge.1]) {
+                forest.0 += weight;
+                forest.1.push(edge);
+            }
+        }
+        forest
+    }
+
+    // lists the edges other than self-loops with their weights, in order of increasing
+    // weight and ties broken by the edges themselves
+    pub fn sorted_edges(&self) -> Vec<(Weight, Edge)> {
+        let mut edges: Vec<(Weight, Edge)> = self.edges().iter()
+            .filter(|&&(v, w)| v != w)
+            .map(|edge| (self.weight(edge), *edge))
+            .collect();
+        edges.sort_by(|a, b| a.0.total_cmp(&b.0).then_with(|| a.1.cmp(&b.1)));
+        edges
+    }
+
+    // counts the spanning trees of the graph using Kirchhoff's matrix tree theorem: the
+    // count is the determinant of the Laplacian matrix with one row and column removed;
+    // edge directions, self-loops and parallel edges are ignored
+    // the determinant is computed exactly from its residues modulo enough primes to exceed
+    // Hadamard's bound on it; returns None if the count does not fit into 128 bits
+    pub fn count_spanning_trees(&self) -> Option<u128> {
+        let (vertices, index) = self.indexed_vertices();
+        if vertices.is_empty() {
+            return Some(0);
+        }
+        let n = vertices.len() - 1;
+        let mut neighbours: Vec<HashSet<usize>> = vec![HashSet::new(); vertices.len()];
+        for edge in self.edges().iter().filter(|&&(v, w)| v != w) {
+            let (i, j) = (index[&edge.0], index[&edge.1]);
+            neighbours[i].insert(j);
+            neighbours[j].insert(i);
+        }
+        // the row and column of the last vertex are left out
+        let mut laplacian = vec![vec![0i64; n]; n];
+        for (i, row) in laplacian.iter_mut().enumerate() {
+            row[i] = neighbours[i].len() as i64;
+            for j in neighbours[i].iter().filter(|j| **j < n) {
+                row[*j] = -1;
+            }
+        }
+        determinant(&laplacian)
+    }
+}
+
+// computes the non-negative determinant of an integer matrix by Gaussian elimination modulo
+// primes of 31 bits, combining the residues with Garner's algorithm; returns None if the
+// determinant does not fit into 128 bits
+fn determinant(m: &[Vec<i64>]) -> Option<u128> {
+    // Hadamard's bound: the determinant is at most the product of the lengths of the rows
+    let bits: f64 = m.iter()
+        .map(|row| (row.iter().map(|x| (x * x) as f64).sum::<f64>()).sqrt().max(1.0).log2())
+        .sum();
+    let count = (bits as usize + 2) / 30 + 1;
+
+    let mut primes: Vec<u64> = vec![];
+    let mut candidate = (1u64 << 31) - 1;
+    while primes.len() < count {
+        if (2..).take_while(|d| d * d <= candidate).all(|d| !candidate.is_multiple_of(d)) {
+            primes.push(candidate);
+        }
+        candidate -= 2;
+    }
+    let residues: Vec<u64> = primes.iter().map(|p| determinant_modulo(m, *p)).collect();
+
+    // mixed radix digits: the determinant equals d0 + p0 (d1 + p1 (d2 + ...))
+    let mut digits: Vec<u64> = vec![];
+    for (i, p) in primes.iter().enumerate() {
+        let mut digit = residues[i];
+        for (j, d) in digits.iter().enumerate() {
+            digit = (digit + p - d % p) % p * power(primes[j] % p, p - 2, *p) % p;
+        }
+        digits.push(digit);
+    }
+    let mut value: u128 = 0;
+    for (d, p) in digits.iter().zip(&primes).rev() {
+        value = value.checked_mul(*p as u128)?.checked_add(*d as u128)?;
+    }
+    Some(value)
+}
+
+fn determinant_modulo(m: &[Vec<i64>], p: u64) -> u64 {
+    let n = m.len();
+    let mut a: Vec<Vec<u64>> = m.iter().map(|row| row.iter().map(|x| x.rem_euclid(p as i64) as u64).collect()).collect();
+    let mut det = 1;
+    for k in 0..n {
+        let pivot = match (k..n).find(|i| a[*i][k] != 0) {
+            Some(i) => i,
+            None => return 0
+        };
+        if pivot != k {
+            a.swap(pivot, k);
+            det = p - det;
+        }
+        det = det * a[k][k] % p;
+        let inverse = power(a[k][k], p - 2, p);
+        let (top, bottom) = a.split_at_mut(k + 1);
+        let pivot_row = &top[k];
+        for row in bottom {
+            let factor = row[k] * inverse % p;
+            if factor == 0 {
+                continue;
+            }
+            for (x, y) in row[k..].iter_mut().zip(&pivot_row[k..]) {
+                *x = (*x + p - factor * y % p) % p;
+            }
+        }
+    }
+    det
+}
+
+// computes base^exponent modulo p by repeated squaring
+fn power(mut base: u64, mut exponent: u64, p: u64) -> u64 {
+    let mut result = 1;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result * base % p;
+        }
+        base = base * base % p;
+        exponent >>= 1;
+    }
+    result
+}
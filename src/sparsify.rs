@@ -0,0 +1,102 @@
+use std::collections::BinaryHeap;
+
+use graph::{Edge, Graph, HashMap, Vertex, Weight, WeightedAdjacencies};
+use random::Random;
+use shortest_paths::{distances, QueueEntry};
+
+impl Graph {
+
+    // builds a t-spanner with the greedy algorithm of Althoefer et al.: the edges are taken
+    // from lightest to heaviest, and an edge is kept only if the spanner built so far has
+    // no path between its ends of length at most t times its weight. The shortest paths of
+    // the spanner are thus at most t times longer than those of the graph, while for
+    // t = 2k - 1 it has O(n^(1 + 1/k)) edges; weights must not be negative and t at least 1
+    pub fn t_spanner(&self, t: Weight) -> Graph {
+        let mut edges: Vec<(Weight, Edge)> = self.edges().iter()
+            .filter(|&&(v, w)| v != w)
+            .map(|edge| (self.weight(edge), *edge))
+            .collect();
+        edges.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let mut adj: WeightedAdjacencies = self.vertices().iter().map(|v| (*v, HashMap::new())).collect();
+        let mut spanner = Graph::new(self.vertices().clone(), Default::default(), self.is_directed());
+        for (weight, (v, w)) in edges {
+            if bounded_distance(&adj, v, w, t * weight).is_some() {
+                continue;
+            }
+            adj.get_mut(&v).unwrap().insert(w, weight);
+            if !self.is_directed() {
+                adj.get_mut(&w).unwrap().insert(v, weight);
+            }
+            spanner.add_weighted_edge(v, w, weight);
+        }
+        spanner
+    }
+
+    // keeps every edge independently with the given probability, derived from the seed,
+    // and divides the weights of the edges kept by it, so that the weight of every cut is
+    // preserved in expectation. By Karger's sampling theorem all cuts are within a factor
+    // of 1 +- epsilon of their weight with high probability once the probability is at
+    // least about 3 ln(n) / (epsilon^2 c) for integer weights and a minimum cut of weight
+    // c; distances are not preserved, see t_spanner for that
+    pub fn sample_edges(&self, probability: f64, seed: u64) -> Graph {
+        let mut random = Random::new(seed);
+        let mut edges: Vec<Edge> = self.edges().iter().cloned().collect();
+        edges.sort();
+        let mut sample = Graph::new(self.vertices().clone(), Default::default(), self.is_directed());
+        for edge in edges {
+            if random.next_f64() < probability {
+                sample.add_weighted_edge(edge.0, edge.1, self.weight(&edge) / probability);
+            }
+        }
+        sample
+    }
+
+    // the largest factor by which the sparse graph, on the same vertices, stretches the
+    // shortest paths of this one, or infinity if it disconnects vertices; it suffices to
+    // check every edge, as every shortest path is made of edges, so this bounds the error
+    // of all distances computed on the sparse graph instead
+    pub fn stretch(&self, sparse: &Graph) -> Weight {
+        let original = self.weighted_adjacencies();
+        let adj = sparse.weighted_adjacencies();
+        let mut stretch: Weight = 1.0;
+        for v in self.sorted_vertices() {
+            if original[&v].keys().all(|w| *w == v) {
+                continue;
+            }
+            let distance = if adj.contains_key(&v) { distances(&adj, v) } else { HashMap::new() };
+            for (w, weight) in original[&v].iter().filter(|(w, _)| **w != v) {
+                let d = distance.get(w).cloned().unwrap_or(Weight::INFINITY);
+                if d > *weight {
+                    stretch = stretch.max(d / weight);
+                }
+            }
+        }
+        stretch
+    }
+}
+
+// the distance from source to target if it is at most limit, using Dijkstra's algorithm but
+// giving up on paths longer than limit
+fn bounded_distance(adj: &WeightedAdjacencies, source: Vertex, target: Vertex, limit: Weight) -> Option<Weight> {
+    let mut distance = HashMap::new();
+    let mut queue = BinaryHeap::new();
+    distance.insert(source, 0.0);
+    queue.push(QueueEntry { distance: 0.0, vertex: source });
+    while let Some(QueueEntry { distance: d, vertex: v }) = queue.pop() {
+        if v == target {
+            return Some(d);
+        }
+        if d > distance[&v] {
+            continue;
+        }
+        for (w, weight) in &adj[&v] {
+            let alt = d + weight;
+            if alt <= limit && distance.get(w).is_none_or(|current| alt < *current) {
+                distance.insert(*w, alt);
+                queue.push(QueueEntry { distance: alt, vertex: *w });
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,76 @@
+use std::fmt;
+
+use disjoint_sets::DisjointSets;
+use graph::{Graph, HashMap, Vertex};
+
+// basic statistics of a graph; the degree of a vertex is the number of edges it is an
+// end of (in and out edges for directed graphs), with self-loops counted twice
+pub struct GraphStats {
+    pub directed: bool,
+    // the number of vertices and edges
+    pub order: usize,
+    pub size: usize,
+    // the fraction of all possible edges between distinct vertices that are present
+    pub density: f64,
+    // connected components, weakly connected ones for directed graphs
+    pub components: usize,
+    pub min_degree: usize,
+    pub max_degree: usize,
+    pub average_degree: f64,
+    pub self_loops: usize,
+    // pairs of edges joining the same vertices, which only undirected graphs can have by
+    // containing an edge in both directions
+    pub parallel_edges: usize
+}
+
+impl fmt::Display for GraphStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Type: {}", if self.directed { "directed" } else { "undirected" })?;
+        writeln!(f, "Vertices: {}", self.order)?;
+        writeln!(f, "Edges: {}", self.size)?;
+        writeln!(f, "Density: {:.6}", self.density)?;
+        writeln!(f, "Components: {}", self.components)?;
+        writeln!(f, "Degree: min {}, max {}, average {:.3}", self.min_degree, self.max_degree, self.average_degree)?;
+        writeln!(f, "Self-loops: {}", self.self_loops)?;
+        writeln!(f, "Parallel edges: {}", self.parallel_edges)
+    }
+}
+
+impl Graph {
+
+    pub fn stats(&self) -> GraphStats {
+        let (vertices, index) = self.indexed_vertices();
+        let mut degree: HashMap<Vertex, usize> = vertices.iter().map(|v| (*v, 0)).collect();
+        let mut sets = DisjointSets::new(vertices.len());
+        let mut components = vertices.len();
+        let (mut self_loops, mut parallel_edges) = (0, 0);
+        for edge in self.edges() {
+            *degree.get_mut(&edge.0).unwrap() += 1;
+            *degree.get_mut(&edge.1).unwrap() += 1;
+            if edge.0 == edge.1 {
+                self_loops += 1;
+            } else if !self.is_directed() && edge.0 < edge.1 && self.edges().contains(&(edge.1, edge.0)) {
+                parallel_edges += 1;
+            }
+            if sets.union(index[&edge.0], index[&edge.1]) {
+                components -= 1;
+            }
+        }
+
+        let n = vertices.len();
+        let pairs = if self.is_directed() { n * n.saturating_sub(1) } else { n * n.saturating_sub(1) / 2 };
+        let distinct = self.edges().len() - self_loops - parallel_edges;
+        GraphStats {
+            directed: self.is_directed(),
+            order: n,
+            size: self.edges().len(),
+            density: if pairs == 0 { 0.0 } else { distinct as f64 / pairs as f64 },
+            components,
+            min_degree: degree.values().cloned().min().unwrap_or(0),
+            max_degree: degree.values().cloned().max().unwrap_or(0),
+            average_degree: if n == 0 { 0.0 } else { 2.0 * self.edges().len() as f64 / n as f64 },
+            self_loops,
+            parallel_edges
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use disjoint_sets::DisjointSets;
+use graph::{Edge, Graph, HashMap, HashSet, Vertex};
+use random::Random;
+
+// notification that an edge joined two previously separate components,
+// together with the sizes of these components
+pub struct Merge {
+    pub edge: Edge,
+    pub sizes: (usize, usize)
+}
+
+// tracks the connected components of an undirected graph whose edges arrive one at a
+// time, without storing the edges themselves; memory use only grows with the vertices
+pub struct StreamingGraph {
+    index: HashMap<Vertex, usize>,
+    sets: DisjointSets,
+    // size of each component, stored with its representative
+    size: Vec<usize>,
+    components: usize,
+    edges: usize
+}
+
+impl StreamingGraph {
+
+    pub fn new() -> StreamingGraph {
+        StreamingGraph { index: HashMap::new(), sets: DisjointSets::new(0), size: vec![], components: 0, edges: 0 }
+    }
+
+    pub fn add_vertex(&mut self, v: Vertex) -> usize {
+        if let Some(i) = self.index.get(&v) {
+            return *i;
+        }
+        let i = self.sets.push();
+        self.index.insert(v, i);
+        self.size.push(1);
+        self.components += 1;
+        i
+    }
+
+    // adds an edge, adding its vertices if they have not been seen before;
+    // returns the merge if the edge joined two components
+    pub fn add_edge(&mut self, edge: Edge) -> Option<Merge> {
+        let (u, v) = (self.add_vertex(edge.0), self.add_vertex(edge.1));
+        self.edges += 1;
+        let (ru, rv) = (self.sets.find(u), self.sets.find(v));
+        if !self.sets.union(ru, rv) {
+            return None;
+        }
+        let sizes = (self.size[ru], self.size[rv]);
+        let root = self.sets.find(ru);
+        self.size[root] = sizes.0 + sizes.1;
+        self.components -= 1;
+        Some(Merge { edge, sizes })
+    }
+
+    // adds all edges of the stream, reporting every merge of two components
+    pub fn extend<I, F>(&mut self, edges: I, mut on_merge: F) where I: IntoIterator<Item = Edge>, F: FnMut(Merge) {
+        for edge in edges {
+            if let Some(merge) = self.add_edge(edge) {
+                on_merge(merge);
+            }
+        }
+    }
+
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.index.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges
+    }
+
+    // returns true if u and v have been connected by the edges seen so far
+    pub fn same_component(&mut self, u: Vertex, v: Vertex) -> bool {
+        match (self.index.get(&u).cloned(), self.index.get(&v).cloned()) {
+            (Some(i), Some(j)) => self.sets.find(i) == self.sets.find(j),
+            _ => false
+        }
+    }
+
+    // returns the number of vertices in the component of v
+    pub fn component_size(&mut self, v: Vertex) -> usize {
+        match self.index.get(&v).cloned() {
+            Some(i) => {
+                let root = self.sets.find(i);
+                self.size[root]
+            },
+            None => 0
+        }
+    }
+}
+
+// samples k edges of a stream of edges uniformly at random using the seed, by reservoir
+// sampling: the i-th edge replaces a random edge of the sample with probability k / i, so
+// that the stream is read once and only the sample is kept; returns the graph of the
+// edges sampled and their ends
+pub fn sample_edges<I: IntoIterator<Item = Edge>>(edges: I, k: usize, seed: u64, directed: bool) -> Graph {
+    let sample = reservoir(edges, k, &mut Random::new(seed));
+    let vertices = sample.iter().flat_map(|edge| [edge.0, edge.1]).collect();
+    Graph::new(vertices, sample.into_iter().collect(), directed)
+}
+
+// samples k vertices of a stream of vertices uniformly at random using the seed, like
+// sample_edges, and returns the subgraph they induce in the stream of edges that follows,
+// keeping only the edges between vertices of the sample
+pub fn sample_vertices_with_induced<V, E>(vertices: V, edges: E, k: usize, seed: u64, directed: bool) -> Graph
+    where V: IntoIterator<Item = Vertex>, E: IntoIterator<Item = Edge> {
+    let sample: HashSet<Vertex> = reservoir(vertices, k, &mut Random::new(seed)).into_iter().collect();
+    let induced = edges.into_iter().filter(|edge| sample.contains(&edge.0) && sample.contains(&edge.1)).collect();
+    Graph::new(sample, induced, directed)
+}
+
+// a uniform sample of k items of the stream, by Vitter's algorithm R
+fn reservoir<T, I: IntoIterator<Item = T>>(items: I, k: usize, random: &mut Random) -> Vec<T> {
+    let mut sample = Vec::with_capacity(k);
+    for (i, item) in items.into_iter().enumerate() {
+        if i < k {
+            sample.push(item);
+        } else {
+            let j = random.below(i + 1);
+            if j < k {
+                sample[j] = item;
+            }
+        }
+    }
+    sample
+}
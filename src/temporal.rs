@@ -0,0 +1,88 @@
+use graph::{HashMap, Vertex};
+
+// a point in time, in any unit
+pub type Time = f64;
+
+// a temporal edge: a trip from one vertex to another that leaves and arrives at fixed
+// times, like a train between two stations
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Connection {
+    pub from: Vertex,
+    pub to: Vertex,
+    pub departure: Time,
+    pub arrival: Time
+}
+
+// a directed graph whose edges are only available at certain times, stored as the list of
+// its connections ordered by departure (and arrival) time; a path through it must respect
+// time, i.e. each connection must depart no earlier than the previous one arrived
+pub struct TemporalGraph {
+    connections: Vec<Connection>
+}
+
+impl TemporalGraph {
+
+    pub fn new() -> TemporalGraph {
+        TemporalGraph { connections: vec![] }
+    }
+
+    // adds a connection, keeping the connections ordered; panics if it arrives before it
+    // departs
+    pub fn add_connection(&mut self, from: Vertex, to: Vertex, departure: Time, arrival: Time) {
+        assert!(arrival >= departure, "Connection arrives before it departs!");
+        let key = |c: &Connection| (c.departure, c.arrival);
+        let position = self.connections.partition_point(|c| key(c) <= (departure, arrival));
+        self.connections.insert(position, Connection { from, to, departure, arrival });
+    }
+
+    pub fn len(&self) -> usize {
+        self.connections.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.connections.is_empty()
+    }
+
+    // computes the earliest time at which every vertex can be reached when starting at
+    // source at the given time, using the connection scan algorithm: as the connections
+    // are ordered by departure, one pass over them suffices, taking every connection that
+    // departs from a vertex already reached and arrives earlier than known so far
+    // returns the arrival times together with the connection last taken to every vertex
+    fn scan(&self, source: Vertex, start: Time) -> (HashMap<Vertex, Time>, HashMap<Vertex, Connection>) {
+        let mut arrival: HashMap<Vertex, Time> = HashMap::new();
+        let mut via = HashMap::new();
+        arrival.insert(source, start);
+        let first = self.connections.partition_point(|c| c.departure < start);
+        for c in &self.connections[first..] {
+            let reachable = arrival.get(&c.from).is_some_and(|t| *t <= c.departure);
+            if reachable && arrival.get(&c.to).is_none_or(|t| c.arrival < *t) {
+                arrival.insert(c.to, c.arrival);
+                via.insert(c.to, *c);
+            }
+        }
+        (arrival, via)
+    }
+
+    // the earliest arrival times at all vertices reachable from source when starting there
+    // at the given time
+    pub fn earliest_arrivals(&self, source: Vertex, start: Time) -> HashMap<Vertex, Time> {
+        self.scan(source, start).0
+    }
+
+    // finds a journey from source to target that arrives as early as possible when starting
+    // at the given time, returning the arrival time and the connections taken, or None if
+    // the target cannot be reached
+    pub fn earliest_arrival_path(&self, source: Vertex, target: Vertex, start: Time) -> Option<(Time, Vec<Connection>)> {
+        let (arrival, via) = self.scan(source, start);
+        let time = *arrival.get(&target)?;
+        let mut journey = vec![];
+        let mut v = target;
+        while v != source {
+            let c = via[&v];
+            journey.push(c);
+            v = c.from;
+        }
+        journey.reverse();
+        Some((time, journey))
+    }
+}
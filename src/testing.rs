@@ -0,0 +1,55 @@
+use graph::{sorted_members, Component, Graph, HashMap, Vertex};
+use shortest_paths::Path;
+use signature::same_partition;
+
+// the sets as sorted lists in ascending order, for printing them the same on every run
+fn sorted_sets(sets: &[Component]) -> Vec<Vec<Vertex>> {
+    let mut sorted = sorted_members(sets.to_vec());
+    sorted.sort();
+    sorted
+}
+
+// the classes of vertices of the same color
+fn color_classes(coloring: &HashMap<Vertex, usize>) -> Vec<Component> {
+    let mut classes: HashMap<usize, Component> = HashMap::new();
+    for (v, color) in coloring {
+        classes.entry(*color).or_default().insert(*v);
+    }
+    classes.into_values().collect()
+}
+
+// asserts that the two lists of components hold the same sets of vertices, in whatever
+// order the lists and the sets are, which hashing and ties leave open; panics with both
+// partitions in ascending order otherwise, as the other assertions do with what is wrong
+pub fn assert_same_partition(a: &[Component], b: &[Component]) {
+    assert!(same_partition(a, b), "partitions differ: {:?} and {:?}", sorted_sets(a), sorted_sets(b));
+}
+
+// asserts that the two colorings color the same vertices and put the same ones together,
+// whatever the colors are called
+pub fn assert_same_coloring(a: &HashMap<Vertex, usize>, b: &HashMap<Vertex, usize>) {
+    assert_same_partition(&color_classes(a), &color_classes(b));
+}
+
+// asserts that the coloring colors every vertex of the graph and no two adjacent ones, at
+// the ends of an edge in either direction, alike; self-loops are ignored
+pub fn assert_proper_coloring(graph: &Graph, coloring: &HashMap<Vertex, usize>) {
+    let mut vertices = graph.sorted_vertices();
+    vertices.retain(|v| !coloring.contains_key(v));
+    assert!(vertices.is_empty(), "vertices {:?} are not colored", vertices);
+    let mut edges: Vec<_> = graph.edges().iter().filter(|(v, w)| v != w && coloring[v] == coloring[w]).collect();
+    edges.sort();
+    assert!(edges.is_empty(), "edges {:?} join vertices of the same color", edges);
+}
+
+// asserts that the path leads from source to target along edges of the graph, in their
+// direction if it is directed
+pub fn assert_valid_path(graph: &Graph, path: &Path, source: Vertex, target: Vertex) {
+    assert!(path.first() == Some(&source), "path {:?} does not start at {}", path, source);
+    assert!(path.last() == Some(&target), "path {:?} does not end at {}", path, target);
+    assert!(graph.vertices().contains(&source), "path {:?} leaves the graph at {}", path, source);
+    if !path.windows(2).all(|pair| graph.has_edge(pair[0], pair[1])) {
+        let pair = path.windows(2).find(|pair| !graph.has_edge(pair[0], pair[1])).unwrap();
+        panic!("path {:?} has no edge {} - {}", path, pair[0], pair[1]);
+    }
+}
@@ -0,0 +1,129 @@
+use disjoint_sets::DisjointSets;
+use graph::{Component, Graph, HashMap, Vertex, Weight};
+
+// which edges are present at a threshold: those of at least its weight, such as for
+// similarities, or those of at most its weight, such as for distances
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Keep {
+    AtLeast,
+    AtMost
+}
+
+// the merge of two clusters when an edge of the given weight becomes present, leaving so
+// many components; the clusters are numbered as in a single linkage dendrogram: the vertices
+// in ascending order from 0, and every merge the next number after them
+pub struct Merge {
+    pub threshold: Weight,
+    pub left: usize,
+    pub right: usize,
+    pub components: usize
+}
+
+// the merges of the components of a graph as the threshold sweeps from the strictest to the
+// most lenient, the edges present growing along the way
+pub struct MergeProfile {
+    pub keep: Keep,
+    pub vertices: Vec<Vertex>,
+    pub merges: Vec<Merge>
+}
+
+impl Keep {
+
+    // whether an edge of the weight is present at the threshold
+    pub fn keeps(self, weight: Weight, threshold: Weight) -> bool {
+        match self {
+            Keep::AtLeast => weight >= threshold,
+            Keep::AtMost => weight <= threshold
+        }
+    }
+}
+
+impl Graph {
+
+    // the connected components of the graph with only the edges present at the threshold,
+    // ignoring edge directions, ordered by their smallest vertices
+    pub fn components_at_threshold(&self, threshold: Weight, keep: Keep) -> Vec<Component> {
+        self.merge_profile(keep).components_at(threshold)
+    }
+
+    // the merge profile of the graph, by adding the edges from the strictest weight to the
+    // most lenient and merging the components of their ends, ties broken by the edges; this is
+    // single linkage clustering, the merges being those of Kruskal's algorithm, so their
+    // thresholds are those of the edges of a minimum spanning forest, or a maximum one for
+    // similarities. Edge directions and self-loops are ignored
+    pub fn merge_profile(&self, keep: Keep) -> MergeProfile {
+        let (vertices, index) = self.indexed_vertices();
+        let mut edges = self.sorted_edges();
+        if keep == Keep::AtLeast {
+            edges.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+        }
+        let mut sets = DisjointSets::new(vertices.len());
+        // the cluster of every set, by its representative
+        let mut cluster: Vec<usize> = (0..vertices.len()).collect();
+        let mut merges = vec![];
+        for (weight, (v, w)) in edges {
+            let (i, j) = (sets.find(index[&v]), sets.find(index[&w]));
+            if i == j || weight.is_nan() {
+                continue;
+            }
+            let (left, right) = (cluster[i].min(cluster[j]), cluster[i].max(cluster[j]));
+            sets.union(i, j);
+            cluster[sets.find(i)] = vertices.len() + merges.len();
+            merges.push(Merge { threshold: weight, left, right, components: vertices.len() - merges.len() - 1 });
+        }
+        MergeProfile { keep, vertices, merges }
+    }
+}
+
+impl MergeProfile {
+
+    // the merges that have happened at the threshold, a prefix of all of them
+    pub fn merges_at(&self, threshold: Weight) -> &[Merge] {
+        let count = self.merges.iter().take_while(|merge| self.keep.keeps(merge.threshold, threshold)).count();
+        &self.merges[..count]
+    }
+
+    // the number of connected components at the threshold
+    pub fn component_count(&self, threshold: Weight) -> usize {
+        self.vertices.len() - self.merges_at(threshold).len()
+    }
+
+    // the connected components at the threshold, ordered by their smallest vertices, the
+    // same as components_at_threshold, by replaying the merges up to it
+    pub fn components_at(&self, threshold: Weight) -> Vec<Component> {
+        let n = self.vertices.len();
+        let merges = self.merges_at(threshold);
+        // every cluster by one of its vertices, as the merged clusters hold different ones
+        let mut member: Vec<usize> = (0..n).collect();
+        let mut sets = DisjointSets::new(n);
+        for merge in merges {
+            let (v, w) = (member[merge.left], member[merge.right]);
+            sets.union(v, w);
+            member.push(v);
+        }
+        let mut position: HashMap<usize, usize> = HashMap::new();
+        let mut components: Vec<Component> = vec![];
+        for (i, v) in self.vertices.iter().enumerate() {
+            let next = components.len();
+            let c = *position.entry(sets.find(i)).or_insert(next);
+            if c == components.len() {
+                components.push(Component::new());
+            }
+            components[c].insert(*v);
+        }
+        components
+    }
+
+    // the thresholds at which the number of components changes, from the strictest on, each
+    // with the number of components once all merges at it have happened
+    pub fn steps(&self) -> Vec<(Weight, usize)> {
+        let mut steps: Vec<(Weight, usize)> = vec![];
+        for merge in &self.merges {
+            match steps.last_mut() {
+                Some(last) if last.0 == merge.threshold => last.1 = merge.components,
+                _ => steps.push((merge.threshold, merge.components))
+            }
+        }
+        steps
+    }
+}
@@ -0,0 +1,262 @@
+use std::collections::BinaryHeap;
+use std::collections::VecDeque;
+use std::fmt;
+use std::io;
+use std::io::Write;
+
+use graph::{Edge, Graph, HashMap, HashSet, Vertex, Weight};
+use report::number;
+use shortest_paths::QueueEntry;
+use traversal::Visitor;
+
+// an event of a traced run of an algorithm
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    // a vertex becomes the root of a new tree of a traversal, or the source
+    Start(Vertex),
+    Discover(Vertex),
+    // a vertex is done with: all its edges examined, or taken off the queue of a breadth
+    // first search, see Visitor::finish_vertex
+    Finish(Vertex),
+    TreeEdge(Edge),
+    BackEdge(Edge),
+    ForwardOrCrossEdge(Edge),
+    NonTreeEdge(Edge),
+    // Dijkstra's algorithm: a vertex taken off the queue with its final distance
+    Settle(Vertex, Weight),
+    // Dijkstra's algorithm: an edge giving its head the smaller tentative distance
+    Relax(Edge, Weight),
+    // Dijkstra's algorithm: an edge examined without improving the distance of its head
+    Examine(Edge)
+}
+
+// a step of a traced run: the event with the frontier right after it, i.e. the vertices
+// waiting to be taken up in the order the algorithm takes them, each with its key: the queue
+// of a breadth first search with the depths, the path of a depth first search from the
+// root with the depths, and the queue of Dijkstra's algorithm with the tentative distances
+#[derive(Clone, Debug, PartialEq)]
+pub struct Step {
+    pub event: Event,
+    pub frontier: Vec<(Vertex, Weight)>
+}
+
+// the steps of a traced run of an algorithm on a graph, for replaying it as an animation;
+// write_json writes the graph and the steps for a frontend, and Display lists the steps
+pub struct Trace {
+    pub algorithm: &'static str,
+    pub steps: Vec<Step>,
+    directed: bool,
+    vertices: Vec<Vertex>,
+    edges: Vec<(Vertex, Vertex, Weight)>
+}
+
+// records the events of a traversal with the frontier it implies: a breadth first search
+// queues a vertex when discovering it and takes it off when finishing it, a depth first
+// search puts it on its path for the same time
+struct Recorder {
+    depth_first: bool,
+    depth: HashMap<Vertex, usize>,
+    frontier: VecDeque<Vertex>,
+    steps: Vec<Step>
+}
+
+impl Recorder {
+
+    fn record(&mut self, event: Event) {
+        let frontier = self.frontier.iter().map(|v| (*v, self.depth[v] as Weight)).collect();
+        self.steps.push(Step { event, frontier });
+    }
+}
+
+impl Visitor for Recorder {
+
+    fn start_vertex(&mut self, v: Vertex) {
+        self.depth.insert(v, 0);
+        self.record(Event::Start(v));
+    }
+
+    fn discover_vertex(&mut self, v: Vertex) {
+        self.frontier.push_back(v);
+        self.record(Event::Discover(v));
+    }
+
+    fn tree_edge(&mut self, edge: Edge) {
+        let depth = self.depth[&edge.0] + 1;
+        self.depth.insert(edge.1, depth);
+        self.record(Event::TreeEdge(edge));
+    }
+
+    fn back_edge(&mut self, edge: Edge) {
+        self.record(Event::BackEdge(edge));
+    }
+
+    fn forward_or_cross_edge(&mut self, edge: Edge) {
+        self.record(Event::ForwardOrCrossEdge(edge));
+    }
+
+    fn non_tree_edge(&mut self, edge: Edge) {
+        self.record(Event::NonTreeEdge(edge));
+    }
+
+    fn finish_vertex(&mut self, v: Vertex) {
+        if self.depth_first {
+            self.frontier.pop_back();
+        } else {
+            self.frontier.pop_front();
+        }
+        self.record(Event::Finish(v));
+    }
+}
+
+impl Graph {
+
+    // traces the breadth first search of breadth_first_visit
+    pub fn trace_bfs(&self) -> Trace {
+        self.trace_traversal(false)
+    }
+
+    // traces the depth first search of depth_first_visit
+    pub fn trace_dfs(&self) -> Trace {
+        self.trace_traversal(true)
+    }
+
+    // traces Dijkstra's algorithm from the source, examining the neighbours of every vertex
+    // in ascending order; a vertex is settled when taken off the queue for the first time,
+    // and entries left over from distances improved since are skipped without a step; as
+    // every step copies the queue, this is meant for the small graphs of a lecture. Edge
+    // weights must not be negative
+    pub fn trace_dijkstra(&self, source: Vertex) -> Trace {
+        let adj = self.weighted_adjacencies();
+        let mut trace = self.empty_trace("dijkstra");
+        if !adj.contains_key(&source) {
+            return trace;
+        }
+        let mut distance: HashMap<Vertex, Weight> = HashMap::new();
+        let mut settled = HashSet::new();
+        let mut queue = BinaryHeap::new();
+        // the queue without stale entries, by distance and vertex
+        let frontier = |queue: &BinaryHeap<QueueEntry>, distance: &HashMap<Vertex, Weight>, settled: &HashSet<Vertex>| {
+            let mut waiting: Vec<(Vertex, Weight)> = queue.iter()
+                .filter(|entry| !settled.contains(&entry.vertex) && distance[&entry.vertex] == entry.distance)
+                .map(|entry| (entry.vertex, entry.distance))
+                .collect();
+            waiting.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+            waiting
+        };
+        distance.insert(source, 0.0);
+        queue.push(QueueEntry { distance: 0.0, vertex: source });
+        trace.steps.push(Step { event: Event::Start(source), frontier: vec![(source, 0.0)] });
+        while let Some(QueueEntry { distance: d, vertex: v }) = queue.pop() {
+            if settled.contains(&v) || d > distance[&v] {
+                continue;
+            }
+            settled.insert(v);
+            trace.steps.push(Step { event: Event::Settle(v, d), frontier: frontier(&queue, &distance, &settled) });
+            let mut adjacent: Vec<(&Vertex, &Weight)> = adj[&v].iter().collect();
+            adjacent.sort_by_key(|(w, _)| **w);
+            for (w, weight) in adjacent {
+                let alt = d + weight;
+                let event = if !settled.contains(w) && distance.get(w).is_none_or(|current| alt < *current) {
+                    distance.insert(*w, alt);
+                    queue.push(QueueEntry { distance: alt, vertex: *w });
+                    Event::Relax((v, *w), alt)
+                } else {
+                    Event::Examine((v, *w))
+                };
+                trace.steps.push(Step { event, frontier: frontier(&queue, &distance, &settled) });
+            }
+        }
+        trace
+    }
+
+    fn trace_traversal(&self, depth_first: bool) -> Trace {
+        let mut recorder = Recorder { depth_first, depth: HashMap::new(), frontier: VecDeque::new(), steps: vec![] };
+        if depth_first {
+            self.depth_first_visit(&mut recorder);
+        } else {
+            self.breadth_first_visit(&mut recorder);
+        }
+        let mut trace = self.empty_trace(if depth_first { "dfs" } else { "bfs" });
+        trace.steps = recorder.steps;
+        trace
+    }
+
+    fn empty_trace(&self, algorithm: &'static str) -> Trace {
+        let mut edges: Vec<(Vertex, Vertex, Weight)> = self.edges().iter().map(|edge| (edge.0, edge.1, self.weight(edge))).collect();
+        edges.sort_by_key(|&(v, w, _)| (v, w));
+        Trace { algorithm, steps: vec![], directed: self.is_directed(), vertices: self.sorted_vertices(), edges }
+    }
+}
+
+impl Event {
+
+    // the name of the kind of event, as written by write_json
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Event::Start(_) => "start",
+            Event::Discover(_) => "discover",
+            Event::Finish(_) => "finish",
+            Event::TreeEdge(_) => "tree_edge",
+            Event::BackEdge(_) => "back_edge",
+            Event::ForwardOrCrossEdge(_) => "forward_or_cross_edge",
+            Event::NonTreeEdge(_) => "non_tree_edge",
+            Event::Settle(_, _) => "settle",
+            Event::Relax(_, _) => "relax",
+            Event::Examine(_) => "examine"
+        }
+    }
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Event::Start(v) | Event::Discover(v) | Event::Finish(v) => write!(f, "{} {}", self.name(), v),
+            Event::TreeEdge((v, w)) | Event::BackEdge((v, w)) | Event::ForwardOrCrossEdge((v, w)) |
+            Event::NonTreeEdge((v, w)) | Event::Examine((v, w)) => write!(f, "{} {} -> {}", self.name(), v, w),
+            Event::Settle(v, d) => write!(f, "{} {} at {}", self.name(), v, d),
+            Event::Relax((v, w), d) => write!(f, "{} {} -> {} to {}", self.name(), v, w, d)
+        }
+    }
+}
+
+impl Trace {
+
+    // writes the trace as a JSON object with the algorithm, the graph and the steps, one per
+    // line: every step has the name of its event, the vertex or the ends "from" and "to"
+    // of the edge, the distance of settle and relax steps, and the frontier as pairs of
+    // vertices and keys
+    pub fn write_json<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writeln!(writer, "{{")?;
+        writeln!(writer, r#"  "algorithm": "{}", "directed": {},"#, self.algorithm, self.directed)?;
+        let vertices: Vec<String> = self.vertices.iter().map(|v| v.to_string()).collect();
+        writeln!(writer, r#"  "vertices": [{}],"#, vertices.join(", "))?;
+        let edges: Vec<String> = self.edges.iter().map(|(v, w, weight)| format!("[{}, {}, {}]", v, w, number(*weight))).collect();
+        writeln!(writer, r#"  "edges": [{}],"#, edges.join(", "))?;
+        write!(writer, r#"  "steps": ["#)?;
+        for (i, step) in self.steps.iter().enumerate() {
+            let subject = match step.event {
+                Event::Start(v) | Event::Discover(v) | Event::Finish(v) => format!(r#""vertex": {}"#, v),
+                Event::Settle(v, d) => format!(r#""vertex": {}, "distance": {}"#, v, number(d)),
+                Event::TreeEdge((v, w)) | Event::BackEdge((v, w)) | Event::ForwardOrCrossEdge((v, w)) |
+                Event::NonTreeEdge((v, w)) | Event::Examine((v, w)) => format!(r#""from": {}, "to": {}"#, v, w),
+                Event::Relax((v, w), d) => format!(r#""from": {}, "to": {}, "distance": {}"#, v, w, number(d))
+            };
+            let frontier: Vec<String> = step.frontier.iter().map(|(v, key)| format!("[{}, {}]", v, number(*key))).collect();
+            write!(writer, "{}\n    {{\"event\": \"{}\", {}, \"frontier\": [{}]}}",
+                   if i == 0 { "" } else { "," }, step.event.name(), subject, frontier.join(", "))?;
+        }
+        writeln!(writer, "\n  ]")?;
+        writeln!(writer, "}}")
+    }
+}
+
+// lists the steps one per line, each event followed by the frontier after it
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, step) in self.steps.iter().enumerate() {
+            let frontier: Vec<String> = step.frontier.iter().map(|(v, key)| format!("{} ({})", v, key)).collect();
+            writeln!(f, "{}. {} [{}]", i + 1, step.event, frontier.join(", "))?;
+        }
+        Ok(())
+    }
+}
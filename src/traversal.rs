@@ -0,0 +1,633 @@
+use std::collections::VecDeque;
+use std::fmt;
+
+use graph::{Adjacencies, Edge, Graph, HashMap, HashSet, Vertex};
+use random::Random;
+use shortest_paths::Path;
+
+// the forest of a traversal of the entire graph: the parent of every vertex that is
+// not the root of a tree, plus the times at which each vertex was discovered and
+// finished, counted on a single clock that ticks on every discovery and finish
+pub struct TraversalForest {
+    pub order: Vec<Vertex>,
+    pub parent: HashMap<Vertex, Vertex>,
+    pub discovered: HashMap<Vertex, usize>,
+    pub finished: HashMap<Vertex, usize>
+}
+
+impl TraversalForest {
+
+    fn new() -> TraversalForest {
+        TraversalForest { order: vec![], parent: HashMap::new(), discovered: HashMap::new(), finished: HashMap::new() }
+    }
+
+    fn time(&self) -> usize {
+        self.discovered.len() + self.finished.len()
+    }
+
+    // whether u is an ancestor of v in the forest, counting every vertex as an ancestor of
+    // itself: exactly when v was discovered and finished within the time u was
+    pub fn is_ancestor(&self, u: Vertex, v: Vertex) -> bool {
+        self.discovered[&u] <= self.discovered[&v] && self.finished[&v] <= self.finished[&u]
+    }
+
+    // the vertices by decreasing finishing time, which for the depth first forest of a
+    // directed acyclic graph is a topological order
+    pub fn reverse_postorder(&self) -> Vec<Vertex> {
+        let mut order = self.order.clone();
+        order.sort_by_key(|v| std::cmp::Reverse(self.finished[v]));
+        order
+    }
+}
+
+impl Visitor for TraversalForest {
+
+    fn discover_vertex(&mut self, v: Vertex) {
+        self.discovered.insert(v, self.time());
+        self.order.push(v);
+    }
+
+    fn tree_edge(&mut self, edge: Edge) {
+        self.parent.insert(edge.1, edge.0);
+    }
+
+    fn finish_vertex(&mut self, v: Vertex) {
+        self.finished.insert(v, self.time());
+    }
+}
+
+// callbacks invoked by depth_first_visit and breadth_first_visit for the events of a
+// traversal, in the spirit of the visitors of the Boost Graph Library; all of them do
+// nothing unless overridden
+pub trait Visitor {
+    // a vertex is about to become the root of a new tree of the traversal
+    fn start_vertex(&mut self, _v: Vertex) {}
+    fn discover_vertex(&mut self, _v: Vertex) {}
+    // an edge leading to an undiscovered vertex, called before discovering it
+    fn tree_edge(&mut self, _edge: Edge) {}
+    // depth first search only: an edge leading to a vertex discovered but not finished yet,
+    // which closes a cycle (for undirected graphs this includes the edge back to the parent)
+    fn back_edge(&mut self, _edge: Edge) {}
+    // depth first search only: an edge leading to a vertex already finished
+    fn forward_or_cross_edge(&mut self, _edge: Edge) {}
+    // breadth first search only: an edge leading to a vertex already discovered
+    fn non_tree_edge(&mut self, _edge: Edge) {}
+    fn finish_vertex(&mut self, _v: Vertex) {}
+}
+
+// the kind of an edge in a depth first search: an edge to an undiscovered vertex, to a
+// vertex on the current path, to a descendant already finished, or to a vertex in a
+// subtree or tree finished before
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EdgeClass {
+    Tree,
+    Back,
+    Forward,
+    Cross
+}
+
+impl fmt::Display for EdgeClass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            EdgeClass::Tree => "tree",
+            EdgeClass::Back => "back",
+            EdgeClass::Forward => "forward",
+            EdgeClass::Cross => "cross"
+        })
+    }
+}
+
+// the edges of the graph classified by a depth first search
+pub struct EdgeClasses {
+    pub tree: Vec<Edge>,
+    pub back: Vec<Edge>,
+    pub forward: Vec<Edge>,
+    pub cross: Vec<Edge>
+}
+
+// visitor telling forward from cross edges by when their ends were discovered
+struct EdgeClassifier {
+    discovered: HashMap<Vertex, usize>,
+    edges: Vec<(Edge, EdgeClass)>
+}
+
+impl Visitor for EdgeClassifier {
+
+    fn discover_vertex(&mut self, v: Vertex) {
+        let time = self.discovered.len();
+        self.discovered.insert(v, time);
+    }
+
+    fn tree_edge(&mut self, edge: Edge) {
+        self.edges.push((edge, EdgeClass::Tree));
+    }
+
+    fn back_edge(&mut self, edge: Edge) {
+        self.edges.push((edge, EdgeClass::Back));
+    }
+
+    fn forward_or_cross_edge(&mut self, edge: Edge) {
+        let class = if self.discovered[&edge.0] < self.discovered[&edge.1] { EdgeClass::Forward } else { EdgeClass::Cross };
+        self.edges.push((edge, class));
+    }
+}
+
+// lazy depth first search from a start vertex, yielding the vertices reachable from it in
+// the order they are discovered, visiting neighbours in sorted order; nothing if the start
+// vertex is not in the graph
+pub struct Dfs {
+    adj: HashMap<Vertex, Vec<Vertex>>,
+    discovered: HashSet<Vertex>,
+    // the vertices on the current path, with the position of the next neighbour to look at
+    stack: Vec<(Vertex, usize)>,
+    start: Option<Vertex>
+}
+
+impl Dfs {
+
+    pub fn new(graph: &Graph, start: Vertex) -> Dfs {
+        let adj = sorted_adjacencies(&graph.adjacencies());
+        let start = Some(start).filter(|v| adj.contains_key(v));
+        Dfs { adj, discovered: HashSet::new(), stack: vec![], start }
+    }
+}
+
+impl Iterator for Dfs {
+    type Item = Vertex;
+
+    fn next(&mut self) -> Option<Vertex> {
+        if let Some(start) = self.start.take() {
+            self.discovered.insert(start);
+            self.stack.push((start, 0));
+            return Some(start);
+        }
+        while let Some(&mut (v, ref mut next)) = self.stack.last_mut() {
+            let adjacent = &self.adj[&v];
+            while *next < adjacent.len() && self.discovered.contains(&adjacent[*next]) {
+                *next += 1;
+            }
+            match adjacent.get(*next) {
+                Some(w) => {
+                    self.discovered.insert(*w);
+                    self.stack.push((*w, 0));
+                    return Some(*w);
+                },
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+        None
+    }
+}
+
+// lazy breadth first search from a start vertex, yielding the vertices reachable from it in
+// order of their distance, visiting neighbours in sorted order; nothing if the start vertex
+// is not in the graph
+pub struct Bfs {
+    adj: HashMap<Vertex, Vec<Vertex>>,
+    discovered: HashSet<Vertex>,
+    queue: VecDeque<Vertex>
+}
+
+impl Bfs {
+
+    pub fn new(graph: &Graph, start: Vertex) -> Bfs {
+        let adj = sorted_adjacencies(&graph.adjacencies());
+        let start: Vec<Vertex> = Some(start).into_iter().filter(|v| adj.contains_key(v)).collect();
+        Bfs { adj, discovered: start.iter().cloned().collect(), queue: start.into_iter().collect() }
+    }
+}
+
+impl Iterator for Bfs {
+    type Item = Vertex;
+
+    fn next(&mut self) -> Option<Vertex> {
+        let v = self.queue.pop_front()?;
+        for w in &self.adj[&v] {
+            if self.discovered.insert(*w) {
+                self.queue.push_back(*w);
+            }
+        }
+        Some(v)
+    }
+}
+
+// lazy enumeration of the paths from source to target that visit no vertex twice, by a
+// depth first search over the paths from source, visiting neighbours in sorted order
+pub struct SimplePaths {
+    adj: HashMap<Vertex, Vec<Vertex>>,
+    target: Vertex,
+    max_len: Option<usize>,
+    // the current path, with the position of the next neighbour to look at for each vertex
+    stack: Vec<(Vertex, usize)>,
+    on_path: HashSet<Vertex>,
+    trivial: Option<Path>
+}
+
+impl Iterator for SimplePaths {
+    type Item = Path;
+
+    fn next(&mut self) -> Option<Path> {
+        if let Some(path) = self.trivial.take() {
+            return Some(path);
+        }
+        while let Some(&(v, _)) = self.stack.last() {
+            let edges = self.stack.len();
+            let extend = v != self.target && self.max_len.is_none_or(|max| edges <= max);
+            let next = &mut self.stack.last_mut().unwrap().1;
+            match self.adj[&v].get(*next) {
+                Some(w) if extend => {
+                    *next += 1;
+                    if self.on_path.insert(*w) {
+                        self.stack.push((*w, 0));
+                        if *w == self.target {
+                            return Some(self.stack.iter().map(|entry| entry.0).collect());
+                        }
+                    }
+                },
+                _ => {
+                    self.stack.pop();
+                    self.on_path.remove(&v);
+                }
+            }
+        }
+        None
+    }
+}
+
+// lazy depth first search from a start vertex that goes at most max_depth edges deep and
+// only avoids the vertices on the current path, yielding every vertex each time a path
+// enters it, visiting neighbours in sorted order; it only keeps the current path, so its
+// memory grows with the depth rather than with the number of vertices reached, for the
+// price of entering vertices again on every path to them; nothing is yielded if the start
+// vertex is not in the graph
+pub struct DepthLimited {
+    adj: HashMap<Vertex, Vec<Vertex>>,
+    max_depth: usize,
+    // the current path, with the position of the next neighbour to look at for each vertex
+    stack: Vec<(Vertex, usize)>,
+    on_path: HashSet<Vertex>,
+    start: Vertex,
+    started: bool,
+    // whether the search stopped at a vertex with edges to vertices not on the path because
+    // it was max_depth edges deep
+    cut_off: bool
+}
+
+impl DepthLimited {
+
+    pub fn new(graph: &Graph, start: Vertex, max_depth: usize) -> DepthLimited {
+        let adj = sorted_adjacencies(&graph.adjacencies());
+        DepthLimited { adj, max_depth, stack: vec![], on_path: HashSet::new(), start, started: false, cut_off: false }
+    }
+
+    // the path from the start vertex to the vertex yielded last
+    pub fn path(&self) -> Path {
+        self.stack.iter().map(|entry| entry.0).collect()
+    }
+
+    // searches again from the same start vertex with a different depth limit
+    fn restart(&mut self, max_depth: usize) {
+        self.started = false;
+        self.stack.clear();
+        self.on_path.clear();
+        self.max_depth = max_depth;
+        self.cut_off = false;
+    }
+}
+
+impl Iterator for DepthLimited {
+    type Item = Vertex;
+
+    fn next(&mut self) -> Option<Vertex> {
+        if !self.started {
+            self.started = true;
+            if !self.adj.contains_key(&self.start) {
+                return None;
+            }
+            self.on_path.insert(self.start);
+            self.stack.push((self.start, 0));
+            return Some(self.start);
+        }
+        while let Some(&(v, next)) = self.stack.last() {
+            let adjacent = &self.adj[&v];
+            let open = adjacent[next..].iter().position(|w| !self.on_path.contains(w));
+            match open {
+                Some(offset) if self.stack.len() <= self.max_depth => {
+                    let w = adjacent[next + offset];
+                    self.stack.last_mut().unwrap().1 = next + offset + 1;
+                    self.on_path.insert(w);
+                    self.stack.push((w, 0));
+                    return Some(w);
+                },
+                _ => {
+                    self.cut_off |= open.is_some();
+                    self.stack.pop();
+                    self.on_path.remove(&v);
+                }
+            }
+        }
+        None
+    }
+}
+
+impl Graph {
+
+    // depth first search of the entire graph, visiting roots and neighbours in sorted order,
+    // reporting every event to the visitor; a vertex is finished once all vertices
+    // reachable through it have been finished
+    pub fn depth_first_visit<T: Visitor>(&self, visitor: &mut T) {
+        // the search keeps its own stack of the vertices being visited with the position of
+        // the next neighbour to look at, so that long paths cannot overflow the call stack
+        fn visit<T: Visitor>(root: Vertex, adj: &HashMap<Vertex, Vec<Vertex>>, finished: &mut HashMap<Vertex, bool>, visitor: &mut T) {
+            visitor.discover_vertex(root);
+            finished.insert(root, false);
+            let mut calls = vec![(root, 0)];
+            while let Some(&mut (v, ref mut next)) = calls.last_mut() {
+                if let Some(w) = adj[&v].get(*next) {
+                    *next += 1;
+                    match finished.get(w) {
+                        None => {
+                            visitor.tree_edge((v, *w));
+                            visitor.discover_vertex(*w);
+                            finished.insert(*w, false);
+                            calls.push((*w, 0));
+                        },
+                        Some(false) => visitor.back_edge((v, *w)),
+                        Some(true) => visitor.forward_or_cross_edge((v, *w))
+                    }
+                    continue;
+                }
+                calls.pop();
+                finished.insert(v, true);
+                visitor.finish_vertex(v);
+            }
+        }
+
+        let adj = sorted_adjacencies(&self.adjacencies());
+        // whether each vertex discovered so far has been finished
+        let mut finished = HashMap::new();
+        for v in self.sorted_vertices() {
+            if !finished.contains_key(&v) {
+                visitor.start_vertex(v);
+                visit(v, &adj, &mut finished, visitor);
+            }
+        }
+    }
+
+    // breadth first search of the entire graph, visiting roots and neighbours in sorted
+    // order, reporting every event to the visitor; a vertex is finished when it is taken
+    // off the queue, before its edges are examined
+    pub fn breadth_first_visit<T: Visitor>(&self, visitor: &mut T) {
+        let adj = sorted_adjacencies(&self.adjacencies());
+        let mut discovered = HashSet::new();
+        for root in self.sorted_vertices() {
+            if !discovered.insert(root) {
+                continue;
+            }
+            visitor.start_vertex(root);
+            visitor.discover_vertex(root);
+            let mut queue = VecDeque::new();
+            queue.push_back(root);
+            while let Some(v) = queue.pop_front() {
+                visitor.finish_vertex(v);
+                for w in &adj[&v] {
+                    if discovered.insert(*w) {
+                        visitor.tree_edge((v, *w));
+                        visitor.discover_vertex(*w);
+                        queue.push_back(*w);
+                    } else {
+                        visitor.non_tree_edge((v, *w));
+                    }
+                }
+            }
+        }
+    }
+
+    // the forest of a depth first search of the entire graph
+    pub fn dfs_forest(&self) -> TraversalForest {
+        let mut forest = TraversalForest::new();
+        self.depth_first_visit(&mut forest);
+        forest
+    }
+
+    // the forest of a breadth first search of the entire graph
+    pub fn bfs_forest(&self) -> TraversalForest {
+        let mut forest = TraversalForest::new();
+        self.breadth_first_visit(&mut forest);
+        forest
+    }
+
+    // classifies the edges of the graph as tree, back, forward and cross edges of a depth
+    // first search; for undirected graphs every edge is seen from both ends, so the edges
+    // from a vertex to its parent count as back edges too
+    pub fn classify_edges(&self) -> EdgeClasses {
+        let mut classes = EdgeClasses { tree: vec![], back: vec![], forward: vec![], cross: vec![] };
+        for (edge, class) in self.labeled_edges() {
+            match class {
+                EdgeClass::Tree => classes.tree.push(edge),
+                EdgeClass::Back => classes.back.push(edge),
+                EdgeClass::Forward => classes.forward.push(edge),
+                EdgeClass::Cross => classes.cross.push(edge)
+            }
+        }
+        classes
+    }
+
+    // the edges of the graph labeled with their class, in the order the depth first search
+    // of classify_edges examines them
+    pub fn labeled_edges(&self) -> Vec<(Edge, EdgeClass)> {
+        let mut classifier = EdgeClassifier { discovered: HashMap::new(), edges: vec![] };
+        self.depth_first_visit(&mut classifier);
+        classifier.edges
+    }
+
+    // computes the number of edges on a shortest path from source to every vertex
+    // reachable from it, using a single breadth first search
+    pub fn distances_from(&self, source: Vertex) -> HashMap<Vertex, u32> {
+        self.bounded_distances(source, u32::MAX, None)
+    }
+
+    // computes for every vertex reachable from any of the sources the number of edges on a
+    // shortest path from the nearest source, together with that source, using a single
+    // breadth first search started from all sources at once; of several sources at the
+    // same distance, the smallest one is reported; sources not in the graph are ignored
+    pub fn bfs_multi(&self, sources: &[Vertex]) -> HashMap<Vertex, (u32, Vertex)> {
+        let adj = self.adjacencies();
+        let mut nearest: HashMap<Vertex, (u32, Vertex)> = HashMap::new();
+        let mut queue = VecDeque::new();
+        let mut sorted = sources.to_vec();
+        sorted.sort();
+        sorted.dedup();
+        sorted.retain(|s| adj.contains_key(s));
+        for s in sorted {
+            nearest.insert(s, (0, s));
+            queue.push_back(s);
+        }
+        // a vertex is expanded only after all vertices one edge closer to the sources, so
+        // its source can still be improved until then
+        while let Some(v) = queue.pop_front() {
+            let (d, source) = nearest[&v];
+            for w in &adj[&v] {
+                match nearest.get_mut(w) {
+                    None => {
+                        nearest.insert(*w, (d + 1, source));
+                        queue.push_back(*w);
+                    },
+                    Some(entry) if entry.0 == d + 1 && source < entry.1 => entry.1 = source,
+                    Some(_) => ()
+                }
+            }
+        }
+        nearest
+    }
+
+    // enumerates the simple paths from source to target lazily, optionally only those with at
+    // most max_len edges; there are none from a source not in the graph
+    pub fn all_simple_paths(&self, source: Vertex, target: Vertex, max_len: Option<usize>) -> SimplePaths {
+        let adj = sorted_adjacencies(&self.adjacencies());
+        let known = adj.contains_key(&source);
+        // the path without any edges is the only one from a vertex to itself
+        SimplePaths {
+            stack: if source == target || !known { vec![] } else { vec![(source, 0)] },
+            on_path: vec![source].into_iter().collect(),
+            trivial: if source == target && known { Some(vec![source]) } else { None },
+            adj, target, max_len
+        }
+    }
+
+    // finds a path from source to target with the fewest edges, at most max_depth of them,
+    // by iterative deepening: depth limited searches, see DepthLimited, with the limits 0,
+    // 1, 2 and so on, until one finds the target or none went as deep as allowed. The
+    // memory only grows with the depth, unlike that of a breadth first search, while the
+    // time may grow exponentially with it where many paths lead to the same vertices
+    pub fn iddfs(&self, source: Vertex, target: Vertex, max_depth: usize) -> Option<Path> {
+        let mut search = DepthLimited::new(self, source, 0);
+        for depth in 0..=max_depth {
+            search.restart(depth);
+            while let Some(v) = search.next() {
+                if v == target {
+                    return Some(search.path());
+                }
+            }
+            if !search.cut_off {
+                return None;
+            }
+        }
+        None
+    }
+
+    // returns the vertices within radius hops of v, with the number of hops to each
+    pub fn neighborhood(&self, v: Vertex, radius: u32) -> HashMap<Vertex, u32> {
+        self.bounded_distances(v, radius, None)
+    }
+
+    // the ego network of v: the subgraph induced by the vertices within radius hops of it,
+    // along the edge directions of a directed graph, with the vertex numbers and weights of
+    // the graph, together with the number of hops to each of its vertices
+    pub fn ego_network(&self, v: Vertex, radius: u32) -> (Graph, HashMap<Vertex, u32>) {
+        let hops = self.neighborhood(v, radius);
+        (self.induced_subgraph(&hops.keys().cloned().collect()), hops)
+    }
+
+    // returns true if w can be reached from v using at most max_hops edges; the search
+    // stops as soon as w is found
+    pub fn is_reachable_within(&self, v: Vertex, w: Vertex, max_hops: u32) -> bool {
+        self.bounded_distances(v, max_hops, Some(w)).contains_key(&w)
+    }
+
+    // samples a random walk of the given number of steps from start, each step moving to a
+    // neighbour chosen uniformly at random, or with probability restart jumping back to
+    // start instead; the walk ends early at a vertex without any outgoing edges, and is empty
+    // if start is not in the graph
+    pub fn random_walk(&self, start: Vertex, steps: usize, restart: f64, seed: u64) -> Path {
+        walk(&sorted_adjacencies(&self.adjacencies()), start, steps, restart, &mut Random::new(seed))
+    }
+
+    // samples one random walk from each of the start vertices, as by random_walk
+    pub fn random_walks(&self, starts: &[Vertex], steps: usize, restart: f64, seed: u64) -> Vec<Path> {
+        let adj = sorted_adjacencies(&self.adjacencies());
+        let mut random = Random::new(seed);
+        starts.iter().map(|start| walk(&adj, *start, steps, restart, &mut random)).collect()
+    }
+
+    // breadth first search from source that stops at the given number of hops, or once the
+    // target is discovered; reaches nothing from a source not in the graph
+    fn bounded_distances(&self, source: Vertex, radius: u32, target: Option<Vertex>) -> HashMap<Vertex, u32> {
+        let adj = self.adjacencies();
+        let mut distance = HashMap::new();
+        if !adj.contains_key(&source) {
+            return distance;
+        }
+        let mut queue = VecDeque::new();
+        distance.insert(source, 0);
+        queue.push_back(source);
+        while let Some(v) = queue.pop_front() {
+            if target == Some(v) || distance[&v] == radius {
+                break;
+            }
+            let d = distance[&v] + 1;
+            for w in &adj[&v] {
+                if !distance.contains_key(w) {
+                    distance.insert(*w, d);
+                    queue.push_back(*w);
+                }
+            }
+        }
+        distance
+    }
+
+    // breadth first search from source that only enters the vertices and follows the edges
+    // accepted by the given predicates, as if all others were removed from the graph
+    // returns the vertices reached in the order they were discovered, none if source is not
+    // in the graph
+    pub fn traverse_filtered<V, E>(&self, source: Vertex, vertex_filter: V, edge_filter: E) -> Vec<Vertex>
+        where V: Fn(Vertex) -> bool, E: Fn(Edge) -> bool {
+        let adj = sorted_adjacencies(&self.adjacencies());
+        if !vertex_filter(source) || !adj.contains_key(&source) {
+            return vec![];
+        }
+        let mut order = vec![source];
+        let mut discovered: HashSet<Vertex> = order.iter().cloned().collect();
+        let mut next = 0;
+        while next < order.len() {
+            let v = order[next];
+            for w in &adj[&v] {
+                if !discovered.contains(w) && vertex_filter(*w) && edge_filter((v, *w)) {
+                    discovered.insert(*w);
+                    order.push(*w);
+                }
+            }
+            next += 1;
+        }
+        order
+    }
+}
+
+fn walk(adj: &HashMap<Vertex, Vec<Vertex>>, start: Vertex, steps: usize, restart: f64, random: &mut Random) -> Path {
+    if !adj.contains_key(&start) {
+        return vec![];
+    }
+    let mut path = vec![start];
+    for _ in 0..steps {
+        let adjacent = &adj[&path[path.len() - 1]];
+        if adjacent.is_empty() {
+            break;
+        }
+        let next = if random.next_f64() < restart { start } else { adjacent[random.below(adjacent.len())] };
+        path.push(next);
+    }
+    path
+}
+
+// turns the adjacency sets into sorted lists, so that traversals are deterministic
+pub fn sorted_adjacencies(adj: &Adjacencies) -> HashMap<Vertex, Vec<Vertex>> {
+    adj.iter()
+        .map(|(v, adjacent)| {
+            let mut adjacent: Vec<Vertex> = adjacent.iter().cloned().collect();
+            adjacent.sort();
+            (*v, adjacent)
+        })
+        .collect()
+}
@@ -0,0 +1,332 @@
+use std::collections::VecDeque;
+use std::fmt;
+
+use disjoint_sets::DisjointSets;
+use graph::{Edge, Graph, HashMap, HashSet, Vertex};
+use shortest_paths::{trace_path, Path};
+
+// the reason why a graph fails to be a tree, a forest or connected
+#[derive(Debug)]
+pub enum TreeViolation {
+    // an edge closing a cycle with the edges before it (in sorted order)
+    ExtraEdge(Edge),
+    // two vertices without any path between them
+    Disconnected(Vertex, Vertex)
+}
+
+impl fmt::Display for TreeViolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TreeViolation::ExtraEdge((u, v)) => write!(f, "edge {} - {} closes a cycle", u, v),
+            TreeViolation::Disconnected(u, v) => write!(f, "no path between {} and {}", u, v)
+        }
+    }
+}
+
+// the Euler tour of a rooted tree: the sequence of vertices met by a depth first walk that
+// records a vertex on entering it and again after returning from each of its children, so
+// that a tree with n vertices yields a tour of 2n - 1 entries and the subtree of every
+// vertex occupies the range of the tour between its first and last occurrence
+pub struct EulerTour {
+    pub tour: Vec<Vertex>,
+    pub depth: Vec<usize>,
+    pub first: HashMap<Vertex, usize>,
+    pub last: HashMap<Vertex, usize>
+}
+
+impl EulerTour {
+
+    // returns true if u lies on the path from the root to v (including v itself)
+    pub fn is_ancestor(&self, u: Vertex, v: Vertex) -> bool {
+        match (self.first.get(&u), self.first.get(&v)) {
+            (Some(fu), Some(fv)) => fu <= fv && self.last[&v] <= self.last[&u],
+            _ => false
+        }
+    }
+
+    // of two positions in the tour, returns the one with the smaller depth
+    fn shallower(&self, i: usize, j: usize) -> usize {
+        if self.depth[j] < self.depth[i] { j } else { i }
+    }
+
+    // the number of vertices in the subtree of v, none if v is not in the tree
+    pub fn subtree_size(&self, v: Vertex) -> usize {
+        self.first.get(&v).map_or(0, |first| (self.last[&v] - first) / 2 + 1)
+    }
+}
+
+// answers lowest common ancestor queries in constant time using a sparse table over the
+// Euler tour: the lowest common ancestor of u and v is the shallowest vertex on the tour
+// between their first occurrences
+pub struct LcaIndex {
+    euler: EulerTour,
+    // sparse[k][i] is the position of the shallowest entry among tour[i..i + 2^k]
+    sparse: Vec<Vec<usize>>
+}
+
+impl LcaIndex {
+
+    fn new(euler: EulerTour) -> LcaIndex {
+        let mut sparse = vec![(0..euler.tour.len()).collect::<Vec<usize>>()];
+        let mut width = 1;
+        while 2 * width <= euler.tour.len() {
+            let previous = &sparse[sparse.len() - 1];
+            let level = (0..=euler.tour.len() - 2 * width)
+                .map(|i| euler.shallower(previous[i], previous[i + width]))
+                .collect();
+            sparse.push(level);
+            width *= 2;
+        }
+        LcaIndex { euler, sparse }
+    }
+
+    // returns the lowest common ancestor of u and v, or None unless both are in the tree
+    pub fn lca(&self, u: Vertex, v: Vertex) -> Option<Vertex> {
+        let (fu, fv) = (*self.euler.first.get(&u)?, *self.euler.first.get(&v)?);
+        let (i, j) = if fu <= fv { (fu, fv + 1) } else { (fv, fu + 1) };
+        // two overlapping ranges of the same power of two width cover tour[i..j]
+        let k = (j - i).ilog2() as usize;
+        let position = self.euler.shallower(self.sparse[k][i], self.sparse[k][j - (1 << k)]);
+        Some(self.euler.tour[position])
+    }
+
+    // returns the number of edges on the tree path between u and v
+    pub fn distance_in_tree(&self, u: Vertex, v: Vertex) -> Option<usize> {
+        let lca = self.lca(u, v)?;
+        let depth = |w: Vertex| self.euler.depth[self.euler.first[&w]];
+        Some(depth(u) + depth(v) - 2 * depth(lca))
+    }
+}
+
+// a centroid decomposition of a tree: the centroid of the tree, whose removal leaves
+// subtrees of at most half the size, becomes the root, and the centroids of the remaining
+// subtrees become its children, recursively; every tree path passes through the lowest
+// common centroid ancestor of its endpoints, and every vertex has O(log n) ancestors
+pub struct CentroidDecomposition {
+    pub roots: Vec<Vertex>,
+    pub parent: HashMap<Vertex, Vertex>,
+    pub level: HashMap<Vertex, usize>
+}
+
+impl CentroidDecomposition {
+
+    // lists v and its ancestors in the centroid tree, from v up to the root
+    pub fn ancestors(&self, v: Vertex) -> Vec<Vertex> {
+        let mut ancestors = vec![v];
+        while let Some(p) = self.parent.get(&ancestors[ancestors.len() - 1]) {
+            ancestors.push(*p);
+        }
+        ancestors
+    }
+}
+
+impl Graph {
+
+    // checks that the graph is connected and acyclic, i.e. that it is a forest with
+    // n - 1 edges; edge directions are ignored
+    pub fn is_tree(&self) -> Result<(), TreeViolation> {
+        self.is_forest()?;
+        self.is_connected()
+    }
+
+    // checks that the graph contains no cycles; edge directions are ignored, and so
+    // self-loops and parallel edges count as cycles
+    pub fn is_forest(&self) -> Result<(), TreeViolation> {
+        let (vertices, index) = self.indexed_vertices();
+        let mut edges: Vec<Edge> = self.edges().iter().cloned().collect();
+        edges.sort();
+        let mut sets = DisjointSets::new(vertices.len());
+        for edge in edges {
+            if !sets.union(index[&edge.0], index[&edge.1]) {
+                return Err(TreeViolation::ExtraEdge(edge));
+            }
+        }
+        Ok(())
+    }
+
+    // checks that there is a path between any two vertices; edge directions are ignored
+    // a single search from the smallest vertex suffices, which stops as soon as it has
+    // reached all vertices
+    pub fn is_connected(&self) -> Result<(), TreeViolation> {
+        let first = match self.vertices().iter().min() {
+            Some(v) => *v,
+            None => return Ok(())
+        };
+        match self.first_unreached(&self.undirected_adjacencies(), first) {
+            Some(v) => Err(TreeViolation::Disconnected(first, v)),
+            None => Ok(())
+        }
+    }
+
+    // builds an index for lowest common ancestor queries in the tree containing root
+    pub fn lca_index(&self, root: Vertex) -> LcaIndex {
+        LcaIndex::new(self.euler_tour(root))
+    }
+
+    // finds a longest path in the tree using two breadth first searches: the vertex farthest
+    // from any start vertex is an end of a longest path, whose other end is the vertex
+    // farthest from it; edge directions are ignored, and of a forest the tree of the
+    // smallest vertex is taken. Returns the number of edges on the path and the path itself
+    // fails with an edge closing a cycle, see is_forest
+    pub fn tree_diameter(&self) -> Result<(usize, Path), TreeViolation> {
+        self.is_forest()?;
+        let start = match self.sorted_vertices().first() {
+            Some(v) => *v,
+            None => return Ok((0, vec![]))
+        };
+        let adj = self.tree_adjacencies();
+        let (end, _) = farthest(&adj, start);
+        let (other, previous) = farthest(&adj, end);
+        let path = trace_path(&previous, other);
+        Ok((path.len() - 1, path))
+    }
+
+    // finds the one or two vertices in the middle of a longest path, which minimize the
+    // distance to the farthest vertex of the tree
+    // fails with an edge closing a cycle, see is_forest
+    pub fn tree_center(&self) -> Result<Vec<Vertex>, TreeViolation> {
+        let (length, path) = self.tree_diameter()?;
+        let mut center: Vec<Vertex> = path.iter().skip(length / 2).take(1 + length % 2).cloned().collect();
+        center.sort();
+        Ok(center)
+    }
+
+    // computes the centroid decomposition of every tree of the forest; edge directions are
+    // ignored
+    // fails with an edge closing a cycle, see is_forest
+    pub fn centroid_decomposition(&self) -> Result<CentroidDecomposition, TreeViolation> {
+        self.is_forest()?;
+        let adj = self.tree_adjacencies();
+        let mut decomposition = CentroidDecomposition { roots: vec![], parent: HashMap::new(), level: HashMap::new() };
+        let mut removed = HashSet::new();
+        for v in self.sorted_vertices() {
+            if removed.contains(&v) {
+                continue;
+            }
+            // subtrees still to be decomposed, with the centroid whose removal created them
+            let mut pending = vec![(v, None)];
+            while let Some((start, centroid)) = pending.pop() {
+                // sizes of the subtrees of the remaining tree when rooted at start
+                let mut order = vec![start];
+                let mut tree_parent = HashMap::new();
+                let mut i = 0;
+                while i < order.len() {
+                    let u = order[i];
+                    for w in &adj[&u] {
+                        if !removed.contains(w) && tree_parent.get(&u) != Some(w) {
+                            tree_parent.insert(*w, u);
+                            order.push(*w);
+                        }
+                    }
+                    i += 1;
+                }
+                let mut size: HashMap<Vertex, usize> = HashMap::new();
+                for u in order.iter().rev() {
+                    let below = adj[u].iter()
+                        .filter(|w| tree_parent.get(w) == Some(u))
+                        .map(|w| size[w])
+                        .sum::<usize>();
+                    size.insert(*u, below + 1);
+                }
+
+                // walk towards the heavy subtree until no subtree holds more than half
+                let mut c = start;
+                while let Some(w) = adj[&c].iter().find(|w| tree_parent.get(w) == Some(&c) && 2 * size[w] > order.len()) {
+                    c = *w;
+                }
+                removed.insert(c);
+                match centroid {
+                    Some(p) => {
+                        decomposition.parent.insert(c, p);
+                        decomposition.level.insert(c, decomposition.level[&p] + 1);
+                    },
+                    None => {
+                        decomposition.roots.push(c);
+                        decomposition.level.insert(c, 0);
+                    }
+                }
+                for w in adj[&c].iter().rev() {
+                    if !removed.contains(w) {
+                        pending.push((*w, Some(c)));
+                    }
+                }
+            }
+        }
+        Ok(decomposition)
+    }
+
+    // the sorted lists of neighbours of each vertex, ignoring edge directions
+    fn tree_adjacencies(&self) -> HashMap<Vertex, Vec<Vertex>> {
+        let mut adj: HashMap<Vertex, Vec<Vertex>> = self.vertices().iter().map(|v| (*v, vec![])).collect();
+        for edge in self.edges() {
+            adj.get_mut(&edge.0).unwrap().push(edge.1);
+            adj.get_mut(&edge.1).unwrap().push(edge.0);
+        }
+        for adjacent in adj.values_mut() {
+            adjacent.sort();
+            adjacent.dedup();
+        }
+        adj
+    }
+
+    // builds the Euler tour of the tree containing root, visiting children in sorted order;
+    // edge directions are ignored, and if the graph is not a tree the tour follows the
+    // depth first search tree instead; the tour is empty if root is not in the graph
+    pub fn euler_tour(&self, root: Vertex) -> EulerTour {
+        fn record(euler: &mut EulerTour, v: Vertex, depth: usize) {
+            euler.last.insert(v, euler.tour.len());
+            euler.tour.push(v);
+            euler.depth.push(depth);
+        }
+
+        let adj = self.tree_adjacencies();
+        let mut euler = EulerTour { tour: vec![], depth: vec![], first: HashMap::new(), last: HashMap::new() };
+        if !adj.contains_key(&root) {
+            return euler;
+        }
+        euler.first.insert(root, 0);
+        record(&mut euler, root, 0);
+        // the walk keeps a stack of vertices together with the position of the next
+        // neighbour to look at, so that deep trees cannot overflow the call stack
+        let mut stack = vec![(root, 0)];
+        while let Some(&mut (v, ref mut next)) = stack.last_mut() {
+            let child = adj[&v][*next..].iter().position(|w| !euler.first.contains_key(w));
+            match child {
+                Some(offset) => {
+                    let w = adj[&v][*next + offset];
+                    *next += offset + 1;
+                    euler.first.insert(w, euler.tour.len());
+                    record(&mut euler, w, stack.len());
+                    stack.push((w, 0));
+                },
+                None => {
+                    // the parent is recorded again after returning from the child
+                    stack.pop();
+                    if let Some(&(p, _)) = stack.last() {
+                        record(&mut euler, p, stack.len() - 1);
+                    }
+                }
+            }
+        }
+        euler
+    }
+}
+
+// breadth first search from source, returning the last vertex discovered, which is one of
+// those farthest away, and the parent of every vertex discovered
+fn farthest(adj: &HashMap<Vertex, Vec<Vertex>>, source: Vertex) -> (Vertex, HashMap<Vertex, Vertex>) {
+    let mut parent = HashMap::new();
+    let mut last = source;
+    let mut queue = VecDeque::new();
+    queue.push_back(source);
+    while let Some(v) = queue.pop_front() {
+        last = v;
+        for w in &adj[&v] {
+            if *w != source && !parent.contains_key(w) {
+                parent.insert(*w, v);
+                queue.push_back(*w);
+            }
+        }
+    }
+    (last, parent)
+}
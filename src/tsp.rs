@@ -0,0 +1,160 @@
+use graph::{Graph, Vertex, Weight};
+use shortest_paths::Path;
+
+// the largest number of vertices for which the exact dynamic program is run, as it takes
+// time and space exponential in the number of vertices
+pub const HELD_KARP_LIMIT: usize = 18;
+
+// tolerance below which a change in the length of a tour does not count as an improvement,
+// so that rounding errors cannot make the local search cycle
+const EPSILON: Weight = 1e-9;
+
+// a round trip through the graph, listing every vertex exactly once with the start vertex
+// first; its length includes the edge back from the last vertex to the start
+pub type Tour = (Weight, Path);
+
+impl Graph {
+
+    // constructs a tour starting from the smallest vertex, always moving on to the nearest
+    // vertex not visited yet; the graph ought to be complete, as missing edges count as
+    // infinitely long
+    pub fn tsp_nearest_neighbor(&self) -> Tour {
+        let (vertices, d) = self.distance_matrix();
+        let mut tour = vec![];
+        let mut visited = vec![false; vertices.len()];
+        let mut current = 0;
+        while tour.len() < vertices.len() {
+            visited[current] = true;
+            tour.push(current);
+            let next = (0..vertices.len())
+                .filter(|j| !visited[*j])
+                .min_by(|a, b| d[current][*a].total_cmp(&d[current][*b]));
+            match next {
+                Some(next) => current = next,
+                None => break
+            }
+        }
+        to_tour(&vertices, &d, &tour)
+    }
+
+    // improves a tour by local search until no move shortens it any further: 2-opt moves
+    // replace two edges by reversing the part of the tour between them, Or-opt moves take
+    // out a run of up to three consecutive vertices and insert it elsewhere; reversing
+    // parts of the tour assumes symmetric, i.e. undirected, edge weights
+    pub fn tsp_improve(&self, tour: &Tour) -> Tour {
+        let (vertices, d) = self.distance_matrix();
+        let index = self.indexed_vertices().1;
+        let mut tour: Vec<usize> = tour.1.iter().map(|v| index[v]).collect();
+        let n = tour.len();
+        let mut improved = n > 3;
+        while improved {
+            improved = false;
+            for i in 0..n - 1 {
+                for j in i + 2..n {
+                    let (a, b, c, e) = (tour[i], tour[i + 1], tour[j], tour[(j + 1) % n]);
+                    if a != e && d[a][c] + d[b][e] - d[a][b] - d[c][e] < -EPSILON {
+                        tour[i + 1..=j].reverse();
+                        improved = true;
+                    }
+                }
+            }
+            for length in 1..=3.min(n - 2) {
+                for i in 0..n {
+                    // the run starts at position i, and reinsertion goes after position j
+                    let run: Vec<usize> = (0..length).map(|k| tour[(i + k) % n]).collect();
+                    let (before, after) = (tour[(i + n - 1) % n], tour[(i + length) % n]);
+                    let removal = d[before][after] - d[before][run[0]] - d[run[length - 1]][after];
+                    let best = (0..n - length - 1)
+                        .map(|k| (i + length + k) % n)
+                        .map(|j| {
+                            let (x, y) = (tour[j], tour[(j + 1) % n]);
+                            (removal + d[x][run[0]] + d[run[length - 1]][y] - d[x][y], j)
+                        })
+                        .min_by(|a, b| a.0.total_cmp(&b.0));
+                    if let Some((delta, j)) = best {
+                        if delta < -EPSILON {
+                            let x = tour[j];
+                            tour.retain(|v| !run.contains(v));
+                            let position = tour.iter().position(|v| *v == x).unwrap() + 1;
+                            tour.splice(position..position, run);
+                            improved = true;
+                        }
+                    }
+                }
+            }
+        }
+        // rotate the tour back to start at the smallest vertex
+        let start = tour.iter().position(|v| *v == 0).unwrap_or(0);
+        tour.rotate_left(start);
+        to_tour(&vertices, &d, &tour)
+    }
+
+    // finds a shortest tour using the Held-Karp dynamic program over subsets of vertices;
+    // returns None if the graph has more than HELD_KARP_LIMIT vertices or no finite tour
+    pub fn tsp_held_karp(&self) -> Option<Tour> {
+        let (vertices, d) = self.distance_matrix();
+        let n = vertices.len();
+        if n > HELD_KARP_LIMIT {
+            return None;
+        }
+        if n <= 1 {
+            return Some((0.0, vertices));
+        }
+        // length[set][j] is the length of a shortest path starting at vertex 0, visiting the
+        // vertices of the set (bit k standing for vertex k + 1) and ending at vertex j + 1
+        let sets = 1 << (n - 1);
+        let mut length = vec![vec![Weight::INFINITY; n - 1]; sets];
+        let mut previous = vec![vec![usize::MAX; n - 1]; sets];
+        for j in 0..n - 1 {
+            length[1 << j][j] = d[0][j + 1];
+        }
+        for set in 1..sets {
+            for j in (0..n - 1).filter(|j| set & (1 << j) != 0) {
+                let without = set & !(1 << j);
+                for k in (0..n - 1).filter(|k| without & (1 << k) != 0) {
+                    let alt = length[without][k] + d[k + 1][j + 1];
+                    if alt < length[set][j] {
+                        length[set][j] = alt;
+                        previous[set][j] = k;
+                    }
+                }
+            }
+        }
+        let full = sets - 1;
+        let (total, last) = (0..n - 1)
+            .map(|j| (length[full][j] + d[j + 1][0], j))
+            .min_by(|a, b| a.0.total_cmp(&b.0))?;
+        if total == Weight::INFINITY {
+            return None;
+        }
+        let mut tour = vec![];
+        let (mut set, mut j) = (full, last);
+        while j != usize::MAX {
+            tour.push(j + 1);
+            let k = previous[set][j];
+            set &= !(1 << j);
+            j = k;
+        }
+        tour.push(0);
+        tour.reverse();
+        Some(to_tour(&vertices, &d, &tour))
+    }
+
+    // the vertices in ascending order and the matrix of the weights of the lightest edges
+    // between them, infinite where there is no edge
+    fn distance_matrix(&self) -> (Vec<Vertex>, Vec<Vec<Weight>>) {
+        let (vertices, index) = self.indexed_vertices();
+        let mut d = vec![vec![Weight::INFINITY; vertices.len()]; vertices.len()];
+        for (v, adjacent) in self.weighted_adjacencies() {
+            for (w, weight) in adjacent {
+                d[index[&v]][index[&w]] = weight;
+            }
+        }
+        (vertices, d)
+    }
+}
+
+fn to_tour(vertices: &[Vertex], d: &[Vec<Weight>], tour: &[usize]) -> Tour {
+    let length = (0..tour.len()).map(|i| d[tour[i]][tour[(i + 1) % tour.len()]]).sum();
+    (if tour.len() < 2 { 0.0 } else { length }, tour.iter().map(|i| vertices[*i]).collect())
+}
@@ -1,15 +1,35 @@
 use std::io::BufRead;
 
-// reads pairs of numbers from a given input stream
+use graph::Vertex;
+
+// reads single numbers or pairs of numbers, optionally followed by a weight,
+// from a given input stream; the numbers are vertices or counts
 pub trait TupleReader {
-    fn next_tuple(&mut self) -> (u32, u32);
+    fn next_number(&mut self) -> Vertex;
+    fn next_tuple(&mut self) -> (Vertex, Vertex);
+    fn next_weighted_tuple(&mut self) -> (Vertex, Vertex, f64);
 }
 
 impl<T: BufRead> TupleReader for T {
-    fn next_tuple(&mut self) -> (u32, u32) {
+    fn next_number(&mut self) -> Vertex {
+        let mut buffer = String::new();
+        self.read_line(&mut buffer).unwrap();
+        buffer.split_whitespace().next().unwrap().parse().unwrap()
+    }
+
+    fn next_tuple(&mut self) -> (Vertex, Vertex) {
         let mut buffer = String::new();
         self.read_line(&mut buffer).unwrap();
         let mut iter = buffer.split_whitespace().map(|s| s.parse().unwrap()).take(2);
         (iter.next().unwrap(), iter.next().unwrap())
     }
+
+    fn next_weighted_tuple(&mut self) -> (Vertex, Vertex, f64) {
+        let mut buffer = String::new();
+        self.read_line(&mut buffer).unwrap();
+        let mut iter = buffer.split_whitespace();
+        let from = iter.next().unwrap().parse().unwrap();
+        let to = iter.next().unwrap().parse().unwrap();
+        (from, to, iter.next().unwrap().parse().unwrap())
+    }
 }
@@ -0,0 +1,47 @@
+use graph::{Graph, HashMap, HashSet, Vertex};
+
+// a variable x_i as i or its negation as -i, for the variables numbered from 1
+pub type Literal = i64;
+
+// the vertex of the implication graph standing for the literal: 2i - 1 for x_i and 2i for
+// its negation
+fn vertex(literal: Literal, variables: usize) -> Vertex {
+    let i = literal.unsigned_abs() as usize;
+    if literal == 0 || i > variables {
+        panic!("Invalid literal!");
+    }
+    (2 * i - if literal > 0 { 1 } else { 0 }) as Vertex
+}
+
+// builds the implication graph of the clauses (a or b): as a clause can only be false if
+// both literals are, it contributes the implications not a => b and not b => a
+pub fn implication_graph(variables: usize, clauses: &[(Literal, Literal)]) -> Graph {
+    let vertices = (1..2 * variables as Vertex + 1).collect();
+    let mut edges = HashSet::new();
+    for &(a, b) in clauses {
+        edges.insert((vertex(-a, variables), vertex(b, variables)));
+        edges.insert((vertex(-b, variables), vertex(a, variables)));
+    }
+    Graph::new(vertices, edges, true)
+}
+
+// decides whether the conjunction of the clauses of two literals each is satisfiable: it is
+// unless some variable is in the same strongly connected component of the implication
+// graph as its negation, as they would then imply each other; otherwise setting every
+// literal whose component comes after the one of its negation in topological order true
+// satisfies all clauses
+// returns the values of the variables 1..n in a satisfying assignment, or a variable that
+// is equivalent to its own negation
+pub fn two_sat(variables: usize, clauses: &[(Literal, Literal)]) -> Result<Vec<bool>, usize> {
+    let graph = implication_graph(variables, clauses);
+    // the components are listed in reverse topological order
+    let component: HashMap<Vertex, usize> = graph.strongly_connected_components().into_iter().enumerate()
+        .flat_map(|(i, component)| component.into_iter().map(move |v| (v, i)))
+        .collect();
+    (1..variables + 1)
+        .map(|i| {
+            let (positive, negative) = (component[&vertex(i as Literal, variables)], component[&vertex(-(i as Literal), variables)]);
+            if positive == negative { Err(i) } else { Ok(positive < negative) }
+        })
+        .collect()
+}
@@ -0,0 +1,100 @@
+use std::fmt;
+
+use graph::{Edge, Graph, HashMap, HashSet, Vertex, Weight};
+
+// which kinds of edges validate accepts
+pub struct ValidationPolicy {
+    pub allow_self_loops: bool,
+    // for undirected graphs, whether an edge may be contained in both directions
+    pub allow_parallel_edges: bool
+}
+
+// an inconsistency found by validate
+#[derive(Debug)]
+pub enum Violation {
+    // an edge with an end that is not in the vertex set
+    MissingVertex(Edge, Vertex),
+    SelfLoop(Edge),
+    // an undirected edge that is contained in the opposite direction as well
+    ParallelEdge(Edge),
+    // an edge whose weight is not a number
+    InvalidWeight(Edge)
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Violation::MissingVertex((u, v), w) => write!(f, "edge {} - {} has unknown vertex {}", u, v, w),
+            Violation::SelfLoop((u, v)) => write!(f, "edge {} - {} is a self-loop", u, v),
+            Violation::ParallelEdge((u, v)) => write!(f, "edge {} - {} is also contained as {} - {}", u, v, v, u),
+            Violation::InvalidWeight((u, v)) => write!(f, "edge {} - {} has an invalid weight", u, v)
+        }
+    }
+}
+
+impl Graph {
+
+    // checks that all edges join vertices of the graph, have valid weights and are allowed
+    // by the policy, returning the violations found in order of the edges; the other
+    // algorithms assume that there are no missing vertices and may panic otherwise
+    pub fn validate(&self, policy: &ValidationPolicy) -> Vec<Violation> {
+        let mut edges: Vec<&Edge> = self.edges().iter().collect();
+        edges.sort();
+        let mut violations = vec![];
+        for edge in edges {
+            let ends = if edge.0 == edge.1 { vec![edge.0] } else { vec![edge.0, edge.1] };
+            for v in ends {
+                if !self.vertices().contains(&v) {
+                    violations.push(Violation::MissingVertex(*edge, v));
+                }
+            }
+            if edge.0 == edge.1 && !policy.allow_self_loops {
+                violations.push(Violation::SelfLoop(*edge));
+            }
+            if !self.is_directed() && !policy.allow_parallel_edges && edge.0 < edge.1 && self.edges().contains(&(edge.1, edge.0)) {
+                violations.push(Violation::ParallelEdge(*edge));
+            }
+            if self.weight(edge).is_nan() {
+                violations.push(Violation::InvalidWeight(*edge));
+            }
+        }
+        violations
+    }
+
+    // returns a cleaned copy of the graph: undirected edges point from the smaller to the
+    // larger vertex, so an edge contained in both directions is kept only once with the
+    // smaller weight, and self-loops are removed unless they are to be kept
+    pub fn normalize(&self, keep_self_loops: bool) -> Graph {
+        let mut edges = HashSet::new();
+        let mut weights: HashMap<Edge, Weight> = HashMap::new();
+        for edge in self.edges() {
+            if edge.0 == edge.1 && !keep_self_loops {
+                continue;
+            }
+            let normalized = if self.is_directed() { *edge } else { (edge.0.min(edge.1), edge.0.max(edge.1)) };
+            edges.insert(normalized);
+            if self.is_weighted() {
+                let weight = weights.entry(normalized).or_insert(self.weight(edge));
+                *weight = weight.min(self.weight(edge));
+            }
+        }
+        Graph::with_weights(self.vertices().clone(), edges, weights, self.is_directed())
+    }
+
+    // returns a copy of the graph with the vertices numbered 0..n - 1 in ascending order of
+    // their original ids, together with the new id of every original vertex and the original
+    // id of every new vertex; ends of edges missing from the vertex set count as vertices
+    pub fn relabel_dense(&self) -> (Graph, HashMap<Vertex, Vertex>, Vec<Vertex>) {
+        let mut original: Vec<Vertex> = self.vertices().iter().cloned()
+            .chain(self.edges().iter().flat_map(|edge| vec![edge.0, edge.1]))
+            .collect::<HashSet<Vertex>>()
+            .into_iter()
+            .collect();
+        original.sort();
+        let dense: HashMap<Vertex, Vertex> = original.iter().enumerate().map(|(i, v)| (*v, i as Vertex)).collect();
+        let edges = self.edges().iter().map(|edge| (dense[&edge.0], dense[&edge.1])).collect();
+        let weights = self.weights().iter().map(|(edge, weight)| ((dense[&edge.0], dense[&edge.1]), *weight)).collect();
+        let graph = Graph::with_weights(dense.values().cloned().collect(), edges, weights, self.is_directed());
+        (graph, dense, original)
+    }
+}
@@ -0,0 +1,140 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use graph::{Adjacencies, Graph, HashSet, Vertex, WeightedAdjacencies};
+use shortest_paths::{dijkstra, PathResult};
+
+// an immutable snapshot of a graph with its adjacency maps built once up front, for
+// answering read-only queries from many threads at the same time; cloning a view is cheap
+// and shares the snapshot, and since nothing in it can change after construction, all
+// queries see the same graph without any locking
+#[derive(Clone)]
+pub struct GraphView {
+    snapshot: Arc<Snapshot>
+}
+
+struct Snapshot {
+    graph: Graph,
+    adjacencies: Adjacencies,
+    weighted_adjacencies: WeightedAdjacencies
+}
+
+// views are shared between threads, which must keep compiling
+const _: fn() = || {
+    fn shareable<T: Send + Sync>() {}
+    shareable::<GraphView>();
+};
+
+impl GraphView {
+
+    // turns the view back into a graph that can be changed; the graph is only copied if
+    // other clones of the view are still around, which keep seeing the old version
+    pub fn thaw(self) -> Graph {
+        match Arc::try_unwrap(self.snapshot) {
+            Ok(snapshot) => snapshot.graph,
+            Err(shared) => shared.graph.clone()
+        }
+    }
+
+    pub fn graph(&self) -> &Graph {
+        &self.snapshot.graph
+    }
+
+    // the vertices adjacent to v, if it is a vertex of the graph
+    pub fn neighbours(&self, v: Vertex) -> Option<&HashSet<Vertex>> {
+        self.snapshot.adjacencies.get(&v)
+    }
+
+    // returns true if w can be reached from v, using breadth first search
+    pub fn is_reachable(&self, v: Vertex, w: Vertex) -> bool {
+        let mut visited: HashSet<Vertex> = vec![v].into_iter().collect();
+        let mut queue: VecDeque<Vertex> = vec![v].into_iter().collect();
+        while let Some(u) = queue.pop_front() {
+            if u == w {
+                return true;
+            }
+            for x in self.neighbours(u).into_iter().flatten() {
+                if visited.insert(*x) {
+                    queue.push_back(*x);
+                }
+            }
+        }
+        false
+    }
+
+    // finds a shortest path like Graph::shortest_path; edge weights must not be negative
+    pub fn shortest_path(&self, source: Vertex, target: Vertex) -> Option<PathResult> {
+        if !self.snapshot.weighted_adjacencies.contains_key(&source) {
+            return None;
+        }
+        dijkstra(&self.snapshot.weighted_adjacencies, source, target, |_| true, |_| true)
+    }
+}
+
+impl Graph {
+
+    // takes a snapshot of the graph for concurrent queries
+    pub fn view(&self) -> GraphView {
+        self.clone().freeze()
+    }
+
+    // turns the graph into a view for concurrent queries without copying it
+    pub fn freeze(self) -> GraphView {
+        let adjacencies = self.adjacencies();
+        let weighted_adjacencies = self.weighted_adjacencies();
+        GraphView { snapshot: Arc::new(Snapshot { graph: self, adjacencies, weighted_adjacencies }) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use graph::{Graph, HashSet, Vertex, Weight};
+    use shortest_paths::Path;
+
+    // a directed graph of a weighted ring with chords, and a separate pair of vertices that
+    // the ring cannot reach
+    fn ring_with_chords() -> Graph {
+        let mut graph = Graph::new(HashSet::new(), HashSet::new(), true);
+        for v in 1..=40 {
+            graph.add_weighted_edge(v, v % 40 + 1, (v % 3 + 1) as Weight);
+            graph.add_weighted_edge(v, v * 7 % 40 + 1, 2.5);
+        }
+        graph.add_weighted_edge(41, 42, 1.0);
+        graph
+    }
+
+    #[test]
+    fn concurrent_readers_agree_with_single_threaded_queries() {
+        let graph = ring_with_chords();
+        let pairs: Vec<(Vertex, Vertex)> = (1..=43).flat_map(|u| (1..=43).map(move |v| (u, v))).collect();
+        let answer = |reachable: bool, found: Option<(Weight, Path)>| (reachable, found);
+        let expected: Vec<_> = pairs.iter()
+            .map(|&(u, v)| answer(graph.is_reachable(u, v), graph.shortest_path(u, v).unwrap().map(|found| (found.distance, found.path))))
+            .collect();
+
+        let view = graph.view();
+        let readers = 4;
+        let answers: Vec<Vec<_>> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..readers).map(|reader| {
+                let (view, pairs) = (&view, &pairs);
+                scope.spawn(move || {
+                    // every reader asks about all pairs, starting at a different one
+                    (0..pairs.len()).map(|i| {
+                        let (u, v) = pairs[(i + reader * pairs.len() / readers) % pairs.len()];
+                        answer(view.is_reachable(u, v), view.shortest_path(u, v).map(|found| (found.distance, found.path)))
+                    }).collect()
+                })
+            }).collect();
+            handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+        });
+
+        for (reader, answers) in answers.iter().enumerate() {
+            for (i, answer) in answers.iter().enumerate() {
+                let j = (i + reader * pairs.len() / readers) % pairs.len();
+                assert_eq!(answer, &expected[j], "reader {} differs on {:?}", reader, pairs[j]);
+            }
+        }
+    }
+}
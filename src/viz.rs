@@ -0,0 +1,230 @@
+use std::io;
+use std::io::Write;
+
+use attributes::VertexMap;
+use dimacs::Coordinates;
+use graph::{Edge, Graph, Vertex};
+use random::Random;
+
+// width and height of the drawing in pixels, and the margin kept free around the layout
+const SIZE: f64 = 600.0;
+const MARGIN: f64 = 30.0;
+const RADIUS: f64 = 10.0;
+
+// fill colors for the groups of vertices, repeating when there are more groups than colors
+const PALETTE: [&str; 10] = [
+    "#1f77b4", "#ff7f0e", "#2ca02c", "#d62728", "#9467bd",
+    "#8c564b", "#e377c2", "#7f7f7f", "#bcbd22", "#17becf"
+];
+
+// how write_svg colors the vertices
+pub enum Coloring {
+    Uniform,
+    // by connected component, strongly connected for directed graphs
+    Components,
+    // by the communities found by label propagation with the given seed
+    Communities(u64)
+}
+
+impl Graph {
+
+    // places the vertices in the unit square with the force-directed algorithm of
+    // Fruchterman and Reingold: all pairs of vertices repel each other while the ends of
+    // every edge attract each other, and the vertices move along the resulting forces by
+    // at most the temperature, which cools down linearly over the iterations
+    pub fn layout(&self, iterations: usize, seed: u64) -> VertexMap<Coordinates> {
+        let (vertices, index) = self.indexed_vertices();
+        let n = vertices.len();
+        let mut random = Random::new(seed);
+        let mut position: Vec<Coordinates> = (0..n).map(|_| (random.next_f64(), random.next_f64())).collect();
+        let mut edges: Vec<(usize, usize)> = self.edges().iter()
+            .filter(|edge| edge.0 != edge.1)
+            .map(|edge| (index[&edge.0], index[&edge.1]))
+            .collect();
+        edges.sort();
+
+        // the ideal distance between adjacent vertices
+        let k = (1.0 / n.max(1) as f64).sqrt();
+        for iteration in 0..iterations {
+            let temperature = 0.1 * (1.0 - iteration as f64 / iterations as f64);
+            let mut displacement = vec![(0.0, 0.0); n];
+            let mut push = |i: usize, j: usize, force: f64, dx: f64, dy: f64, distance: f64| {
+                let (fx, fy) = (dx / distance * force, dy / distance * force);
+                displacement[i].0 += fx;
+                displacement[i].1 += fy;
+                displacement[j].0 -= fx;
+                displacement[j].1 -= fy;
+            };
+            for i in 0..n {
+                for j in i + 1..n {
+                    let (dx, dy) = (position[i].0 - position[j].0, position[i].1 - position[j].1);
+                    let distance = (dx * dx + dy * dy).sqrt().max(1e-9);
+                    push(i, j, k * k / distance, dx, dy, distance);
+                }
+            }
+            for &(i, j) in &edges {
+                let (dx, dy) = (position[i].0 - position[j].0, position[i].1 - position[j].1);
+                let distance = (dx * dx + dy * dy).sqrt().max(1e-9);
+                push(i, j, -distance * distance / k, dx, dy, distance);
+            }
+            for (p, (dx, dy)) in position.iter_mut().zip(displacement) {
+                let length = (dx * dx + dy * dy).sqrt().max(1e-9);
+                let step = length.min(temperature);
+                p.0 = (p.0 + dx / length * step).clamp(0.0, 1.0);
+                p.1 = (p.1 + dy / length * step).clamp(0.0, 1.0);
+            }
+        }
+
+        let mut layout = self.vertex_map();
+        for (v, p) in vertices.into_iter().zip(position) {
+            layout.set(v, p);
+        }
+        layout
+    }
+
+    // draws the graph as an SVG image, with the vertices at their positions in the layout
+    // scaled to fill the drawing, labeled with their numbers and colored as requested;
+    // directed edges end in arrow heads
+    pub fn write_svg<W: Write>(&self, writer: &mut W, layout: &VertexMap<Coordinates>, coloring: &Coloring) -> io::Result<()> {
+        let (min, max) = layout.iter().fold(((f64::MAX, f64::MAX), (f64::MIN, f64::MIN)), |(min, max), (_, p)| {
+            ((min.0.min(p.0), min.1.min(p.1)), (max.0.max(p.0), max.1.max(p.1)))
+        });
+        let scale = |value: f64, low: f64, high: f64| {
+            let range = if high > low { high - low } else { 1.0 };
+            MARGIN + (value - low) / range * (SIZE - 2.0 * MARGIN)
+        };
+        let point = |v: Vertex| {
+            let p = layout.get(v).cloned().unwrap_or((min.0, min.1));
+            (scale(p.0, min.0, max.0), scale(p.1, min.1, max.1))
+        };
+
+        let mut group = self.vertex_map();
+        match *coloring {
+            Coloring::Uniform => (),
+            Coloring::Components => {
+                for (v, component) in self.component_map().iter() {
+                    group.set(v, *component);
+                }
+            },
+            Coloring::Communities(seed) => {
+                let mut communities: Vec<Vec<Vertex>> = self.communities_label_propagation(seed, 100).into_iter()
+                    .map(|community| community.into_iter().collect())
+                    .collect();
+                communities.sort_by_key(|community| community.iter().min().cloned());
+                for (i, community) in communities.iter().enumerate() {
+                    for v in community {
+                        group.set(*v, i);
+                    }
+                }
+            }
+        }
+
+        writeln!(writer, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{0}" viewBox="0 0 {0} {0}">"#, SIZE)?;
+        if self.is_directed() {
+            writeln!(writer, r#"<defs><marker id="arrow" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="6" markerHeight="6" orient="auto"><path d="M 0 0 L 10 5 L 0 10 z" fill="black"/></marker></defs>"#)?;
+        }
+        let mut edges: Vec<&Edge> = self.edges().iter().collect();
+        edges.sort();
+        for edge in edges.into_iter().filter(|edge| edge.0 != edge.1) {
+            let ((x1, y1), (x2, y2)) = (point(edge.0), point(edge.1));
+            // directed edges stop at the border of the target vertex, where the arrow head is
+            let length = ((x2 - x1) * (x2 - x1) + (y2 - y1) * (y2 - y1)).sqrt().max(1e-9);
+            let shorten = if self.is_directed() { RADIUS / length } else { 0.0 };
+            let (x2, y2) = (x2 - (x2 - x1) * shorten, y2 - (y2 - y1) * shorten);
+            let marker = if self.is_directed() { r#" marker-end="url(#arrow)""# } else { "" };
+            writeln!(writer, r#"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="black"{}/>"#, x1, y1, x2, y2, marker)?;
+        }
+        for v in self.sorted_vertices() {
+            let (x, y) = point(v);
+            let fill = group.get(v).map_or("white", |g| PALETTE[g % PALETTE.len()]);
+            writeln!(writer, r#"<circle cx="{:.1}" cy="{:.1}" r="{}" fill="{}" stroke="black"/>"#, x, y, RADIUS, fill)?;
+            writeln!(writer, r#"<text x="{:.1}" y="{:.1}" font-size="10" text-anchor="middle" dominant-baseline="central">{}</text>"#, x, y, v)?;
+        }
+        writeln!(writer, "</svg>")
+    }
+
+    // renders the adjacency matrix of the graph as text, marking an edge from the vertex of
+    // the row to the vertex of the column with 1; meant for small graphs
+    pub fn adjacency_matrix_text(&self) -> String {
+        let vertices = self.sorted_vertices();
+        let adj = self.adjacencies();
+        let width = vertices.iter().map(|v| v.to_string().len()).max().unwrap_or(1);
+        let mut text = format!("{:w$}", "", w = width);
+        for v in &vertices {
+            text += &format!(" {:>w$}", v, w = width);
+        }
+        text += "\n";
+        for v in &vertices {
+            text += &format!("{:>w$}", v, w = width);
+            for w in &vertices {
+                text += &format!(" {:>w$}", if adj[v].contains(w) { "1" } else { "." }, w = width);
+            }
+            text += "\n";
+        }
+        text
+    }
+
+    // renders the graph as a drawing on a grid of characters with the vertices at their
+    // positions in the layout, showing each vertex by its number and each edge as a line of
+    // box-drawing characters; meant for small graphs, as vertices close to each other in
+    // the layout may overlap on the grid
+    pub fn text_drawing(&self, layout: &VertexMap<Coordinates>, columns: usize, rows: usize) -> String {
+        let (columns, rows) = (columns.max(2), rows.max(2));
+        let mut canvas = vec![vec![' '; columns]; rows];
+        let (min, max) = layout.iter().fold(((f64::MAX, f64::MAX), (f64::MIN, f64::MIN)), |(min, max), (_, p)| {
+            ((min.0.min(p.0), min.1.min(p.1)), (max.0.max(p.0), max.1.max(p.1)))
+        });
+        let scale = |value: f64, low: f64, high: f64, cells: usize| {
+            let range = if high > low { high - low } else { 1.0 };
+            ((value - low) / range * (cells - 1) as f64).round() as i64
+        };
+        let cell = |v: Vertex| {
+            let p = layout.get(v).cloned().unwrap_or((min.0, min.1));
+            (scale(p.0, min.0, max.0, columns), scale(p.1, min.1, max.1, rows))
+        };
+
+        // draws every edge with Bresenham's algorithm, choosing the character by the direction
+        // of each step
+        let mut edges: Vec<&Edge> = self.edges().iter().collect();
+        edges.sort();
+        for edge in edges {
+            let ((mut x, mut y), (x1, y1)) = (cell(edge.0), cell(edge.1));
+            let (dx, dy) = ((x1 - x).abs(), -(y1 - y).abs());
+            let (sx, sy) = ((x1 - x).signum(), (y1 - y).signum());
+            let mut error = dx + dy;
+            while (x, y) != (x1, y1) {
+                let doubled = 2 * error;
+                let (mut step_x, mut step_y) = (0, 0);
+                if doubled >= dy {
+                    error += dy;
+                    step_x = sx;
+                }
+                if doubled <= dx {
+                    error += dx;
+                    step_y = sy;
+                }
+                x += step_x;
+                y += step_y;
+                canvas[y as usize][x as usize] = match (step_x, step_y) {
+                    (_, 0) => '─',
+                    (0, _) => '│',
+                    (a, b) if a == b => '╲',
+                    _ => '╱'
+                };
+            }
+        }
+        for v in self.sorted_vertices() {
+            let (x, y) = cell(v);
+            let label: Vec<char> = v.to_string().chars().collect();
+            let start = (x as usize).min(columns.saturating_sub(label.len()));
+            for (i, c) in label.into_iter().enumerate() {
+                if start + i < columns {
+                    canvas[y as usize][start + i] = c;
+                }
+            }
+        }
+        canvas.into_iter()
+            .map(|row| row.into_iter().collect::<String>().trim_end().to_string() + "\n")
+            .collect()
+    }
+}
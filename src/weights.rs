@@ -0,0 +1,123 @@
+use graph::{Edge, Graph, Vertex, Weight};
+use preconditions::PreconditionError;
+use random::Random;
+use shortest_paths::PathResult;
+
+// a distribution to draw edge weights from: all 1, integers from low to high inclusive
+// with equal probability, or exponentially distributed reals of the given mean, which
+// resemble e.g. latencies with a few long ones
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WeightDistribution {
+    Unit,
+    UniformInt(i64, i64),
+    Exponential(f64)
+}
+
+impl WeightDistribution {
+
+    // draws a weight; uniform integers with high below low are all low
+    pub fn sample(self, random: &mut Random) -> Weight {
+        match self {
+            WeightDistribution::Unit => 1.0,
+            WeightDistribution::UniformInt(low, high) => (low + random.below((high.max(low) - low) as usize + 1) as i64) as Weight,
+            WeightDistribution::Exponential(mean) => -mean * (1.0 - random.next_f64()).ln()
+        }
+    }
+}
+
+impl Graph {
+
+    // the graph with the weight of every edge replaced by the function of the edge and its
+    // weight, which is 1 for edges of unweighted graphs; vertices, edges and directions stay
+    pub fn map_weights<F: Fn(Edge, Weight) -> Weight>(&self, f: F) -> Graph {
+        let weights = self.edges().iter().map(|edge| (*edge, f(*edge, self.weight(edge)))).collect();
+        let mut graph = Graph::with_weights(self.vertices().clone(), self.edges().clone(), weights, self.is_directed());
+        graph.set_deterministic(self.is_deterministic());
+        graph
+    }
+
+    // the graph with a weight drawn from the distribution for every edge, in ascending order
+    // of the edges using the seed, so that a generated graph becomes the same weighted one
+    // on every run; vertices, edges and directions stay
+    pub fn random_weights(&self, distribution: WeightDistribution, seed: u64) -> Graph {
+        let mut random = Random::new(seed);
+        let mut edges: Vec<Edge> = self.edges().iter().cloned().collect();
+        edges.sort();
+        let weights = edges.into_iter().map(|edge| (edge, distribution.sample(&mut random))).collect();
+        let mut graph = Graph::with_weights(self.vertices().clone(), self.edges().clone(), weights, self.is_directed());
+        graph.set_deterministic(self.is_deterministic());
+        graph
+    }
+
+    // the graph with every weight multiplied by the factor, such as for converting units;
+    // a negative factor negates the weights without the checks of negate_weights
+    pub fn scale_weights(&self, factor: Weight) -> Graph {
+        self.map_weights(|_, weight| weight * factor)
+    }
+
+    // the graph with the weights mapped linearly onto the range from low to high, the
+    // smallest weight becoming low and the largest high, or all of them low if they are
+    // equal; infinite weights stay as they are and do not count for the range
+    pub fn rescale_weights(&self, low: Weight, high: Weight) -> Graph {
+        let finite: Vec<Weight> = self.edges().iter().map(|edge| self.weight(edge)).filter(|weight| weight.is_finite()).collect();
+        let smallest = finite.iter().cloned().fold(Weight::INFINITY, Weight::min);
+        let largest = finite.iter().cloned().fold(Weight::NEG_INFINITY, Weight::max);
+        self.map_weights(|_, weight| match weight {
+            weight if !weight.is_finite() => weight,
+            _ if largest == smallest => low,
+            weight => low + (weight - smallest) / (largest - smallest) * (high - low)
+        })
+    }
+
+    // turns weights that are probabilities, such as of edges working or of a message getting
+    // through, into the costs -ln(p), so that the probability of a path, the product of
+    // those of its edges, is e to the minus its cost, the sum of theirs: the shortest path by
+    // cost is the most probable one. Costs are never negative, so Dijkstra's algorithm
+    // applies; edges of probability 0 are dropped, as no path can use them
+    // fails with the smallest edge whose weight is no probability between 0 and 1
+    pub fn log_transform(&self) -> Result<Graph, PreconditionError> {
+        let mut edges: Vec<&Edge> = self.edges().iter().collect();
+        edges.sort();
+        if let Some(edge) = edges.into_iter().find(|edge| !(0.0..=1.0).contains(&self.weight(edge))) {
+            return Err(PreconditionError::NotProbability(*edge, self.weight(edge)));
+        }
+        let mut graph = self.map_weights(|_, probability| -probability.ln());
+        for (v, w) in self.edges() {
+            if self.weight(&(*v, *w)) == 0.0 {
+                graph.remove_edge(*v, *w);
+            }
+        }
+        Ok(graph)
+    }
+
+    // finds the most probable path from source to target when the weights are the
+    // probabilities of the edges, independently of each other, by a shortest path along the
+    // costs of log_transform; returns the path with its probability as the distance, or
+    // None if target cannot be reached along edges of positive probability
+    pub fn most_reliable_path(&self, source: Vertex, target: Vertex) -> Result<Option<PathResult>, PreconditionError> {
+        let costs = self.log_transform()?;
+        Ok(costs.shortest_path(source, target)?.map(|mut found| {
+            found.distance = (-found.distance).exp();
+            found
+        }))
+    }
+
+    // the graph with every weight negated, so that longest paths become shortest ones, as
+    // dag_shortest_paths then finds them; this only works without cycles of positive weight,
+    // which would become negative cycles that no path is shortest around, so a directed
+    // graph must be acyclic and an undirected one, where every edge is a cycle there and
+    // back, must have no edge of positive weight
+    // fails with a cycle of the directed graph or the smallest edge of positive weight
+    pub fn negate_weights(&self) -> Result<Graph, PreconditionError> {
+        if self.is_directed() {
+            self.topological_order()?;
+        } else {
+            let mut edges: Vec<&Edge> = self.edges().iter().collect();
+            edges.sort();
+            if let Some(&(v, w)) = edges.into_iter().find(|edge| self.weight(edge) > 0.0) {
+                return Err(PreconditionError::Cycle(vec![v, w, v]));
+            }
+        }
+        Ok(self.map_weights(|_, weight| -weight))
+    }
+}